@@ -0,0 +1,284 @@
+//! Record and replay HTTP exchanges as fixture files.
+//!
+//! [`RecordingBackend`] wraps another [`HttpBackend`] and writes every
+//! exchange it forwards into a directory; [`ReplayBackend`] answers requests
+//! from such a directory without touching the network. Pointing all clients
+//! at the same directory records a whole session -- crates.io, docs.rs,
+//! OSV.dev and the sparse index -- which can then be replayed
+//! deterministically, for example with the server's `--replay <dir>` flag.
+//!
+//! Each exchange is one file, named by a stable hash of the request's
+//! method, URL and body: a JSON header line describing the request and
+//! response, then the raw response body. Request headers are not part of the
+//! key and are never written, so tokens sent with a request stay out of the
+//! fixtures. When the same request is recorded twice, the later response
+//! replaces the earlier one.
+
+use std::collections::HashMap;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use futures::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+
+use super::disk_cache::fnv1a;
+use super::http::{
+    HeaderName, HeaderValue, HttpBackend, HttpError, HttpRequest, HttpResponse, StatusCode,
+};
+
+/// File extension of recorded exchanges.
+const EXTENSION: &str = "http";
+
+/// The JSON header line of a recorded exchange. The request is only there
+/// for whoever reads the fixture; replay goes by file name.
+#[derive(Debug, Serialize, Deserialize)]
+struct Exchange {
+    method: String,
+    url: String,
+    status: u16,
+    #[serde(default)]
+    headers: Vec<(String, String)>,
+}
+
+/// Forwards requests to another backend and records each exchange.
+///
+/// `304 Not Modified` responses are passed through but not recorded, since
+/// they only mean something next to the cached copy they revalidate.
+pub struct RecordingBackend {
+    inner: Arc<dyn HttpBackend>,
+    dir: PathBuf,
+}
+
+impl RecordingBackend {
+    /// Record exchanges sent through `inner` into `dir`, creating it if needed.
+    pub fn new(inner: Arc<dyn HttpBackend>, dir: impl Into<PathBuf>) -> io::Result<Self> {
+        let dir = dir.into();
+        std::fs::create_dir_all(&dir)?;
+        Ok(Self { inner, dir })
+    }
+
+    /// The directory exchanges are written to.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    async fn record(&self, request: &HttpRequest, response: &HttpResponse) -> io::Result<()> {
+        let exchange = Exchange {
+            method: request.method.to_string(),
+            url: request.url.clone(),
+            status: response.status.as_u16(),
+            headers: response
+                .headers
+                .iter()
+                .filter(|(name, _)| *name != reqwest::header::SET_COOKIE)
+                .filter_map(|(name, value)| {
+                    Some((name.to_string(), value.to_str().ok()?.to_string()))
+                })
+                .collect(),
+        };
+        let mut data = serde_json::to_vec(&exchange)?;
+        data.push(b'\n');
+        data.extend_from_slice(&response.body);
+
+        let path = self.dir.join(file_name(request));
+        let tmp = path.with_extension(format!("tmp{}", std::process::id()));
+        tokio::fs::write(&tmp, &data).await?;
+        tokio::fs::rename(&tmp, &path).await
+    }
+}
+
+impl HttpBackend for RecordingBackend {
+    fn send(&self, request: HttpRequest) -> BoxFuture<'_, Result<HttpResponse, HttpError>> {
+        Box::pin(async move {
+            let response = self.inner.send(request.clone()).await?;
+            if response.status != StatusCode::NOT_MODIFIED {
+                self.record(&request, &response).await.map_err(|e| {
+                    HttpError::new(format!(
+                        "failed to record {} {}: {e}",
+                        request.method, request.url
+                    ))
+                })?;
+            }
+            Ok(response)
+        })
+    }
+}
+
+/// Answers requests from a directory of recorded exchanges.
+///
+/// A request that was never recorded fails with an [`HttpError`] naming it,
+/// rather than going to the network.
+#[derive(Debug, Default)]
+pub struct ReplayBackend {
+    responses: HashMap<String, HttpResponse>,
+}
+
+impl ReplayBackend {
+    /// Load every exchange recorded in `dir`.
+    pub fn open(dir: &Path) -> io::Result<Self> {
+        let mut responses = HashMap::new();
+        for file in std::fs::read_dir(dir)? {
+            let path = file?.path();
+            if path.extension().is_none_or(|ext| ext != EXTENSION) {
+                continue;
+            }
+            let data = std::fs::read(&path)?;
+            let (exchange, body) = parse(&data).ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("invalid recorded exchange {}", path.display()),
+                )
+            })?;
+            let status = StatusCode::from_u16(exchange.status)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+            let mut response = HttpResponse::new(status).with_body(body.to_vec());
+            for (name, value) in &exchange.headers {
+                if let (Ok(name), Ok(value)) = (
+                    HeaderName::from_bytes(name.as_bytes()),
+                    HeaderValue::from_str(value),
+                ) {
+                    response.headers.append(name, value);
+                }
+            }
+            let name = path.file_name().unwrap_or_default().to_string_lossy();
+            responses.insert(name.into_owned(), response);
+        }
+        Ok(Self { responses })
+    }
+
+    /// Number of recorded exchanges.
+    pub fn len(&self) -> usize {
+        self.responses.len()
+    }
+
+    /// Whether nothing was recorded.
+    pub fn is_empty(&self) -> bool {
+        self.responses.is_empty()
+    }
+}
+
+impl HttpBackend for ReplayBackend {
+    fn send(&self, request: HttpRequest) -> BoxFuture<'_, Result<HttpResponse, HttpError>> {
+        let response = self
+            .responses
+            .get(&file_name(&request))
+            .cloned()
+            .ok_or_else(|| {
+                HttpError::new(format!(
+                    "no recorded response for {} {}",
+                    request.method, request.url
+                ))
+            });
+        Box::pin(async move { response })
+    }
+}
+
+/// The file an exchange is stored in. Requests with a body (OSV.dev queries)
+/// are told apart by it.
+fn file_name(request: &HttpRequest) -> String {
+    let mut key = format!("{} {}", request.method, request.url);
+    if let Some(body) = &request.body {
+        key.push('\n');
+        key.push_str(&String::from_utf8_lossy(body));
+    }
+    format!("{:016x}.{EXTENSION}", fnv1a(&key))
+}
+
+fn parse(data: &[u8]) -> Option<(Exchange, &[u8])> {
+    let newline = data.iter().position(|&b| b == b'\n')?;
+    let exchange = serde_json::from_slice(&data[..newline]).ok()?;
+    Some((exchange, &data[newline + 1..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::disk_cache::tests::temp_dir;
+    use super::super::http::{MemoryBackend, Method};
+    use super::*;
+
+    #[tokio::test]
+    async fn recorded_exchanges_replay() {
+        let dir = temp_dir("cassette");
+        let upstream = Arc::new(MemoryBackend::new());
+        upstream.route(
+            Method::GET,
+            "https://crates.io/api/v1/crates/serde",
+            HttpResponse::json(&serde_json::json!({"crate": {"name": "serde"}}))
+                .with_header(reqwest::header::ETAG, "\"abc\""),
+        );
+        upstream.route(
+            Method::GET,
+            "https://docs.rs/crate/serde/latest/json",
+            HttpResponse::new(StatusCode::OK).with_body(vec![0x28, 0xb5, 0x2f, 0xfd, 0x00]),
+        );
+        upstream.route(
+            Method::POST,
+            "https://api.osv.dev/v1/query",
+            HttpResponse::json(&serde_json::json!({"vulns": []})),
+        );
+
+        let recorder = RecordingBackend::new(upstream, &dir).unwrap();
+        let osv_query = |name: &str| {
+            HttpRequest::post("https://api.osv.dev/v1/query")
+                .json(&serde_json::json!({"package": {"name": name}}))
+                .unwrap()
+        };
+        for request in [
+            HttpRequest::get("https://crates.io/api/v1/crates/serde")
+                .header(reqwest::header::AUTHORIZATION, "secret-token"),
+            HttpRequest::get("https://docs.rs/crate/serde/latest/json"),
+            osv_query("serde"),
+        ] {
+            recorder.send(request).await.unwrap();
+        }
+        for file in std::fs::read_dir(&dir).unwrap() {
+            let data = std::fs::read(file.unwrap().path()).unwrap();
+            assert!(!String::from_utf8_lossy(&data).contains("secret-token"));
+        }
+
+        let replay = ReplayBackend::open(&dir).unwrap();
+        assert_eq!(replay.len(), 3);
+
+        let crate_info = replay
+            .send(HttpRequest::get("https://crates.io/api/v1/crates/serde"))
+            .await
+            .unwrap();
+        assert_eq!(crate_info.status, StatusCode::OK);
+        assert_eq!(crate_info.headers[reqwest::header::ETAG], "\"abc\"");
+        assert_eq!(crate_info.text(), r#"{"crate":{"name":"serde"}}"#);
+
+        let docs = replay
+            .send(HttpRequest::get("https://docs.rs/crate/serde/latest/json"))
+            .await
+            .unwrap();
+        assert_eq!(&docs.body[..], [0x28, 0xb5, 0x2f, 0xfd, 0x00]);
+
+        assert!(replay.send(osv_query("serde")).await.is_ok());
+        let err = replay.send(osv_query("tokio")).await.unwrap_err();
+        assert!(err.to_string().contains("no recorded response for POST"));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn not_modified_is_not_recorded() {
+        let dir = temp_dir("cassette-304");
+        let upstream = Arc::new(MemoryBackend::new());
+        upstream.route(
+            Method::GET,
+            "https://crates.io/api/v1/crates/serde",
+            HttpResponse::new(StatusCode::NOT_MODIFIED),
+        );
+
+        let recorder = RecordingBackend::new(upstream, &dir).unwrap();
+        let resp = recorder
+            .send(HttpRequest::get("https://crates.io/api/v1/crates/serde"))
+            .await
+            .unwrap();
+        assert_eq!(resp.status, StatusCode::NOT_MODIFIED);
+        assert!(ReplayBackend::open(&dir).unwrap().is_empty());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -1,7 +1,10 @@
 //! Category-related API endpoints.
 
+use futures::Stream;
+
 use super::CratesIoClient;
 use super::error::Error;
+use super::pagination;
 use super::types::{CategoriesPage, Category, CategorySlug};
 use super::wire::{CategoryResponse, CategorySlugsResponse};
 
@@ -12,6 +15,9 @@ impl CratesIoClient {
         page: Option<u64>,
         per_page: Option<u64>,
     ) -> Result<CategoriesPage, Error> {
+        if let Some(dump) = self.dump() {
+            return Ok(dump.categories(page, per_page));
+        }
         let mut params: Vec<(String, String)> = Vec::new();
         if let Some(page) = page {
             params.push(("page".into(), page.to_string()));
@@ -22,8 +28,27 @@ impl CratesIoClient {
         self.get_json_query("/categories", &params).await
     }
 
+    /// Stream every category, fetching pages as they're read.
+    pub fn categories_stream(&self) -> impl Stream<Item = Result<Category, Error>> {
+        let params = vec![("per_page".to_string(), pagination::MAX_PER_PAGE.to_string())];
+        pagination::items(pagination::pages(params, move |params| async move {
+            if let Some(dump) = self.dump() {
+                let page = pagination::param(&params, "page");
+                let per_page = pagination::param(&params, "per_page");
+                return Ok(dump.categories(page, per_page));
+            }
+            self.get_json_query::<CategoriesPage>("/categories", &params)
+                .await
+        }))
+    }
+
     /// Get a single category by slug.
     pub async fn category(&self, slug: &str) -> Result<Category, Error> {
+        if let Some(dump) = self.dump() {
+            return dump
+                .category(slug)
+                .ok_or_else(|| Error::NotFound(format!("/categories/{slug}")));
+        }
         let resp: CategoryResponse = self.get_json(&format!("/categories/{slug}")).await?;
         Ok(resp.category)
     }
@@ -1,5 +1,6 @@
 //! Changelog fetching from GitHub repositories.
 
+use super::http::Method;
 use super::{CratesIoClient, Error};
 
 /// The result of a changelog fetch attempt.
@@ -53,12 +54,11 @@ impl CratesIoClient {
                 "{}/{}/{}/HEAD/{}",
                 self.github_raw_base_url, owner, repo, filename
             );
-            let resp = self.http.get(&url).send().await?;
-            if resp.status().is_success() {
-                let content = resp.text().await?;
+            let resp = self.http.send(self.request_url(Method::GET, &url)).await?;
+            if resp.status.is_success() {
                 return Ok(ChangelogResult::Found {
                     filename: filename.to_string(),
-                    content,
+                    content: resp.text(),
                 });
             }
         }
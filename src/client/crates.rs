@@ -2,11 +2,14 @@
 
 use std::collections::HashMap;
 
+use futures::Stream;
+
 use super::CratesIoClient;
 use super::error::Error;
+use super::pagination;
 use super::query::CratesQuery;
 use super::types::{
-    CrateDownloads, CrateResponse, CrateSettings, CratesPage, FollowingResponse, OkResponse,
+    Crate, CrateDownloads, CrateResponse, CrateSettings, CratesPage, FollowingResponse, OkResponse,
     ReverseDependencies, ReverseDependency, Summary,
 };
 use super::wire::{ReverseDependenciesRaw, UpdateCrateRequest};
@@ -20,24 +23,38 @@ impl CratesIoClient {
 
     /// Search for crates.
     pub async fn crates(&self, query: CratesQuery) -> Result<CratesPage, Error> {
-        let mut params: Vec<(String, String)> = Vec::new();
-        if let Some(search) = query.search {
-            params.push(("q".into(), search));
-        }
-        if let Some(sort) = query.sort {
-            params.push(("sort".into(), sort.as_str().into()));
-        }
-        if let Some(page) = query.page {
-            params.push(("page".into(), page.to_string()));
-        }
-        if let Some(per_page) = query.per_page {
-            params.push(("per_page".into(), per_page.to_string()));
+        if let Some(dump) = self.dump() {
+            return Ok(dump.search(&query));
         }
-        self.get_json_query("/crates", &params).await
+        self.get_json_query("/crates", &query.params()).await
+    }
+
+    /// Stream every crate matching a search, fetching pages as they're read.
+    ///
+    /// The query's `page` and `per_page` set where the stream starts and how
+    /// many crates each request fetches.
+    pub fn crates_stream(&self, query: CratesQuery) -> impl Stream<Item = Result<Crate, Error>> {
+        pagination::items(pagination::pages(query.params(), move |params| {
+            let query = CratesQuery {
+                page: pagination::param(&params, "page"),
+                ..query.clone()
+            };
+            async move {
+                if let Some(dump) = self.dump() {
+                    return Ok(dump.search(&query));
+                }
+                self.get_json_query("/crates", &params).await
+            }
+        }))
     }
 
     /// Get detailed information about a crate.
     pub async fn get_crate(&self, name: &str) -> Result<CrateResponse, Error> {
+        if let Some(dump) = self.dump() {
+            return dump
+                .get_crate(name)
+                .ok_or_else(|| Error::NotFound(format!("/crates/{name}")));
+        }
         self.get_json(&format!("/crates/{name}")).await
     }
 
@@ -51,9 +68,50 @@ impl CratesIoClient {
         &self,
         name: &str,
     ) -> Result<ReverseDependencies, Error> {
-        let raw: ReverseDependenciesRaw = self
-            .get_json(&format!("/crates/{name}/reverse_dependencies"))
-            .await?;
+        self.reverse_dependencies_page(name, Vec::new()).await
+    }
+
+    /// Stream every page of a crate's reverse dependencies, each carrying the
+    /// overall total in its `meta`.
+    pub fn reverse_dependencies_pages(
+        &self,
+        name: &str,
+    ) -> impl Stream<Item = Result<ReverseDependencies, Error>> {
+        let params = vec![("per_page".to_string(), pagination::MAX_PER_PAGE.to_string())];
+        pagination::pages(params, move |params| {
+            self.reverse_dependencies_page(name, params)
+        })
+    }
+
+    /// Stream every reverse dependency of a crate, fetching pages as they're
+    /// read.
+    pub fn reverse_dependencies_stream(
+        &self,
+        name: &str,
+    ) -> impl Stream<Item = Result<ReverseDependency, Error>> {
+        pagination::items(self.reverse_dependencies_pages(name))
+    }
+
+    /// One page of reverse dependencies, with their dependent versions joined
+    /// in.
+    async fn reverse_dependencies_page(
+        &self,
+        name: &str,
+        params: Vec<(String, String)>,
+    ) -> Result<ReverseDependencies, Error> {
+        let path = format!("/crates/{name}/reverse_dependencies");
+        if let Some(dump) = self.dump() {
+            let page = pagination::param(&params, "page");
+            let per_page = pagination::param(&params, "per_page");
+            return dump
+                .crate_reverse_dependencies(name, page, per_page)
+                .ok_or(Error::NotFound(path));
+        }
+        let raw: ReverseDependenciesRaw = if params.is_empty() {
+            self.get_json(&path).await?
+        } else {
+            self.get_json_query(&path, &params).await?
+        };
 
         // Build a lookup from version ID to (crate_name, version_num)
         let version_map: HashMap<u64, (String, String)> = raw
@@ -0,0 +1,587 @@
+//! Cargo credential and registry configuration discovery.
+//!
+//! Finds registry tokens the way cargo does, so the client can authenticate
+//! with whatever `cargo login` or a credential provider already set up. Each
+//! registry's credential providers are tried in turn, highest precedence
+//! first:
+//!
+//! - `credential-provider` for the registry (`[registry]` for crates.io,
+//!   `[registries.<name>]` otherwise, or the matching
+//!   `CARGO_REGISTRY_CREDENTIAL_PROVIDER` / `CARGO_REGISTRIES_<NAME>_CREDENTIAL_PROVIDER`
+//!   variable), else
+//! - `registry.global-credential-providers`, last entry first, else
+//! - `cargo:token`: `CARGO_REGISTRY_TOKEN` or `CARGO_REGISTRIES_<NAME>_TOKEN`,
+//!   then the token in `$CARGO_HOME/credentials.toml`, then a (deprecated)
+//!   token in `$CARGO_HOME/config.toml`.
+//!
+//! `cargo:token-from-stdout <command>` runs the command and reads the token
+//! from its output, and external providers are spoken to over cargo's
+//! credential-provider protocol. Providers may be `[credential-alias]`
+//! names. The platform keychain built-ins (`cargo:libsecret`,
+//! `cargo:macos-keychain`, `cargo:wincred`) and `cargo:paseto` are skipped.
+//!
+//! Only `$CARGO_HOME` is read, not the `.cargo/config.toml` files of
+//! project directories.
+
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+
+/// The index URL cargo reports to credential providers for crates.io.
+const CRATES_IO_INDEX: &str = "https://github.com/rust-lang/crates.io-index";
+
+/// Cargo's name for crates.io.
+const CRATES_IO: &str = "crates-io";
+
+/// Errors returned when reading cargo configuration or running a credential
+/// provider.
+#[derive(Debug, thiserror::Error)]
+pub enum CredentialError {
+    /// A configuration file exists but could not be read.
+    #[error("failed to read {}: {source}", path.display())]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
+    /// A configuration file is not valid TOML of the expected shape.
+    #[error("invalid {}: {source}", path.display())]
+    Toml {
+        path: PathBuf,
+        #[source]
+        source: toml::de::Error,
+    },
+
+    /// A credential provider could not be run or reported an error.
+    #[error("credential provider `{provider}` failed: {message}")]
+    Provider { provider: String, message: String },
+}
+
+/// A provider as written in configuration: a whitespace-separated string or
+/// a list of words.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+enum ProviderSpec {
+    Words(String),
+    List(Vec<String>),
+}
+
+impl ProviderSpec {
+    fn words(&self) -> Vec<String> {
+        match self {
+            Self::Words(s) => s.split_whitespace().map(str::to_string).collect(),
+            Self::List(words) => words.clone(),
+        }
+    }
+}
+
+/// The parts of `config.toml` and `credentials.toml` used here; the two
+/// files share the `[registry]` / `[registries.<name>]` layout.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct CargoFile {
+    #[serde(default)]
+    registry: RegistryTable,
+    #[serde(default)]
+    registries: HashMap<String, RegistryTable>,
+    #[serde(default)]
+    credential_alias: HashMap<String, ProviderSpec>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct RegistryTable {
+    index: Option<String>,
+    token: Option<String>,
+    credential_provider: Option<ProviderSpec>,
+    #[serde(default)]
+    global_credential_providers: Vec<String>,
+}
+
+/// Cargo's registry configuration and credentials, as read from
+/// `$CARGO_HOME` and the environment.
+#[derive(Debug)]
+pub struct CargoCredentials {
+    cargo_home: PathBuf,
+    env: HashMap<String, String>,
+    config: CargoFile,
+    credentials: CargoFile,
+}
+
+impl CargoCredentials {
+    /// Read cargo's configuration from `$CARGO_HOME` (by default `~/.cargo`)
+    /// and the process environment.
+    pub fn load() -> Result<Self, CredentialError> {
+        let env: HashMap<String, String> = std::env::vars().collect();
+        let cargo_home = env
+            .get("CARGO_HOME")
+            .map(PathBuf::from)
+            .or_else(|| std::env::home_dir().map(|home| home.join(".cargo")))
+            .unwrap_or_else(|| PathBuf::from(".cargo"));
+        Self::load_from(&cargo_home, env)
+    }
+
+    /// Read cargo's configuration from `cargo_home`, with `env` standing in
+    /// for the process environment.
+    pub fn load_from(
+        cargo_home: &Path,
+        env: HashMap<String, String>,
+    ) -> Result<Self, CredentialError> {
+        Ok(Self {
+            cargo_home: cargo_home.to_path_buf(),
+            config: read_cargo_file(cargo_home, "config")?,
+            credentials: read_cargo_file(cargo_home, "credentials")?,
+            env,
+        })
+    }
+
+    /// The directory configuration was read from.
+    pub fn cargo_home(&self) -> &Path {
+        &self.cargo_home
+    }
+
+    /// The index URL configured for registry `name`, from
+    /// `CARGO_REGISTRIES_<NAME>_INDEX` or `[registries.<name>] index`.
+    pub fn registry_index(&self, name: &str) -> Option<String> {
+        self.registry_var(name, "INDEX")
+            .or_else(|| self.config.registries.get(name)?.index.clone())
+    }
+
+    /// The token cargo would use for crates.io, if any.
+    pub fn crates_io_token(&self) -> Result<Option<String>, CredentialError> {
+        self.token(None, CRATES_IO_INDEX)
+    }
+
+    /// The token cargo would use for the alternative registry `name` at
+    /// `index_url`, if any.
+    pub fn registry_token(
+        &self,
+        name: &str,
+        index_url: &str,
+    ) -> Result<Option<String>, CredentialError> {
+        self.token(Some(name), index_url)
+    }
+
+    fn token(
+        &self,
+        name: Option<&str>,
+        index_url: &str,
+    ) -> Result<Option<String>, CredentialError> {
+        for provider in self.providers(name) {
+            let token = match provider.split_first() {
+                None => None,
+                Some((kind, _)) if kind == "cargo:token" => self.stored_token(name),
+                Some((kind, command)) if kind == "cargo:token-from-stdout" => {
+                    Some(token_from_stdout(command, name, index_url)?)
+                }
+                Some((kind, _)) if kind.starts_with("cargo:") => {
+                    tracing::debug!(provider = %kind, "Skipping unsupported credential provider");
+                    None
+                }
+                Some((path, args)) => plugin_token(path, args, name, index_url)?,
+            };
+            if token.is_some() {
+                return Ok(token);
+            }
+        }
+        Ok(None)
+    }
+
+    /// The providers to try for a registry, highest precedence first.
+    fn providers(&self, name: Option<&str>) -> Vec<Vec<String>> {
+        let specific = match name {
+            None => self
+                .env
+                .get("CARGO_REGISTRY_CREDENTIAL_PROVIDER")
+                .map(|v| ProviderSpec::Words(v.clone()))
+                .or_else(|| self.config.registry.credential_provider.clone()),
+            Some(name) => self
+                .registry_var(name, "CREDENTIAL_PROVIDER")
+                .map(ProviderSpec::Words)
+                .or_else(|| {
+                    self.config
+                        .registries
+                        .get(name)?
+                        .credential_provider
+                        .clone()
+                }),
+        };
+        if let Some(spec) = specific {
+            return vec![self.resolve_alias(spec.words())];
+        }
+
+        let global = &self.config.registry.global_credential_providers;
+        if global.is_empty() {
+            return vec![vec!["cargo:token".to_string()]];
+        }
+        global
+            .iter()
+            .rev()
+            .map(|p| self.resolve_alias(ProviderSpec::Words(p.clone()).words()))
+            .collect()
+    }
+
+    /// Expand a leading `[credential-alias]` name, keeping any extra words.
+    fn resolve_alias(&self, words: Vec<String>) -> Vec<String> {
+        let Some(alias) = words
+            .first()
+            .and_then(|first| self.config.credential_alias.get(first))
+        else {
+            return words;
+        };
+        let mut expanded = alias.words();
+        expanded.extend_from_slice(&words[1..]);
+        expanded
+    }
+
+    /// The `cargo:token` provider: environment, then `credentials.toml`,
+    /// then `config.toml`.
+    fn stored_token(&self, name: Option<&str>) -> Option<String> {
+        let from_file = |file: &CargoFile| match name {
+            None => file.registry.token.clone(),
+            Some(name) => file.registries.get(name)?.token.clone(),
+        };
+        let from_env = match name {
+            None => self.env.get("CARGO_REGISTRY_TOKEN").cloned(),
+            Some(name) => self.registry_var(name, "TOKEN"),
+        };
+        from_env
+            .or_else(|| from_file(&self.credentials))
+            .or_else(|| from_file(&self.config))
+            .filter(|token| !token.is_empty())
+    }
+
+    /// `CARGO_REGISTRIES_<NAME>_<suffix>`, with the name upper-cased and
+    /// dashes replaced as cargo does.
+    fn registry_var(&self, name: &str, suffix: &str) -> Option<String> {
+        let key = format!(
+            "CARGO_REGISTRIES_{}_{suffix}",
+            name.to_uppercase().replace('-', "_")
+        );
+        self.env.get(&key).cloned()
+    }
+}
+
+/// Read `<stem>.toml` from `cargo_home`, or the extension-less legacy
+/// `<stem>`, which cargo prefers when both exist. A missing file is empty.
+fn read_cargo_file<T: DeserializeOwned + Default>(
+    cargo_home: &Path,
+    stem: &str,
+) -> Result<T, CredentialError> {
+    for path in [
+        cargo_home.join(stem),
+        cargo_home.join(format!("{stem}.toml")),
+    ] {
+        let text = match std::fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(source) => return Err(CredentialError::Io { path, source }),
+        };
+        return toml::from_str(&text).map_err(|source| CredentialError::Toml { path, source });
+    }
+    Ok(T::default())
+}
+
+/// `cargo:token-from-stdout`: run `command` and read the token from the
+/// first line of its output.
+fn token_from_stdout(
+    command: &[String],
+    name: Option<&str>,
+    index_url: &str,
+) -> Result<String, CredentialError> {
+    let fail = |message: String| CredentialError::Provider {
+        provider: "cargo:token-from-stdout".to_string(),
+        message,
+    };
+    let (program, args) = command
+        .split_first()
+        .ok_or_else(|| fail("no command given".to_string()))?;
+
+    let mut cmd = Command::new(program);
+    cmd.args(args)
+        .env("CARGO_REGISTRY_INDEX_URL", index_url)
+        .stdin(Stdio::null());
+    if let Some(name) = name {
+        cmd.env("CARGO_REGISTRY_NAME_OPT", name);
+    }
+    let output = cmd
+        .output()
+        .map_err(|e| fail(format!("failed to run `{program}`: {e}")))?;
+    if !output.status.success() {
+        return Err(fail(format!(
+            "`{program}` exited with {}: {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    match stdout.lines().next().map(str::trim) {
+        Some(token) if !token.is_empty() => Ok(token.to_string()),
+        _ => Err(fail(format!("`{program}` printed no token"))),
+    }
+}
+
+/// A credential provider's reply to a `get` request.
+#[derive(Debug, Deserialize)]
+enum ProviderReply {
+    Ok { token: Option<String> },
+    Err(ProviderFailure),
+}
+
+#[derive(Debug, Deserialize)]
+struct ProviderFailure {
+    kind: String,
+    #[serde(default)]
+    message: Option<String>,
+}
+
+/// Ask an external provider for a token over cargo's credential-provider
+/// protocol (version 1): a JSON hello, one JSON request, one JSON reply.
+/// Providers that don't know the registry answer `not-found`.
+fn plugin_token(
+    path: &str,
+    args: &[String],
+    name: Option<&str>,
+    index_url: &str,
+) -> Result<Option<String>, CredentialError> {
+    let fail = |message: String| CredentialError::Provider {
+        provider: path.to_string(),
+        message,
+    };
+
+    let mut child = Command::new(path)
+        .arg("--cargo-plugin")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::inherit())
+        .spawn()
+        .map_err(|e| fail(format!("failed to start: {e}")))?;
+    let mut stdin = child.stdin.take().expect("stdin is piped");
+    let mut stdout = BufReader::new(child.stdout.take().expect("stdout is piped"));
+    let mut read_line = || {
+        let mut line = String::new();
+        match stdout.read_line(&mut line) {
+            Ok(0) => Err(fail("exited before replying".to_string())),
+            Ok(_) => Ok(line),
+            Err(e) => Err(fail(e.to_string())),
+        }
+    };
+
+    #[derive(Deserialize)]
+    struct Hello {
+        v: Vec<u32>,
+    }
+    let hello: Hello =
+        serde_json::from_str(&read_line()?).map_err(|e| fail(format!("invalid hello: {e}")))?;
+    if !hello.v.contains(&1) {
+        return Err(fail(format!("unsupported protocol versions {:?}", hello.v)));
+    }
+
+    let request = serde_json::json!({
+        "v": 1,
+        "registry": {
+            "index-url": index_url,
+            "name": name.unwrap_or(CRATES_IO),
+        },
+        "kind": "get",
+        "operation": "read",
+        "args": args,
+    });
+    writeln!(stdin, "{request}").map_err(|e| fail(e.to_string()))?;
+    let reply: ProviderReply =
+        serde_json::from_str(&read_line()?).map_err(|e| fail(format!("invalid reply: {e}")))?;
+    drop(stdin);
+    let _ = child.wait();
+
+    match reply {
+        ProviderReply::Ok { token } => Ok(token.filter(|t| !t.is_empty())),
+        ProviderReply::Err(failure)
+            if failure.kind == "not-found" || failure.kind == "url-not-supported" =>
+        {
+            Ok(None)
+        }
+        ProviderReply::Err(failure) => Err(fail(failure.message.unwrap_or(failure.kind))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::disk_cache::tests::temp_dir;
+    use super::*;
+
+    fn env(vars: &[(&str, &str)]) -> HashMap<String, String> {
+        vars.iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    /// A cargo home holding `config.toml` and `credentials.toml`.
+    fn cargo_home(name: &str, config: &str, credentials: &str) -> PathBuf {
+        let dir = temp_dir(name);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("config.toml"), config).unwrap();
+        std::fs::write(dir.join("credentials.toml"), credentials).unwrap();
+        dir
+    }
+
+    #[test]
+    fn environment_overrides_credentials_file() {
+        let home = cargo_home(
+            "credentials-env",
+            "[registries.my-registry]\nindex = \"sparse+https://cargo.example.com/index/\"\n",
+            "[registry]\ntoken = \"file-token\"\n\n[registries.my-registry]\ntoken = \"company-file\"\n",
+        );
+
+        let creds = CargoCredentials::load_from(&home, HashMap::new()).unwrap();
+        assert_eq!(
+            creds.crates_io_token().unwrap().as_deref(),
+            Some("file-token")
+        );
+        assert_eq!(
+            creds
+                .registry_token("my-registry", "https://x")
+                .unwrap()
+                .as_deref(),
+            Some("company-file")
+        );
+        assert_eq!(
+            creds.registry_index("my-registry").as_deref(),
+            Some("sparse+https://cargo.example.com/index/")
+        );
+        assert_eq!(creds.registry_token("other", "https://x").unwrap(), None);
+
+        let creds = CargoCredentials::load_from(
+            &home,
+            env(&[
+                ("CARGO_REGISTRY_TOKEN", "env-token"),
+                ("CARGO_REGISTRIES_MY_REGISTRY_TOKEN", "company-env"),
+            ]),
+        )
+        .unwrap();
+        assert_eq!(
+            creds.crates_io_token().unwrap().as_deref(),
+            Some("env-token")
+        );
+        assert_eq!(
+            creds
+                .registry_token("my-registry", "https://x")
+                .unwrap()
+                .as_deref(),
+            Some("company-env")
+        );
+
+        std::fs::remove_dir_all(&home).unwrap();
+    }
+
+    #[test]
+    fn missing_cargo_home_has_no_tokens() {
+        let creds =
+            CargoCredentials::load_from(&temp_dir("credentials-none"), HashMap::new()).unwrap();
+        assert_eq!(creds.crates_io_token().unwrap(), None);
+        assert_eq!(creds.registry_index("company"), None);
+    }
+
+    #[test]
+    fn invalid_credentials_file_is_an_error() {
+        let home = cargo_home("credentials-invalid", "", "[registry\n");
+        let err = CargoCredentials::load_from(&home, HashMap::new()).unwrap_err();
+        assert!(matches!(err, CredentialError::Toml { .. }), "{err:?}");
+        std::fs::remove_dir_all(&home).unwrap();
+    }
+
+    #[test]
+    fn unsupported_providers_are_skipped() {
+        let home = cargo_home(
+            "credentials-skip",
+            "[registry]\nglobal-credential-providers = [\"cargo:token\", \"cargo:libsecret\"]\n",
+            "[registry]\ntoken = \"file-token\"\n",
+        );
+        let creds = CargoCredentials::load_from(&home, HashMap::new()).unwrap();
+        assert_eq!(
+            creds.providers(None),
+            [
+                vec!["cargo:libsecret".to_string()],
+                vec!["cargo:token".to_string()]
+            ]
+        );
+        assert_eq!(
+            creds.crates_io_token().unwrap().as_deref(),
+            Some("file-token")
+        );
+        std::fs::remove_dir_all(&home).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn token_from_stdout_runs_the_command() {
+        let home = cargo_home(
+            "credentials-stdout",
+            "[credential-alias]\nprint = [\"cargo:token-from-stdout\", \"sh\", \"-c\"]\n\n\
+             [registries.company]\n\
+             credential-provider = [\"print\", \"echo $CARGO_REGISTRY_NAME_OPT-token\"]\n",
+            "",
+        );
+        let creds = CargoCredentials::load_from(&home, HashMap::new()).unwrap();
+        assert_eq!(
+            creds
+                .registry_token("company", "https://x")
+                .unwrap()
+                .as_deref(),
+            Some("company-token")
+        );
+        std::fs::remove_dir_all(&home).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn external_provider_speaks_the_protocol() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let home = cargo_home("credentials-plugin", "", "");
+        let provider = home.join("cargo-credential-test");
+        std::fs::write(
+            &provider,
+            "#!/bin/sh\n\
+             echo '{\"v\":[1]}'\n\
+             read request\n\
+             case \"$request\" in\n\
+             *'\"name\":\"crates-io\"'*) echo '{\"Ok\":{\"kind\":\"get\",\"token\":\"plugin-token\",\"cache\":\"session\",\"operation_independent\":true}}' ;;\n\
+             *) echo '{\"Err\":{\"kind\":\"not-found\"}}' ;;\n\
+             esac\n",
+        )
+        .unwrap();
+        std::fs::set_permissions(&provider, std::fs::Permissions::from_mode(0o755)).unwrap();
+        std::fs::write(
+            home.join("config.toml"),
+            format!(
+                "[registry]\nglobal-credential-providers = [\"cargo:token\", \"{}\"]\n",
+                provider.display()
+            ),
+        )
+        .unwrap();
+
+        let creds = CargoCredentials::load_from(
+            &home,
+            env(&[("CARGO_REGISTRIES_COMPANY_TOKEN", "env-token")]),
+        )
+        .unwrap();
+        assert_eq!(
+            creds.crates_io_token().unwrap().as_deref(),
+            Some("plugin-token")
+        );
+        // Not found by the plugin, so the next provider answers
+        assert_eq!(
+            creds
+                .registry_token("company", "https://x")
+                .unwrap()
+                .as_deref(),
+            Some("env-token")
+        );
+        std::fs::remove_dir_all(&home).unwrap();
+    }
+}
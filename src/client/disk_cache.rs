@@ -0,0 +1,457 @@
+//! Persistent on-disk cache for HTTP response bodies.
+//!
+//! Survives restarts, so a stdio server that is started per session does not
+//! re-download multi-megabyte rustdoc JSON or re-query crates.io for data it
+//! already has. Entries are keyed by request URL and grouped into
+//! [`ResourceClass`]es: content that can never change once published is kept
+//! until the size budget evicts it, everything else expires after its class's
+//! TTL.
+//!
+//! The cache is best-effort: I/O errors are logged and treated as misses.
+
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime};
+
+use serde::{Deserialize, Serialize};
+
+use super::http::HttpRequest;
+
+/// After exceeding the size budget, evict down to this fraction of it so
+/// every write near the limit doesn't trigger another directory scan.
+const EVICT_TO: f64 = 0.9;
+
+/// How long a cached response stays valid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResourceClass {
+    /// Content fixed at publish time, such as one version's dependency list or
+    /// rustdoc JSON. Never expires.
+    Immutable,
+    /// Crate, version, owner and listing data that changes over time.
+    Metadata,
+    /// Rustdoc JSON for `latest` or another non-exact version, which moves
+    /// when a new release is documented.
+    Docs,
+}
+
+/// Response validators for conditional requests.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Validators {
+    /// The response's `ETag`, sent back as `If-None-Match`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub etag: Option<String>,
+    /// The response's `Last-Modified`, sent back as `If-Modified-Since`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_modified: Option<String>,
+}
+
+impl Validators {
+    /// Read `ETag` and `Last-Modified` from response headers.
+    pub fn from_headers(headers: &reqwest::header::HeaderMap) -> Self {
+        let header = |name| {
+            headers
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_string)
+        };
+        Self {
+            etag: header(reqwest::header::ETAG),
+            last_modified: header(reqwest::header::LAST_MODIFIED),
+        }
+    }
+
+    /// Whether there is anything to revalidate with.
+    pub fn is_empty(&self) -> bool {
+        self.etag.is_none() && self.last_modified.is_none()
+    }
+
+    /// Make `request` conditional on the cached copy being out of date.
+    pub fn apply(&self, mut request: HttpRequest) -> HttpRequest {
+        if let Some(etag) = &self.etag {
+            request = request.header(reqwest::header::IF_NONE_MATCH, etag);
+        }
+        if let Some(last_modified) = &self.last_modified {
+            request = request.header(reqwest::header::IF_MODIFIED_SINCE, last_modified);
+        }
+        request
+    }
+}
+
+/// A cached response, possibly past its TTL.
+#[derive(Debug)]
+pub struct CachedResponse {
+    pub body: Vec<u8>,
+    pub validators: Validators,
+    /// Whether the entry is within its class's TTL. Stale entries can still
+    /// be revalidated with their [`Validators`].
+    pub fresh: bool,
+}
+
+/// First line of each entry file.
+#[derive(Serialize, Deserialize)]
+struct EntryHeader {
+    key: String,
+    #[serde(flatten)]
+    validators: Validators,
+}
+
+/// A directory of cached response bodies with a total size budget.
+#[derive(Debug)]
+pub struct DiskCache {
+    dir: PathBuf,
+    max_bytes: u64,
+    metadata_ttl: Duration,
+    docs_ttl: Duration,
+    /// Bytes currently on disk (approximate between scans).
+    used: AtomicU64,
+}
+
+impl DiskCache {
+    /// Open (creating if needed) a cache in `dir` holding at most `max_bytes`.
+    ///
+    /// Metadata expires after 5 minutes and non-exact rustdoc JSON after an
+    /// hour unless changed with [`with_metadata_ttl`](Self::with_metadata_ttl)
+    /// and [`with_docs_ttl`](Self::with_docs_ttl).
+    pub fn open(dir: impl Into<PathBuf>, max_bytes: u64) -> io::Result<Self> {
+        let dir = dir.into();
+        std::fs::create_dir_all(&dir)?;
+        let used = entries(&dir)?.iter().map(|e| e.len).sum();
+        Ok(Self {
+            dir,
+            max_bytes,
+            metadata_ttl: Duration::from_secs(300),
+            docs_ttl: Duration::from_secs(3600),
+            used: AtomicU64::new(used),
+        })
+    }
+
+    /// Set how long [`ResourceClass::Metadata`] entries stay valid.
+    pub fn with_metadata_ttl(mut self, ttl: Duration) -> Self {
+        self.metadata_ttl = ttl;
+        self
+    }
+
+    /// Set how long [`ResourceClass::Docs`] entries stay valid.
+    pub fn with_docs_ttl(mut self, ttl: Duration) -> Self {
+        self.docs_ttl = ttl;
+        self
+    }
+
+    /// The directory entries are stored in.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn ttl(&self, class: ResourceClass) -> Option<Duration> {
+        match class {
+            ResourceClass::Immutable => None,
+            ResourceClass::Metadata => Some(self.metadata_ttl),
+            ResourceClass::Docs => Some(self.docs_ttl),
+        }
+    }
+
+    /// Entry files are named by a stable hash of the key, spread over 256
+    /// subdirectories.
+    fn path_for(&self, key: &str) -> PathBuf {
+        let hash = fnv1a(key);
+        self.dir
+            .join(format!("{:02x}", hash >> 56))
+            .join(format!("{hash:016x}"))
+    }
+
+    /// The cached body for `key`, if present and not expired for `class`.
+    pub async fn get(&self, key: &str, class: ResourceClass) -> Option<Vec<u8>> {
+        self.lookup(key, class)
+            .await
+            .filter(|cached| cached.fresh)
+            .map(|cached| cached.body)
+    }
+
+    /// The cached response for `key`, fresh or stale. Stale entries without
+    /// validators can't be revalidated, so they are deleted instead.
+    pub async fn lookup(&self, key: &str, class: ResourceClass) -> Option<CachedResponse> {
+        let path = self.path_for(key);
+        let data = tokio::fs::read(&path).await.ok()?;
+        let (header, body) = split_entry(&data)?;
+        if header.key != key {
+            // Hash collision: treat as a miss, the next put replaces it
+            return None;
+        }
+        let fresh = match self.ttl(class) {
+            None => true,
+            Some(ttl) => {
+                let written = tokio::fs::metadata(&path).await.ok()?.modified().ok()?;
+                written.elapsed().unwrap_or_default() <= ttl
+            }
+        };
+        if !fresh && header.validators.is_empty() {
+            self.remove(&path).await;
+            return None;
+        }
+        Some(CachedResponse {
+            body: body.to_vec(),
+            validators: header.validators,
+            fresh,
+        })
+    }
+
+    /// Restart the TTL of `key`'s entry, after the server confirmed it is
+    /// still current.
+    pub async fn refresh(&self, key: &str) {
+        let path = self.path_for(key);
+        let touched = tokio::task::spawn_blocking(move || {
+            std::fs::File::options()
+                .write(true)
+                .open(&path)?
+                .set_modified(SystemTime::now())
+        })
+        .await;
+        if let Ok(Err(e)) = touched {
+            tracing::debug!(key, error = %e, "disk cache refresh failed");
+        }
+    }
+
+    /// Store `body` under `key`, evicting the oldest entries if the cache
+    /// grows past its size budget. Bodies larger than the whole budget are
+    /// not stored.
+    pub async fn put(&self, key: &str, body: &[u8]) {
+        self.put_validated(key, body, &Validators::default()).await;
+    }
+
+    /// Like [`put`](Self::put), keeping the response's validators so a stale
+    /// entry can be revalidated with a conditional request.
+    pub async fn put_validated(&self, key: &str, body: &[u8], validators: &Validators) {
+        let Ok(header) = serde_json::to_vec(&EntryHeader {
+            key: key.to_string(),
+            validators: validators.clone(),
+        }) else {
+            return;
+        };
+        let len = (header.len() + 1 + body.len()) as u64;
+        if len > self.max_bytes {
+            return;
+        }
+
+        let path = self.path_for(key);
+        let previous = tokio::fs::metadata(&path).await.map_or(0, |m| m.len());
+        if let Err(e) = write_entry(&path, &header, body).await {
+            tracing::debug!(path = %path.display(), error = %e, "disk cache write failed");
+            return;
+        }
+        let used = (self.used.load(Ordering::Relaxed) + len).saturating_sub(previous);
+        self.used.store(used, Ordering::Relaxed);
+
+        if used > self.max_bytes {
+            self.evict().await;
+        }
+    }
+
+    async fn remove(&self, path: &Path) {
+        if let Ok(meta) = tokio::fs::metadata(path).await
+            && tokio::fs::remove_file(path).await.is_ok()
+        {
+            self.used.fetch_sub(meta.len(), Ordering::Relaxed);
+        }
+    }
+
+    /// Delete the least recently written entries until the cache is back
+    /// under [`EVICT_TO`] of its budget.
+    async fn evict(&self) {
+        let dir = self.dir.clone();
+        let Ok(Ok(mut entries)) = tokio::task::spawn_blocking(move || entries(&dir)).await else {
+            return;
+        };
+        entries.sort_by_key(|e| e.modified);
+
+        let mut used: u64 = entries.iter().map(|e| e.len).sum();
+        let target = (self.max_bytes as f64 * EVICT_TO) as u64;
+        for entry in entries {
+            if used <= target {
+                break;
+            }
+            if tokio::fs::remove_file(&entry.path).await.is_ok() {
+                used -= entry.len;
+            }
+        }
+        self.used.store(used, Ordering::Relaxed);
+    }
+}
+
+/// 64-bit FNV-1a, which unlike `DefaultHasher` is stable across Rust
+/// releases, so entry names survive toolchain upgrades.
+pub(crate) fn fnv1a(key: &str) -> u64 {
+    key.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+fn split_entry(data: &[u8]) -> Option<(EntryHeader, &[u8])> {
+    let newline = data.iter().position(|&b| b == b'\n')?;
+    let header = serde_json::from_slice(&data[..newline]).ok()?;
+    Some((header, &data[newline + 1..]))
+}
+
+/// Write via a temporary file and rename, so readers never see a partial
+/// entry.
+async fn write_entry(path: &Path, header: &[u8], body: &[u8]) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    let mut data = Vec::with_capacity(header.len() + 1 + body.len());
+    data.extend_from_slice(header);
+    data.push(b'\n');
+    data.extend_from_slice(body);
+
+    let tmp = path.with_extension(format!("tmp{}", std::process::id()));
+    tokio::fs::write(&tmp, &data).await?;
+    tokio::fs::rename(&tmp, path).await
+}
+
+struct Entry {
+    path: PathBuf,
+    len: u64,
+    modified: SystemTime,
+}
+
+/// Every entry file in the cache directory.
+fn entries(dir: &Path) -> io::Result<Vec<Entry>> {
+    let mut found = Vec::new();
+    for shard in std::fs::read_dir(dir)? {
+        let shard = shard?;
+        if !shard.file_type()?.is_dir() {
+            continue;
+        }
+        for file in std::fs::read_dir(shard.path())? {
+            let file = file?;
+            let meta = file.metadata()?;
+            if meta.is_file() {
+                found.push(Entry {
+                    path: file.path(),
+                    len: meta.len(),
+                    modified: meta.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+                });
+            }
+        }
+    }
+    Ok(found)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// A fresh, empty cache directory under the system temp dir.
+    pub(crate) fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "cratesio-mcp-cache-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[tokio::test]
+    async fn round_trip_survives_reopen() {
+        let dir = temp_dir("round-trip");
+        let cache = DiskCache::open(&dir, 1024 * 1024).unwrap();
+        cache
+            .put("https://crates.io/api/v1/crates/serde", b"{}")
+            .await;
+        assert_eq!(
+            cache
+                .get(
+                    "https://crates.io/api/v1/crates/serde",
+                    ResourceClass::Metadata
+                )
+                .await
+                .as_deref(),
+            Some(&b"{}"[..])
+        );
+        assert!(
+            cache
+                .get(
+                    "https://crates.io/api/v1/crates/tokio",
+                    ResourceClass::Metadata
+                )
+                .await
+                .is_none()
+        );
+
+        let reopened = DiskCache::open(&dir, 1024 * 1024).unwrap();
+        assert!(reopened.used.load(Ordering::Relaxed) > 2);
+        assert!(
+            reopened
+                .get(
+                    "https://crates.io/api/v1/crates/serde",
+                    ResourceClass::Immutable
+                )
+                .await
+                .is_some()
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn mutable_classes_expire() {
+        let dir = temp_dir("expire");
+        let cache = DiskCache::open(&dir, 1024 * 1024)
+            .unwrap()
+            .with_metadata_ttl(Duration::from_millis(1));
+        cache.put("key", b"value").await;
+        tokio::time::sleep(Duration::from_millis(20)).await;
+
+        // Immutable content is still served; metadata has expired
+        assert!(cache.get("key", ResourceClass::Immutable).await.is_some());
+        assert!(cache.get("key", ResourceClass::Metadata).await.is_none());
+        // Without validators the stale entry is dropped
+        assert!(cache.get("key", ResourceClass::Immutable).await.is_none());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn stale_entries_keep_validators() {
+        let dir = temp_dir("validators");
+        let cache = DiskCache::open(&dir, 1024 * 1024)
+            .unwrap()
+            .with_metadata_ttl(Duration::from_millis(1));
+        let validators = Validators {
+            etag: Some("\"abc\"".to_string()),
+            last_modified: None,
+        };
+        cache.put_validated("key", b"value", &validators).await;
+        tokio::time::sleep(Duration::from_millis(20)).await;
+
+        let stale = cache.lookup("key", ResourceClass::Metadata).await.unwrap();
+        assert!(!stale.fresh);
+        assert_eq!(stale.validators, validators);
+        assert_eq!(stale.body, b"value");
+
+        cache.refresh("key").await;
+        let cache = cache.with_metadata_ttl(Duration::from_secs(60));
+        assert!(cache.get("key", ResourceClass::Metadata).await.is_some());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn size_budget_evicts_oldest() {
+        let dir = temp_dir("evict");
+        let cache = DiskCache::open(&dir, 300).unwrap();
+        let body = [b'x'; 100];
+        cache.put("first", &body).await;
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        cache.put("second", &body).await;
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        cache.put("third", &body).await;
+
+        assert!(cache.get("first", ResourceClass::Immutable).await.is_none());
+        assert!(cache.get("third", ResourceClass::Immutable).await.is_some());
+        assert!(cache.used.load(Ordering::Relaxed) <= 300);
+
+        // Larger than the whole budget: not stored
+        cache.put("huge", &[b'x'; 400]).await;
+        assert!(cache.get("huge", ResourceClass::Immutable).await.is_none());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
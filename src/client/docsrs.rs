@@ -3,14 +3,28 @@
 use flate2::read::GzDecoder;
 use rustdoc_types::Crate;
 use std::io::Read;
+use std::sync::Arc;
 use std::time::Duration;
 
+use super::disk_cache::{DiskCache, ResourceClass};
+use super::http::{HttpBackend, HttpError, HttpRequest, ReqwestBackend};
+use super::resilience::{self, CircuitBreaker, RetryPolicy, UpstreamUnavailable};
+use super::single_flight::ShareError;
+
 /// Errors from the docs.rs client.
 #[derive(Debug, thiserror::Error)]
 pub enum DocsRsError {
     /// HTTP transport error.
     #[error("HTTP error: {0}")]
-    Http(#[from] reqwest::Error),
+    Http(#[from] HttpError),
+
+    /// docs.rs answered with an unexpected error status.
+    #[error("docs.rs error ({0})")]
+    Status(u16),
+
+    /// docs.rs keeps failing and its circuit breaker is open.
+    #[error(transparent)]
+    UpstreamUnavailable(#[from] UpstreamUnavailable),
 
     /// Crate or version not found on docs.rs.
     #[error("not found: {name} v{version}")]
@@ -53,6 +67,48 @@ pub enum DocsRsError {
     },
 }
 
+impl ShareError for DocsRsError {
+    fn share(&self) -> Self {
+        match self {
+            Self::Http(err) => Self::Http(err.share()),
+            Self::Status(status) => Self::Status(*status),
+            Self::UpstreamUnavailable(err) => Self::UpstreamUnavailable(err.clone()),
+            Self::NotFound { name, version } => Self::NotFound {
+                name: name.clone(),
+                version: version.clone(),
+            },
+            Self::DocsNotAvailable { name, version } => Self::DocsNotAvailable {
+                name: name.clone(),
+                version: version.clone(),
+            },
+            Self::ResponseTooLarge { name, size, limit } => Self::ResponseTooLarge {
+                name: name.clone(),
+                size: *size,
+                limit: *limit,
+            },
+            Self::Decompress { name, source } => Self::Decompress {
+                name: name.clone(),
+                source: source.share(),
+            },
+            Self::Parse { name, source } => Self::Parse {
+                name: name.clone(),
+                source: source.share(),
+            },
+            Self::FormatMismatch {
+                name,
+                expected,
+                actual,
+                source,
+            } => Self::FormatMismatch {
+                name: name.clone(),
+                expected: *expected,
+                actual: *actual,
+                source: source.share(),
+            },
+        }
+    }
+}
+
 /// Maximum number of bytes read or decompressed from a docs.rs response.
 ///
 /// Caps both the compressed response body and the decompressed rustdoc JSON so
@@ -106,8 +162,12 @@ fn decode_body(name: &str, bytes: &[u8], limit: u64) -> Result<Vec<u8>, DocsRsEr
 
 /// HTTP client for the docs.rs rustdoc JSON API.
 pub struct DocsRsClient {
-    http: reqwest::Client,
+    http: Arc<dyn HttpBackend>,
+    user_agent: String,
     base_url: String,
+    disk_cache: Option<Arc<DiskCache>>,
+    retry: RetryPolicy,
+    breaker: Arc<CircuitBreaker>,
 }
 
 impl DocsRsClient {
@@ -122,24 +182,87 @@ impl DocsRsClient {
         timeout: Duration,
         base_url: &str,
     ) -> Result<Self, DocsRsError> {
-        let http = reqwest::Client::builder()
-            .user_agent(user_agent)
-            .timeout(timeout)
-            .build()?;
         Ok(Self {
-            http,
+            http: Arc::new(ReqwestBackend::new(timeout)?),
+            user_agent: user_agent.to_string(),
             base_url: base_url.trim_end_matches('/').to_string(),
+            disk_cache: None,
+            retry: RetryPolicy::default(),
+            breaker: Arc::new(CircuitBreaker::new("docs.rs")),
         })
     }
 
+    /// Send requests through `backend` instead of the default reqwest client.
+    ///
+    /// The outbound timeout given to the constructor only applies to the
+    /// default backend.
+    pub fn with_backend(mut self, backend: Arc<dyn HttpBackend>) -> Self {
+        self.http = backend;
+        self
+    }
+
+    /// Set how transient failures (429, 5xx) are retried.
+    pub fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Share a circuit breaker with other clients of the same upstream.
+    pub fn with_circuit_breaker(mut self, breaker: Arc<CircuitBreaker>) -> Self {
+        self.breaker = breaker;
+        self
+    }
+
+    /// The breaker guarding docs.rs requests.
+    pub fn circuit_breaker(&self) -> &CircuitBreaker {
+        &self.breaker
+    }
+
+    /// Keep downloaded rustdoc JSON in a persistent cache, shared across
+    /// restarts.
+    pub fn with_disk_cache(mut self, cache: Arc<DiskCache>) -> Self {
+        self.disk_cache = Some(cache);
+        self
+    }
+
     /// Fetch the rustdoc JSON for a crate version.
     ///
     /// The `version` parameter accepts `"latest"` or a specific semver string.
+    /// With a disk cache, an exact version's JSON is kept until evicted and
+    /// other versions for the cache's docs TTL.
     pub async fn fetch_rustdoc(&self, name: &str, version: &str) -> Result<Crate, DocsRsError> {
         let url = format!("{}/crate/{}/{}/json.gz", self.base_url, name, version);
-        let resp = self.http.get(&url).send().await?;
+        let Some(cache) = &self.disk_cache else {
+            let bytes = self.download(&url, name, version).await?;
+            return parse_rustdoc(name, &bytes);
+        };
+
+        let class = if semver::Version::parse(version).is_ok() {
+            ResourceClass::Immutable
+        } else {
+            ResourceClass::Docs
+        };
+        if let Some(bytes) = cache.get(&url, class).await
+            && let Ok(krate) = parse_rustdoc(name, &bytes)
+        {
+            return Ok(krate);
+        }
+        let bytes = self.download(&url, name, version).await?;
+        let krate = parse_rustdoc(name, &bytes)?;
+        cache.put(&url, &bytes).await;
+        Ok(krate)
+    }
 
-        let status = resp.status();
+    /// Download the (usually gzipped) rustdoc JSON body.
+    async fn download(&self, url: &str, name: &str, version: &str) -> Result<Vec<u8>, DocsRsError> {
+        let request = HttpRequest::get(url)
+            .header(reqwest::header::USER_AGENT, &self.user_agent)
+            .max_response_bytes(MAX_RESPONSE_BYTES);
+        let resp = resilience::send::<_, _, DocsRsError>(&self.retry, &self.breaker, url, || {
+            self.http.send(request.clone())
+        })
+        .await?;
+        let status = resp.status;
         if status == reqwest::StatusCode::NOT_FOUND {
             return Err(DocsRsError::NotFound {
                 name: name.to_string(),
@@ -154,14 +277,11 @@ impl DocsRsClient {
             });
         }
         if !status.is_success() {
-            // Map other errors to reqwest error via error_for_status
-            let resp = resp.error_for_status()?;
-            // unreachable but satisfy the compiler
-            return Ok(resp.json().await?);
+            return Err(DocsRsError::Status(status.as_u16()));
         }
 
-        // Reject responses that advertise a size above the limit before
-        // buffering the body, so a huge payload cannot exhaust memory.
+        // A response that advertises a size above the limit isn't buffered
+        // at all; the body itself is checked against the limit when decoded.
         if let Some(len) = resp.content_length()
             && len > MAX_RESPONSE_BYTES
         {
@@ -172,60 +292,63 @@ impl DocsRsClient {
             });
         }
 
-        let bytes = resp.bytes().await?;
-
-        // docs.rs serves rustdoc JSON with Content-Type: application/gzip,
-        // which reqwest does not auto-decompress (it only handles
-        // Content-Encoding: gzip). Decompress manually, enforcing the size
-        // limit on both the compressed body and the decompressed result.
-        let json_bytes = decode_body(name, &bytes, MAX_RESPONSE_BYTES)?;
-
-        // Pre-check format version before full deserialization.
-        let actual_version = serde_json::from_slice::<FormatVersionCheck>(&json_bytes)
-            .ok()
-            .map(|c| c.format_version);
+        Ok(resp.body.to_vec())
+    }
+}
 
-        let expected = rustdoc_types::FORMAT_VERSION;
-        if let Some(actual) = actual_version
-            && actual != expected
-        {
-            let diff = actual.abs_diff(expected);
-            if diff <= 2 {
-                tracing::warn!(
-                    crate_name = name,
-                    expected = expected,
-                    actual = actual,
-                    "rustdoc JSON format version mismatch (close): \
+/// Decode and parse a docs.rs rustdoc JSON body.
+fn parse_rustdoc(name: &str, bytes: &[u8]) -> Result<Crate, DocsRsError> {
+    // docs.rs serves rustdoc JSON with Content-Type: application/gzip,
+    // which reqwest does not auto-decompress (it only handles
+    // Content-Encoding: gzip). Decompress manually, enforcing the size
+    // limit on both the compressed body and the decompressed result.
+    let json_bytes = decode_body(name, bytes, MAX_RESPONSE_BYTES)?;
+
+    // Pre-check format version before full deserialization.
+    let actual_version = serde_json::from_slice::<FormatVersionCheck>(&json_bytes)
+        .ok()
+        .map(|c| c.format_version);
+
+    let expected = rustdoc_types::FORMAT_VERSION;
+    if let Some(actual) = actual_version
+        && actual != expected
+    {
+        let diff = actual.abs_diff(expected);
+        if diff <= 2 {
+            tracing::warn!(
+                crate_name = name,
+                expected = expected,
+                actual = actual,
+                "rustdoc JSON format version mismatch (close): \
                      docs.rs serves v{actual}, we support v{expected}"
-                );
-            } else {
-                tracing::warn!(
-                    crate_name = name,
-                    expected = expected,
-                    actual = actual,
-                    "rustdoc JSON format version mismatch (far): \
+            );
+        } else {
+            tracing::warn!(
+                crate_name = name,
+                expected = expected,
+                actual = actual,
+                "rustdoc JSON format version mismatch (far): \
                      docs.rs serves v{actual}, we support v{expected}"
-                );
-            }
+            );
         }
+    }
 
-        serde_json::from_slice(&json_bytes).map_err(|source| {
-            if let Some(actual) = actual_version
-                && actual != expected
-            {
-                return DocsRsError::FormatMismatch {
-                    name: name.to_string(),
-                    expected,
-                    actual,
-                    source,
-                };
-            }
-            DocsRsError::Parse {
+    serde_json::from_slice(&json_bytes).map_err(|source| {
+        if let Some(actual) = actual_version
+            && actual != expected
+        {
+            return DocsRsError::FormatMismatch {
                 name: name.to_string(),
+                expected,
+                actual,
                 source,
-            }
-        })
-    }
+            };
+        }
+        DocsRsError::Parse {
+            name: name.to_string(),
+            source,
+        }
+    })
 }
 
 #[cfg(test)]
@@ -351,6 +474,33 @@ mod tests {
         assert_eq!(decoded_plain, original);
     }
 
+    #[tokio::test]
+    async fn fetch_rustdoc_uses_disk_cache() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/crate/serde/1.0.0/json.gz"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_bytes(gzip_compress(&synthetic_crate_json()))
+                    .insert_header("content-type", "application/gzip"),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let dir = crate::client::disk_cache::tests::temp_dir("docsrs");
+        for _ in 0..2 {
+            let cache = Arc::new(DiskCache::open(&dir, 1024 * 1024).unwrap());
+            let client =
+                DocsRsClient::with_base_url("test", Duration::from_secs(30), &server.uri())
+                    .unwrap()
+                    .with_disk_cache(cache);
+            let krate = client.fetch_rustdoc("serde", "1.0.0").await.unwrap();
+            assert_eq!(krate.crate_version.as_deref(), Some("1.0.0"));
+        }
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
     #[tokio::test]
     async fn fetch_rustdoc_not_found() {
         let server = MockServer::start().await;
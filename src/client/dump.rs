@@ -0,0 +1,1126 @@
+//! Offline data from the crates.io database dump.
+//!
+//! crates.io publishes its public tables nightly as CSV files in
+//! `db-dump.tar.gz` (see <https://crates.io/data-access>). [`DumpStore`] loads
+//! the ones the read-only tools need -- crates, versions, dependencies,
+//! categories, keywords and owners -- into memory and answers the same queries
+//! as the API client, so a [`CratesIoClient`](super::CratesIoClient) built with
+//! [`with_dump`](super::CratesIoClient::with_dump) makes no HTTP requests for
+//! them.
+//!
+//! The large `version_downloads.csv` is not loaded, so recent download counts
+//! are unavailable offline.
+
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+use flate2::read::GzDecoder;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer};
+
+use super::query::{CratesQuery, Sort};
+use super::types::{
+    CategoriesPage, Category, Crate, CrateResponse, CrateVersion, CratesPage, Dependency, Keyword,
+    KeywordsPage, Meta, ReverseDependencies, ReverseDependency, User, Version,
+};
+
+/// The CSV files a dump is built from. Anything else in the archive is
+/// skipped without being decompressed into memory.
+const TABLES: &[&str] = &[
+    "crates.csv",
+    "crate_downloads.csv",
+    "versions.csv",
+    "dependencies.csv",
+    "categories.csv",
+    "crates_categories.csv",
+    "keywords.csv",
+    "crates_keywords.csv",
+    "crate_owners.csv",
+    "users.csv",
+    "teams.csv",
+];
+
+/// Errors returned when loading a database dump.
+#[derive(Debug, thiserror::Error)]
+pub enum DumpError {
+    /// The archive or directory could not be read.
+    #[error("I/O error reading dump: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// A CSV file could not be parsed.
+    #[error("invalid {file}: {source}")]
+    Csv {
+        file: String,
+        #[source]
+        source: csv::Error,
+    },
+
+    /// A required table is missing from the dump.
+    #[error("dump has no {0}")]
+    Missing(&'static str),
+}
+
+// ── CSV rows ────────────────────────────────────────────────────────────────
+
+#[derive(Deserialize)]
+struct CrateRow {
+    id: u64,
+    name: String,
+    description: Option<String>,
+    created_at: String,
+    updated_at: String,
+    repository: Option<String>,
+    documentation: Option<String>,
+    homepage: Option<String>,
+    /// Only in dumps from before `crate_downloads.csv` was split out.
+    downloads: Option<u64>,
+}
+
+#[derive(Deserialize)]
+struct CrateDownloadsRow {
+    crate_id: u64,
+    downloads: u64,
+}
+
+#[derive(Deserialize)]
+struct VersionRow {
+    id: u64,
+    crate_id: u64,
+    num: String,
+    created_at: String,
+    #[serde(default)]
+    downloads: u64,
+    #[serde(deserialize_with = "pg_bool")]
+    yanked: bool,
+    license: Option<String>,
+    rust_version: Option<String>,
+    /// JSON object of feature name to enabled features.
+    features: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct DependencyRow {
+    /// The crate depended on.
+    crate_id: u64,
+    /// The dependent version.
+    version_id: u64,
+    req: String,
+    #[serde(deserialize_with = "pg_bool")]
+    optional: bool,
+    #[serde(deserialize_with = "pg_bool")]
+    default_features: bool,
+    /// Postgres array literal, e.g. `{derive,std}`.
+    features: String,
+    /// 0 = normal, 1 = build, 2 = dev.
+    kind: u8,
+    target: Option<String>,
+    /// The dependent's name for a renamed dependency.
+    explicit_name: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct CategoryRow {
+    id: u64,
+    category: String,
+    slug: String,
+    description: Option<String>,
+    crates_cnt: u64,
+}
+
+#[derive(Deserialize)]
+struct CrateCategoryRow {
+    crate_id: u64,
+    category_id: u64,
+}
+
+#[derive(Deserialize)]
+struct KeywordRow {
+    id: u64,
+    keyword: String,
+    crates_cnt: u64,
+}
+
+#[derive(Deserialize)]
+struct CrateKeywordRow {
+    crate_id: u64,
+    keyword_id: u64,
+}
+
+#[derive(Deserialize)]
+struct CrateOwnerRow {
+    crate_id: u64,
+    owner_id: u64,
+    /// 0 = user, 1 = team.
+    owner_kind: u8,
+}
+
+#[derive(Deserialize)]
+struct UserRow {
+    id: u64,
+    gh_login: String,
+    name: Option<String>,
+    gh_avatar: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct TeamRow {
+    id: u64,
+    login: String,
+    name: Option<String>,
+    avatar: Option<String>,
+}
+
+/// Postgres dumps booleans as `t` / `f`.
+fn pg_bool<'de, D: Deserializer<'de>>(deserializer: D) -> Result<bool, D::Error> {
+    let value = String::deserialize(deserializer)?;
+    Ok(matches!(value.as_str(), "t" | "true"))
+}
+
+/// Parse a Postgres array literal such as `{derive,"with space"}`.
+fn pg_array(value: &str) -> Vec<String> {
+    let inner = value.trim().trim_start_matches('{').trim_end_matches('}');
+    inner
+        .split(',')
+        .map(|item| item.trim().trim_matches('"').to_string())
+        .filter(|item| !item.is_empty())
+        .collect()
+}
+
+/// Parse a Postgres timestamp (`2015-05-01 12:00:00.123456`, UTC). Falls back
+/// to the Unix epoch for values that don't parse.
+fn parse_timestamp(value: &str) -> DateTime<Utc> {
+    let trimmed = value.trim_end_matches("+00:00").trim_end_matches("+00");
+    NaiveDateTime::parse_from_str(trimmed, "%Y-%m-%d %H:%M:%S%.f")
+        .map(|naive| naive.and_utc())
+        .or_else(|_| DateTime::parse_from_rfc3339(value).map(|dt| dt.with_timezone(&Utc)))
+        .unwrap_or_default()
+}
+
+/// Crate names are unique ignoring case and `-` / `_`.
+fn canonical(name: &str) -> String {
+    name.to_ascii_lowercase().replace('-', "_")
+}
+
+fn each_row<T: DeserializeOwned>(
+    file: &str,
+    reader: impl Read,
+    mut f: impl FnMut(T),
+) -> Result<(), DumpError> {
+    for row in csv::Reader::from_reader(reader).into_deserialize() {
+        f(row.map_err(|source| DumpError::Csv {
+            file: file.to_string(),
+            source,
+        })?);
+    }
+    Ok(())
+}
+
+fn read_rows<T: DeserializeOwned>(file: &str, reader: impl Read) -> Result<Vec<T>, DumpError> {
+    let mut rows = Vec::new();
+    each_row(file, reader, |row| rows.push(row))?;
+    Ok(rows)
+}
+
+// ── Loading ─────────────────────────────────────────────────────────────────
+
+/// A dependency edge as stored, keyed by the dependent version's id.
+struct StoredDependency {
+    crate_id: u64,
+    req: String,
+    kind: u8,
+    optional: bool,
+    default_features: bool,
+    features: Vec<String>,
+    target: Option<String>,
+    explicit_name: Option<String>,
+}
+
+/// Tables read so far. Files can appear in any order in the archive, so rows
+/// are joined only once everything has been read.
+#[derive(Default)]
+struct Tables {
+    crates: Option<Vec<CrateRow>>,
+    crate_downloads: Vec<CrateDownloadsRow>,
+    versions: Option<Vec<VersionRow>>,
+    dependencies: HashMap<u64, Vec<StoredDependency>>,
+    categories: Vec<CategoryRow>,
+    crates_categories: Vec<CrateCategoryRow>,
+    keywords: Vec<KeywordRow>,
+    crates_keywords: Vec<CrateKeywordRow>,
+    crate_owners: Vec<CrateOwnerRow>,
+    users: Vec<UserRow>,
+    teams: Vec<TeamRow>,
+}
+
+impl Tables {
+    fn load(&mut self, file: &str, reader: impl Read) -> Result<(), DumpError> {
+        match file {
+            "crates.csv" => self.crates = Some(read_rows(file, reader)?),
+            "crate_downloads.csv" => self.crate_downloads = read_rows(file, reader)?,
+            "versions.csv" => self.versions = Some(read_rows(file, reader)?),
+            "dependencies.csv" => {
+                // By far the largest table: store each row compactly as it is read
+                each_row(file, reader, |row: DependencyRow| {
+                    self.dependencies
+                        .entry(row.version_id)
+                        .or_default()
+                        .push(StoredDependency {
+                            crate_id: row.crate_id,
+                            req: row.req,
+                            kind: row.kind,
+                            optional: row.optional,
+                            default_features: row.default_features,
+                            features: pg_array(&row.features),
+                            target: row.target,
+                            explicit_name: row.explicit_name,
+                        });
+                })?;
+            }
+            "categories.csv" => self.categories = read_rows(file, reader)?,
+            "crates_categories.csv" => self.crates_categories = read_rows(file, reader)?,
+            "keywords.csv" => self.keywords = read_rows(file, reader)?,
+            "crates_keywords.csv" => self.crates_keywords = read_rows(file, reader)?,
+            "crate_owners.csv" => self.crate_owners = read_rows(file, reader)?,
+            "users.csv" => self.users = read_rows(file, reader)?,
+            "teams.csv" => self.teams = read_rows(file, reader)?,
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn finish(self) -> Result<DumpStore, DumpError> {
+        let crate_rows = self.crates.ok_or(DumpError::Missing("crates.csv"))?;
+        let version_rows = self.versions.ok_or(DumpError::Missing("versions.csv"))?;
+
+        let downloads: HashMap<u64, u64> = self
+            .crate_downloads
+            .iter()
+            .map(|row| (row.crate_id, row.downloads))
+            .collect();
+        let categories: HashMap<u64, &CategoryRow> =
+            self.categories.iter().map(|c| (c.id, c)).collect();
+        let keywords: HashMap<u64, &KeywordRow> = self.keywords.iter().map(|k| (k.id, k)).collect();
+        let users: HashMap<u64, &UserRow> = self.users.iter().map(|u| (u.id, u)).collect();
+        let teams: HashMap<u64, &TeamRow> = self.teams.iter().map(|t| (t.id, t)).collect();
+
+        let mut crates: Vec<StoredCrate> = Vec::with_capacity(crate_rows.len());
+        let mut by_id: HashMap<u64, usize> = HashMap::with_capacity(crate_rows.len());
+        for row in crate_rows {
+            by_id.insert(row.id, crates.len());
+            crates.push(StoredCrate {
+                id: row.id,
+                data: Crate {
+                    downloads: downloads
+                        .get(&row.id)
+                        .copied()
+                        .or(row.downloads)
+                        .unwrap_or(0),
+                    name: row.name,
+                    description: row.description,
+                    max_version: String::new(),
+                    max_stable_version: None,
+                    recent_downloads: None,
+                    created_at: parse_timestamp(&row.created_at),
+                    updated_at: parse_timestamp(&row.updated_at),
+                    repository: row.repository,
+                    documentation: row.documentation,
+                    homepage: row.homepage,
+                    keywords: Some(Vec::new()),
+                    categories: Some(Vec::new()),
+                },
+                versions: Vec::new(),
+                owners: Vec::new(),
+                default_version: None,
+            });
+        }
+
+        for row in version_rows {
+            let Some(&idx) = by_id.get(&row.crate_id) else {
+                continue;
+            };
+            crates[idx].versions.push(Version {
+                id: row.id,
+                num: row.num,
+                yanked: row.yanked,
+                created_at: parse_timestamp(&row.created_at),
+                downloads: row.downloads,
+                license: row.license,
+                rust_version: row.rust_version,
+                features: row
+                    .features
+                    .and_then(|f| serde_json::from_str(&f).ok())
+                    .unwrap_or_default(),
+            });
+        }
+
+        for row in &self.crates_categories {
+            if let (Some(&idx), Some(category)) =
+                (by_id.get(&row.crate_id), categories.get(&row.category_id))
+                && let Some(slugs) = crates[idx].data.categories.as_mut()
+            {
+                slugs.push(category.slug.clone());
+            }
+        }
+        for row in &self.crates_keywords {
+            if let (Some(&idx), Some(keyword)) =
+                (by_id.get(&row.crate_id), keywords.get(&row.keyword_id))
+                && let Some(names) = crates[idx].data.keywords.as_mut()
+            {
+                names.push(keyword.keyword.clone());
+            }
+        }
+        for row in &self.crate_owners {
+            let Some(&idx) = by_id.get(&row.crate_id) else {
+                continue;
+            };
+            let owner = match row.owner_kind {
+                0 => users.get(&row.owner_id).map(|u| User {
+                    id: u.id,
+                    login: u.gh_login.clone(),
+                    name: u.name.clone(),
+                    url: format!("https://github.com/{}", u.gh_login),
+                    avatar: u.gh_avatar.clone(),
+                    kind: Some("user".to_string()),
+                }),
+                _ => teams.get(&row.owner_id).map(|t| User {
+                    id: t.id,
+                    login: t.login.clone(),
+                    name: t.name.clone(),
+                    // Team logins look like `github:org:team`
+                    url: t
+                        .login
+                        .split(':')
+                        .nth(1)
+                        .map(|org| format!("https://github.com/{org}"))
+                        .unwrap_or_default(),
+                    avatar: t.avatar.clone(),
+                    kind: Some("team".to_string()),
+                }),
+            };
+            crates[idx].owners.extend(owner);
+        }
+
+        for krate in &mut crates {
+            krate.finish_versions();
+        }
+
+        // Reverse dependencies count what each crate's current version uses
+        let mut dependents: HashMap<u64, Vec<(usize, u64)>> = HashMap::new();
+        for (idx, krate) in crates.iter().enumerate() {
+            let Some(version_id) = krate.default_version else {
+                continue;
+            };
+            for dep in self.dependencies.get(&version_id).into_iter().flatten() {
+                dependents
+                    .entry(dep.crate_id)
+                    .or_default()
+                    .push((idx, version_id));
+            }
+        }
+
+        let mut category_list: Vec<Category> = self
+            .categories
+            .iter()
+            .map(|c| Category {
+                category: c.category.clone(),
+                crates_cnt: c.crates_cnt,
+                slug: Some(c.slug.clone()),
+                description: c.description.clone(),
+            })
+            .collect();
+        category_list.sort_by(|a, b| a.category.cmp(&b.category));
+        let mut keyword_list: Vec<Keyword> = self
+            .keywords
+            .iter()
+            .map(|k| Keyword {
+                keyword: k.keyword.clone(),
+                crates_cnt: k.crates_cnt,
+            })
+            .collect();
+        keyword_list.sort_by(|a, b| a.keyword.cmp(&b.keyword));
+
+        let by_name = crates
+            .iter()
+            .enumerate()
+            .map(|(idx, c)| (canonical(&c.data.name), idx))
+            .collect();
+
+        Ok(DumpStore {
+            crates,
+            by_name,
+            by_id,
+            dependencies: self.dependencies,
+            dependents,
+            categories: category_list,
+            keywords: keyword_list,
+        })
+    }
+}
+
+// ── Store ───────────────────────────────────────────────────────────────────
+
+struct StoredCrate {
+    id: u64,
+    data: Crate,
+    /// Newest first, as the API lists them.
+    versions: Vec<Version>,
+    owners: Vec<User>,
+    /// Id of the version `max_version` names.
+    default_version: Option<u64>,
+}
+
+impl StoredCrate {
+    /// Sort versions newest first and derive `max_version` and
+    /// `max_stable_version` the way crates.io does: the highest non-yanked
+    /// release, or the highest release if all are yanked.
+    fn finish_versions(&mut self) {
+        self.versions.sort_by(|a, b| {
+            let a = semver::Version::parse(&a.num).ok();
+            let b = semver::Version::parse(&b.num).ok();
+            b.cmp(&a)
+        });
+        let max = self
+            .versions
+            .iter()
+            .find(|v| !v.yanked)
+            .or(self.versions.first());
+        if let Some(max) = max {
+            self.data.max_version = max.num.clone();
+            self.default_version = Some(max.id);
+        }
+        self.data.max_stable_version = self
+            .versions
+            .iter()
+            .find(|v| {
+                !v.yanked
+                    && semver::Version::parse(&v.num).is_ok_and(|parsed| parsed.pre.is_empty())
+            })
+            .map(|v| v.num.clone());
+    }
+
+    /// Whether the crate passes a search's category, keyword, owner, name and
+    /// letter filters.
+    fn matches_filters(&self, query: &CratesQuery) -> bool {
+        let name = &self.data.name;
+        let categories = self.data.categories.as_deref().unwrap_or_default();
+        let keywords = self.data.keywords.as_deref().unwrap_or_default();
+        let has_keyword =
+            |keyword: &String| keywords.iter().any(|k| k.eq_ignore_ascii_case(keyword));
+        let owned_by = |kind: &str, id: u64| {
+            self.owners
+                .iter()
+                .any(|o| o.id == id && o.kind.as_deref() == Some(kind))
+        };
+        // A category includes its subcategories, e.g. `parsing` covers `parsing::json`
+        let in_category = |slug: &String| {
+            categories
+                .iter()
+                .any(|c| c == slug || c.starts_with(&format!("{slug}::")))
+        };
+
+        query.category.as_ref().is_none_or(in_category)
+            && query.keyword.as_ref().is_none_or(has_keyword)
+            && query.all_keywords.iter().all(has_keyword)
+            && query.user_id.is_none_or(|id| owned_by("user", id))
+            && query.team_id.is_none_or(|id| owned_by("team", id))
+            && (query.ids.is_empty() || query.ids.iter().any(|id| canonical(id) == canonical(name)))
+            && query.letter.is_none_or(|letter| {
+                name.chars()
+                    .next()
+                    .is_some_and(|first| first.eq_ignore_ascii_case(&letter))
+            })
+    }
+
+    /// Relevance of the crate to a lowercased search, or `None` if some term
+    /// matches neither its name, keywords nor description.
+    fn relevance(&self, terms: &[String]) -> Option<u64> {
+        let name = canonical(&self.data.name);
+        let description = self
+            .data
+            .description
+            .as_deref()
+            .unwrap_or_default()
+            .to_lowercase();
+        let keywords = self.data.keywords.as_deref().unwrap_or_default();
+
+        let mut score = 0;
+        for term in terms {
+            let term_score = if name == canonical(term) {
+                100
+            } else if name.contains(&canonical(term)) {
+                10
+            } else if keywords.iter().any(|k| k == term) {
+                5
+            } else if description.contains(term.as_str()) {
+                1
+            } else {
+                return None;
+            };
+            score += term_score;
+        }
+        Some(score)
+    }
+}
+
+/// crates.io data loaded from a database dump.
+pub struct DumpStore {
+    crates: Vec<StoredCrate>,
+    by_name: HashMap<String, usize>,
+    by_id: HashMap<u64, usize>,
+    /// Dependencies of each version, by version id.
+    dependencies: HashMap<u64, Vec<StoredDependency>>,
+    /// Crates whose current version depends on a crate, by crate id.
+    dependents: HashMap<u64, Vec<(usize, u64)>>,
+    /// Sorted by name.
+    categories: Vec<Category>,
+    /// Sorted by name.
+    keywords: Vec<Keyword>,
+}
+
+impl std::fmt::Debug for DumpStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DumpStore")
+            .field("crates", &self.crates.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl DumpStore {
+    /// Load a dump from `path`: either the `db-dump.tar.gz` archive as
+    /// downloaded, or a directory it was extracted to (containing the CSV
+    /// files directly or in a `data/` subdirectory).
+    pub fn open(path: &Path) -> Result<Self, DumpError> {
+        if path.is_dir() {
+            Self::from_dir(path)
+        } else {
+            Self::from_archive(File::open(path)?)
+        }
+    }
+
+    /// Load a dump from a gzipped tar archive.
+    pub fn from_archive(reader: impl Read) -> Result<Self, DumpError> {
+        let mut tables = Tables::default();
+        let mut archive = tar::Archive::new(GzDecoder::new(reader));
+        for entry in archive.entries()? {
+            let entry = entry?;
+            let path = entry.path()?;
+            let Some(file) = path
+                .file_name()
+                .and_then(|f| f.to_str())
+                .filter(|f| TABLES.contains(f))
+                .map(str::to_string)
+            else {
+                continue;
+            };
+            tables.load(&file, entry)?;
+        }
+        tables.finish()
+    }
+
+    /// Load a dump from an extracted directory.
+    pub fn from_dir(dir: &Path) -> Result<Self, DumpError> {
+        let data = dir.join("data");
+        let dir = if data.is_dir() { data.as_path() } else { dir };
+        let mut tables = Tables::default();
+        for file in TABLES {
+            let path = dir.join(file);
+            if path.is_file() {
+                tables.load(file, File::open(path)?)?;
+            }
+        }
+        tables.finish()
+    }
+
+    /// Number of crates in the dump.
+    pub fn crate_count(&self) -> usize {
+        self.crates.len()
+    }
+
+    fn find(&self, name: &str) -> Option<&StoredCrate> {
+        self.by_name
+            .get(&canonical(name))
+            .map(|&idx| &self.crates[idx])
+    }
+
+    /// Search crates, like `GET /crates`. Every whitespace-separated term must
+    /// match the crate's name, a keyword or the description, and the crate
+    /// must pass every filter the query sets. Sorting by recent
+    /// downloads uses all-time downloads, since the dump has no recent counts.
+    pub fn search(&self, query: &CratesQuery) -> CratesPage {
+        let terms: Vec<String> = query
+            .search
+            .as_deref()
+            .unwrap_or_default()
+            .split_whitespace()
+            .map(str::to_lowercase)
+            .collect();
+        let mut hits: Vec<(u64, &Crate)> = self
+            .crates
+            .iter()
+            .filter(|c| c.matches_filters(query))
+            .filter_map(|c| Some((c.relevance(&terms)?, &c.data)))
+            .collect();
+
+        let default_sort = if terms.is_empty() {
+            Sort::Alphabetical
+        } else {
+            Sort::Relevance
+        };
+        match query.sort.unwrap_or(default_sort) {
+            Sort::Alphabetical => hits.sort_by(|a, b| a.1.name.cmp(&b.1.name)),
+            Sort::Relevance => {
+                hits.sort_by(|a, b| b.0.cmp(&a.0).then(b.1.downloads.cmp(&a.1.downloads)))
+            }
+            Sort::Downloads | Sort::RecentDownloads => {
+                hits.sort_by_key(|hit| Reverse(hit.1.downloads))
+            }
+            Sort::RecentUpdates => hits.sort_by_key(|hit| Reverse(hit.1.updated_at)),
+            Sort::NewlyAdded => hits.sort_by_key(|hit| Reverse(hit.1.created_at)),
+        }
+
+        let total = hits.len() as u64;
+        CratesPage {
+            crates: paginate(hits, query.page, query.per_page)
+                .map(|(_, c)| c.clone())
+                .collect(),
+            meta: Meta {
+                total,
+                next_page: None,
+            },
+        }
+    }
+
+    /// A crate and its versions, like `GET /crates/{name}`.
+    pub fn get_crate(&self, name: &str) -> Option<CrateResponse> {
+        self.find(name).map(|c| CrateResponse {
+            crate_data: c.data.clone(),
+            versions: c.versions.clone(),
+        })
+    }
+
+    /// Dependencies of one version, like `GET /crates/{name}/{version}/dependencies`.
+    pub fn crate_dependencies(&self, name: &str, version: &str) -> Option<Vec<Dependency>> {
+        let krate = self.find(name)?;
+        let version = krate.versions.iter().find(|v| v.num == version)?;
+        Some(
+            self.dependencies
+                .get(&version.id)
+                .into_iter()
+                .flatten()
+                .filter_map(|dep| self.dependency(dep, version.id))
+                .collect(),
+        )
+    }
+
+    fn dependency(&self, dep: &StoredDependency, version_id: u64) -> Option<Dependency> {
+        let target = &self.crates[*self.by_id.get(&dep.crate_id)?];
+        Some(Dependency {
+            crate_id: target.data.name.clone(),
+            req: dep.req.clone(),
+            kind: match dep.kind {
+                1 => "build",
+                2 => "dev",
+                _ => "normal",
+            }
+            .to_string(),
+            optional: dep.optional,
+            version_id,
+            features: dep.features.clone(),
+            default_features: dep.default_features,
+            target: dep.target.clone(),
+            registry: None,
+            explicit_name: dep.explicit_name.clone(),
+        })
+    }
+
+    /// Crates whose current version depends on `name`, most downloaded first,
+    /// like `GET /crates/{name}/reverse_dependencies`.
+    pub fn crate_reverse_dependencies(
+        &self,
+        name: &str,
+        page: Option<u64>,
+        per_page: Option<u64>,
+    ) -> Option<ReverseDependencies> {
+        let krate = self.find(name)?;
+        let mut dependents: Vec<&(usize, u64)> = self
+            .dependents
+            .get(&krate.id)
+            .into_iter()
+            .flatten()
+            .collect();
+        dependents.sort_by(|a, b| {
+            self.crates[b.0]
+                .data
+                .downloads
+                .cmp(&self.crates[a.0].data.downloads)
+        });
+
+        let total = dependents.len() as u64;
+        let dependencies = paginate(dependents, page, per_page)
+            .filter_map(|&(idx, version_id)| {
+                let dependent = &self.crates[idx];
+                let dep = self
+                    .dependencies
+                    .get(&version_id)?
+                    .iter()
+                    .find(|d| d.crate_id == krate.id)?;
+                Some(ReverseDependency {
+                    crate_version: CrateVersion {
+                        crate_name: dependent.data.name.clone(),
+                        num: dependent.data.max_version.clone(),
+                    },
+                    dependency: self.dependency(dep, version_id)?,
+                })
+            })
+            .collect();
+        Some(ReverseDependencies {
+            dependencies,
+            meta: Meta {
+                total,
+                next_page: None,
+            },
+        })
+    }
+
+    /// Users and teams owning a crate, like `GET /crates/{name}/owners`.
+    pub fn crate_owners(&self, name: &str) -> Option<Vec<User>> {
+        self.find(name).map(|c| c.owners.clone())
+    }
+
+    /// Categories in alphabetical order, like `GET /categories`.
+    pub fn categories(&self, page: Option<u64>, per_page: Option<u64>) -> CategoriesPage {
+        CategoriesPage {
+            categories: paginate(self.categories.iter(), page, per_page)
+                .cloned()
+                .collect(),
+            meta: Meta {
+                total: self.categories.len() as u64,
+                next_page: None,
+            },
+        }
+    }
+
+    /// A single category by slug, like `GET /categories/{slug}`.
+    pub fn category(&self, slug: &str) -> Option<Category> {
+        self.categories
+            .iter()
+            .find(|c| c.slug.as_deref() == Some(slug))
+            .cloned()
+    }
+
+    /// Keywords in alphabetical order, like `GET /keywords`.
+    pub fn keywords(&self, page: Option<u64>, per_page: Option<u64>) -> KeywordsPage {
+        KeywordsPage {
+            keywords: paginate(self.keywords.iter(), page, per_page)
+                .cloned()
+                .collect(),
+            meta: Meta {
+                total: self.keywords.len() as u64,
+                next_page: None,
+            },
+        }
+    }
+
+    /// A single keyword, like `GET /keywords/{id}`.
+    pub fn keyword(&self, id: &str) -> Option<Keyword> {
+        self.keywords.iter().find(|k| k.keyword == id).cloned()
+    }
+}
+
+/// Apply the API's pagination: 1-based pages of 10 by default, at most 100.
+fn paginate<T>(
+    items: impl IntoIterator<Item = T>,
+    page: Option<u64>,
+    per_page: Option<u64>,
+) -> impl Iterator<Item = T> {
+    let per_page = per_page.unwrap_or(10).clamp(1, 100) as usize;
+    let page = usize::try_from(page.unwrap_or(1)).unwrap_or(usize::MAX);
+    let skip = page.saturating_sub(1).saturating_mul(per_page);
+    items.into_iter().skip(skip).take(per_page)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// CSV contents of a small dump, in the column layout crates.io uses.
+    pub(crate) const TABLE_FIXTURES: &[(&str, &str)] = &[
+        (
+            "crates.csv",
+            "created_at,description,documentation,homepage,id,max_features,max_upload_size,name,readme,repository,updated_at\n\
+             2015-01-01 00:00:00.000000,A serialization framework,,,1,,,serde,# serde,https://github.com/serde-rs/serde,2025-01-01 00:00:00.000000\n\
+             2016-01-01 00:00:00.000000,JSON support for serde,,,2,,,serde_json,,,2025-02-01 00:00:00.000000\n\
+             2017-01-01 00:00:00.000000,Command line parsing,,,3,,,clap,,,2024-06-01 12:30:00\n",
+        ),
+        (
+            "crate_downloads.csv",
+            "crate_id,downloads\n1,500\n2,300\n3,200\n",
+        ),
+        (
+            "versions.csv",
+            "bin_names,categories,checksum,crate_id,crate_size,created_at,description,documentation,downloads,edition,features,has_lib,homepage,id,keywords,license,links,num,num_no_build,published_by,repository,rust_version,updated_at,yanked\n\
+             {},{},x,1,1,2024-01-01 00:00:00,,,400,2021,\"{\"\"derive\"\": [\"\"serde_derive\"\"]}\",t,,10,{},MIT OR Apache-2.0,,1.0.0,1.0.0,,,1.31,2024-01-01 00:00:00,f\n\
+             {},{},x,1,1,2025-01-01 00:00:00,,,100,2021,{},t,,11,{},MIT OR Apache-2.0,,1.1.0,1.1.0,,,1.31,2025-01-01 00:00:00,t\n\
+             {},{},x,2,1,2025-02-01 00:00:00,,,300,2021,{},t,,20,{},MIT,,1.0.5,1.0.5,,,,2025-02-01 00:00:00,f\n\
+             {},{},x,3,1,2024-06-01 00:00:00,,,200,2021,{},t,,30,{},MIT,,4.5.0,4.5.0,,,,2024-06-01 00:00:00,f\n",
+        ),
+        (
+            "dependencies.csv",
+            "crate_id,default_features,explicit_name,features,id,kind,optional,req,target,version_id\n\
+             1,f,,\"{std,alloc}\",100,0,f,^1.0,,20\n\
+             2,t,,{},101,2,t,^1,cfg(unix),30\n\
+             1,t,,{},102,0,f,^1,,30\n",
+        ),
+        (
+            "categories.csv",
+            "category,crates_cnt,created_at,description,id,path,slug\n\
+             Encoding,2,2017-01-01 00:00:00,Encoding and decoding,1,encoding,encoding\n\
+             Command-line interface,1,2017-01-01 00:00:00,CLI tools,2,command_line_interface,command-line-interface\n",
+        ),
+        (
+            "crates_categories.csv",
+            "category_id,crate_id\n1,1\n1,2\n2,3\n",
+        ),
+        (
+            "keywords.csv",
+            "crates_cnt,created_at,id,keyword\n2,2017-01-01 00:00:00,1,serialization\n1,2017-01-01 00:00:00,2,cli\n",
+        ),
+        (
+            "crates_keywords.csv",
+            "crate_id,keyword_id\n1,1\n2,1\n3,2\n",
+        ),
+        (
+            "crate_owners.csv",
+            "crate_id,created_at,created_by,owner_id,owner_kind\n1,2015-01-01 00:00:00,,7,0\n1,2015-01-01 00:00:00,,3,1\n",
+        ),
+        (
+            "users.csv",
+            "gh_avatar,gh_id,gh_login,id,name\nhttps://avatars.example/dtolnay,1,dtolnay,7,David Tolnay\n",
+        ),
+        (
+            "teams.csv",
+            "avatar,github_id,id,login,name,org_id\n,1,3,github:serde-rs:publish,Publish,1\n",
+        ),
+        ("version_downloads.csv", "date,downloads,version_id\n"),
+    ];
+
+    /// The fixture tables packed the way crates.io ships them.
+    pub(crate) fn fixture_archive() -> Vec<u8> {
+        let encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::fast());
+        let mut builder = tar::Builder::new(encoder);
+        for (name, contents) in TABLE_FIXTURES {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(contents.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder
+                .append_data(
+                    &mut header,
+                    format!("2025-03-01-020000/data/{name}"),
+                    contents.as_bytes(),
+                )
+                .unwrap();
+        }
+        builder.into_inner().unwrap().finish().unwrap()
+    }
+
+    pub(crate) fn fixture_store() -> DumpStore {
+        DumpStore::from_archive(fixture_archive().as_slice()).unwrap()
+    }
+
+    #[test]
+    fn loads_crates_and_versions() {
+        let store = fixture_store();
+        assert_eq!(store.crate_count(), 3);
+
+        let serde = store.get_crate("serde").unwrap();
+        assert_eq!(serde.crate_data.downloads, 500);
+        // 1.1.0 is yanked, so 1.0.0 is current
+        assert_eq!(serde.crate_data.max_version, "1.0.0");
+        assert_eq!(serde.versions[0].num, "1.1.0");
+        assert!(serde.versions[0].yanked);
+        assert_eq!(serde.versions[1].features["derive"], vec!["serde_derive"]);
+        assert_eq!(
+            serde.crate_data.keywords.as_deref(),
+            Some(&["serialization".to_string()][..])
+        );
+        assert_eq!(
+            serde.crate_data.categories.as_deref(),
+            Some(&["encoding".to_string()][..])
+        );
+
+        // Lookups ignore case and `-` / `_`
+        assert!(store.get_crate("Serde-JSON").is_some());
+        assert!(store.get_crate("missing").is_none());
+    }
+
+    #[test]
+    fn dependencies_and_dependents() {
+        let store = fixture_store();
+
+        let deps = store.crate_dependencies("clap", "4.5.0").unwrap();
+        assert_eq!(deps.len(), 2);
+        let json = deps.iter().find(|d| d.crate_id == "serde_json").unwrap();
+        assert_eq!(json.kind, "dev");
+        assert!(json.optional);
+        assert_eq!(json.target.as_deref(), Some("cfg(unix)"));
+
+        let serde = store.crate_dependencies("serde_json", "1.0.5").unwrap();
+        assert_eq!(serde[0].features, vec!["std", "alloc"]);
+        assert!(!serde[0].default_features);
+        assert!(store.crate_dependencies("clap", "9.9.9").is_none());
+
+        let reverse = store
+            .crate_reverse_dependencies("serde", None, None)
+            .unwrap();
+        assert_eq!(reverse.meta.total, 2);
+        // Most downloaded dependent first
+        assert_eq!(
+            reverse.dependencies[0].crate_version.crate_name,
+            "serde_json"
+        );
+        assert_eq!(reverse.dependencies[1].crate_version.num, "4.5.0");
+    }
+
+    #[test]
+    fn search_matches_every_term() {
+        let store = fixture_store();
+
+        let page = store.search(&CratesQuery::builder().search("serde").build());
+        assert_eq!(page.meta.total, 2);
+        // Exact name match ranks first
+        assert_eq!(page.crates[0].name, "serde");
+
+        let page = store.search(&CratesQuery::builder().search("json serde").build());
+        assert_eq!(page.meta.total, 1);
+
+        let page = store.search(
+            &CratesQuery::builder()
+                .sort(Sort::Downloads)
+                .per_page(2)
+                .page(2)
+                .build(),
+        );
+        assert_eq!(page.meta.total, 3);
+        assert_eq!(page.crates.len(), 1);
+        assert_eq!(page.crates[0].name, "clap");
+    }
+
+    #[test]
+    fn search_filters() {
+        let store = fixture_store();
+        let names = |query: CratesQuery| -> Vec<String> {
+            store
+                .search(&query)
+                .crates
+                .into_iter()
+                .map(|c| c.name)
+                .collect()
+        };
+
+        assert_eq!(
+            names(CratesQuery::builder().category("encoding").build()),
+            ["serde", "serde_json"]
+        );
+        assert_eq!(
+            names(CratesQuery::builder().keyword("cli").build()),
+            ["clap"]
+        );
+        assert_eq!(
+            names(
+                CratesQuery::builder()
+                    .search("json")
+                    .all_keywords(&["serialization"])
+                    .build()
+            ),
+            ["serde_json"]
+        );
+        assert_eq!(names(CratesQuery::builder().user_id(7).build()), ["serde"]);
+        assert_eq!(names(CratesQuery::builder().team_id(3).build()), ["serde"]);
+        assert!(names(CratesQuery::builder().team_id(7).build()).is_empty());
+        assert_eq!(
+            names(CratesQuery::builder().ids(&["clap", "serde"]).build()),
+            ["clap", "serde"]
+        );
+        assert_eq!(names(CratesQuery::builder().letter('C').build()), ["clap"]);
+    }
+
+    #[test]
+    fn owners_categories_and_keywords() {
+        let store = fixture_store();
+
+        let owners = store.crate_owners("serde").unwrap();
+        assert_eq!(owners.len(), 2);
+        assert_eq!(owners[0].url, "https://github.com/dtolnay");
+        assert_eq!(owners[1].kind.as_deref(), Some("team"));
+        assert_eq!(owners[1].url, "https://github.com/serde-rs");
+
+        let categories = store.categories(None, None);
+        assert_eq!(categories.meta.total, 2);
+        assert_eq!(categories.categories[0].category, "Command-line interface");
+        assert_eq!(store.category("encoding").unwrap().crates_cnt, 2);
+
+        assert_eq!(store.keywords(Some(1), Some(1)).keywords[0].keyword, "cli");
+        assert_eq!(store.keyword("serialization").unwrap().crates_cnt, 2);
+        assert!(store.keyword("nope").is_none());
+    }
+
+    #[test]
+    fn pages_past_the_end_are_empty() {
+        let store = fixture_store();
+
+        let categories = store.categories(Some(u64::MAX), Some(100));
+        assert!(categories.categories.is_empty());
+        assert_eq!(categories.meta.total, 2);
+        assert!(store.keywords(Some(u64::MAX), None).keywords.is_empty());
+        assert_eq!(store.categories(Some(0), None).categories.len(), 2);
+    }
+
+    #[test]
+    fn extracted_directory() {
+        let dir = std::env::temp_dir().join(format!("cratesio-mcp-dump-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("data")).unwrap();
+        for (name, contents) in TABLE_FIXTURES {
+            std::fs::write(dir.join("data").join(name), contents).unwrap();
+        }
+        let store = DumpStore::open(&dir);
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(store.unwrap().crate_count(), 3);
+    }
+
+    #[test]
+    fn missing_tables_are_an_error() {
+        let err = DumpStore::from_archive(
+            {
+                let encoder =
+                    flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::fast());
+                tar::Builder::new(encoder)
+                    .into_inner()
+                    .unwrap()
+                    .finish()
+                    .unwrap()
+            }
+            .as_slice(),
+        )
+        .unwrap_err();
+        assert_eq!(err.to_string(), "dump has no crates.csv");
+    }
+
+    #[test]
+    fn postgres_values() {
+        assert_eq!(pg_array("{}"), Vec::<String>::new());
+        assert_eq!(pg_array("{a,\"b c\"}"), vec!["a", "b c"]);
+        assert_eq!(
+            parse_timestamp("2024-06-01 12:30:00").to_rfc3339(),
+            "2024-06-01T12:30:00+00:00"
+        );
+        assert_eq!(
+            parse_timestamp("2024-06-01 12:30:00.5+00").timestamp_subsec_millis(),
+            500
+        );
+    }
+}
@@ -1,11 +1,13 @@
 //! Error types for the crates.io API client.
 
+use super::single_flight::ShareError;
+
 /// Errors returned by the crates.io API client.
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
     /// HTTP transport error.
     #[error("HTTP error: {0}")]
-    Http(#[from] reqwest::Error),
+    Http(#[from] super::http::HttpError),
 
     /// Resource not found (404).
     #[error("not found: {0}")]
@@ -35,7 +37,41 @@ pub enum Error {
     #[error("JSON error: {0}")]
     Json(#[from] serde_json::Error),
 
+    /// The client is answering from a database dump, which has no data for
+    /// this request.
+    #[error("not available offline: {0}")]
+    Offline(String),
+
+    /// The upstream keeps failing and its circuit breaker is open.
+    #[error(transparent)]
+    UpstreamUnavailable(#[from] super::resilience::UpstreamUnavailable),
+
     /// Response body exceeded the maximum allowed size.
     #[error("response too large for {path}: {size} bytes exceeds limit of {limit}")]
     ResponseTooLarge { path: String, size: u64, limit: u64 },
 }
+
+impl ShareError for Error {
+    fn share(&self) -> Self {
+        match self {
+            Self::Http(err) => Self::Http(err.share()),
+            Self::NotFound(what) => Self::NotFound(what.clone()),
+            Self::PermissionDenied => Self::PermissionDenied,
+            Self::Api { status, message } => Self::Api {
+                status: *status,
+                message: message.clone(),
+            },
+            Self::RateLimited => Self::RateLimited,
+            Self::AuthRequired => Self::AuthRequired,
+            Self::Unauthorized => Self::Unauthorized,
+            Self::Json(err) => Self::Json(err.share()),
+            Self::Offline(what) => Self::Offline(what.clone()),
+            Self::UpstreamUnavailable(err) => Self::UpstreamUnavailable(err.clone()),
+            Self::ResponseTooLarge { path, size, limit } => Self::ResponseTooLarge {
+                path: path.clone(),
+                size: *size,
+                limit: *limit,
+            },
+        }
+    }
+}
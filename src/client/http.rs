@@ -0,0 +1,400 @@
+//! Pluggable HTTP transport for the API clients.
+//!
+//! The crates.io, docs.rs, OSV.dev and sparse index clients send every
+//! request through an [`HttpBackend`]. They use [`ReqwestBackend`] unless
+//! given another with `with_backend`, which is the place to add proxies,
+//! custom TLS roots, request signing or recording. [`MemoryBackend`] answers
+//! requests from canned responses, for unit-testing code built on the
+//! clients without a mock server.
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use bytes::Bytes;
+use futures::future::BoxFuture;
+pub use reqwest::Method;
+pub use reqwest::StatusCode;
+pub use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+/// A request failed without producing an HTTP response, e.g. a connection,
+/// TLS or timeout error.
+#[derive(Debug, thiserror::Error)]
+#[error(transparent)]
+pub struct HttpError(Box<dyn std::error::Error + Send + Sync>);
+
+impl HttpError {
+    /// Wrap a backend's own error.
+    pub fn new(err: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> Self {
+        Self(err.into())
+    }
+}
+
+impl From<reqwest::Error> for HttpError {
+    fn from(err: reqwest::Error) -> Self {
+        Self::new(err)
+    }
+}
+
+/// Sends HTTP requests on behalf of the API clients.
+///
+/// Retries, rate limiting and status handling stay in the clients; a backend
+/// only moves bytes. Any status is a response, not an error.
+pub trait HttpBackend: Send + Sync {
+    /// Send `request` and buffer the response.
+    fn send(&self, request: HttpRequest) -> BoxFuture<'_, Result<HttpResponse, HttpError>>;
+}
+
+// ── Request / response ─────────────────────────────────────────────────────
+
+/// An outgoing HTTP request.
+#[derive(Debug, Clone)]
+pub struct HttpRequest {
+    pub method: Method,
+    /// Absolute URL, including any query string.
+    pub url: String,
+    pub headers: HeaderMap,
+    pub body: Option<Bytes>,
+    /// Largest response body the caller accepts. A backend may stop reading
+    /// once it is exceeded; the caller rejects the response either way.
+    pub max_response_bytes: Option<u64>,
+}
+
+impl HttpRequest {
+    /// A request with no headers or body.
+    pub fn new(method: Method, url: impl Into<String>) -> Self {
+        Self {
+            method,
+            url: url.into(),
+            headers: HeaderMap::new(),
+            body: None,
+            max_response_bytes: None,
+        }
+    }
+
+    /// A `GET` request.
+    pub fn get(url: impl Into<String>) -> Self {
+        Self::new(Method::GET, url)
+    }
+
+    /// A `POST` request.
+    pub fn post(url: impl Into<String>) -> Self {
+        Self::new(Method::POST, url)
+    }
+
+    /// Set a header, replacing any previous value. Values that aren't valid
+    /// header text are dropped.
+    pub fn header(mut self, name: HeaderName, value: &str) -> Self {
+        if let Ok(value) = HeaderValue::from_str(value) {
+            self.headers.insert(name, value);
+        }
+        self
+    }
+
+    /// Append URL-encoded query parameters.
+    pub fn query(mut self, params: &[(String, String)]) -> Self {
+        if !params.is_empty()
+            && let Ok(mut url) = reqwest::Url::parse(&self.url)
+        {
+            url.query_pairs_mut().extend_pairs(params);
+            self.url = url.into();
+        }
+        self
+    }
+
+    /// Send `body` as JSON.
+    pub fn json<T: Serialize + ?Sized>(self, body: &T) -> Result<Self, serde_json::Error> {
+        let body = serde_json::to_vec(body)?;
+        Ok(self.body("application/json", body))
+    }
+
+    /// Send raw bytes with the given content type.
+    pub fn body(mut self, content_type: &str, body: impl Into<Bytes>) -> Self {
+        self.body = Some(body.into());
+        self.header(reqwest::header::CONTENT_TYPE, content_type)
+    }
+
+    /// Cap the size of the response body the caller will accept.
+    pub fn max_response_bytes(mut self, limit: u64) -> Self {
+        self.max_response_bytes = Some(limit);
+        self
+    }
+}
+
+/// A buffered HTTP response.
+#[derive(Debug, Clone)]
+pub struct HttpResponse {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub body: Bytes,
+}
+
+impl HttpResponse {
+    /// A response with no headers or body.
+    pub fn new(status: StatusCode) -> Self {
+        Self {
+            status,
+            headers: HeaderMap::new(),
+            body: Bytes::new(),
+        }
+    }
+
+    /// A `200 OK` response carrying `value` as JSON.
+    pub fn json<T: Serialize + ?Sized>(value: &T) -> Self {
+        Self::new(StatusCode::OK)
+            .with_header(reqwest::header::CONTENT_TYPE, "application/json")
+            .with_body(serde_json::to_vec(value).unwrap_or_default())
+    }
+
+    /// Replace the body.
+    pub fn with_body(mut self, body: impl Into<Bytes>) -> Self {
+        self.body = body.into();
+        self
+    }
+
+    /// Set a header, replacing any previous value.
+    pub fn with_header(mut self, name: HeaderName, value: &str) -> Self {
+        if let Ok(value) = HeaderValue::from_str(value) {
+            self.headers.insert(name, value);
+        }
+        self
+    }
+
+    /// The body size the server declared, if it did.
+    pub fn content_length(&self) -> Option<u64> {
+        self.headers
+            .get(reqwest::header::CONTENT_LENGTH)?
+            .to_str()
+            .ok()?
+            .parse()
+            .ok()
+    }
+
+    /// Deserialize the body as JSON.
+    pub fn json_body<T: DeserializeOwned>(&self) -> Result<T, serde_json::Error> {
+        serde_json::from_slice(&self.body)
+    }
+
+    /// The body as text, replacing invalid UTF-8.
+    pub fn text(&self) -> String {
+        String::from_utf8_lossy(&self.body).into_owned()
+    }
+}
+
+// ── reqwest ────────────────────────────────────────────────────────────────
+
+/// The default backend, built on [`reqwest`].
+#[derive(Debug, Clone)]
+pub struct ReqwestBackend {
+    client: reqwest::Client,
+}
+
+impl ReqwestBackend {
+    /// A backend whose requests time out after `timeout`.
+    pub fn new(timeout: Duration) -> Result<Self, HttpError> {
+        let client = reqwest::Client::builder().timeout(timeout).build()?;
+        Ok(Self { client })
+    }
+
+    /// Use an already configured client, e.g. one with a proxy or extra root
+    /// certificates.
+    pub fn from_client(client: reqwest::Client) -> Self {
+        Self { client }
+    }
+}
+
+impl HttpBackend for ReqwestBackend {
+    fn send(&self, request: HttpRequest) -> BoxFuture<'_, Result<HttpResponse, HttpError>> {
+        Box::pin(async move {
+            let mut builder = self
+                .client
+                .request(request.method, &request.url)
+                .headers(request.headers);
+            if let Some(body) = request.body {
+                builder = builder.body(body);
+            }
+            let mut resp = builder.send().await?;
+
+            let status = resp.status();
+            let headers = resp.headers().clone();
+            let Some(limit) = request.max_response_bytes else {
+                let body = resp.bytes().await?;
+                return Ok(HttpResponse {
+                    status,
+                    headers,
+                    body,
+                });
+            };
+
+            // Don't download a body that is declared too large, and stop
+            // reading one that turns out to be (Content-Length may be absent
+            // or understated)
+            let mut body = Vec::new();
+            if resp.content_length().is_none_or(|len| len <= limit) {
+                while let Some(chunk) = resp.chunk().await? {
+                    body.extend_from_slice(&chunk);
+                    if body.len() as u64 > limit {
+                        break;
+                    }
+                }
+            }
+            Ok(HttpResponse {
+                status,
+                headers,
+                body: body.into(),
+            })
+        })
+    }
+}
+
+// ── In-memory ──────────────────────────────────────────────────────────────
+
+/// A backend that answers from canned responses and records what it was
+/// asked, for tests.
+///
+/// Requests with no matching route get a `404 Not Found`.
+///
+/// ```
+/// use std::sync::Arc;
+/// use std::time::Duration;
+///
+/// use cratesio_mcp::client::CratesIoClient;
+/// use cratesio_mcp::client::http::{HttpResponse, MemoryBackend, Method};
+///
+/// let backend = Arc::new(MemoryBackend::new());
+/// backend.route(
+///     Method::GET,
+///     "https://crates.io/api/v1/crates/serde/owners",
+///     HttpResponse::json(&serde_json::json!({ "users": [] })),
+/// );
+/// let client = CratesIoClient::new("my-tests", Duration::ZERO, Duration::from_secs(5))
+///     .unwrap()
+///     .with_backend(backend.clone());
+/// ```
+#[derive(Debug, Default)]
+pub struct MemoryBackend {
+    routes: Mutex<Vec<(Method, String, HttpResponse)>>,
+    requests: Mutex<Vec<HttpRequest>>,
+}
+
+impl MemoryBackend {
+    /// A backend with no routes.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Answer `method` requests for `url` with `response`.
+    ///
+    /// A URL without a query string also matches requests that have one.
+    /// When several routes match, the one added last wins.
+    pub fn route(&self, method: Method, url: &str, response: HttpResponse) {
+        self.routes
+            .lock()
+            .unwrap()
+            .push((method, url.to_string(), response));
+    }
+
+    /// Every request sent so far, oldest first.
+    pub fn requests(&self) -> Vec<HttpRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    fn respond(&self, request: &HttpRequest) -> HttpResponse {
+        let without_query = request.url.split('?').next().unwrap_or_default();
+        let routes = self.routes.lock().unwrap();
+        routes
+            .iter()
+            .rev()
+            .find(|(method, url, _)| {
+                *method == request.method && (*url == request.url || url == without_query)
+            })
+            .map(|(_, _, response)| response.clone())
+            .unwrap_or_else(|| {
+                HttpResponse::new(StatusCode::NOT_FOUND)
+                    .with_body(format!("no route for {} {}", request.method, request.url))
+            })
+    }
+}
+
+impl HttpBackend for MemoryBackend {
+    fn send(&self, request: HttpRequest) -> BoxFuture<'_, Result<HttpResponse, HttpError>> {
+        let response = self.respond(&request);
+        self.requests.lock().unwrap().push(request);
+        Box::pin(async move { Ok(response) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn query_parameters_are_encoded() {
+        let request = HttpRequest::get("https://crates.io/api/v1/crates").query(&[
+            ("q".to_string(), "serde json".to_string()),
+            ("ids[]".to_string(), "a&b".to_string()),
+        ]);
+        assert_eq!(
+            request.url,
+            "https://crates.io/api/v1/crates?q=serde+json&ids%5B%5D=a%26b"
+        );
+        assert_eq!(
+            HttpRequest::get("https://x.test/a").query(&[]).url,
+            "https://x.test/a"
+        );
+    }
+
+    #[tokio::test]
+    async fn memory_backend_matches_routes() {
+        let backend = MemoryBackend::new();
+        backend.route(
+            Method::GET,
+            "https://x.test/a",
+            HttpResponse::new(StatusCode::OK).with_body("any query"),
+        );
+        backend.route(
+            Method::GET,
+            "https://x.test/a?page=2",
+            HttpResponse::new(StatusCode::OK).with_body("page 2"),
+        );
+
+        let body = |url: &str| {
+            let request = HttpRequest::get(url);
+            async { backend.send(request).await.unwrap() }
+        };
+        assert_eq!(body("https://x.test/a?page=2").await.text(), "page 2");
+        assert_eq!(body("https://x.test/a?page=3").await.text(), "any query");
+        assert_eq!(body("https://x.test/b").await.status, StatusCode::NOT_FOUND);
+        let posted = backend
+            .send(HttpRequest::post("https://x.test/a"))
+            .await
+            .unwrap();
+        assert_eq!(posted.status, StatusCode::NOT_FOUND);
+
+        assert_eq!(backend.requests().len(), 4);
+    }
+
+    #[tokio::test]
+    async fn reqwest_backend_caps_the_body() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(vec![b'x'; 64 * 1024]))
+            .mount(&server)
+            .await;
+
+        let backend = ReqwestBackend::new(Duration::from_secs(30)).unwrap();
+        let full = backend.send(HttpRequest::get(server.uri())).await.unwrap();
+        assert_eq!(full.body.len(), 64 * 1024);
+
+        let capped = backend
+            .send(HttpRequest::get(server.uri()).max_response_bytes(1024))
+            .await
+            .unwrap();
+        assert!(capped.body.len() < 64 * 1024);
+        assert_eq!(capped.content_length(), Some(64 * 1024));
+    }
+}
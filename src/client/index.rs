@@ -0,0 +1,458 @@
+//! crates.io sparse index client.
+//!
+//! Reads per-crate files from [`index.crates.io`](https://index.crates.io),
+//! the registry index cargo itself uses. Each file is newline-delimited JSON
+//! with one record per published version, including that version's
+//! dependencies and features, so a single fetch covers what the JSON API
+//! needs one request per version for. The index is served from a CDN and is
+//! not subject to the API's crawler rate limit.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+#[cfg(feature = "mcp")]
+use schemars::JsonSchema;
+use semver::Version;
+use serde::{Deserialize, Serialize};
+
+use super::http::{HttpBackend, HttpError, HttpRequest, HttpResponse, ReqwestBackend};
+use super::types::Dependency;
+
+/// The public crates.io sparse index.
+pub const CRATES_IO_INDEX: &str = "https://index.crates.io";
+
+/// Errors returned by the sparse index client.
+#[derive(Debug, thiserror::Error)]
+pub enum IndexError {
+    /// HTTP transport error.
+    #[error("HTTP error: {0}")]
+    Http(#[from] HttpError),
+
+    /// The registry's `config.json` is not valid.
+    #[error("invalid index config: {0}")]
+    Config(#[source] serde_json::Error),
+
+    /// The crate has no index entry.
+    #[error("crate not found in index: {0}")]
+    NotFound(String),
+
+    /// The name cannot be a crate name, so it has no place in the index.
+    #[error("invalid crate name: {0:?}")]
+    InvalidName(String),
+
+    /// Non-200 response from the index.
+    #[error("index error ({status}) for {name}")]
+    Api { status: u16, name: String },
+
+    /// A line of the index file is not a valid version record.
+    #[error("invalid index entry for {name}: {source}")]
+    Parse {
+        name: String,
+        source: serde_json::Error,
+    },
+}
+
+/// A registry's `config.json`, found at the root of its index.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "mcp", derive(JsonSchema))]
+pub struct IndexConfig {
+    /// Download URL template for `.crate` files.
+    pub dl: String,
+    /// Base URL of the registry's web API (without `/api/v1`), if it has one.
+    #[serde(default)]
+    pub api: Option<String>,
+    /// Whether every request, including index reads, needs a token.
+    #[serde(default, rename = "auth-required")]
+    pub auth_required: bool,
+}
+
+/// One published version as recorded in the index.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "mcp", derive(JsonSchema))]
+pub struct IndexVersion {
+    pub name: String,
+    pub vers: String,
+    #[serde(default)]
+    pub deps: Vec<IndexDependency>,
+    /// SHA-256 of the `.crate` file.
+    pub cksum: String,
+    #[serde(default)]
+    pub features: HashMap<String, Vec<String>>,
+    /// Features using `dep:` or `?` syntax, kept apart so older cargo
+    /// versions can still read `features`.
+    #[serde(default)]
+    pub features2: Option<HashMap<String, Vec<String>>>,
+    #[serde(default)]
+    pub yanked: bool,
+    #[serde(default)]
+    pub links: Option<String>,
+    #[serde(default)]
+    pub rust_version: Option<String>,
+    /// Index schema version (absent means 1).
+    #[serde(default)]
+    pub v: Option<u32>,
+}
+
+/// A dependency of an index version.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "mcp", derive(JsonSchema))]
+pub struct IndexDependency {
+    /// Name the dependency is referred to by; the crate name unless
+    /// `package` is set.
+    pub name: String,
+    pub req: String,
+    #[serde(default)]
+    pub features: Vec<String>,
+    #[serde(default)]
+    pub optional: bool,
+    #[serde(default = "default_true")]
+    pub default_features: bool,
+    #[serde(default)]
+    pub target: Option<String>,
+    /// `normal`, `dev` or `build` (absent means `normal`).
+    #[serde(default)]
+    pub kind: Option<String>,
+    /// Index URL of the dependency's registry, if not this one.
+    #[serde(default)]
+    pub registry: Option<String>,
+    /// The real crate name of a renamed dependency.
+    #[serde(default)]
+    pub package: Option<String>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl IndexVersion {
+    /// `features` and `features2` merged into one table, as cargo reads them.
+    pub fn all_features(&self) -> HashMap<String, Vec<String>> {
+        let mut features = self.features.clone();
+        for (name, entries) in self.features2.iter().flatten() {
+            features
+                .entry(name.clone())
+                .or_default()
+                .extend(entries.iter().cloned());
+        }
+        features
+    }
+
+    /// Dependencies in the shape the JSON API reports them.
+    pub fn dependencies(&self) -> Vec<Dependency> {
+        self.deps.iter().map(Dependency::from).collect()
+    }
+}
+
+impl From<&IndexDependency> for Dependency {
+    fn from(dep: &IndexDependency) -> Self {
+        Self {
+            crate_id: dep.package.clone().unwrap_or_else(|| dep.name.clone()),
+            req: dep.req.clone(),
+            kind: dep.kind.clone().unwrap_or_else(|| "normal".to_string()),
+            optional: dep.optional,
+            version_id: 0,
+            features: dep.features.clone(),
+            default_features: dep.default_features,
+            target: dep.target.clone(),
+            registry: dep.registry.clone(),
+            explicit_name: dep.package.as_ref().map(|_| dep.name.clone()),
+        }
+    }
+}
+
+/// The newest non-yanked version in `versions`, preferring stable releases.
+pub fn latest_version(versions: &[IndexVersion]) -> Option<&IndexVersion> {
+    let parsed = || {
+        versions
+            .iter()
+            .filter(|v| !v.yanked)
+            .filter_map(|v| Some((Version::parse(&v.vers).ok()?, v)))
+    };
+    parsed()
+        .filter(|(version, _)| version.pre.is_empty())
+        .max_by(|a, b| a.0.cmp(&b.0))
+        .or_else(|| parsed().max_by(|a, b| a.0.cmp(&b.0)))
+        .map(|(_, v)| v)
+}
+
+/// Path of a crate's file within the index, e.g. `se/rd/serde`.
+///
+/// Crate names are non-empty ASCII alphanumerics, `-` and `_`; anything else
+/// is rejected rather than looked up.
+///
+/// # Examples
+///
+/// ```
+/// use cratesio_mcp::client::index::index_path;
+///
+/// assert_eq!(index_path("Serde").unwrap(), "se/rd/serde");
+/// assert_eq!(index_path("syn").unwrap(), "3/s/syn");
+/// assert_eq!(index_path("cc").unwrap(), "2/cc");
+/// assert!(index_path("../config").is_err());
+/// ```
+pub fn index_path(name: &str) -> Result<String, IndexError> {
+    let valid = !name.is_empty()
+        && name
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_');
+    if !valid {
+        return Err(IndexError::InvalidName(name.to_string()));
+    }
+    let name = name.to_ascii_lowercase();
+    Ok(match name.len() {
+        1 => format!("1/{name}"),
+        2 => format!("2/{name}"),
+        3 => format!("3/{}/{name}", &name[..1]),
+        _ => format!("{}/{}/{name}", &name[..2], &name[2..4]),
+    })
+}
+
+/// Client for the crates.io sparse index.
+pub struct SparseIndexClient {
+    http: Arc<dyn HttpBackend>,
+    user_agent: String,
+    base_url: String,
+    auth: Option<String>,
+}
+
+impl SparseIndexClient {
+    /// Create a new client for [`CRATES_IO_INDEX`] with the given user agent
+    /// and outbound timeout.
+    pub fn new(user_agent: &str, timeout: Duration) -> Result<Self, IndexError> {
+        Self::with_base_url(user_agent, timeout, CRATES_IO_INDEX)
+    }
+
+    /// Create a new client with a custom base URL (for testing or mirrors).
+    pub fn with_base_url(
+        user_agent: &str,
+        timeout: Duration,
+        base_url: &str,
+    ) -> Result<Self, IndexError> {
+        Ok(Self {
+            http: Arc::new(ReqwestBackend::new(timeout)?),
+            user_agent: user_agent.to_string(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            auth: None,
+        })
+    }
+
+    /// Send requests through `backend` instead of the default reqwest client.
+    ///
+    /// The outbound timeout given to the constructor only applies to the
+    /// default backend.
+    pub fn with_backend(mut self, backend: Arc<dyn HttpBackend>) -> Self {
+        self.http = backend;
+        self
+    }
+
+    /// Send `token` with every request, for registries whose `config.json`
+    /// sets `auth-required`.
+    pub fn with_auth(mut self, token: impl Into<String>) -> Self {
+        self.auth = Some(token.into());
+        self
+    }
+
+    /// The index URL this client reads from.
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    async fn get(&self, path: &str) -> Result<HttpResponse, HttpError> {
+        let mut request = HttpRequest::get(format!("{}/{}", self.base_url, path))
+            .header(reqwest::header::USER_AGENT, &self.user_agent);
+        if let Some(token) = &self.auth {
+            request = request.header(reqwest::header::AUTHORIZATION, token);
+        }
+        self.http.send(request).await
+    }
+
+    /// Fetch the registry's `config.json`, which names its API and download
+    /// endpoints.
+    pub async fn config(&self) -> Result<IndexConfig, IndexError> {
+        let resp = self.get("config.json").await?;
+        let status = resp.status;
+        if !status.is_success() {
+            return Err(IndexError::Api {
+                status: status.as_u16(),
+                name: "config.json".to_string(),
+            });
+        }
+        resp.json_body().map_err(IndexError::Config)
+    }
+
+    /// Every published version of a crate, oldest first, as recorded in the
+    /// index.
+    pub async fn crate_versions(&self, name: &str) -> Result<Vec<IndexVersion>, IndexError> {
+        let resp = self.get(&index_path(name)?).await?;
+
+        let status = resp.status;
+        // The index answers 403 on S3-backed mirrors for missing files
+        if status == reqwest::StatusCode::NOT_FOUND || status == reqwest::StatusCode::FORBIDDEN {
+            return Err(IndexError::NotFound(name.to_string()));
+        }
+        if !status.is_success() {
+            return Err(IndexError::Api {
+                status: status.as_u16(),
+                name: name.to_string(),
+            });
+        }
+
+        parse_index_file(name, &resp.text())
+    }
+
+    /// A single version's index record.
+    pub async fn crate_version(
+        &self,
+        name: &str,
+        version: &str,
+    ) -> Result<IndexVersion, IndexError> {
+        self.crate_versions(name)
+            .await?
+            .into_iter()
+            .find(|v| v.vers == version)
+            .ok_or_else(|| IndexError::NotFound(format!("{name} v{version}")))
+    }
+}
+
+/// Parse the newline-delimited records of an index file.
+fn parse_index_file(name: &str, text: &str) -> Result<Vec<IndexVersion>, IndexError> {
+    text.lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            serde_json::from_str(line).map_err(|source| IndexError::Parse {
+                name: name.to_string(),
+                source,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    const SERDE_INDEX: &str = concat!(
+        r#"{"name":"serde","vers":"1.0.0","deps":[],"cksum":"aa","features":{},"yanked":true}"#,
+        "\n",
+        r#"{"name":"serde","vers":"1.0.219","deps":[{"name":"serde_derive","req":"=1.0.219","features":[],"optional":true,"default_features":true,"target":null,"kind":"normal"},{"name":"derive_dev","req":"^1","features":["x"],"optional":false,"default_features":false,"target":"cfg(unix)","kind":"dev","package":"serde_derive"}],"cksum":"bb","features":{"default":["std"],"std":[]},"features2":{"derive":["dep:serde_derive"]},"yanked":false,"rust_version":"1.31","v":2}"#,
+        "\n",
+        r#"{"name":"serde","vers":"2.0.0-alpha.1","deps":[],"cksum":"cc","features":{},"yanked":false}"#,
+        "\n",
+    );
+
+    fn client(server: &MockServer) -> SparseIndexClient {
+        SparseIndexClient::with_base_url("test", Duration::from_secs(5), &server.uri()).unwrap()
+    }
+
+    #[test]
+    fn index_paths_follow_cargo_layout() {
+        assert_eq!(index_path("a").unwrap(), "1/a");
+        assert_eq!(index_path("ab").unwrap(), "2/ab");
+        assert_eq!(index_path("abc").unwrap(), "3/a/abc");
+        assert_eq!(index_path("tokio").unwrap(), "to/ki/tokio");
+        assert_eq!(index_path("Inflector").unwrap(), "in/fl/inflector");
+    }
+
+    #[tokio::test]
+    async fn invalid_names_are_rejected_without_a_request() {
+        let server = MockServer::start().await;
+        let client = client(&server);
+
+        for name in ["éa", "", "a/b", "../config"] {
+            let err = client.crate_versions(name).await.unwrap_err();
+            assert!(matches!(err, IndexError::InvalidName(ref n) if n == name));
+        }
+        assert!(server.received_requests().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn crate_versions_parses_every_line() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/se/rd/serde"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(SERDE_INDEX))
+            .mount(&server)
+            .await;
+
+        let versions = client(&server).crate_versions("serde").await.unwrap();
+        assert_eq!(versions.len(), 3);
+        assert!(versions[0].yanked);
+
+        let v = &versions[1];
+        assert_eq!(v.rust_version.as_deref(), Some("1.31"));
+        assert_eq!(v.v, Some(2));
+        assert_eq!(v.all_features()["derive"], vec!["dep:serde_derive"]);
+        assert!(v.all_features().contains_key("std"));
+
+        let deps = v.dependencies();
+        assert_eq!(deps[0].crate_id, "serde_derive");
+        assert!(deps[0].optional);
+        // Renamed dependency resolves to the real crate name
+        assert_eq!(deps[1].crate_id, "serde_derive");
+        assert_eq!(deps[1].kind, "dev");
+        assert!(!deps[1].default_features);
+        assert_eq!(deps[1].target.as_deref(), Some("cfg(unix)"));
+
+        // Stable releases win over a newer pre-release
+        assert_eq!(latest_version(&versions).unwrap().vers, "1.0.219");
+    }
+
+    #[tokio::test]
+    async fn crate_version_picks_one_record() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/se/rd/serde"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(SERDE_INDEX))
+            .mount(&server)
+            .await;
+
+        let client = client(&server);
+        assert_eq!(
+            client.crate_version("serde", "1.0.0").await.unwrap().cksum,
+            "aa"
+        );
+        let err = client.crate_version("serde", "9.9.9").await.unwrap_err();
+        assert!(matches!(err, IndexError::NotFound(_)));
+    }
+
+    #[tokio::test]
+    async fn config_is_discovered_with_auth() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/config.json"))
+            .and(header("authorization", "secret"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "dl": "https://example.com/api/v1/crates",
+                "api": "https://example.com",
+                "auth-required": true
+            })))
+            .mount(&server)
+            .await;
+
+        let config = client(&server).with_auth("secret").config().await.unwrap();
+        assert_eq!(config.api.as_deref(), Some("https://example.com"));
+        assert!(config.auth_required);
+    }
+
+    #[tokio::test]
+    async fn missing_crate_is_not_found() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/no/pe/nope"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&server)
+            .await;
+
+        let err = client(&server).crate_versions("nope").await.unwrap_err();
+        assert!(matches!(err, IndexError::NotFound(name) if name == "nope"));
+    }
+
+    #[test]
+    fn invalid_line_is_a_parse_error() {
+        let err = parse_index_file("x", "{not json}\n").unwrap_err();
+        assert!(err.to_string().starts_with("invalid index entry for x"));
+    }
+}
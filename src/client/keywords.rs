@@ -1,7 +1,10 @@
 //! Keyword-related API endpoints.
 
+use futures::Stream;
+
 use super::CratesIoClient;
 use super::error::Error;
+use super::pagination;
 use super::types::{Keyword, KeywordsPage};
 use super::wire::KeywordResponse;
 
@@ -12,6 +15,9 @@ impl CratesIoClient {
         page: Option<u64>,
         per_page: Option<u64>,
     ) -> Result<KeywordsPage, Error> {
+        if let Some(dump) = self.dump() {
+            return Ok(dump.keywords(page, per_page));
+        }
         let mut params: Vec<(String, String)> = Vec::new();
         if let Some(page) = page {
             params.push(("page".into(), page.to_string()));
@@ -22,8 +28,27 @@ impl CratesIoClient {
         self.get_json_query("/keywords", &params).await
     }
 
+    /// Stream every keyword, fetching pages as they're read.
+    pub fn keywords_stream(&self) -> impl Stream<Item = Result<Keyword, Error>> {
+        let params = vec![("per_page".to_string(), pagination::MAX_PER_PAGE.to_string())];
+        pagination::items(pagination::pages(params, move |params| async move {
+            if let Some(dump) = self.dump() {
+                let page = pagination::param(&params, "page");
+                let per_page = pagination::param(&params, "per_page");
+                return Ok(dump.keywords(page, per_page));
+            }
+            self.get_json_query::<KeywordsPage>("/keywords", &params)
+                .await
+        }))
+    }
+
     /// Get a single keyword by ID.
     pub async fn keyword(&self, id: &str) -> Result<Keyword, Error> {
+        if let Some(dump) = self.dump() {
+            return dump
+                .keyword(id)
+                .ok_or_else(|| Error::NotFound(format!("/keywords/{id}")));
+        }
         let resp: KeywordResponse = self.get_json(&format!("/keywords/{id}")).await?;
         Ok(resp.keyword)
     }
@@ -0,0 +1,171 @@
+//! `Cargo.lock` parsing.
+//!
+//! Reads the resolved package graph out of a lockfile so every locked crate
+//! can be checked at its exact version (see [`OsvClient::audit_lockfile`]).
+//!
+//! [`OsvClient::audit_lockfile`]: super::osv::OsvClient::audit_lockfile
+
+#[cfg(feature = "mcp")]
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// `source` prefixes that identify packages published to crates.io (git
+/// index and sparse index protocols).
+const CRATES_IO_SOURCES: &[&str] = &[
+    "registry+https://github.com/rust-lang/crates.io-index",
+    "sparse+https://index.crates.io/",
+];
+
+/// Errors returned when parsing a `Cargo.lock`.
+#[derive(Debug, thiserror::Error)]
+pub enum LockfileError {
+    /// The text is not valid TOML or does not match the lockfile layout.
+    #[error("invalid Cargo.lock: {0}")]
+    Toml(#[from] toml::de::Error),
+}
+
+/// A parsed `Cargo.lock`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "mcp", derive(JsonSchema))]
+pub struct Lockfile {
+    /// Lockfile format version (absent in v1 lockfiles).
+    #[serde(default)]
+    pub version: Option<u32>,
+    /// Every `[[package]]` entry, in file order.
+    #[serde(default, rename = "package")]
+    pub packages: Vec<LockedPackage>,
+}
+
+/// A single `[[package]]` entry from a lockfile.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "mcp", derive(JsonSchema))]
+pub struct LockedPackage {
+    pub name: String,
+    /// The exact resolved version.
+    pub version: String,
+    /// Where the package came from (`registry+...`, `sparse+...`, `git+...`).
+    /// Absent for workspace members and path dependencies.
+    #[serde(default)]
+    pub source: Option<String>,
+    #[serde(default)]
+    pub checksum: Option<String>,
+    /// Dependencies as written in the lockfile (`name` or `name version`).
+    #[serde(default)]
+    pub dependencies: Vec<String>,
+}
+
+impl LockedPackage {
+    /// Whether this package was resolved from the crates.io registry.
+    pub fn is_crates_io(&self) -> bool {
+        self.source
+            .as_deref()
+            .is_some_and(|s| CRATES_IO_SOURCES.iter().any(|p| s.starts_with(p)))
+    }
+}
+
+impl Lockfile {
+    /// Parse the text of a `Cargo.lock`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cratesio_mcp::client::lockfile::Lockfile;
+    ///
+    /// let lock = Lockfile::parse(r#"
+    /// version = 4
+    ///
+    /// [[package]]
+    /// name = "serde"
+    /// version = "1.0.219"
+    /// source = "registry+https://github.com/rust-lang/crates.io-index"
+    /// "#).unwrap();
+    /// assert_eq!(lock.packages[0].version, "1.0.219");
+    /// ```
+    pub fn parse(text: &str) -> Result<Self, LockfileError> {
+        Ok(toml::from_str(text)?)
+    }
+
+    /// Packages resolved from crates.io -- the only ones OSV's `crates.io`
+    /// ecosystem can describe. Path, git, and alternative-registry packages
+    /// are excluded.
+    pub fn crates_io_packages(&self) -> impl Iterator<Item = &LockedPackage> {
+        self.packages.iter().filter(|p| p.is_crates_io())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOCKFILE: &str = r#"
+# This file is automatically @generated by Cargo.
+# It is not intended for manual editing.
+version = 4
+
+[[package]]
+name = "my-app"
+version = "0.1.0"
+dependencies = [
+ "serde",
+ "time 0.1.45",
+]
+
+[[package]]
+name = "serde"
+version = "1.0.219"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+checksum = "5f0e2c6ed6606019b4e29e69dbaba95b11854410e5347d525002456dbbb786b6"
+
+[[package]]
+name = "time"
+version = "0.1.45"
+source = "sparse+https://index.crates.io/"
+
+[[package]]
+name = "forked"
+version = "0.2.0"
+source = "git+https://github.com/example/forked#0123abcd"
+"#;
+
+    #[test]
+    fn parses_packages() {
+        let lock = Lockfile::parse(LOCKFILE).unwrap();
+        assert_eq!(lock.version, Some(4));
+        assert_eq!(lock.packages.len(), 4);
+        assert_eq!(lock.packages[0].name, "my-app");
+        assert!(lock.packages[0].source.is_none());
+        assert_eq!(lock.packages[0].dependencies, vec!["serde", "time 0.1.45"]);
+        assert!(lock.packages[1].checksum.is_some());
+    }
+
+    #[test]
+    fn crates_io_packages_excludes_path_and_git() {
+        let lock = Lockfile::parse(LOCKFILE).unwrap();
+        let names: Vec<_> = lock.crates_io_packages().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["serde", "time"]);
+    }
+
+    #[test]
+    fn v1_lockfile_without_version_key() {
+        let lock = Lockfile::parse(
+            r#"
+[[package]]
+name = "libc"
+version = "0.2.40"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+
+[metadata]
+"checksum libc 0.2.40 (registry+https://github.com/rust-lang/crates.io-index)" = "abc"
+"#,
+        )
+        .unwrap();
+        assert!(lock.version.is_none());
+        assert_eq!(lock.crates_io_packages().count(), 1);
+    }
+
+    #[test]
+    fn invalid_toml_is_an_error() {
+        let err = Lockfile::parse("[[package]\nname =").unwrap_err();
+        assert!(err.to_string().starts_with("invalid Cargo.lock"));
+    }
+}
@@ -0,0 +1,332 @@
+//! `Cargo.toml` parsing.
+//!
+//! Extracts the declared dependencies of a manifest -- every dependency
+//! section, platform-specific `[target.'cfg(..)'.*]` tables, renamed
+//! `package = "..."` entries and `[workspace.dependencies]` -- so each
+//! requirement can be checked against the registry.
+
+#[cfg(feature = "mcp")]
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use toml::{Table, Value};
+
+/// Dependency tables and the kind each one declares. Cargo accepts the
+/// underscore spellings too.
+const SECTIONS: &[(&str, DependencyKind)] = &[
+    ("dependencies", DependencyKind::Normal),
+    ("dev-dependencies", DependencyKind::Dev),
+    ("dev_dependencies", DependencyKind::Dev),
+    ("build-dependencies", DependencyKind::Build),
+    ("build_dependencies", DependencyKind::Build),
+];
+
+/// Errors returned when parsing a `Cargo.toml`.
+#[derive(Debug, thiserror::Error)]
+pub enum ManifestError {
+    /// The text is not valid TOML.
+    #[error("invalid Cargo.toml: {0}")]
+    Toml(#[from] toml::de::Error),
+}
+
+/// Which section a dependency is declared in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "mcp", derive(JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum DependencyKind {
+    Normal,
+    Dev,
+    Build,
+    /// Declared in `[workspace.dependencies]` for members to inherit.
+    Workspace,
+}
+
+/// Where a dependency comes from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "mcp", derive(JsonSchema))]
+#[serde(rename_all = "lowercase", tag = "type")]
+pub enum DependencySource {
+    /// A registry: crates.io when `registry` is `None`.
+    Registry { registry: Option<String> },
+    /// A local `path` dependency without a registry version.
+    Path { path: String },
+    /// A `git` dependency without a registry version.
+    Git { url: String },
+    /// `workspace = true` with no matching `[workspace.dependencies]` entry
+    /// in this manifest.
+    Workspace,
+}
+
+/// A dependency declared in a manifest.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "mcp", derive(JsonSchema))]
+pub struct ManifestDependency {
+    /// The key the dependency is declared under (what code refers to).
+    pub name: String,
+    /// The crate name in the registry; differs from `name` for renamed
+    /// (`package = "..."`) dependencies.
+    pub package: String,
+    /// Version requirement, if one was given.
+    pub req: Option<String>,
+    pub kind: DependencyKind,
+    /// Platform the dependency is limited to, e.g. `cfg(windows)`.
+    pub target: Option<String>,
+    pub optional: bool,
+    pub source: DependencySource,
+}
+
+impl ManifestDependency {
+    /// Whether the dependency resolves from crates.io with a version
+    /// requirement. Path and git dependencies that also give a `version`
+    /// count, since that is what gets published.
+    pub fn is_crates_io(&self) -> bool {
+        self.req.is_some()
+            && matches!(
+                self.source,
+                DependencySource::Registry { registry: None }
+                    | DependencySource::Path { .. }
+                    | DependencySource::Git { .. }
+            )
+    }
+}
+
+/// The dependencies declared in a `Cargo.toml`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "mcp", derive(JsonSchema))]
+pub struct Manifest {
+    /// `package.name`, absent for virtual workspace manifests.
+    pub package: Option<String>,
+    /// Every declared dependency, by section and then by name.
+    pub dependencies: Vec<ManifestDependency>,
+}
+
+impl Manifest {
+    /// Parse the text of a `Cargo.toml`.
+    ///
+    /// Entries using `workspace = true` are resolved against the manifest's
+    /// own `[workspace.dependencies]` when present; otherwise they are kept
+    /// with a [`DependencySource::Workspace`] source.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cratesio_mcp::client::manifest::Manifest;
+    ///
+    /// let manifest = Manifest::parse(r#"
+    /// [package]
+    /// name = "app"
+    ///
+    /// [dependencies]
+    /// serde = { version = "1", features = ["derive"] }
+    /// json = { package = "serde_json", version = "1.0.100" }
+    /// "#).unwrap();
+    /// let json = manifest.dependencies.iter().find(|d| d.name == "json").unwrap();
+    /// assert_eq!(json.package, "serde_json");
+    /// ```
+    pub fn parse(text: &str) -> Result<Self, ManifestError> {
+        let doc: Table = toml::from_str(text)?;
+
+        let workspace: Vec<ManifestDependency> = doc
+            .get("workspace")
+            .and_then(|w| w.get("dependencies"))
+            .and_then(Value::as_table)
+            .map(|table| parse_section(table, DependencyKind::Workspace, None))
+            .unwrap_or_default();
+
+        let mut dependencies = Vec::new();
+        for (section, kind) in SECTIONS {
+            if let Some(table) = doc.get(*section).and_then(Value::as_table) {
+                dependencies.extend(parse_section(table, *kind, None));
+            }
+        }
+        if let Some(targets) = doc.get("target").and_then(Value::as_table) {
+            for (target, tables) in targets {
+                for (section, kind) in SECTIONS {
+                    if let Some(table) = tables.get(*section).and_then(Value::as_table) {
+                        dependencies.extend(parse_section(table, *kind, Some(target)));
+                    }
+                }
+            }
+        }
+
+        // Fill in inherited entries from `[workspace.dependencies]`
+        for dep in &mut dependencies {
+            if dep.source != DependencySource::Workspace {
+                continue;
+            }
+            if let Some(inherited) = workspace.iter().find(|w| w.name == dep.name) {
+                dep.package = inherited.package.clone();
+                dep.req = inherited.req.clone();
+                dep.source = inherited.source.clone();
+            }
+        }
+        dependencies.extend(workspace);
+
+        Ok(Self {
+            package: doc
+                .get("package")
+                .and_then(|p| p.get("name"))
+                .and_then(Value::as_str)
+                .map(str::to_string),
+            dependencies,
+        })
+    }
+}
+
+fn parse_section(
+    table: &Table,
+    kind: DependencyKind,
+    target: Option<&str>,
+) -> Vec<ManifestDependency> {
+    table
+        .iter()
+        .map(|(name, spec)| {
+            let mut dep = ManifestDependency {
+                name: name.clone(),
+                package: name.clone(),
+                req: None,
+                kind,
+                target: target.map(str::to_string),
+                optional: false,
+                source: DependencySource::Registry { registry: None },
+            };
+            match spec {
+                Value::String(req) => dep.req = Some(req.clone()),
+                Value::Table(fields) => {
+                    let field = |key: &str| fields.get(key).and_then(Value::as_str);
+                    if let Some(package) = field("package") {
+                        dep.package = package.to_string();
+                    }
+                    dep.req = field("version").map(str::to_string);
+                    dep.optional = fields
+                        .get("optional")
+                        .and_then(Value::as_bool)
+                        .unwrap_or(false);
+                    dep.source = if fields.get("workspace").and_then(Value::as_bool) == Some(true) {
+                        DependencySource::Workspace
+                    } else if let Some(path) = field("path") {
+                        DependencySource::Path {
+                            path: path.to_string(),
+                        }
+                    } else if let Some(url) = field("git") {
+                        DependencySource::Git {
+                            url: url.to_string(),
+                        }
+                    } else {
+                        DependencySource::Registry {
+                            registry: field("registry").map(str::to_string),
+                        }
+                    };
+                }
+                _ => {}
+            }
+            dep
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MANIFEST: &str = r#"
+[package]
+name = "my-app"
+version = "0.1.0"
+
+[dependencies]
+serde = "1.0"
+tokio = { version = "1.40", features = ["full"], optional = true }
+json = { package = "serde_json", version = "1" }
+local = { path = "../local" }
+published-local = { path = "../pl", version = "0.3" }
+forked = { git = "https://github.com/example/forked" }
+internal = { version = "2", registry = "company" }
+anyhow = { workspace = true }
+
+[dev-dependencies]
+wiremock = "0.6"
+
+[build_dependencies]
+cc = "1"
+
+[target.'cfg(windows)'.dependencies]
+windows-sys = "0.59"
+
+[workspace.dependencies]
+anyhow = "1.0.80"
+"#;
+
+    fn find<'a>(
+        manifest: &'a Manifest,
+        name: &str,
+        kind: DependencyKind,
+    ) -> &'a ManifestDependency {
+        manifest
+            .dependencies
+            .iter()
+            .find(|d| d.name == name && d.kind == kind)
+            .unwrap_or_else(|| panic!("{name} not found"))
+    }
+
+    #[test]
+    fn parses_every_section() {
+        let manifest = Manifest::parse(MANIFEST).unwrap();
+        assert_eq!(manifest.package.as_deref(), Some("my-app"));
+
+        let tokio = find(&manifest, "tokio", DependencyKind::Normal);
+        assert_eq!(tokio.req.as_deref(), Some("1.40"));
+        assert!(tokio.optional);
+
+        assert_eq!(
+            find(&manifest, "wiremock", DependencyKind::Dev)
+                .req
+                .as_deref(),
+            Some("0.6")
+        );
+        assert_eq!(
+            find(&manifest, "cc", DependencyKind::Build).req.as_deref(),
+            Some("1")
+        );
+
+        let windows = find(&manifest, "windows-sys", DependencyKind::Normal);
+        assert_eq!(windows.target.as_deref(), Some("cfg(windows)"));
+    }
+
+    #[test]
+    fn renamed_and_inherited_dependencies() {
+        let manifest = Manifest::parse(MANIFEST).unwrap();
+
+        let json = find(&manifest, "json", DependencyKind::Normal);
+        assert_eq!(json.package, "serde_json");
+
+        let anyhow = find(&manifest, "anyhow", DependencyKind::Normal);
+        assert_eq!(anyhow.req.as_deref(), Some("1.0.80"));
+        assert!(anyhow.is_crates_io());
+        assert!(find(&manifest, "anyhow", DependencyKind::Workspace).is_crates_io());
+    }
+
+    #[test]
+    fn non_registry_sources() {
+        let manifest = Manifest::parse(MANIFEST).unwrap();
+        let crates_io = |name: &str| find(&manifest, name, DependencyKind::Normal).is_crates_io();
+
+        assert!(crates_io("serde"));
+        assert!(!crates_io("local"));
+        assert!(crates_io("published-local"));
+        assert!(!crates_io("forked"));
+        assert!(!crates_io("internal"));
+    }
+
+    #[test]
+    fn unresolved_workspace_inheritance() {
+        let manifest = Manifest::parse("[dependencies]\nserde.workspace = true\n").unwrap();
+        assert_eq!(manifest.dependencies[0].source, DependencySource::Workspace);
+        assert!(manifest.dependencies[0].req.is_none());
+    }
+
+    #[test]
+    fn invalid_toml_is_an_error() {
+        let err = Manifest::parse("[dependencies\nserde =").unwrap_err();
+        assert!(err.to_string().starts_with("invalid Cargo.toml"));
+    }
+}
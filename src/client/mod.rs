@@ -3,10 +3,21 @@
 //! Async client for the crates.io REST API, built on reqwest with built-in
 //! rate limiting. Supports both anonymous and authenticated access.
 
+pub mod cassette;
+pub mod credentials;
+pub mod disk_cache;
 pub mod docsrs;
+pub mod dump;
 pub mod error;
+pub mod http;
+pub mod index;
+pub mod lockfile;
+pub mod manifest;
 pub mod osv;
 pub mod query;
+pub mod resilience;
+pub mod resolve;
+pub mod single_flight;
 pub mod types;
 pub(crate) mod wire;
 
@@ -16,6 +27,7 @@ mod crates;
 mod keywords;
 mod metadata;
 mod owners;
+mod pagination;
 mod publish;
 mod teams;
 mod tokens;
@@ -29,13 +41,20 @@ mod tests;
 use std::sync::Arc;
 use std::time::Duration;
 
+use bytes::Bytes;
+use reqwest::header::{AUTHORIZATION, USER_AGENT};
 use serde::Serialize;
-use serde::de::DeserializeOwned;
+use serde::de::{DeserializeOwned, IgnoredAny};
 use tokio::sync::Mutex;
 use tokio::time::Instant;
 
+use disk_cache::{DiskCache, ResourceClass, Validators};
+use dump::DumpStore;
 pub use error::Error;
+use http::{HttpBackend, HttpRequest, HttpResponse, Method, ReqwestBackend};
 pub use query::{CratesQuery, CratesQueryBuilder, Sort};
+use resilience::{CircuitBreaker, RetryPolicy};
+use single_flight::SingleFlight;
 pub use types::*;
 
 /// Maximum size in bytes for a text resource read (e.g. README), to bound
@@ -63,17 +82,26 @@ impl std::fmt::Debug for Auth {
 ///
 /// Includes built-in rate limiting to respect the crates.io crawling policy.
 /// Supports optional authentication via API token for write operations.
-/// Retries transient failures (429, 5xx) with exponential backoff.
+/// Retries transient failures (429, 5xx) with jittered exponential backoff,
+/// honouring `Retry-After`, and fails fast while its circuit breaker is open.
+/// Identical concurrent GETs share a single request.
 pub struct CratesIoClient {
-    http: reqwest::Client,
+    http: Arc<dyn HttpBackend>,
+    user_agent: String,
     base_url: String,
     /// Base URL for fetching raw GitHub content (default: `https://raw.githubusercontent.com`).
     pub(crate) github_raw_base_url: String,
     rate_limit: Duration,
     last_request: Arc<Mutex<Option<Instant>>>,
     auth: Option<Auth>,
-    max_retries: u32,
-    initial_backoff: Duration,
+    retry: RetryPolicy,
+    breaker: Arc<CircuitBreaker>,
+    /// Offline data that answers read-only queries instead of the API
+    dump: Option<Arc<DumpStore>>,
+    /// Persistent cache for `get_json` responses
+    disk_cache: Option<Arc<DiskCache>>,
+    /// JSON bodies being fetched, by URL, for concurrent callers to share
+    in_flight: SingleFlight<Bytes, Error>,
 }
 
 impl CratesIoClient {
@@ -89,27 +117,36 @@ impl CratesIoClient {
         timeout: Duration,
         base_url: &str,
     ) -> Result<Self, Error> {
-        let http = reqwest::Client::builder()
-            .user_agent(user_agent)
-            .timeout(timeout)
-            .build()?;
         Ok(Self {
-            http,
+            http: Arc::new(ReqwestBackend::new(timeout)?),
+            user_agent: user_agent.to_string(),
             base_url: base_url.trim_end_matches('/').to_string(),
             github_raw_base_url: "https://raw.githubusercontent.com".to_string(),
             rate_limit,
             last_request: Arc::new(Mutex::new(None)),
             auth: None,
-            max_retries: 3,
-            initial_backoff: Duration::from_millis(500),
+            retry: RetryPolicy::default(),
+            breaker: Arc::new(CircuitBreaker::new("crates.io")),
+            dump: None,
+            disk_cache: None,
+            in_flight: SingleFlight::default(),
         })
     }
 
+    /// Send requests through `backend` instead of the default reqwest client.
+    ///
+    /// The outbound timeout given to the constructor only applies to the
+    /// default backend.
+    pub fn with_backend(mut self, backend: Arc<dyn HttpBackend>) -> Self {
+        self.http = backend;
+        self
+    }
+
     /// Set the maximum number of retries for transient failures.
     ///
     /// Returns `self` for builder-style chaining.
     pub fn with_max_retries(mut self, max_retries: u32) -> Self {
-        self.max_retries = max_retries;
+        self.retry.max_retries = max_retries;
         self
     }
 
@@ -117,16 +154,53 @@ impl CratesIoClient {
     ///
     /// Returns `self` for builder-style chaining.
     pub fn with_initial_backoff(mut self, backoff: Duration) -> Self {
-        self.initial_backoff = backoff;
+        self.retry.initial_backoff = backoff;
+        self
+    }
+
+    /// Share a circuit breaker with other clients of the same upstream.
+    ///
+    /// Returns `self` for builder-style chaining.
+    pub fn with_circuit_breaker(mut self, breaker: Arc<CircuitBreaker>) -> Self {
+        self.breaker = breaker;
         self
     }
 
+    /// The breaker guarding this client's API requests.
+    pub fn circuit_breaker(&self) -> &CircuitBreaker {
+        &self.breaker
+    }
+
     /// Override the base URL used for raw GitHub content (for testing).
     pub fn with_github_raw_url(mut self, url: &str) -> Self {
         self.github_raw_base_url = url.trim_end_matches('/').to_string();
         self
     }
 
+    /// Answer queries from a crates.io database dump instead of the API.
+    ///
+    /// Search, crate, dependency, reverse-dependency, owner, category and
+    /// keyword lookups are served from `dump`; every other request fails with
+    /// [`Error::Offline`] rather than reaching the network.
+    pub fn with_dump(mut self, dump: Arc<DumpStore>) -> Self {
+        self.dump = Some(dump);
+        self
+    }
+
+    /// The dump queries are answered from, if offline.
+    pub(crate) fn dump(&self) -> Option<&DumpStore> {
+        self.dump.as_deref()
+    }
+
+    /// Keep JSON responses in a persistent cache, shared across restarts.
+    ///
+    /// A version's dependency and author lists are cached until evicted;
+    /// other responses expire after the cache's metadata TTL.
+    pub fn with_disk_cache(mut self, cache: Arc<DiskCache>) -> Self {
+        self.disk_cache = Some(cache);
+        self
+    }
+
     /// Enable authentication with an API token.
     ///
     /// Returns `self` for builder-style chaining.
@@ -137,6 +211,20 @@ impl CratesIoClient {
         self
     }
 
+    /// Authenticate with the crates.io token cargo would use, if there is one.
+    ///
+    /// Looks in the same places as cargo: `CARGO_REGISTRY_TOKEN`,
+    /// `$CARGO_HOME/credentials.toml` and the configured credential
+    /// providers. See [`credentials`] for details.
+    pub fn with_cargo_auth(self) -> Result<Self, credentials::CredentialError> {
+        Ok(
+            match credentials::CargoCredentials::load()?.crates_io_token()? {
+                Some(token) => self.with_auth(token),
+                None => self,
+            },
+        )
+    }
+
     /// Returns the auth token or `Error::AuthRequired`.
     pub(crate) fn require_auth(&self) -> Result<&str, Error> {
         self.auth
@@ -159,84 +247,94 @@ impl CratesIoClient {
         *last = Some(Instant::now());
     }
 
-    /// Compute the capped exponential backoff for a given attempt number.
-    ///
-    /// Uses saturating arithmetic to avoid overflow for large attempt values, and
-    /// clamps the result to 30 seconds so a misconfigured max_retries cannot
-    /// produce multi-hour sleeps.
+    /// Compute the capped exponential backoff for a given attempt number,
+    /// before jitter.
+    #[cfg(test)]
     fn backoff_for(&self, attempt: u32) -> Duration {
-        let raw_multiplier = 2u32.saturating_pow(attempt);
-        self.initial_backoff
-            .saturating_mul(raw_multiplier)
-            .min(Duration::from_secs(30))
+        self.retry.backoff(attempt)
+    }
+
+    /// A request to `url`, identified by the client's user agent.
+    pub(crate) fn request_url(&self, method: Method, url: &str) -> HttpRequest {
+        HttpRequest::new(method, url).header(USER_AGENT, &self.user_agent)
     }
 
-    /// Execute an HTTP request with exponential backoff retry on 429 and 5xx responses.
+    /// A request for an API path.
+    fn request(&self, method: Method, path: &str) -> HttpRequest {
+        self.request_url(method, &format!("{}{}", self.base_url, path))
+    }
+
+    /// A request for an API path, authenticated with the client's token.
+    fn auth_request(&self, method: Method, path: &str) -> Result<HttpRequest, Error> {
+        let token = self.require_auth()?;
+        Ok(self.request(method, path).header(AUTHORIZATION, token))
+    }
+
+    /// Execute an HTTP request under the client's retry policy and circuit
+    /// breaker.
     ///
-    /// `make_request` is called once per attempt. Retries are only performed for
-    /// transient failures (429 Too Many Requests, 5xx Server Error). Client errors
-    /// (4xx other than 429) are returned immediately without retrying.
-    async fn send_with_retry<F, Fut>(
+    /// The request is sent once per attempt, after throttling. Retries are
+    /// only performed for transient failures (429 Too Many Requests, 5xx
+    /// Server Error). Client errors (4xx other than 429) are returned
+    /// immediately without retrying.
+    async fn send_with_retry(
         &self,
         path: &str,
-        make_request: F,
-    ) -> Result<reqwest::Response, Error>
-    where
-        F: Fn() -> Fut,
-        Fut: std::future::Future<Output = Result<reqwest::Response, reqwest::Error>>,
-    {
-        let mut attempt = 0u32;
-        loop {
+        request: HttpRequest,
+    ) -> Result<HttpResponse, Error> {
+        if self.dump.is_some() {
+            return Err(Error::Offline(path.to_string()));
+        }
+        let resp = resilience::send::<_, _, Error>(&self.retry, &self.breaker, path, || async {
             self.throttle().await;
-            let resp = make_request().await?;
-            let status = resp.status();
-
-            let retryable =
-                status == reqwest::StatusCode::TOO_MANY_REQUESTS || status.is_server_error();
-
-            if retryable && attempt < self.max_retries {
-                let backoff = self.backoff_for(attempt);
-                tracing::warn!(
-                    attempt = attempt + 1,
-                    max_retries = self.max_retries,
-                    status = status.as_u16(),
-                    path,
-                    backoff_ms = backoff.as_millis(),
-                    "retrying crates.io request"
-                );
-                tokio::time::sleep(backoff).await;
-                attempt += 1;
-                continue;
-            }
+            self.http.send(request.clone()).await
+        })
+        .await?;
 
-            return Self::check_status(resp, path).await;
+        // Only conditional requests get this, and the caller wants it as is
+        if resp.status == reqwest::StatusCode::NOT_MODIFIED {
+            return Ok(resp);
         }
+        Self::check_status(resp, path)
+    }
+
+    /// Send a request once, after throttling, and check the response status.
+    async fn send_once(&self, path: &str, request: HttpRequest) -> Result<HttpResponse, Error> {
+        self.throttle().await;
+        let resp = self.http.send(request).await?;
+        Self::check_status(resp, path)
     }
 
     /// Send a GET request and check the response status.
-    pub(crate) async fn send(&self, path: &str) -> Result<reqwest::Response, Error> {
-        let url = format!("{}{}", self.base_url, path);
-        self.send_with_retry(path, || self.http.get(&url).send())
+    pub(crate) async fn send(&self, path: &str) -> Result<HttpResponse, Error> {
+        self.send_with_retry(path, self.request(Method::GET, path))
             .await
     }
 
+    /// Send a GET request that the server may answer with `304 Not Modified`
+    /// if `validators` still match its copy.
+    pub(crate) async fn send_conditional(
+        &self,
+        path: &str,
+        validators: &Validators,
+    ) -> Result<HttpResponse, Error> {
+        let request = validators.apply(self.request(Method::GET, path));
+        self.send_with_retry(path, request).await
+    }
+
     /// Send a GET request with query parameters and check the response status.
     pub(crate) async fn send_query(
         &self,
         path: &str,
         query: &[(String, String)],
-    ) -> Result<reqwest::Response, Error> {
-        let url = format!("{}{}", self.base_url, path);
-        self.send_with_retry(path, || self.http.get(&url).query(query).send())
-            .await
+    ) -> Result<HttpResponse, Error> {
+        let request = self.request(Method::GET, path).query(query);
+        self.send_with_retry(path, request).await
     }
 
     /// Map non-success HTTP status codes to typed errors.
-    pub(crate) async fn check_status(
-        resp: reqwest::Response,
-        path: &str,
-    ) -> Result<reqwest::Response, Error> {
-        let status = resp.status();
+    pub(crate) fn check_status(resp: HttpResponse, path: &str) -> Result<HttpResponse, Error> {
+        let status = resp.status;
         if status.is_success() {
             Ok(resp)
         } else if status == reqwest::StatusCode::NOT_FOUND {
@@ -248,73 +346,117 @@ impl CratesIoClient {
         } else if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
             Err(Error::RateLimited)
         } else {
-            let text = resp.text().await.unwrap_or_default();
             Err(Error::Api {
                 status: status.as_u16(),
-                message: text,
+                message: resp.text(),
             })
         }
     }
 
-    /// GET a JSON resource.
+    /// GET a JSON resource, from the disk cache when one is configured and
+    /// holds a fresh copy.
+    ///
+    /// Concurrent calls for the same path share one request.
     pub(crate) async fn get_json<T: DeserializeOwned>(&self, path: &str) -> Result<T, Error> {
-        let resp = self.send(path).await?;
-        Ok(resp.json().await?)
+        let url = format!("{}{}", self.base_url, path);
+        let body = self
+            .in_flight
+            .run(&url, || self.fetch_json(&url, path))
+            .await?;
+        Ok(serde_json::from_slice(&body)?)
+    }
+
+    /// Fetch a JSON body for [`get_json`](Self::get_json).
+    ///
+    /// A stale cached copy is revalidated with its `ETag` / `Last-Modified`;
+    /// a `304 Not Modified` answer renews it without transferring the body.
+    async fn fetch_json(&self, url: &str, path: &str) -> Result<Bytes, Error> {
+        let Some(cache) = &self.disk_cache else {
+            return Ok(self.send(path).await?.body);
+        };
+
+        let cached = match cache.lookup(url, resource_class(path)).await {
+            Some(cached)
+                if cached.fresh && serde_json::from_slice::<IgnoredAny>(&cached.body).is_ok() =>
+            {
+                return Ok(Bytes::from(cached.body));
+            }
+            cached => cached,
+        };
+
+        let validators = cached
+            .as_ref()
+            .map(|c| c.validators.clone())
+            .unwrap_or_default();
+        let resp = self.send_conditional(path, &validators).await?;
+        if resp.status == reqwest::StatusCode::NOT_MODIFIED {
+            let Some(cached) = cached else {
+                return Err(Error::Api {
+                    status: 304,
+                    message: "not modified, but nothing is cached".to_string(),
+                });
+            };
+            tracing::debug!(path, "crates.io response not modified");
+            cache.refresh(url).await;
+            return Ok(Bytes::from(cached.body));
+        }
+
+        let validators = Validators::from_headers(&resp.headers);
+        serde_json::from_slice::<IgnoredAny>(&resp.body)?;
+        cache.put_validated(url, &resp.body, &validators).await;
+        Ok(resp.body)
     }
 
     /// GET a JSON resource with query parameters.
+    ///
+    /// Concurrent calls for the same path and query share one request.
     pub(crate) async fn get_json_query<T: DeserializeOwned>(
         &self,
         path: &str,
         query: &[(String, String)],
     ) -> Result<T, Error> {
-        let resp = self.send_query(path, query).await?;
-        Ok(resp.json().await?)
+        // Only used as a key, so the query just has to be unambiguous
+        let key = format!("{}{}{:?}", self.base_url, path, query);
+        let body = self
+            .in_flight
+            .run(&key, || async {
+                Ok::<_, Error>(self.send_query(path, query).await?.body)
+            })
+            .await?;
+        Ok(serde_json::from_slice(&body)?)
     }
 
     /// GET a text resource (e.g. readme), bounded to [`MAX_TEXT_RESPONSE_BYTES`]
     /// so an oversized or malicious response cannot exhaust memory.
     pub(crate) async fn get_text(&self, path: &str) -> Result<String, Error> {
-        let mut resp = self.send(path).await?;
-
-        // Reject early if the server declares an oversized body.
-        if let Some(len) = resp.content_length()
-            && len > MAX_TEXT_RESPONSE_BYTES
-        {
+        let request = self
+            .request(Method::GET, path)
+            .max_response_bytes(MAX_TEXT_RESPONSE_BYTES);
+        let resp = self.send_with_retry(path, request).await?;
+
+        // Content-Length may be absent or understated, so check both it and
+        // what was actually read.
+        let size = resp
+            .content_length()
+            .unwrap_or_default()
+            .max(resp.body.len() as u64);
+        if size > MAX_TEXT_RESPONSE_BYTES {
             return Err(Error::ResponseTooLarge {
                 path: path.to_string(),
-                size: len,
+                size,
                 limit: MAX_TEXT_RESPONSE_BYTES,
             });
         }
 
-        // Content-Length may be absent or understated, so cap the actual read.
-        let mut buf: Vec<u8> = Vec::new();
-        while let Some(chunk) = resp.chunk().await? {
-            let total = buf.len() as u64 + chunk.len() as u64;
-            if total > MAX_TEXT_RESPONSE_BYTES {
-                return Err(Error::ResponseTooLarge {
-                    path: path.to_string(),
-                    size: total,
-                    limit: MAX_TEXT_RESPONSE_BYTES,
-                });
-            }
-            buf.extend_from_slice(&chunk);
-        }
-
-        Ok(String::from_utf8_lossy(&buf).into_owned())
+        Ok(resp.text())
     }
 
     // ── Authenticated HTTP helpers ──────────────────────────────────────
 
     /// Send an authenticated GET request.
-    pub(crate) async fn send_auth(&self, path: &str) -> Result<reqwest::Response, Error> {
-        let token = self.require_auth()?.to_string();
-        let url = format!("{}{}", self.base_url, path);
-        self.send_with_retry(path, || {
-            self.http.get(&url).header("Authorization", &token).send()
-        })
-        .await
+    pub(crate) async fn send_auth(&self, path: &str) -> Result<HttpResponse, Error> {
+        let request = self.auth_request(Method::GET, path)?;
+        self.send_with_retry(path, request).await
     }
 
     /// Send an authenticated GET request with query parameters.
@@ -322,24 +464,15 @@ impl CratesIoClient {
         &self,
         path: &str,
         query: &[(String, String)],
-    ) -> Result<reqwest::Response, Error> {
-        let token = self.require_auth()?;
-        self.throttle().await;
-        let url = format!("{}{}", self.base_url, path);
-        let resp = self
-            .http
-            .get(&url)
-            .header("Authorization", token)
-            .query(query)
-            .send()
-            .await?;
-        Self::check_status(resp, path).await
+    ) -> Result<HttpResponse, Error> {
+        let request = self.auth_request(Method::GET, path)?.query(query);
+        self.send_once(path, request).await
     }
 
     /// GET a JSON resource with authentication.
     pub(crate) async fn get_json_auth<T: DeserializeOwned>(&self, path: &str) -> Result<T, Error> {
         let resp = self.send_auth(path).await?;
-        Ok(resp.json().await?)
+        Ok(resp.json_body()?)
     }
 
     /// GET a JSON resource with query params and authentication.
@@ -349,7 +482,7 @@ impl CratesIoClient {
         query: &[(String, String)],
     ) -> Result<T, Error> {
         let resp = self.send_query_auth(path, query).await?;
-        Ok(resp.json().await?)
+        Ok(resp.json_body()?)
     }
 
     /// PUT a JSON body and return a deserialized response. Requires auth.
@@ -358,18 +491,8 @@ impl CratesIoClient {
         path: &str,
         body: &B,
     ) -> Result<T, Error> {
-        let token = self.require_auth()?;
-        self.throttle().await;
-        let url = format!("{}{}", self.base_url, path);
-        let resp = self
-            .http
-            .put(&url)
-            .header("Authorization", token)
-            .json(body)
-            .send()
-            .await?;
-        let resp = Self::check_status(resp, path).await?;
-        Ok(resp.json().await?)
+        let request = self.auth_request(Method::PUT, path)?.json(body)?;
+        Ok(self.send_once(path, request).await?.json_body()?)
     }
 
     /// PUT a JSON body, expecting no meaningful response body. Requires auth.
@@ -378,57 +501,27 @@ impl CratesIoClient {
         path: &str,
         body: &B,
     ) -> Result<(), Error> {
-        let token = self.require_auth()?;
-        self.throttle().await;
-        let url = format!("{}{}", self.base_url, path);
-        let resp = self
-            .http
-            .put(&url)
-            .header("Authorization", token)
-            .json(body)
-            .send()
-            .await?;
-        Self::check_status(resp, path).await?;
+        let request = self.auth_request(Method::PUT, path)?.json(body)?;
+        self.send_once(path, request).await?;
         Ok(())
     }
 
     /// PUT with no body, returning a deserialized JSON response. Requires auth.
     pub(crate) async fn put_empty<T: DeserializeOwned>(&self, path: &str) -> Result<T, Error> {
-        let token = self.require_auth()?;
-        self.throttle().await;
-        let url = format!("{}{}", self.base_url, path);
-        let resp = self
-            .http
-            .put(&url)
-            .header("Authorization", token)
-            .send()
-            .await?;
-        let resp = Self::check_status(resp, path).await?;
-        Ok(resp.json().await?)
+        let request = self.auth_request(Method::PUT, path)?;
+        Ok(self.send_once(path, request).await?.json_body()?)
     }
 
     /// PUT with no body, returning deserialized JSON. No auth.
     pub(crate) async fn put_empty_json<T: DeserializeOwned>(&self, path: &str) -> Result<T, Error> {
-        self.throttle().await;
-        let url = format!("{}{}", self.base_url, path);
-        let resp = self.http.put(&url).send().await?;
-        let resp = Self::check_status(resp, path).await?;
-        Ok(resp.json().await?)
+        let request = self.request(Method::PUT, path);
+        Ok(self.send_once(path, request).await?.json_body()?)
     }
 
     /// DELETE and return a deserialized JSON response. Requires auth.
     pub(crate) async fn delete_json<T: DeserializeOwned>(&self, path: &str) -> Result<T, Error> {
-        let token = self.require_auth()?;
-        self.throttle().await;
-        let url = format!("{}{}", self.base_url, path);
-        let resp = self
-            .http
-            .delete(&url)
-            .header("Authorization", token)
-            .send()
-            .await?;
-        let resp = Self::check_status(resp, path).await?;
-        Ok(resp.json().await?)
+        let request = self.auth_request(Method::DELETE, path)?;
+        Ok(self.send_once(path, request).await?.json_body()?)
     }
 
     /// DELETE with a JSON body and return deserialized response. Requires auth.
@@ -437,32 +530,14 @@ impl CratesIoClient {
         path: &str,
         body: &B,
     ) -> Result<T, Error> {
-        let token = self.require_auth()?;
-        self.throttle().await;
-        let url = format!("{}{}", self.base_url, path);
-        let resp = self
-            .http
-            .delete(&url)
-            .header("Authorization", token)
-            .json(body)
-            .send()
-            .await?;
-        let resp = Self::check_status(resp, path).await?;
-        Ok(resp.json().await?)
+        let request = self.auth_request(Method::DELETE, path)?.json(body)?;
+        Ok(self.send_once(path, request).await?.json_body()?)
     }
 
     /// DELETE expecting no response body (just check status). Requires auth.
     pub(crate) async fn delete_ok(&self, path: &str) -> Result<(), Error> {
-        let token = self.require_auth()?;
-        self.throttle().await;
-        let url = format!("{}{}", self.base_url, path);
-        let resp = self
-            .http
-            .delete(&url)
-            .header("Authorization", token)
-            .send()
-            .await?;
-        Self::check_status(resp, path).await?;
+        let request = self.auth_request(Method::DELETE, path)?;
+        self.send_once(path, request).await?;
         Ok(())
     }
 
@@ -471,15 +546,10 @@ impl CratesIoClient {
     /// trusted-publishing token revoke, which is authed by the temporary token
     /// itself.
     pub(crate) async fn delete_ok_with_token(&self, path: &str, token: &str) -> Result<(), Error> {
-        self.throttle().await;
-        let url = format!("{}{}", self.base_url, path);
-        let resp = self
-            .http
-            .delete(&url)
-            .header("Authorization", token)
-            .send()
-            .await?;
-        Self::check_status(resp, path).await?;
+        let request = self
+            .request(Method::DELETE, path)
+            .header(AUTHORIZATION, token);
+        self.send_once(path, request).await?;
         Ok(())
     }
 
@@ -489,18 +559,8 @@ impl CratesIoClient {
         path: &str,
         body: &B,
     ) -> Result<T, Error> {
-        let token = self.require_auth()?;
-        self.throttle().await;
-        let url = format!("{}{}", self.base_url, path);
-        let resp = self
-            .http
-            .patch(&url)
-            .header("Authorization", token)
-            .json(body)
-            .send()
-            .await?;
-        let resp = Self::check_status(resp, path).await?;
-        Ok(resp.json().await?)
+        let request = self.auth_request(Method::PATCH, path)?.json(body)?;
+        Ok(self.send_once(path, request).await?.json_body()?)
     }
 
     /// POST a JSON body and return deserialized response. Requires auth.
@@ -509,18 +569,8 @@ impl CratesIoClient {
         path: &str,
         body: &B,
     ) -> Result<T, Error> {
-        let token = self.require_auth()?;
-        self.throttle().await;
-        let url = format!("{}{}", self.base_url, path);
-        let resp = self
-            .http
-            .post(&url)
-            .header("Authorization", token)
-            .json(body)
-            .send()
-            .await?;
-        let resp = Self::check_status(resp, path).await?;
-        Ok(resp.json().await?)
+        let request = self.auth_request(Method::POST, path)?.json(body)?;
+        Ok(self.send_once(path, request).await?.json_body()?)
     }
 
     /// POST a JSON body without authentication and return deserialized response.
@@ -529,11 +579,8 @@ impl CratesIoClient {
         path: &str,
         body: &B,
     ) -> Result<T, Error> {
-        self.throttle().await;
-        let url = format!("{}{}", self.base_url, path);
-        let resp = self.http.post(&url).json(body).send().await?;
-        let resp = Self::check_status(resp, path).await?;
-        Ok(resp.json().await?)
+        let request = self.request(Method::POST, path).json(body)?;
+        Ok(self.send_once(path, request).await?.json_body()?)
     }
 
     /// PUT raw bytes with a custom content type and return deserialized JSON. Requires auth.
@@ -543,18 +590,23 @@ impl CratesIoClient {
         body: Vec<u8>,
         content_type: &str,
     ) -> Result<T, Error> {
-        let token = self.require_auth()?;
-        self.throttle().await;
-        let url = format!("{}{}", self.base_url, path);
-        let resp = self
-            .http
-            .put(&url)
-            .header("Authorization", token)
-            .header("Content-Type", content_type)
-            .body(body)
-            .send()
-            .await?;
-        let resp = Self::check_status(resp, path).await?;
-        Ok(resp.json().await?)
+        let request = self
+            .auth_request(Method::PUT, path)?
+            .body(content_type, body);
+        Ok(self.send_once(path, request).await?.json_body()?)
+    }
+}
+
+/// How long a `get_json` response may be reused. A published version's
+/// dependencies and authors can never change; everything else can.
+fn resource_class(path: &str) -> ResourceClass {
+    let segments: Vec<&str> = path.trim_start_matches('/').split('/').collect();
+    match segments.as_slice() {
+        ["crates", _, version, "dependencies" | "authors"]
+            if semver::Version::parse(version).is_ok() =>
+        {
+            ResourceClass::Immutable
+        }
+        _ => ResourceClass::Metadata,
     }
 }
@@ -3,12 +3,21 @@
 //! Queries the [OSV.dev](https://osv.dev/) API to check Rust crates for known
 //! security vulnerabilities aggregated from RustSec, GHSA, and NVD.
 
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
 use std::time::Duration;
 
+use futures::stream::{self, StreamExt};
 #[cfg(feature = "mcp")]
 use schemars::JsonSchema;
+use semver::{Version, VersionReq};
 use serde::{Deserialize, Serialize};
 
+use super::http::{HttpBackend, HttpError, HttpRequest, HttpResponse, ReqwestBackend};
+use super::lockfile::{LockedPackage, Lockfile};
+use super::resilience::{self, CircuitBreaker, RetryPolicy, UpstreamUnavailable};
+use super::resolve::minimum_version;
+
 // ── Error ──────────────────────────────────────────────────────────────────
 
 /// Errors returned by the OSV.dev API client.
@@ -16,11 +25,27 @@ use serde::{Deserialize, Serialize};
 pub enum OsvError {
     /// HTTP transport error.
     #[error("HTTP error: {0}")]
-    Http(#[from] reqwest::Error),
+    Http(#[from] HttpError),
+
+    /// A request or response body was not the expected JSON.
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+
+    /// OSV.dev keeps failing and its circuit breaker is open.
+    #[error(transparent)]
+    UpstreamUnavailable(#[from] UpstreamUnavailable),
 
     /// Non-200 response from the API.
     #[error("OSV API error ({status}): {message}")]
     Api { status: u16, message: String },
+
+    /// A batch response did not carry one result per query.
+    #[error("OSV returned {actual} results for a batch of {expected} queries")]
+    BatchMismatch { expected: usize, actual: usize },
+
+    /// A batch query kept returning page tokens past the page limit.
+    #[error("OSV results for a batch query ran past {0} pages")]
+    TooManyPages(usize),
 }
 
 // ── Response types ─────────────────────────────────────────────────────────
@@ -62,6 +87,9 @@ pub struct OsvSeverity {
 pub struct OsvAffected {
     pub package: Option<OsvPackage>,
     pub ranges: Option<Vec<OsvRange>>,
+    /// Explicitly enumerated affected versions.
+    #[serde(default)]
+    pub versions: Option<Vec<String>>,
 }
 
 /// Package identifier within an ecosystem.
@@ -81,12 +109,26 @@ pub struct OsvRange {
     pub events: Vec<OsvEvent>,
 }
 
-/// A version event (introduced/fixed boundary).
+/// A version event (introduced/fixed/last_affected boundary).
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[cfg_attr(feature = "mcp", derive(JsonSchema))]
 pub struct OsvEvent {
     pub introduced: Option<String>,
     pub fixed: Option<String>,
+    /// Last version known to be affected (inclusive upper bound).
+    #[serde(default)]
+    pub last_affected: Option<String>,
+}
+
+/// How a vulnerability relates to a dependency requirement.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Exposure {
+    /// The newest version matching the requirement is affected. `fixed` is
+    /// the minimal fixed version above it, if one exists.
+    Affected { fixed: Option<Version> },
+    /// The newest version matching the requirement is not affected: the
+    /// advisory only covers older versions, or ones the requirement excludes.
+    Historical,
 }
 
 /// A reference link (advisory URL, etc).
@@ -98,6 +140,270 @@ pub struct OsvReference {
     pub url: String,
 }
 
+// ── Range evaluation ───────────────────────────────────────────────────────
+
+/// Parse an OSV event version. `"0"` is OSV's "since the first release".
+fn parse_event_version(v: &str) -> Option<Version> {
+    if v == "0" {
+        return Some(Version::new(0, 0, 0));
+    }
+    Version::parse(v).ok()
+}
+
+impl OsvEvent {
+    /// The version this event refers to, whichever kind it is.
+    fn version(&self) -> Option<Version> {
+        self.introduced
+            .as_deref()
+            .or(self.fixed.as_deref())
+            .or(self.last_affected.as_deref())
+            .and_then(parse_event_version)
+    }
+}
+
+impl OsvRange {
+    /// Whether this range uses semver ordering (`SEMVER`, or `ECOSYSTEM`,
+    /// which for crates.io is semver). `GIT` ranges cannot be evaluated
+    /// against a version number.
+    pub fn is_semver(&self) -> bool {
+        matches!(self.range_type.as_str(), "SEMVER" | "ECOSYSTEM")
+    }
+
+    /// Events sorted by version; events with unparseable versions are dropped.
+    fn sorted_events(&self) -> Vec<(Version, &OsvEvent)> {
+        let mut events: Vec<_> = self
+            .events
+            .iter()
+            .filter_map(|e| e.version().map(|v| (v, e)))
+            .collect();
+        events.sort_by(|(a, _), (b, _)| a.cmp(b));
+        events
+    }
+
+    /// Whether `version` falls inside this range, following the OSV
+    /// evaluation algorithm: walk the sorted events and toggle on
+    /// `introduced`, off at `fixed` and after `last_affected`.
+    pub fn contains(&self, version: &Version) -> bool {
+        if !self.is_semver() {
+            return false;
+        }
+        let mut affected = false;
+        for (event_version, event) in self.sorted_events() {
+            if event.introduced.is_some() && version >= &event_version {
+                affected = true;
+            } else if (event.fixed.is_some() && version >= &event_version)
+                || (event.last_affected.is_some() && version > &event_version)
+            {
+                affected = false;
+            }
+        }
+        affected
+    }
+
+    /// The lowest `fixed` version above `version`, if `version` is affected.
+    pub fn fixed_after(&self, version: &Version) -> Option<Version> {
+        if !self.contains(version) {
+            return None;
+        }
+        self.sorted_events()
+            .into_iter()
+            .find(|(v, e)| e.fixed.is_some() && v > version)
+            .map(|(v, _)| v)
+    }
+}
+
+impl OsvAffected {
+    /// Whether this entry describes `name` in the crates.io ecosystem.
+    /// Entries without package info are assumed to apply.
+    fn is_for(&self, name: &str) -> bool {
+        self.package
+            .as_ref()
+            .is_none_or(|p| p.name == name && p.ecosystem == "crates.io")
+    }
+
+    /// Whether `version` is affected per the enumerated versions or any
+    /// semver range.
+    pub fn contains(&self, version: &Version) -> bool {
+        let listed = self.versions.as_ref().is_some_and(|vs| {
+            vs.iter()
+                .any(|v| Version::parse(v).ok().as_ref() == Some(version))
+        });
+        listed
+            || self
+                .ranges
+                .iter()
+                .flatten()
+                .any(|range| range.contains(version))
+    }
+}
+
+impl OsvVulnerability {
+    /// A stand-in for an advisory whose full record could not be fetched.
+    ///
+    /// It carries no version data, so it is conservatively treated as
+    /// affecting every version, and its summary says why.
+    pub fn unhydrated(id: &str, err: &OsvError) -> Self {
+        Self {
+            id: id.to_string(),
+            summary: Some(format!("Advisory details unavailable: {err}")),
+            details: None,
+            severity: None,
+            affected: None,
+            references: None,
+        }
+    }
+
+    /// Affected entries describing `name`.
+    fn entries_for<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a OsvAffected> {
+        self.affected
+            .iter()
+            .flatten()
+            .filter(move |a| a.is_for(name))
+    }
+
+    /// Whether this advisory carries evaluable version data for `name`.
+    fn has_version_data(&self, name: &str) -> bool {
+        self.entries_for(name).any(|a| {
+            a.versions.as_ref().is_some_and(|v| !v.is_empty())
+                || a.ranges.iter().flatten().any(OsvRange::is_semver)
+        })
+    }
+
+    /// Whether `version` of crate `name` is affected.
+    ///
+    /// Advisories with no evaluable version data (no semver ranges or version
+    /// lists) are conservatively treated as affecting every version.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cratesio_mcp::client::osv::OsvVulnerability;
+    /// use semver::Version;
+    ///
+    /// let vuln: OsvVulnerability = serde_json::from_value(serde_json::json!({
+    ///     "id": "RUSTSEC-2020-0071",
+    ///     "affected": [{
+    ///         "package": {"name": "time", "ecosystem": "crates.io"},
+    ///         "ranges": [{"type": "SEMVER", "events": [{"introduced": "0"}, {"fixed": "0.2.23"}]}]
+    ///     }]
+    /// })).unwrap();
+    ///
+    /// assert!(vuln.affects("time", &Version::new(0, 1, 45)));
+    /// assert!(!vuln.affects("time", &Version::new(0, 3, 0)));
+    /// ```
+    pub fn affects(&self, name: &str, version: &Version) -> bool {
+        if !self.has_version_data(name) {
+            return true;
+        }
+        self.entries_for(name).any(|a| a.contains(version))
+    }
+
+    /// The minimal version that fixes this advisory for someone on `version`.
+    ///
+    /// Returns `None` when `version` is not affected, or when no fixed
+    /// version has been published (e.g. ranges that end in `last_affected`).
+    pub fn fixed_version(&self, name: &str, version: &Version) -> Option<Version> {
+        if !self.affects(name, version) {
+            return None;
+        }
+        // Every range containing the version must be escaped, so take the
+        // highest of the per-range minimal fixes.
+        self.entries_for(name)
+            .flat_map(|a| a.ranges.iter().flatten())
+            .filter_map(|range| range.fixed_after(version))
+            .max()
+    }
+
+    /// Classify this advisory against a dependency requirement.
+    ///
+    /// Cargo resolves a requirement to the newest version it matches, so a
+    /// requirement is exposed when the newest matching version is affected.
+    pub fn exposure(&self, name: &str, req: &VersionReq) -> Exposure {
+        let newest = self.newest_matching(name, req);
+        if !self.affects(name, &newest) {
+            return Exposure::Historical;
+        }
+        Exposure::Affected {
+            fixed: self.fixed_version(name, &newest),
+        }
+    }
+
+    /// The newest version matching `req` as far as this advisory can tell.
+    ///
+    /// The affected status only changes at event boundaries, so it is enough
+    /// to consider the requirement's lowest version, every boundary it
+    /// matches, and the patch release after each boundary (where an inclusive
+    /// `last_affected` or a listed version stops applying).
+    fn newest_matching(&self, name: &str, req: &VersionReq) -> Version {
+        let entries: Vec<_> = self.entries_for(name).collect();
+        let boundaries = entries
+            .iter()
+            .flat_map(|a| a.ranges.iter().flatten())
+            .filter(|range| range.is_semver())
+            .flat_map(|range| range.sorted_events().into_iter().map(|(v, _)| v))
+            .chain(
+                entries
+                    .iter()
+                    .flat_map(|a| a.versions.iter().flatten())
+                    .filter_map(|v| Version::parse(v).ok()),
+            );
+        boundaries
+            .flat_map(|v| {
+                let release = Version::new(v.major, v.minor, v.patch);
+                let next = Version::new(v.major, v.minor, v.patch + 1);
+                [v, release, next]
+            })
+            .filter(|v| req.matches(v))
+            .fold(minimum_version(req), Version::max)
+    }
+}
+
+/// Vulnerabilities affecting one locked package at its exact version.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "mcp", derive(JsonSchema))]
+pub struct LockedPackageVulns {
+    pub package: LockedPackage,
+    pub vulns: Vec<OsvVulnerability>,
+}
+
+/// Result of auditing every crates.io package in a `Cargo.lock`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "mcp", derive(JsonSchema))]
+pub struct LockfileAudit {
+    /// Number of crates.io packages queried.
+    pub packages_checked: u64,
+    /// Number of path, git, or alternative-registry packages not queried.
+    pub packages_skipped: u64,
+    /// Packages with at least one vulnerability, in lockfile order.
+    pub affected: Vec<LockedPackageVulns>,
+}
+
+/// One package in a [`OsvClient::query_batch`] call.
+#[derive(Debug, Clone, Copy)]
+pub struct PackageQuery<'a> {
+    pub name: &'a str,
+    /// Exact version to match; `None` returns advisories for every version.
+    pub version: Option<&'a str>,
+}
+
+impl<'a> PackageQuery<'a> {
+    /// Query advisories for any version of `name`.
+    pub fn any(name: &'a str) -> Self {
+        Self {
+            name,
+            version: None,
+        }
+    }
+
+    /// Query advisories affecting `name` at exactly `version`.
+    pub fn at(name: &'a str, version: &'a str) -> Self {
+        Self {
+            name,
+            version: Some(version),
+        }
+    }
+}
+
 // ── Request body ───────────────────────────────────────────────────────────
 
 #[derive(Serialize)]
@@ -105,8 +411,56 @@ struct OsvQueryRequest<'a> {
     package: OsvPackageQuery<'a>,
     #[serde(skip_serializing_if = "Option::is_none")]
     version: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    page_token: Option<String>,
+}
+
+impl<'a> From<PackageQuery<'a>> for OsvQueryRequest<'a> {
+    fn from(q: PackageQuery<'a>) -> Self {
+        Self {
+            package: OsvPackageQuery {
+                name: q.name,
+                ecosystem: "crates.io",
+            },
+            version: q.version,
+            page_token: None,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct OsvBatchRequest<'a> {
+    queries: &'a [OsvQueryRequest<'a>],
+}
+
+/// Response from `POST /v1/querybatch`: one result per query, IDs only.
+#[derive(Deserialize)]
+struct OsvBatchResponse {
+    #[serde(default)]
+    results: Vec<OsvBatchResult>,
+}
+
+#[derive(Default, Deserialize)]
+struct OsvBatchResult {
+    #[serde(default)]
+    vulns: Vec<OsvVulnId>,
+    next_page_token: Option<String>,
 }
 
+#[derive(Deserialize)]
+struct OsvVulnId {
+    id: String,
+}
+
+/// OSV accepts at most this many queries per `querybatch` request.
+const MAX_BATCH_QUERIES: usize = 1000;
+
+/// Pages followed per batch before giving up on a runaway `next_page_token`.
+const MAX_BATCH_PAGES: usize = 20;
+
+/// Concurrent `GET /vulns/{id}` requests when hydrating batch results.
+const HYDRATE_CONCURRENCY: usize = 8;
+
 #[derive(Serialize)]
 struct OsvPackageQuery<'a> {
     name: &'a str,
@@ -117,8 +471,11 @@ struct OsvPackageQuery<'a> {
 
 /// Async client for the OSV.dev vulnerability API.
 pub struct OsvClient {
-    http: reqwest::Client,
+    http: Arc<dyn HttpBackend>,
+    user_agent: String,
     base_url: String,
+    retry: RetryPolicy,
+    breaker: Arc<CircuitBreaker>,
 }
 
 impl OsvClient {
@@ -133,56 +490,233 @@ impl OsvClient {
         timeout: Duration,
         base_url: &str,
     ) -> Result<Self, OsvError> {
-        let http = reqwest::Client::builder()
-            .user_agent(user_agent)
-            .timeout(timeout)
-            .build()?;
         Ok(Self {
-            http,
+            http: Arc::new(ReqwestBackend::new(timeout)?),
+            user_agent: user_agent.to_string(),
             base_url: base_url.trim_end_matches('/').to_string(),
+            retry: RetryPolicy::default(),
+            breaker: Arc::new(CircuitBreaker::new("OSV.dev")),
         })
     }
 
+    /// Send requests through `backend` instead of the default reqwest client.
+    ///
+    /// The outbound timeout given to the constructor only applies to the
+    /// default backend.
+    pub fn with_backend(mut self, backend: Arc<dyn HttpBackend>) -> Self {
+        self.http = backend;
+        self
+    }
+
+    /// Set how transient failures (429, 5xx) are retried.
+    pub fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Share a circuit breaker with other clients of the same upstream.
+    pub fn with_circuit_breaker(mut self, breaker: Arc<CircuitBreaker>) -> Self {
+        self.breaker = breaker;
+        self
+    }
+
+    /// The breaker guarding OSV.dev requests.
+    pub fn circuit_breaker(&self) -> &CircuitBreaker {
+        &self.breaker
+    }
+
     /// Query OSV for vulnerabilities affecting a specific package version.
     pub async fn query_package(
         &self,
         name: &str,
         version: &str,
     ) -> Result<OsvQueryResponse, OsvError> {
-        let body = OsvQueryRequest {
-            package: OsvPackageQuery {
-                name,
-                ecosystem: "crates.io",
-            },
-            version: Some(version),
-        };
-        self.post_query(&body).await
+        self.post_query(&PackageQuery::at(name, version).into())
+            .await
     }
 
     /// Query OSV for all known vulnerabilities for a package (any version).
     pub async fn query_package_any(&self, name: &str) -> Result<OsvQueryResponse, OsvError> {
-        let body = OsvQueryRequest {
-            package: OsvPackageQuery {
-                name,
-                ecosystem: "crates.io",
-            },
-            version: None,
-        };
-        self.post_query(&body).await
+        self.post_query(&PackageQuery::any(name).into()).await
+    }
+
+    /// Look up advisory IDs for many packages at once via `POST /querybatch`.
+    ///
+    /// Returns one list of IDs per query, in query order. Batches larger than
+    /// OSV's limit are split, and paginated results are followed for up to
+    /// `MAX_BATCH_PAGES` pages. The batch
+    /// endpoint only returns IDs; use [`hydrate`](Self::hydrate) (or
+    /// [`query_batch`](Self::query_batch)) to fetch full records.
+    pub async fn query_batch_ids(
+        &self,
+        queries: &[PackageQuery<'_>],
+    ) -> Result<Vec<Vec<String>>, OsvError> {
+        let mut ids: Vec<Vec<String>> = vec![Vec::new(); queries.len()];
+
+        for (chunk_idx, chunk) in queries.chunks(MAX_BATCH_QUERIES).enumerate() {
+            let offset = chunk_idx * MAX_BATCH_QUERIES;
+            // (index into `queries`, page token) for queries still in flight
+            let mut pending: Vec<(usize, Option<String>)> =
+                (0..chunk.len()).map(|i| (offset + i, None)).collect();
+
+            let mut pages = 0;
+            while !pending.is_empty() {
+                if pages == MAX_BATCH_PAGES {
+                    return Err(OsvError::TooManyPages(MAX_BATCH_PAGES));
+                }
+                pages += 1;
+                let requests: Vec<OsvQueryRequest<'_>> = pending
+                    .iter()
+                    .map(|(i, token)| OsvQueryRequest {
+                        page_token: token.clone(),
+                        ..queries[*i].into()
+                    })
+                    .collect();
+                let resp = self.post_querybatch(&requests).await?;
+                if resp.results.len() != requests.len() {
+                    return Err(OsvError::BatchMismatch {
+                        expected: requests.len(),
+                        actual: resp.results.len(),
+                    });
+                }
+
+                let mut next = Vec::new();
+                for ((i, _), result) in pending.into_iter().zip(resp.results) {
+                    ids[i].extend(result.vulns.into_iter().map(|v| v.id));
+                    if let Some(token) = result.next_page_token {
+                        next.push((i, Some(token)));
+                    }
+                }
+                pending = next;
+            }
+        }
+
+        Ok(ids)
+    }
+
+    /// Fetch a full advisory record via `GET /vulns/{id}`.
+    pub async fn get_vulnerability(&self, id: &str) -> Result<OsvVulnerability, OsvError> {
+        let mut url = reqwest::Url::parse(&self.base_url).map_err(HttpError::new)?;
+        url.path_segments_mut()
+            .map_err(|()| HttpError::new("OSV base URL cannot take a path"))?
+            .pop_if_empty()
+            .extend(["vulns", id]);
+        let url = String::from(url);
+        let resp = self.send(&url, HttpRequest::get(&url)).await?;
+        Self::parse_response(resp)
+    }
+
+    /// Fetch full records for a set of advisory IDs.
+    ///
+    /// Duplicate IDs are fetched once, with a small number of requests in
+    /// flight at a time. Each ID maps to its own outcome, so one failed fetch
+    /// does not lose the others.
+    pub async fn hydrate<'a>(
+        &self,
+        ids: impl IntoIterator<Item = &'a str>,
+    ) -> HashMap<String, Result<OsvVulnerability, OsvError>> {
+        let unique: HashSet<&str> = ids.into_iter().collect();
+        let fetches: Vec<_> = unique
+            .into_iter()
+            .map(|id| async move { (id.to_string(), self.get_vulnerability(id).await) })
+            .collect();
+        stream::iter(fetches)
+            .buffer_unordered(HYDRATE_CONCURRENCY)
+            .collect()
+            .await
+    }
+
+    /// Query many packages in one batch and return full advisory records,
+    /// one list per query in query order.
+    ///
+    /// An advisory reported for several packages is only fetched once. An
+    /// advisory whose record could not be fetched is kept as a stub (see
+    /// [`OsvVulnerability::unhydrated`]) rather than dropped.
+    pub async fn query_batch(
+        &self,
+        queries: &[PackageQuery<'_>],
+    ) -> Result<Vec<Vec<OsvVulnerability>>, OsvError> {
+        let ids = self.query_batch_ids(queries).await?;
+        let records = self.hydrate(ids.iter().flatten().map(String::as_str)).await;
+        Ok(ids
+            .into_iter()
+            .map(|ids| {
+                ids.iter()
+                    .filter_map(|id| {
+                        records.get(id).map(|record| match record {
+                            Ok(vuln) => vuln.clone(),
+                            Err(e) => OsvVulnerability::unhydrated(id, e),
+                        })
+                    })
+                    .collect()
+            })
+            .collect())
+    }
+
+    /// Check every crates.io package in a lockfile at its exact locked version.
+    ///
+    /// All packages go out in one [`query_batch`](Self::query_batch), so OSV
+    /// only reports advisories whose affected ranges include the locked
+    /// version. Packages that did not come from crates.io are counted in
+    /// [`LockfileAudit::packages_skipped`] and not queried.
+    pub async fn audit_lockfile(&self, lockfile: &Lockfile) -> Result<LockfileAudit, OsvError> {
+        let packages: Vec<&LockedPackage> = lockfile.crates_io_packages().collect();
+        let queries: Vec<PackageQuery<'_>> = packages
+            .iter()
+            .map(|p| PackageQuery::at(&p.name, &p.version))
+            .collect();
+        let results = self.query_batch(&queries).await?;
+
+        let affected = packages
+            .iter()
+            .zip(results)
+            .filter(|(_, vulns)| !vulns.is_empty())
+            .map(|(package, vulns)| LockedPackageVulns {
+                package: (*package).clone(),
+                vulns,
+            })
+            .collect();
+
+        Ok(LockfileAudit {
+            packages_checked: packages.len() as u64,
+            packages_skipped: (lockfile.packages.len() - packages.len()) as u64,
+            affected,
+        })
     }
 
     async fn post_query(&self, body: &OsvQueryRequest<'_>) -> Result<OsvQueryResponse, OsvError> {
         let url = format!("{}/query", self.base_url);
-        let resp = self.http.post(&url).json(body).send().await?;
-        let status = resp.status();
-        if !status.is_success() {
-            let message = resp.text().await.unwrap_or_default();
+        let resp = self.send(&url, HttpRequest::post(&url).json(body)?).await?;
+        Self::parse_response(resp)
+    }
+
+    async fn post_querybatch(
+        &self,
+        queries: &[OsvQueryRequest<'_>],
+    ) -> Result<OsvBatchResponse, OsvError> {
+        let url = format!("{}/querybatch", self.base_url);
+        let request = HttpRequest::post(&url).json(&OsvBatchRequest { queries })?;
+        let resp = self.send(&url, request).await?;
+        Self::parse_response(resp)
+    }
+
+    /// Send a request under the client's retry policy and circuit breaker.
+    async fn send(&self, url: &str, request: HttpRequest) -> Result<HttpResponse, OsvError> {
+        let request = request.header(reqwest::header::USER_AGENT, &self.user_agent);
+        resilience::send(&self.retry, &self.breaker, url, || {
+            self.http.send(request.clone())
+        })
+        .await
+    }
+
+    fn parse_response<T: serde::de::DeserializeOwned>(resp: HttpResponse) -> Result<T, OsvError> {
+        if !resp.status.is_success() {
             return Err(OsvError::Api {
-                status: status.as_u16(),
-                message,
+                status: resp.status.as_u16(),
+                message: resp.text(),
             });
         }
-        Ok(resp.json().await?)
+        Ok(resp.json_body()?)
     }
 }
 
@@ -190,7 +724,7 @@ impl OsvClient {
 
 #[cfg(test)]
 mod tests {
-    use wiremock::matchers::{body_json, method, path};
+    use wiremock::matchers::{body_json, body_partial_json, method, path};
     use wiremock::{Mock, MockServer, ResponseTemplate};
 
     use super::*;
@@ -304,6 +838,452 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn query_retries_transient_failures() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/query"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({})))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/query"))
+            .respond_with(ResponseTemplate::new(503).insert_header("Retry-After", "0"))
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+
+        let client = test_client(&server.uri()).with_retry_policy(RetryPolicy {
+            max_retries: 1,
+            initial_backoff: Duration::ZERO,
+        });
+        let resp = client.query_package("flaky", "1.0.0").await.unwrap();
+
+        assert!(resp.vulns.is_none());
+        assert!(!client.circuit_breaker().is_open());
+    }
+
+    #[tokio::test]
+    async fn audit_lockfile_queries_locked_versions() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/querybatch"))
+            .and(body_json(serde_json::json!({
+                "queries": [
+                    { "package": { "name": "serde", "ecosystem": "crates.io" }, "version": "1.0.219" },
+                    { "package": { "name": "time", "ecosystem": "crates.io" }, "version": "0.1.45" }
+                ]
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "results": [
+                    {},
+                    { "vulns": [{ "id": "RUSTSEC-2020-0071", "modified": "2024-01-01T00:00:00Z" }] }
+                ]
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        Mock::given(method("GET"))
+            .and(path("/vulns/RUSTSEC-2020-0071"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "id": "RUSTSEC-2020-0071",
+                "summary": "Potential segfault in the time crate"
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let lockfile = Lockfile::parse(
+            r#"
+[[package]]
+name = "app"
+version = "0.1.0"
+
+[[package]]
+name = "serde"
+version = "1.0.219"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+
+[[package]]
+name = "time"
+version = "0.1.45"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+"#,
+        )
+        .unwrap();
+
+        let client = test_client(&server.uri());
+        let audit = client.audit_lockfile(&lockfile).await.unwrap();
+
+        assert_eq!(audit.packages_checked, 2);
+        assert_eq!(audit.packages_skipped, 1);
+        assert_eq!(audit.affected.len(), 1);
+        assert_eq!(audit.affected[0].package.name, "time");
+        assert_eq!(audit.affected[0].vulns[0].id, "RUSTSEC-2020-0071");
+        assert_eq!(
+            audit.affected[0].vulns[0].summary.as_deref(),
+            Some("Potential segfault in the time crate")
+        );
+    }
+
+    #[tokio::test]
+    async fn query_batch_hydrates_shared_advisories_once() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/querybatch"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "results": [
+                    { "vulns": [{ "id": "GHSA-shared" }, { "id": "RUSTSEC-2024-0001" }] },
+                    { "vulns": [{ "id": "GHSA-shared" }] },
+                    {}
+                ]
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        for id in ["GHSA-shared", "RUSTSEC-2024-0001"] {
+            Mock::given(method("GET"))
+                .and(path(format!("/vulns/{id}")))
+                .respond_with(
+                    ResponseTemplate::new(200).set_body_json(serde_json::json!({ "id": id })),
+                )
+                .expect(1)
+                .mount(&server)
+                .await;
+        }
+
+        let client = test_client(&server.uri());
+        let results = client
+            .query_batch(&[
+                PackageQuery::any("a"),
+                PackageQuery::any("b"),
+                PackageQuery::at("c", "1.0.0"),
+            ])
+            .await
+            .unwrap();
+
+        let ids: Vec<Vec<&str>> = results
+            .iter()
+            .map(|vulns| vulns.iter().map(|v| v.id.as_str()).collect())
+            .collect();
+        assert_eq!(
+            ids,
+            vec![
+                vec!["GHSA-shared", "RUSTSEC-2024-0001"],
+                vec!["GHSA-shared"],
+                vec![]
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn query_batch_keeps_advisories_that_fail_to_hydrate() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/querybatch"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "results": [{ "vulns": [{ "id": "GHSA-ok" }, { "id": "GHSA-a/b" }] }]
+            })))
+            .mount(&server)
+            .await;
+
+        Mock::given(method("GET"))
+            .and(path("/vulns/GHSA-ok"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "id": "GHSA-ok",
+                "summary": "Fetched"
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        // The slash is escaped, so the ID stays a single path segment.
+        Mock::given(method("GET"))
+            .and(path("/vulns/GHSA-a%2Fb"))
+            .respond_with(ResponseTemplate::new(404))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = test_client(&server.uri());
+        let results = client.query_batch(&[PackageQuery::any("a")]).await.unwrap();
+
+        let vulns = &results[0];
+        assert_eq!(vulns.len(), 2);
+        let fetched = vulns.iter().find(|v| v.id == "GHSA-ok").unwrap();
+        assert_eq!(fetched.summary.as_deref(), Some("Fetched"));
+        let stub = vulns.iter().find(|v| v.id == "GHSA-a/b").unwrap();
+        assert!(
+            stub.summary
+                .as_deref()
+                .unwrap()
+                .starts_with("Advisory details unavailable")
+        );
+        assert!(stub.affects("a", &v("1.0.0")));
+    }
+
+    #[tokio::test]
+    async fn query_batch_ids_rejects_mismatched_results() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/querybatch"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "results": [{}]
+            })))
+            .mount(&server)
+            .await;
+
+        let client = test_client(&server.uri());
+        let err = client
+            .query_batch_ids(&[PackageQuery::any("a"), PackageQuery::any("b")])
+            .await
+            .unwrap_err();
+
+        assert!(matches!(
+            err,
+            OsvError::BatchMismatch {
+                expected: 2,
+                actual: 1
+            }
+        ));
+    }
+
+    #[tokio::test]
+    async fn query_batch_ids_stops_following_endless_pages() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/querybatch"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "results": [{ "vulns": [{ "id": "A" }], "next_page_token": "again" }]
+            })))
+            .expect(MAX_BATCH_PAGES as u64)
+            .mount(&server)
+            .await;
+
+        let client = test_client(&server.uri());
+        let err = client
+            .query_batch_ids(&[PackageQuery::any("a")])
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, OsvError::TooManyPages(MAX_BATCH_PAGES)));
+    }
+
+    #[tokio::test]
+    async fn query_batch_ids_follows_page_tokens() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/querybatch"))
+            .and(body_partial_json(serde_json::json!({
+                "queries": [{ "page_token": "next" }]
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "results": [{ "vulns": [{ "id": "B" }] }]
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        Mock::given(method("POST"))
+            .and(path("/querybatch"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "results": [
+                    { "vulns": [{ "id": "A" }], "next_page_token": "next" },
+                    {}
+                ]
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = test_client(&server.uri());
+        let ids = client
+            .query_batch_ids(&[PackageQuery::any("paged"), PackageQuery::any("empty")])
+            .await
+            .unwrap();
+
+        assert_eq!(ids, vec![vec!["A".to_string(), "B".to_string()], vec![]]);
+    }
+
+    fn vuln_with_events(events: serde_json::Value) -> OsvVulnerability {
+        serde_json::from_value(serde_json::json!({
+            "id": "RUSTSEC-2024-0001",
+            "affected": [{
+                "package": { "name": "some-crate", "ecosystem": "crates.io" },
+                "ranges": [{ "type": "SEMVER", "events": events }]
+            }]
+        }))
+        .unwrap()
+    }
+
+    fn v(s: &str) -> Version {
+        Version::parse(s).unwrap()
+    }
+
+    #[test]
+    fn introduced_fixed_range() {
+        let vuln = vuln_with_events(serde_json::json!([
+            { "introduced": "0" },
+            { "fixed": "1.2.3" }
+        ]));
+        assert!(vuln.affects("some-crate", &v("0.1.0")));
+        assert!(vuln.affects("some-crate", &v("1.2.2")));
+        assert!(!vuln.affects("some-crate", &v("1.2.3")));
+        assert_eq!(
+            vuln.fixed_version("some-crate", &v("1.0.0")),
+            Some(v("1.2.3"))
+        );
+        assert_eq!(vuln.fixed_version("some-crate", &v("2.0.0")), None);
+    }
+
+    #[test]
+    fn multiple_introduced_fixed_pairs() {
+        // Backported fix on 0.9.x, separate regression window on 1.x.
+        let vuln = vuln_with_events(serde_json::json!([
+            { "introduced": "0.9.0" },
+            { "fixed": "0.9.5" },
+            { "introduced": "1.0.0" },
+            { "fixed": "1.4.0" }
+        ]));
+        assert!(!vuln.affects("some-crate", &v("0.8.0")));
+        assert!(vuln.affects("some-crate", &v("0.9.1")));
+        assert!(!vuln.affects("some-crate", &v("0.9.7")));
+        assert!(vuln.affects("some-crate", &v("1.3.9")));
+        assert_eq!(
+            vuln.fixed_version("some-crate", &v("0.9.1")),
+            Some(v("0.9.5"))
+        );
+        assert_eq!(
+            vuln.fixed_version("some-crate", &v("1.1.0")),
+            Some(v("1.4.0"))
+        );
+    }
+
+    #[test]
+    fn last_affected_is_inclusive_with_no_fix() {
+        let vuln = vuln_with_events(serde_json::json!([
+            { "introduced": "0.3.0" },
+            { "last_affected": "0.3.8" }
+        ]));
+        assert!(vuln.affects("some-crate", &v("0.3.8")));
+        assert!(!vuln.affects("some-crate", &v("0.3.9")));
+        assert_eq!(vuln.fixed_version("some-crate", &v("0.3.1")), None);
+    }
+
+    #[test]
+    fn ecosystem_ranges_and_version_lists_are_evaluated() {
+        let vuln: OsvVulnerability = serde_json::from_value(serde_json::json!({
+            "id": "GHSA-xxxx",
+            "affected": [{
+                "package": { "name": "some-crate", "ecosystem": "crates.io" },
+                "ranges": [{ "type": "ECOSYSTEM", "events": [{ "introduced": "2.0.0" }, { "fixed": "2.1.0" }] }],
+                "versions": ["1.5.0"]
+            }]
+        }))
+        .unwrap();
+        assert!(vuln.affects("some-crate", &v("2.0.4")));
+        assert!(vuln.affects("some-crate", &v("1.5.0")));
+        assert!(!vuln.affects("some-crate", &v("1.5.1")));
+    }
+
+    #[test]
+    fn missing_version_data_is_conservatively_affected() {
+        let vuln: OsvVulnerability =
+            serde_json::from_value(serde_json::json!({ "id": "RUSTSEC-2024-0002" })).unwrap();
+        assert!(vuln.affects("some-crate", &v("9.9.9")));
+
+        let git_only: OsvVulnerability = serde_json::from_value(serde_json::json!({
+            "id": "RUSTSEC-2024-0003",
+            "affected": [{
+                "package": { "name": "some-crate", "ecosystem": "crates.io" },
+                "ranges": [{ "type": "GIT", "events": [{ "introduced": "abc123" }] }]
+            }]
+        }))
+        .unwrap();
+        assert!(git_only.affects("some-crate", &v("1.0.0")));
+    }
+
+    #[test]
+    fn packages_without_an_entry_are_not_ruled_out() {
+        let vuln = vuln_with_events(serde_json::json!([{ "introduced": "0" }]));
+        // No entry describes "other-crate", so its data cannot rule it out.
+        assert!(vuln.affects("other-crate", &v("1.0.0")));
+        assert!(vuln.affects("some-crate", &v("1.0.0")));
+    }
+
+    #[test]
+    fn exposure_against_requirements() {
+        let vuln = vuln_with_events(serde_json::json!([
+            { "introduced": "0" },
+            { "fixed": "1.6.1" }
+        ]));
+        let req = |s: &str| VersionReq::parse(s).unwrap();
+
+        // ^1 admits 1.6.1, so a fresh resolution is already fixed.
+        assert_eq!(
+            vuln.exposure("some-crate", &req("^1")),
+            Exposure::Historical
+        );
+        // Already above the fix.
+        assert_eq!(
+            vuln.exposure("some-crate", &req("^1.7")),
+            Exposure::Historical
+        );
+        // =1.2.0 can never pick up the fix.
+        assert_eq!(
+            vuln.exposure("some-crate", &req("=1.2.0")),
+            Exposure::Affected {
+                fixed: Some(v("1.6.1"))
+            }
+        );
+        // ^0.5 is confined to 0.5.x, all affected.
+        assert_eq!(
+            vuln.exposure("some-crate", &req("^0.5")),
+            Exposure::Affected {
+                fixed: Some(v("1.6.1"))
+            }
+        );
+    }
+
+    #[test]
+    fn exposure_follows_the_newest_matching_version() {
+        let req = |s: &str| VersionReq::parse(s).unwrap();
+
+        // 1.0.0 is clean, but cargo resolves ^1 to an affected 1.5+.
+        let unfixed = vuln_with_events(serde_json::json!([{ "introduced": "1.5.0" }]));
+        assert_eq!(
+            unfixed.exposure("some-crate", &req("^1")),
+            Exposure::Affected { fixed: None }
+        );
+        assert_eq!(
+            unfixed.exposure("some-crate", &req(">=1, <1.5")),
+            Exposure::Historical
+        );
+
+        // A later release past last_affected is still matched by ^0.3.
+        let bounded = vuln_with_events(serde_json::json!([
+            { "introduced": "0.3.0" },
+            { "last_affected": "0.3.8" }
+        ]));
+        assert_eq!(
+            bounded.exposure("some-crate", &req("^0.3")),
+            Exposure::Historical
+        );
+        assert_eq!(
+            bounded.exposure("some-crate", &req("~0.3.2, <0.3.9")),
+            Exposure::Affected { fixed: None }
+        );
+    }
+
     #[tokio::test]
     async fn query_any_omits_version() {
         let server = MockServer::start().await;
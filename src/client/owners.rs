@@ -11,6 +11,11 @@ use super::wire::{
 impl CratesIoClient {
     /// Get owners/maintainers of a crate.
     pub async fn crate_owners(&self, name: &str) -> Result<Vec<User>, Error> {
+        if let Some(dump) = self.dump() {
+            return dump
+                .crate_owners(name)
+                .ok_or_else(|| Error::NotFound(format!("/crates/{name}/owners")));
+        }
         let resp: OwnersResponse = self.get_json(&format!("/crates/{name}/owners")).await?;
         Ok(resp.users)
     }
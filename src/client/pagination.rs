@@ -0,0 +1,226 @@
+//! Streaming iteration over paginated list endpoints.
+//!
+//! A listing is fetched one page at a time, only as the stream is polled, so
+//! bounding it with [`StreamExt::take`](futures::StreamExt::take) stops the
+//! requests as soon as enough items have been read. Every page goes through
+//! the client's rate limiter like any other request.
+//!
+//! The next page is the one the response's `meta.next_page` link points at
+//! (crates.io uses these for seek pagination); endpoints that don't provide
+//! one are walked by page number until their `meta.total` is reached.
+
+use futures::{Stream, TryStreamExt, stream};
+
+use super::error::Error;
+use super::types::{
+    CategoriesPage, Category, Crate, CratesPage, Keyword, KeywordsPage, ReverseDependencies,
+    ReverseDependency, Version, VersionsPage,
+};
+use super::wire::MyUpdatesResponse;
+
+/// Largest page size crates.io serves, used by streams to keep the number of
+/// requests down.
+pub(crate) const MAX_PER_PAGE: u64 = 100;
+
+/// Query parameters for one page request.
+pub(crate) type Params = Vec<(String, String)>;
+
+/// One page of a paginated listing.
+pub(crate) trait Paged {
+    type Item;
+
+    /// Number of items on this page.
+    fn len(&self) -> usize;
+
+    /// Query string of the next page, as given by the API.
+    fn next_page(&self) -> Option<&str> {
+        None
+    }
+
+    /// Whether items remain after the first `fetched` of the listing.
+    fn has_more(&self, fetched: u64) -> bool;
+
+    fn into_items(self) -> Vec<Self::Item>;
+}
+
+/// Stream the pages of a listing, starting from `params`.
+///
+/// `fetch` is called for each page in turn; the stream ends after the last
+/// page or the first error.
+pub(crate) fn pages<P, F, Fut>(params: Params, fetch: F) -> impl Stream<Item = Result<P, Error>>
+where
+    P: Paged,
+    F: FnMut(Params) -> Fut,
+    Fut: Future<Output = Result<P, Error>>,
+{
+    stream::unfold(
+        (Some(params), 0u64, fetch),
+        |(params, fetched, mut fetch)| async move {
+            let params = params?;
+            match fetch(params.clone()).await {
+                Ok(page) => {
+                    let fetched = fetched + page.len() as u64;
+                    let next = next_params(&params, &page, fetched);
+                    Some((Ok(page), (next, fetched, fetch)))
+                }
+                Err(e) => Some((Err(e), (None, fetched, fetch))),
+            }
+        },
+    )
+}
+
+/// Stream the items of a listing, page after page.
+pub(crate) fn items<P: Paged>(
+    pages: impl Stream<Item = Result<P, Error>>,
+) -> impl Stream<Item = Result<P::Item, Error>> {
+    pages
+        .map_ok(|page| stream::iter(page.into_items().into_iter().map(Ok)))
+        .try_flatten()
+}
+
+/// Query parameters for the page after `page`, or `None` if it was the last.
+fn next_params<P: Paged>(current: &[(String, String)], page: &P, fetched: u64) -> Option<Params> {
+    if page.len() == 0 {
+        return None;
+    }
+    if let Some(link) = page.next_page() {
+        let url = reqwest::Url::parse("http://next.page/")
+            .ok()?
+            .join(link)
+            .ok()?;
+        return Some(url.query_pairs().into_owned().collect());
+    }
+    if !page.has_more(fetched) {
+        return None;
+    }
+
+    let number = param(current, "page").unwrap_or(1);
+    let mut next: Params = current
+        .iter()
+        .filter(|(key, _)| key != "page")
+        .cloned()
+        .collect();
+    next.push(("page".into(), (number + 1).to_string()));
+    Some(next)
+}
+
+/// A numeric query parameter, e.g. `page` or `per_page`.
+pub(crate) fn param(params: &[(String, String)], key: &str) -> Option<u64> {
+    params
+        .iter()
+        .find(|(k, _)| k == key)
+        .and_then(|(_, v)| v.parse().ok())
+}
+
+macro_rules! paged_by_meta {
+    ($page:ty, $field:ident, $item:ty) => {
+        impl Paged for $page {
+            type Item = $item;
+
+            fn len(&self) -> usize {
+                self.$field.len()
+            }
+
+            fn next_page(&self) -> Option<&str> {
+                self.meta.next_page.as_deref()
+            }
+
+            fn has_more(&self, fetched: u64) -> bool {
+                fetched < self.meta.total
+            }
+
+            fn into_items(self) -> Vec<$item> {
+                self.$field
+            }
+        }
+    };
+}
+
+paged_by_meta!(CratesPage, crates, Crate);
+paged_by_meta!(VersionsPage, versions, Version);
+paged_by_meta!(ReverseDependencies, dependencies, ReverseDependency);
+paged_by_meta!(CategoriesPage, categories, Category);
+paged_by_meta!(KeywordsPage, keywords, Keyword);
+
+impl Paged for MyUpdatesResponse {
+    type Item = Version;
+
+    fn len(&self) -> usize {
+        self.versions.len()
+    }
+
+    fn has_more(&self, _fetched: u64) -> bool {
+        self.meta.more
+    }
+
+    fn into_items(self) -> Vec<Version> {
+        self.versions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::types::Meta;
+    use super::*;
+
+    fn keywords(names: &[&str], total: u64, next_page: Option<&str>) -> KeywordsPage {
+        KeywordsPage {
+            keywords: names
+                .iter()
+                .map(|k| Keyword {
+                    keyword: k.to_string(),
+                    crates_cnt: 0,
+                })
+                .collect(),
+            meta: Meta {
+                total,
+                next_page: next_page.map(str::to_string),
+            },
+        }
+    }
+
+    fn params(pairs: &[(&str, &str)]) -> Params {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn next_page_link_is_followed() {
+        let page = keywords(&["a"], 100, Some("?per_page=1&seek=abc%3D"));
+        let next = next_params(&params(&[("per_page", "1")]), &page, 1);
+        assert_eq!(next, Some(params(&[("per_page", "1"), ("seek", "abc=")])));
+    }
+
+    #[test]
+    fn page_number_advances_without_a_link() {
+        let page = keywords(&["a", "b"], 5, None);
+        let current = params(&[("page", "2"), ("per_page", "2")]);
+        let next = next_params(&current, &page, 4);
+        assert_eq!(next, Some(params(&[("per_page", "2"), ("page", "3")])));
+    }
+
+    #[test]
+    fn stops_at_total_or_empty_page() {
+        let current = params(&[("per_page", "2")]);
+        assert_eq!(next_params(&current, &keywords(&["a"], 3, None), 3), None);
+        assert_eq!(next_params(&current, &keywords(&[], 9, None), 2), None);
+    }
+
+    #[tokio::test]
+    async fn items_span_pages_in_order() {
+        let fetch = |params: Params| async move {
+            Ok(match param(&params, "page").unwrap_or(1) {
+                1 => keywords(&["a", "b"], 3, None),
+                _ => keywords(&["c"], 3, None),
+            })
+        };
+        let names: Vec<String> = items(pages(Params::new(), fetch))
+            .map_ok(|k| k.keyword)
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(names, ["a", "b", "c"]);
+    }
+}
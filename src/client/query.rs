@@ -37,6 +37,13 @@ pub struct CratesQuery {
     pub(crate) sort: Option<Sort>,
     pub(crate) page: Option<u64>,
     pub(crate) per_page: Option<u64>,
+    pub(crate) category: Option<String>,
+    pub(crate) keyword: Option<String>,
+    pub(crate) all_keywords: Vec<String>,
+    pub(crate) user_id: Option<u64>,
+    pub(crate) team_id: Option<u64>,
+    pub(crate) ids: Vec<String>,
+    pub(crate) letter: Option<char>,
 }
 
 impl CratesQuery {
@@ -51,12 +58,58 @@ impl CratesQuery {
     ///     .search("tower")
     ///     .sort(Sort::Downloads)
     ///     .build();
+    ///
+    /// // Filters work with or without a search term
+    /// let query = CratesQuery::builder()
+    ///     .category("asynchronous")
+    ///     .keyword("runtime")
+    ///     .sort(Sort::Downloads)
+    ///     .build();
     /// ```
     pub fn builder() -> CratesQueryBuilder {
         CratesQueryBuilder {
             query: CratesQuery::default(),
         }
     }
+
+    /// Query string parameters for `GET /crates`.
+    pub(crate) fn params(&self) -> Vec<(String, String)> {
+        let mut params: Vec<(String, String)> = Vec::new();
+        if let Some(search) = &self.search {
+            params.push(("q".into(), search.clone()));
+        }
+        if let Some(sort) = self.sort {
+            params.push(("sort".into(), sort.as_str().into()));
+        }
+        if let Some(page) = self.page {
+            params.push(("page".into(), page.to_string()));
+        }
+        if let Some(per_page) = self.per_page {
+            params.push(("per_page".into(), per_page.to_string()));
+        }
+        if let Some(category) = &self.category {
+            params.push(("category".into(), category.clone()));
+        }
+        if let Some(keyword) = &self.keyword {
+            params.push(("keyword".into(), keyword.clone()));
+        }
+        if !self.all_keywords.is_empty() {
+            params.push(("all_keywords".into(), self.all_keywords.join(" ")));
+        }
+        if let Some(user_id) = self.user_id {
+            params.push(("user_id".into(), user_id.to_string()));
+        }
+        if let Some(team_id) = self.team_id {
+            params.push(("team_id".into(), team_id.to_string()));
+        }
+        for id in &self.ids {
+            params.push(("ids[]".into(), id.clone()));
+        }
+        if let Some(letter) = self.letter {
+            params.push(("letter".into(), letter.to_string()));
+        }
+        params
+    }
 }
 
 /// Builder for [`CratesQuery`].
@@ -89,6 +142,49 @@ impl CratesQueryBuilder {
         self
     }
 
+    /// Only include crates in a category (by slug), or any of its
+    /// subcategories.
+    pub fn category(mut self, slug: &str) -> Self {
+        self.query.category = Some(slug.to_string());
+        self
+    }
+
+    /// Only include crates tagged with a keyword.
+    pub fn keyword(mut self, keyword: &str) -> Self {
+        self.query.keyword = Some(keyword.to_string());
+        self
+    }
+
+    /// Only include crates tagged with every one of these keywords.
+    pub fn all_keywords(mut self, keywords: &[&str]) -> Self {
+        self.query.all_keywords = keywords.iter().map(|k| k.to_string()).collect();
+        self
+    }
+
+    /// Only include crates owned by the user with this ID.
+    pub fn user_id(mut self, user_id: u64) -> Self {
+        self.query.user_id = Some(user_id);
+        self
+    }
+
+    /// Only include crates owned by the team with this ID.
+    pub fn team_id(mut self, team_id: u64) -> Self {
+        self.query.team_id = Some(team_id);
+        self
+    }
+
+    /// Only include the crates with these names.
+    pub fn ids(mut self, names: &[&str]) -> Self {
+        self.query.ids = names.iter().map(|n| n.to_string()).collect();
+        self
+    }
+
+    /// Only include crates whose name starts with this letter.
+    pub fn letter(mut self, letter: char) -> Self {
+        self.query.letter = Some(letter);
+        self
+    }
+
     /// Finalize the builder and produce a [`CratesQuery`].
     pub fn build(self) -> CratesQuery {
         self.query
@@ -0,0 +1,367 @@
+//! Retry and circuit-breaker policy shared by the upstream clients.
+//!
+//! crates.io, docs.rs and OSV.dev requests all go through [`send`]: transient
+//! failures (429, 5xx) are retried with jittered exponential backoff, waiting
+//! at least as long as a `Retry-After` header asks, and a [`CircuitBreaker`]
+//! per upstream stops sending requests to a service that keeps failing.
+
+use std::hash::{BuildHasher, Hasher};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime};
+
+use reqwest::StatusCode;
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+
+use super::http::{HttpError, HttpResponse};
+
+/// Upper bound for a single backoff sleep, and the longest `Retry-After` we
+/// are willing to wait out before giving up on a request.
+pub const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Consecutive failures that open a breaker by default.
+pub const DEFAULT_FAILURE_THRESHOLD: u32 = 5;
+
+/// How long an open breaker rejects requests by default.
+pub const DEFAULT_OPEN_DURATION: Duration = Duration::from_secs(30);
+
+/// An upstream was skipped because its circuit breaker is open.
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("{upstream} is unavailable (retry in {}s)", retry_in.as_secs().max(1))]
+pub struct UpstreamUnavailable {
+    /// Name of the upstream service, e.g. `"docs.rs"`.
+    pub upstream: String,
+    /// Time until the breaker lets a request through again.
+    pub retry_in: Duration,
+}
+
+// ── Retry ──────────────────────────────────────────────────────────────────
+
+/// How many times, and how patiently, transient failures are retried.
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    /// Retries after the first attempt.
+    pub max_retries: u32,
+    /// Backoff before the first retry; doubled for each one after.
+    pub initial_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(500),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// The capped exponential backoff for a given attempt number.
+    ///
+    /// Uses saturating arithmetic to avoid overflow for large attempt values,
+    /// and clamps the result to [`MAX_BACKOFF`] so a misconfigured
+    /// `max_retries` cannot produce multi-hour sleeps.
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        self.initial_backoff
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(MAX_BACKOFF)
+    }
+
+    /// How long to wait before retrying a response, or `None` to give up.
+    ///
+    /// A `Retry-After` header sets the wait; otherwise the backoff is
+    /// jittered so concurrent requests do not retry in lockstep.
+    fn delay(&self, attempt: u32, headers: &HeaderMap) -> Option<Duration> {
+        if attempt >= self.max_retries {
+            return None;
+        }
+        match retry_after(headers, SystemTime::now()) {
+            Some(wait) if wait > MAX_BACKOFF => None,
+            Some(wait) => Some(wait),
+            None => Some(jitter(self.backoff(attempt))),
+        }
+    }
+}
+
+/// Whether a response status is worth retrying.
+pub fn is_retryable(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+/// Parse a `Retry-After` header, given as delay seconds or an HTTP date.
+///
+/// A date in the past means "retry now".
+pub fn retry_after(headers: &HeaderMap, now: SystemTime) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+    let at: SystemTime = chrono::DateTime::parse_from_rfc2822(value).ok()?.into();
+    Some(at.duration_since(now).unwrap_or_default())
+}
+
+/// Pick a duration uniformly between half of `max` and `max`.
+fn jitter(max: Duration) -> Duration {
+    if max.is_zero() {
+        return max;
+    }
+    // Randomly keyed hashers are a good enough source for spreading retries
+    let random = std::collections::hash_map::RandomState::new()
+        .build_hasher()
+        .finish();
+    let half = max / 2;
+    half + half.mul_f64((random >> 11) as f64 / (1u64 << 53) as f64)
+}
+
+// ── Circuit breaker ────────────────────────────────────────────────────────
+
+/// Fails fast while an upstream is down.
+///
+/// After `failure_threshold` consecutive failures (transport errors or 5xx
+/// responses that survived every retry) the breaker opens and rejects
+/// requests with [`UpstreamUnavailable`] for `open_duration`. It then lets a
+/// single probe through: success closes it, failure opens it again.
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    upstream: String,
+    failure_threshold: u32,
+    open_duration: Duration,
+    state: Mutex<BreakerState>,
+}
+
+#[derive(Debug, Default)]
+struct BreakerState {
+    consecutive_failures: u32,
+    opened_at: Option<Instant>,
+    /// When the in-flight probe of a half-open breaker was let through
+    probing: Option<Instant>,
+}
+
+impl CircuitBreaker {
+    /// A breaker for `upstream` with the default threshold and open duration.
+    pub fn new(upstream: impl Into<String>) -> Self {
+        Self::with_policy(upstream, DEFAULT_FAILURE_THRESHOLD, DEFAULT_OPEN_DURATION)
+    }
+
+    /// A breaker that opens after `failure_threshold` consecutive failures
+    /// and stays open for `open_duration`.
+    pub fn with_policy(
+        upstream: impl Into<String>,
+        failure_threshold: u32,
+        open_duration: Duration,
+    ) -> Self {
+        Self {
+            upstream: upstream.into(),
+            failure_threshold: failure_threshold.max(1),
+            open_duration,
+            state: Mutex::new(BreakerState::default()),
+        }
+    }
+
+    /// Name of the upstream this breaker guards.
+    pub fn upstream(&self) -> &str {
+        &self.upstream
+    }
+
+    /// Whether requests are currently being rejected.
+    pub fn is_open(&self) -> bool {
+        let state = self.state.lock().unwrap();
+        state
+            .opened_at
+            .is_some_and(|at| at.elapsed() < self.open_duration)
+            || state.probing.is_some()
+    }
+
+    /// Admit a request, or reject it while the breaker is open.
+    fn acquire(&self) -> Result<(), UpstreamUnavailable> {
+        let mut state = self.state.lock().unwrap();
+        let Some(opened_at) = state.opened_at else {
+            return Ok(());
+        };
+        let elapsed = opened_at.elapsed();
+        if elapsed < self.open_duration {
+            return Err(self.unavailable(self.open_duration - elapsed));
+        }
+        // Someone else's probe is in flight; wait for its verdict, unless
+        // it has been so long that the probe was most likely dropped
+        if let Some(started) = state.probing
+            && started.elapsed() < self.open_duration.max(MAX_BACKOFF)
+        {
+            return Err(self.unavailable(Duration::from_secs(1)));
+        }
+        state.probing = Some(Instant::now());
+        Ok(())
+    }
+
+    fn record_success(&self) {
+        let mut state = self.state.lock().unwrap();
+        if state.opened_at.is_some() {
+            tracing::info!(upstream = %self.upstream, "circuit breaker closed");
+        }
+        *state = BreakerState::default();
+    }
+
+    fn record_failure(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.consecutive_failures += 1;
+        let probe_failed = state.probing.take().is_some();
+        if probe_failed || state.consecutive_failures >= self.failure_threshold {
+            if !probe_failed {
+                tracing::warn!(
+                    upstream = %self.upstream,
+                    failures = state.consecutive_failures,
+                    open_secs = self.open_duration.as_secs(),
+                    "circuit breaker opened"
+                );
+            }
+            state.opened_at = Some(Instant::now());
+        }
+    }
+
+    fn unavailable(&self, retry_in: Duration) -> UpstreamUnavailable {
+        UpstreamUnavailable {
+            upstream: self.upstream.clone(),
+            retry_in,
+        }
+    }
+}
+
+// ── Sending ────────────────────────────────────────────────────────────────
+
+/// Send a request under `policy`, guarded by `breaker`.
+///
+/// `make_request` is called once per attempt. The final response is returned
+/// whatever its status, for the caller to map; transport errors are not
+/// retried. Server errors and transport failures count against the breaker,
+/// any other response resets it.
+pub(crate) async fn send<F, Fut, E>(
+    policy: &RetryPolicy,
+    breaker: &CircuitBreaker,
+    what: &str,
+    make_request: F,
+) -> Result<HttpResponse, E>
+where
+    F: Fn() -> Fut,
+    Fut: Future<Output = Result<HttpResponse, HttpError>>,
+    E: From<HttpError> + From<UpstreamUnavailable>,
+{
+    breaker.acquire()?;
+    let mut attempt = 0u32;
+    loop {
+        let resp = match make_request().await {
+            Ok(resp) => resp,
+            Err(err) => {
+                breaker.record_failure();
+                return Err(err.into());
+            }
+        };
+        let status = resp.status;
+        if is_retryable(status)
+            && let Some(wait) = policy.delay(attempt, &resp.headers)
+        {
+            tracing::warn!(
+                upstream = %breaker.upstream,
+                attempt = attempt + 1,
+                max_retries = policy.max_retries,
+                status = status.as_u16(),
+                what,
+                backoff_ms = wait.as_millis(),
+                "retrying upstream request"
+            );
+            tokio::time::sleep(wait).await;
+            attempt += 1;
+            continue;
+        }
+
+        if status.is_server_error() {
+            breaker.record_failure();
+        } else {
+            breaker.record_success();
+        }
+        return Ok(resp);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::header::HeaderValue;
+
+    use super::*;
+
+    fn headers(retry_after: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(RETRY_AFTER, HeaderValue::from_str(retry_after).unwrap());
+        headers
+    }
+
+    #[test]
+    fn retry_after_accepts_seconds_and_dates() {
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_445_412_470);
+        assert_eq!(
+            retry_after(&headers("120"), now),
+            Some(Duration::from_secs(120))
+        );
+        // 2015-10-21T07:28:00Z is ten seconds after `now`
+        assert_eq!(
+            retry_after(&headers("Wed, 21 Oct 2015 07:28:00 GMT"), now),
+            Some(Duration::from_secs(10))
+        );
+        assert_eq!(
+            retry_after(&headers("Wed, 21 Oct 2015 07:00:00 GMT"), now),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(retry_after(&headers("soon"), now), None);
+        assert_eq!(retry_after(&HeaderMap::new(), now), None);
+    }
+
+    #[test]
+    fn delay_honours_retry_after_within_the_cap() {
+        let policy = RetryPolicy::default();
+        assert_eq!(policy.delay(0, &headers("7")), Some(Duration::from_secs(7)));
+        assert_eq!(policy.delay(0, &headers("3600")), None);
+        assert_eq!(policy.delay(3, &headers("1")), None);
+    }
+
+    #[test]
+    fn jitter_stays_within_half_to_full() {
+        for _ in 0..100 {
+            let d = jitter(Duration::from_millis(800));
+            assert!(d >= Duration::from_millis(400) && d <= Duration::from_millis(800));
+        }
+        assert_eq!(jitter(Duration::ZERO), Duration::ZERO);
+    }
+
+    #[test]
+    fn breaker_opens_after_threshold_and_probes_once() {
+        let breaker = CircuitBreaker::with_policy("test", 2, Duration::ZERO);
+        breaker.acquire().unwrap();
+        breaker.record_failure();
+        assert!(breaker.acquire().is_ok());
+        breaker.record_failure();
+
+        // Open for zero seconds: one probe goes through, the rest wait on it
+        breaker.acquire().unwrap();
+        let err = breaker.acquire().unwrap_err();
+        assert_eq!(err.upstream, "test");
+
+        // A failed probe reopens immediately; a successful one closes
+        breaker.record_failure();
+        breaker.acquire().unwrap();
+        breaker.record_success();
+        assert!(!breaker.is_open());
+        breaker.acquire().unwrap();
+        breaker.acquire().unwrap();
+    }
+
+    #[test]
+    fn open_breaker_reports_time_left() {
+        let breaker = CircuitBreaker::with_policy("docs.rs", 1, Duration::from_secs(60));
+        breaker.record_failure();
+        assert!(breaker.is_open());
+        let err = breaker.acquire().unwrap_err();
+        assert!(err.retry_in > Duration::from_secs(50));
+        assert!(
+            err.to_string()
+                .starts_with("docs.rs is unavailable (retry in")
+        );
+    }
+}
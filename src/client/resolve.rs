@@ -0,0 +1,297 @@
+//! Helpers for reasoning about dependency requirements the way cargo does.
+//!
+//! crates.io reports dependencies as requirement strings (`^1.2`, `>=0.3, <0.5`)
+//! and features as a raw table; these helpers turn them into concrete versions
+//! and activated feature sets.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use semver::{Op, Version, VersionReq};
+
+/// The lowest version a requirement can match, ignoring pre-releases.
+///
+/// Every comparator contributes a lower bound and the highest one wins, so
+/// `>=1.2, <2` yields `1.2.0` and `>0.3.1` yields `0.3.2`. Comparators with
+/// no lower bound (`<`, `<=`) contribute `0.0.0`.
+///
+/// # Examples
+///
+/// ```
+/// use cratesio_mcp::client::resolve::minimum_version;
+/// use semver::{Version, VersionReq};
+///
+/// let req = VersionReq::parse("^1.2").unwrap();
+/// assert_eq!(minimum_version(&req), Version::new(1, 2, 0));
+/// ```
+pub fn minimum_version(req: &VersionReq) -> Version {
+    req.comparators
+        .iter()
+        .map(|c| {
+            let minor = c.minor.unwrap_or(0);
+            let patch = c.patch.unwrap_or(0);
+            match c.op {
+                Op::Greater => match (c.minor, c.patch) {
+                    (Some(minor), Some(patch)) => Version::new(c.major, minor, patch + 1),
+                    (Some(minor), None) => Version::new(c.major, minor + 1, 0),
+                    _ => Version::new(c.major + 1, 0, 0),
+                },
+                Op::Less | Op::LessEq => Version::new(0, 0, 0),
+                _ => {
+                    let mut v = Version::new(c.major, minor, patch);
+                    v.pre = c.pre.clone();
+                    v
+                }
+            }
+        })
+        .max()
+        .unwrap_or_else(|| Version::new(0, 0, 0))
+}
+
+/// Parse a requirement string as crates.io reports it.
+///
+/// Returns `None` for requirements semver cannot parse (rare, mostly very
+/// old crates).
+pub fn parse_req(req: &str) -> Option<VersionReq> {
+    VersionReq::parse(req.trim()).ok()
+}
+
+/// The semver-compatibility bucket cargo unifies a version into.
+///
+/// Two versions of a crate can share one copy in a build only if they have
+/// the same key: the major version for `1.x` and up, `0.minor` for `0.x`,
+/// and `0.0.patch` for `0.0.x`.
+///
+/// # Examples
+///
+/// ```
+/// use cratesio_mcp::client::resolve::compatibility_key;
+/// use semver::Version;
+///
+/// assert_eq!(compatibility_key(&Version::new(2, 0, 50)), "2");
+/// assert_eq!(compatibility_key(&Version::new(0, 14, 2)), "0.14");
+/// ```
+pub fn compatibility_key(version: &Version) -> String {
+    match (version.major, version.minor) {
+        (0, 0) => format!("0.0.{}", version.patch),
+        (0, minor) => format!("0.{}", minor),
+        (major, _) => major.to_string(),
+    }
+}
+
+/// Features and optional dependencies switched on for one crate version.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FeatureActivation {
+    /// Every enabled feature, including ones enabled by other features.
+    pub features: BTreeSet<String>,
+    /// Optional dependencies switched on.
+    pub optional_deps: BTreeSet<String>,
+    /// Features to enable on dependencies (`dep/feature` and `dep?/feature`).
+    pub dep_features: BTreeMap<String, BTreeSet<String>>,
+}
+
+/// Expand the `requested` features of a crate against its feature table.
+///
+/// Follows cargo's rules: features enable other features; `dep:name` switches
+/// on an optional dependency; `name/feature` switches on `name` (if optional)
+/// and enables `feature` on it; `name?/feature` enables `feature` only if
+/// `name` is switched on by something else. An optional dependency that is
+/// never referenced with `dep:` also acts as an implicit feature of the same
+/// name. Requested features that don't exist are ignored.
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::{BTreeSet, HashMap};
+/// use cratesio_mcp::client::resolve::activate_features;
+///
+/// let table = HashMap::from([
+///     ("default".to_string(), vec!["std".to_string()]),
+///     ("std".to_string(), vec![]),
+///     ("json".to_string(), vec!["dep:serde_json".to_string()]),
+/// ]);
+/// let optional = BTreeSet::from(["serde_json"]);
+///
+/// let on = activate_features(&table, &optional, ["default", "json"]);
+/// assert!(on.features.contains("std"));
+/// assert!(on.optional_deps.contains("serde_json"));
+/// ```
+pub fn activate_features<'a>(
+    table: &HashMap<String, Vec<String>>,
+    optional_deps: &BTreeSet<&str>,
+    requested: impl IntoIterator<Item = &'a str>,
+) -> FeatureActivation {
+    // Optional deps named with `dep:` anywhere lose their implicit feature
+    let explicit: BTreeSet<&str> = table
+        .values()
+        .flatten()
+        .filter_map(|entry| entry.strip_prefix("dep:"))
+        .collect();
+    let implicit = |name: &str| optional_deps.contains(name) && !explicit.contains(name);
+
+    let mut activation = FeatureActivation::default();
+    let mut weak: Vec<(&str, &str)> = Vec::new();
+    let mut pending: Vec<&str> = requested.into_iter().collect();
+
+    while let Some(feature) = pending.pop() {
+        let Some(entries) = table.get(feature) else {
+            if implicit(feature) {
+                activation.features.insert(feature.to_string());
+                activation.optional_deps.insert(feature.to_string());
+            }
+            continue;
+        };
+        if !activation.features.insert(feature.to_string()) {
+            continue;
+        }
+        for entry in entries {
+            if let Some(dep) = entry.strip_prefix("dep:") {
+                activation.optional_deps.insert(dep.to_string());
+            } else if let Some((dep, dep_feature)) = entry.split_once('/') {
+                if let Some(dep) = dep.strip_suffix('?') {
+                    weak.push((dep, dep_feature));
+                    continue;
+                }
+                if optional_deps.contains(dep) {
+                    activation.optional_deps.insert(dep.to_string());
+                    if implicit(dep) {
+                        pending.push(dep);
+                    }
+                }
+                activation
+                    .dep_features
+                    .entry(dep.to_string())
+                    .or_default()
+                    .insert(dep_feature.to_string());
+            } else {
+                pending.push(entry);
+            }
+        }
+    }
+
+    for (dep, dep_feature) in weak {
+        if !optional_deps.contains(dep) || activation.optional_deps.contains(dep) {
+            activation
+                .dep_features
+                .entry(dep.to_string())
+                .or_default()
+                .insert(dep_feature.to_string());
+        }
+    }
+
+    activation
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn min(req: &str) -> Version {
+        minimum_version(&VersionReq::parse(req).unwrap())
+    }
+
+    #[test]
+    fn caret_and_tilde_use_their_base() {
+        assert_eq!(min("^1.2.3"), Version::new(1, 2, 3));
+        assert_eq!(min("1.2"), Version::new(1, 2, 0));
+        assert_eq!(min("~0.4"), Version::new(0, 4, 0));
+        assert_eq!(min("=0.9.1"), Version::new(0, 9, 1));
+    }
+
+    #[test]
+    fn wildcard_and_upper_bounds_start_at_zero() {
+        assert_eq!(min("*"), Version::new(0, 0, 0));
+        assert_eq!(min("<2"), Version::new(0, 0, 0));
+        assert_eq!(min("1.*"), Version::new(1, 0, 0));
+    }
+
+    #[test]
+    fn strict_greater_bumps_the_most_specific_component() {
+        assert_eq!(min(">0.3.1"), Version::new(0, 3, 2));
+        assert_eq!(min(">0.3"), Version::new(0, 4, 0));
+        assert_eq!(min(">1"), Version::new(2, 0, 0));
+    }
+
+    #[test]
+    fn combined_comparators_take_the_highest_bound() {
+        assert_eq!(min(">=0.2, <0.4"), Version::new(0, 2, 0));
+        assert_eq!(min(">=1.0, >=1.5"), Version::new(1, 5, 0));
+    }
+
+    #[test]
+    fn parse_req_rejects_garbage() {
+        assert!(parse_req("^1.0").is_some());
+        assert!(parse_req("not a version").is_none());
+    }
+
+    #[test]
+    fn compatibility_key_follows_leftmost_nonzero() {
+        let key = |v: &str| compatibility_key(&Version::parse(v).unwrap());
+        assert_eq!(key("1.0.109"), "1");
+        assert_eq!(key("1.9.0"), key("1.0.0"));
+        assert_eq!(key("0.3.31"), "0.3");
+        assert_ne!(key("0.3.0"), key("0.4.0"));
+        assert_eq!(key("0.0.7"), "0.0.7");
+    }
+
+    fn table(entries: &[(&str, &[&str])]) -> HashMap<String, Vec<String>> {
+        entries
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.iter().map(|s| s.to_string()).collect()))
+            .collect()
+    }
+
+    fn set(items: &[&str]) -> BTreeSet<String> {
+        items.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn features_enable_features_and_dep_syntax() {
+        let t = table(&[
+            ("default", &["std"]),
+            ("std", &["alloc"]),
+            ("alloc", &[]),
+            ("full", &["dep:tokio", "serde/derive"]),
+        ]);
+        let optional = BTreeSet::from(["tokio", "serde"]);
+
+        let on = activate_features(&t, &optional, ["default"]);
+        assert_eq!(on.features, set(&["alloc", "default", "std"]));
+        assert!(on.optional_deps.is_empty());
+
+        let on = activate_features(&t, &optional, ["full"]);
+        assert_eq!(on.optional_deps, set(&["serde", "tokio"]));
+        assert_eq!(on.dep_features["serde"], set(&["derive"]));
+        // serde has no `dep:` entry, so `serde/derive` also turns on its implicit feature
+        assert!(on.features.contains("serde"));
+    }
+
+    #[test]
+    fn implicit_features_for_optional_deps() {
+        let t = table(&[("default", &[])]);
+        let on = activate_features(&t, &BTreeSet::from(["rayon"]), ["rayon", "missing"]);
+        assert_eq!(on.optional_deps, set(&["rayon"]));
+        assert_eq!(on.features, set(&["rayon"]));
+    }
+
+    #[test]
+    fn dep_prefix_hides_implicit_feature() {
+        let t = table(&[("parallel", &["dep:rayon"])]);
+        let on = activate_features(&t, &BTreeSet::from(["rayon"]), ["rayon"]);
+        assert!(on.optional_deps.is_empty());
+    }
+
+    #[test]
+    fn weak_dep_features_need_the_dep_enabled() {
+        let t = table(&[
+            ("serde", &["dep:serde", "chrono?/serde"]),
+            ("clock", &["dep:chrono"]),
+        ]);
+        let optional = BTreeSet::from(["serde", "chrono"]);
+
+        let on = activate_features(&t, &optional, ["serde"]);
+        assert!(!on.dep_features.contains_key("chrono"));
+
+        let on = activate_features(&t, &optional, ["serde", "clock"]);
+        assert_eq!(on.dep_features["chrono"], set(&["serde"]));
+    }
+}
@@ -0,0 +1,246 @@
+//! Deduplication of identical concurrent requests.
+//!
+//! While a request for a key is in flight, further callers with the same key
+//! wait for it and share its result instead of sending their own. Failures
+//! are shared too, so an outage or rate limit costs one request (and one
+//! retry loop) however many callers were waiting.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use tokio::sync::watch;
+
+/// An error that can be handed to each caller that waited on a failed call.
+pub trait ShareError {
+    /// A copy of this error for one waiting caller.
+    fn share(&self) -> Self;
+}
+
+// Leaf errors that can't be cloned are shared as their message.
+
+impl ShareError for super::http::HttpError {
+    fn share(&self) -> Self {
+        Self::new(self.to_string())
+    }
+}
+
+impl ShareError for serde_json::Error {
+    fn share(&self) -> Self {
+        serde::de::Error::custom(self)
+    }
+}
+
+impl ShareError for std::io::Error {
+    fn share(&self) -> Self {
+        Self::new(self.kind(), self.to_string())
+    }
+}
+
+/// Progress of one in-flight call, as seen by the callers waiting on it.
+enum Flight<T, E> {
+    Pending,
+    Done(T),
+    Failed(Arc<E>),
+}
+
+impl<T: Clone, E> Clone for Flight<T, E> {
+    fn clone(&self) -> Self {
+        match self {
+            Self::Pending => Self::Pending,
+            Self::Done(value) => Self::Done(value.clone()),
+            Self::Failed(err) => Self::Failed(Arc::clone(err)),
+        }
+    }
+}
+
+/// Coalesces concurrent calls that share a key into a single call.
+pub struct SingleFlight<T, E> {
+    calls: Mutex<HashMap<String, watch::Receiver<Flight<T, E>>>>,
+}
+
+impl<T, E> Default for SingleFlight<T, E> {
+    fn default() -> Self {
+        Self {
+            calls: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl<T: Clone, E: ShareError> SingleFlight<T, E> {
+    /// Run `call` for `key`, or wait for the identical call already in flight
+    /// and return its result.
+    pub async fn run<F, Fut>(&self, key: &str, call: F) -> Result<T, E>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+    {
+        loop {
+            let joined = {
+                let mut calls = self.calls.lock().unwrap();
+                match calls.get(key) {
+                    Some(flight) => Ok(flight.clone()),
+                    None => {
+                        let (tx, rx) = watch::channel(Flight::Pending);
+                        calls.insert(key.to_string(), rx);
+                        Err(tx)
+                    }
+                }
+            };
+            let mut waiting = match joined {
+                Ok(waiting) => waiting,
+                Err(tx) => return self.lead(key, tx, call).await,
+            };
+
+            let outcome = waiting
+                .wait_for(|flight| !matches!(flight, Flight::Pending))
+                .await
+                .map(|flight| flight.clone());
+            match outcome {
+                Ok(Flight::Done(value)) => return Ok(value),
+                Ok(Flight::Failed(err)) => return Err(err.share()),
+                Ok(Flight::Pending) => unreachable!("waited until the call finished"),
+                // The leader was cancelled before finishing; start over
+                Err(_) => continue,
+            }
+        }
+    }
+
+    /// Make the call on behalf of everyone waiting on `key`.
+    async fn lead<F, Fut>(
+        &self,
+        key: &str,
+        tx: watch::Sender<Flight<T, E>>,
+        call: F,
+    ) -> Result<T, E>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+    {
+        // Dropped before `tx`, even if the call is cancelled, so a waiter
+        // that wakes to a closed channel finds the key free
+        let _landed = Landed { flights: self, key };
+        let result = call().await;
+        tx.send_replace(match &result {
+            Ok(value) => Flight::Done(value.clone()),
+            Err(err) => Flight::Failed(Arc::new(err.share())),
+        });
+        result
+    }
+}
+
+/// Removes a finished (or abandoned) call from the in-flight table.
+struct Landed<'a, T, E> {
+    flights: &'a SingleFlight<T, E>,
+    key: &'a str,
+}
+
+impl<T, E> Drop for Landed<'_, T, E> {
+    fn drop(&mut self) {
+        self.flights.calls.lock().unwrap().remove(self.key);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    use super::*;
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Boom;
+
+    impl ShareError for Boom {
+        fn share(&self) -> Self {
+            self.clone()
+        }
+    }
+
+    #[tokio::test]
+    async fn concurrent_calls_share_one_result() {
+        let flights = SingleFlight::<u32, Boom>::default();
+        let calls = AtomicUsize::new(0);
+        let call = || async {
+            calls.fetch_add(1, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            Ok::<_, Boom>(7)
+        };
+
+        let (a, b, c) = tokio::join!(
+            flights.run("k", call),
+            flights.run("k", call),
+            flights.run("k", call),
+        );
+
+        assert_eq!((a, b, c), (Ok(7), Ok(7), Ok(7)));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert!(flights.calls.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn different_keys_are_not_coalesced() {
+        let flights = SingleFlight::<&str, Boom>::default();
+        let calls = AtomicUsize::new(0);
+        let call = |v| {
+            let calls = &calls;
+            move || async move {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Ok::<_, Boom>(v)
+            }
+        };
+
+        let (a, b) = tokio::join!(flights.run("a", call("a")), flights.run("b", call("b")));
+
+        assert_eq!((a, b), (Ok("a"), Ok("b")));
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn waiters_share_a_failure() {
+        let flights = SingleFlight::<u32, Boom>::default();
+        let calls = AtomicUsize::new(0);
+        let call = || async {
+            calls.fetch_add(1, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            Err(Boom)
+        };
+
+        let (a, b, c) = tokio::join!(
+            flights.run("k", call),
+            flights.run("k", call),
+            flights.run("k", call),
+        );
+
+        assert_eq!((a, b, c), (Err(Boom), Err(Boom), Err(Boom)));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        // The failure is not remembered past the call
+        assert_eq!(flights.run("k", || async { Ok(2) }).await, Ok(2));
+    }
+
+    #[tokio::test]
+    async fn cancelled_leader_hands_over() {
+        let flights = Arc::new(SingleFlight::<u32, Boom>::default());
+
+        let leader = {
+            let flights = Arc::clone(&flights);
+            tokio::spawn(async move {
+                flights
+                    .run("k", || async {
+                        tokio::time::sleep(Duration::from_secs(60)).await;
+                        Ok::<_, Boom>(1)
+                    })
+                    .await
+            })
+        };
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        let follower = {
+            let flights = Arc::clone(&flights);
+            tokio::spawn(async move { flights.run("k", || async { Ok::<_, Boom>(2) }).await })
+        };
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        leader.abort();
+
+        assert_eq!(follower.await.unwrap(), Ok(2));
+    }
+}
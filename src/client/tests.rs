@@ -1,6 +1,7 @@
 use std::time::Duration;
 
-use wiremock::matchers::{header, method, path};
+use futures::{StreamExt, TryStreamExt};
+use wiremock::matchers::{header, method, path, query_param, query_param_is_missing};
 use wiremock::{Mock, MockServer, ResponseTemplate};
 
 use super::CratesIoClient;
@@ -372,6 +373,40 @@ async fn crates_search_parses_response() {
     assert_eq!(page.crates[1].name, "rmcp");
 }
 
+#[tokio::test]
+async fn crates_search_sends_filters() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/crates"))
+        .and(query_param("category", "asynchronous"))
+        .and(query_param("keyword", "runtime"))
+        .and(query_param("all_keywords", "async io"))
+        .and(query_param("user_id", "7"))
+        .and(query_param("team_id", "3"))
+        .and(query_param("ids[]", "tokio"))
+        .and(query_param("letter", "t"))
+        .and(query_param_is_missing("q"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(SEARCH_JSON, "application/json"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = test_client(&server.uri());
+    let query = super::CratesQuery::builder()
+        .category("asynchronous")
+        .keyword("runtime")
+        .all_keywords(&["async", "io"])
+        .user_id(7)
+        .team_id(3)
+        .ids(&["tokio"])
+        .letter('t')
+        .build();
+    let page = client.crates(query).await.unwrap();
+
+    assert_eq!(page.crates.len(), 2);
+}
+
 // ── crate_downloads ────────────────────────────────────────────────────────
 
 const DOWNLOADS_JSON: &str = r#"{
@@ -559,7 +594,10 @@ const DEPENDENCIES_JSON: &str = r#"{
             "req": "^1",
             "kind": "normal",
             "optional": false,
-            "version_id": 100
+            "version_id": 100,
+            "features": ["macros", "rt"],
+            "default_features": false,
+            "target": null
         },
         {
             "crate_id": "serde",
@@ -573,7 +611,8 @@ const DEPENDENCIES_JSON: &str = r#"{
             "req": "^0.6",
             "kind": "dev",
             "optional": false,
-            "version_id": 100
+            "version_id": 100,
+            "target": "cfg(unix)"
         }
     ]
 }"#;
@@ -602,9 +641,16 @@ async fn crate_dependencies_parses_response() {
     assert_eq!(deps[0].req, "^1");
     assert_eq!(deps[0].kind, "normal");
     assert!(!deps[0].optional);
+    assert_eq!(deps[0].features, vec!["macros", "rt"]);
+    assert!(!deps[0].default_features);
     assert!(deps[1].optional);
+    // Missing keys fall back to cargo's defaults
+    assert!(deps[1].features.is_empty());
+    assert!(deps[1].default_features);
+    assert!(deps[1].target.is_none());
     assert_eq!(deps[2].crate_id, "wiremock");
     assert_eq!(deps[2].kind, "dev");
+    assert_eq!(deps[2].target.as_deref(), Some("cfg(unix)"));
 }
 
 // ── crate_authors ──────────────────────────────────────────────────────────
@@ -1017,6 +1063,127 @@ async fn site_metadata_parses_response() {
     assert_eq!(meta.commit.as_deref(), Some("abc123def456"));
 }
 
+// ── disk cache ──────────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn disk_cache_serves_immutable_responses_across_clients() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/crates/tower-mcp/0.6.0/dependencies"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_raw(DEPENDENCIES_JSON, "application/json"),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/crates/tower-mcp/owners"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({"users": []})))
+        .expect(2)
+        .mount(&server)
+        .await;
+
+    let dir = super::disk_cache::tests::temp_dir("client");
+    let cache = || {
+        std::sync::Arc::new(
+            super::disk_cache::DiskCache::open(&dir, 1024 * 1024)
+                .unwrap()
+                .with_metadata_ttl(Duration::ZERO),
+        )
+    };
+
+    let client = test_client(&server.uri()).with_disk_cache(cache());
+    client
+        .crate_dependencies("tower-mcp", "0.6.0")
+        .await
+        .unwrap();
+    client.crate_owners("tower-mcp").await.unwrap();
+
+    // A new client (as after a restart) reuses the dependency list, while
+    // owners have expired and are fetched again
+    let client = test_client(&server.uri()).with_disk_cache(cache());
+    let deps = client
+        .crate_dependencies("tower-mcp", "0.6.0")
+        .await
+        .unwrap();
+    assert_eq!(deps.len(), 3);
+    tokio::time::sleep(Duration::from_millis(5)).await;
+    client.crate_owners("tower-mcp").await.unwrap();
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test]
+async fn stale_cache_entries_are_revalidated() {
+    let server = MockServer::start().await;
+    // Mounted first so it wins when the validator is present
+    Mock::given(method("GET"))
+        .and(path("/crates/tower-mcp/owners"))
+        .and(header("if-none-match", "\"v1\""))
+        .respond_with(ResponseTemplate::new(304))
+        .expect(2)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/crates/tower-mcp/owners"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("etag", "\"v1\"")
+                .insert_header("last-modified", "Wed, 01 Jan 2025 00:00:00 GMT")
+                .set_body_json(serde_json::json!({
+                    "users": [{"id": 1, "login": "someone", "url": ""}]
+                })),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let dir = super::disk_cache::tests::temp_dir("revalidate");
+    let cache = super::disk_cache::DiskCache::open(&dir, 1024 * 1024)
+        .unwrap()
+        .with_metadata_ttl(Duration::ZERO);
+    let client = test_client(&server.uri()).with_disk_cache(std::sync::Arc::new(cache));
+
+    for _ in 0..3 {
+        let owners = client.crate_owners("tower-mcp").await.unwrap();
+        assert_eq!(owners[0].login, "someone");
+        tokio::time::sleep(Duration::from_millis(5)).await;
+    }
+    let requests = server.received_requests().await.unwrap();
+    assert_eq!(
+        requests[1].headers["if-modified-since"],
+        "Wed, 01 Jan 2025 00:00:00 GMT"
+    );
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+// ── offline (database dump) ─────────────────────────────────────────────────
+
+#[tokio::test]
+async fn dump_answers_without_requests() {
+    let server = MockServer::start().await;
+    // Nothing is mounted: any request would 404
+
+    let client = test_client(&server.uri())
+        .with_dump(std::sync::Arc::new(super::dump::tests::fixture_store()));
+
+    let krate = client.get_crate("serde").await.unwrap();
+    assert_eq!(krate.crate_data.max_version, "1.0.0");
+    let owners = client.crate_owners("serde").await.unwrap();
+    assert_eq!(owners.len(), 2);
+    let deps = client.crate_dependencies("clap", "4.5.0").await.unwrap();
+    assert_eq!(deps.len(), 2);
+
+    let err = client.get_crate("missing").await.unwrap_err();
+    assert!(matches!(err, super::Error::NotFound(_)), "got: {err:?}");
+
+    // Endpoints the dump can't answer fail instead of going online
+    let err = client.crate_readme("serde", "1.0.0").await.unwrap_err();
+    assert!(matches!(err, super::Error::Offline(_)), "got: {err:?}");
+    assert!(server.received_requests().await.unwrap().is_empty());
+}
+
 // ── error mapping ───────────────────────────────────────────────────────────
 
 #[tokio::test]
@@ -1939,6 +2106,309 @@ async fn no_retry_on_404() {
     );
 }
 
+#[tokio::test]
+async fn does_not_wait_out_a_long_retry_after() {
+    let server = MockServer::start().await;
+
+    // An hour is past the backoff cap, so the 429 is surfaced at once.
+    Mock::given(method("GET"))
+        .and(path("/summary"))
+        .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", "3600"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = test_retry_client(&server.uri());
+    let err = client.summary().await.unwrap_err();
+
+    assert!(
+        matches!(err, super::Error::RateLimited),
+        "expected RateLimited, got: {err:?}"
+    );
+}
+
+#[tokio::test]
+async fn open_circuit_breaker_fails_fast() {
+    use std::sync::Arc;
+
+    use super::resilience::CircuitBreaker;
+
+    let server = MockServer::start().await;
+
+    // Two failures open the breaker; the third call never reaches the server.
+    Mock::given(method("GET"))
+        .and(path("/summary"))
+        .respond_with(ResponseTemplate::new(503))
+        .expect(2)
+        .mount(&server)
+        .await;
+
+    let client = test_client(&server.uri()).with_circuit_breaker(Arc::new(
+        CircuitBreaker::with_policy("crates.io", 2, Duration::from_secs(60)),
+    ));
+    for _ in 0..2 {
+        let err = client.summary().await.unwrap_err();
+        assert!(matches!(err, super::Error::Api { status: 503, .. }));
+    }
+    let err = client.summary().await.unwrap_err();
+
+    match err {
+        super::Error::UpstreamUnavailable(e) => {
+            assert_eq!(e.upstream, "crates.io");
+            assert!(e.retry_in > Duration::from_secs(50));
+        }
+        other => panic!("expected UpstreamUnavailable, got: {other:?}"),
+    }
+    assert!(client.circuit_breaker().is_open());
+}
+
+// ── request coalescing ──────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn concurrent_identical_requests_are_coalesced() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/summary"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_raw(SUMMARY_JSON, "application/json")
+                .set_delay(Duration::from_millis(100)),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = test_client(&server.uri());
+    let (a, b, c) = tokio::join!(client.summary(), client.summary(), client.summary());
+
+    for summary in [a, b, c] {
+        assert_eq!(summary.unwrap().num_crates, 180000);
+    }
+}
+
+#[tokio::test]
+async fn concurrent_identical_failures_are_shared() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/summary"))
+        .respond_with(ResponseTemplate::new(503).set_delay(Duration::from_millis(100)))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = test_client(&server.uri());
+    let (a, b, c) = tokio::join!(client.summary(), client.summary(), client.summary());
+
+    for summary in [a, b, c] {
+        assert!(matches!(
+            summary,
+            Err(super::Error::Api { status: 503, .. })
+        ));
+    }
+}
+
+#[tokio::test]
+async fn sequential_requests_are_not_coalesced() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/summary"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(SUMMARY_JSON, "application/json"))
+        .expect(2)
+        .mount(&server)
+        .await;
+
+    let client = test_client(&server.uri());
+    client.summary().await.unwrap();
+    client.summary().await.unwrap();
+}
+
+// ── streaming pagination ────────────────────────────────────────────────────
+
+/// A search page holding one crate, linking to `next_page` if given.
+fn search_page(name: &str, total: u64, next_page: Option<&str>) -> serde_json::Value {
+    serde_json::json!({
+        "crates": [{
+            "name": name,
+            "max_version": "1.0.0",
+            "created_at": "2026-01-01T00:00:00Z",
+            "updated_at": "2026-01-01T00:00:00Z"
+        }],
+        "meta": { "total": total, "next_page": next_page }
+    })
+}
+
+#[tokio::test]
+async fn crates_stream_follows_next_page_links() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/crates"))
+        .and(query_param_is_missing("seek"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(search_page(
+            "tower",
+            2,
+            Some("?q=tower&per_page=1&seek=abc"),
+        )))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/crates"))
+        .and(query_param("q", "tower"))
+        .and(query_param("seek", "abc"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(search_page("tower-http", 2, None)))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = test_client(&server.uri());
+    let query = super::CratesQuery::builder()
+        .search("tower")
+        .per_page(1)
+        .build();
+    let names: Vec<String> = client
+        .crates_stream(query)
+        .map_ok(|c| c.name)
+        .try_collect()
+        .await
+        .unwrap();
+
+    assert_eq!(names, ["tower", "tower-http"]);
+}
+
+#[tokio::test]
+async fn reverse_dependencies_stream_walks_page_numbers() {
+    let server = MockServer::start().await;
+
+    let page = |crate_name: &str, version_id: u64| {
+        serde_json::json!({
+            "dependencies": [{ "crate_id": "tower-mcp", "req": "^0.6", "version_id": version_id }],
+            "versions": [{ "id": version_id, "crate": crate_name, "num": "0.1.0" }],
+            "meta": { "total": 2 }
+        })
+    };
+    Mock::given(method("GET"))
+        .and(path("/crates/tower-mcp/reverse_dependencies"))
+        .and(query_param_is_missing("page"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(page("a", 1)))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/crates/tower-mcp/reverse_dependencies"))
+        .and(query_param("page", "2"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(page("b", 2)))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = test_client(&server.uri());
+    let dependents: Vec<String> = client
+        .reverse_dependencies_stream("tower-mcp")
+        .map_ok(|d| d.crate_version.crate_name)
+        .try_collect()
+        .await
+        .unwrap();
+
+    assert_eq!(dependents, ["a", "b"]);
+}
+
+#[tokio::test]
+async fn bounded_stream_stops_fetching() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/keywords"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(KEYWORDS_JSON, "application/json"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = test_client(&server.uri());
+    let keywords: Vec<_> = client.keywords_stream().take(2).collect().await;
+
+    assert_eq!(keywords.len(), 2);
+    assert!(keywords.iter().all(Result::is_ok));
+}
+
+#[tokio::test]
+async fn stream_ends_after_an_error() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/categories"))
+        .respond_with(ResponseTemplate::new(500))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = test_client(&server.uri());
+    let categories: Vec<_> = client.categories_stream().collect().await;
+
+    assert_eq!(categories.len(), 1);
+    assert!(categories[0].is_err());
+}
+
+#[tokio::test]
+async fn dump_streams_every_page() {
+    let client = test_client("http://localhost:1")
+        .with_dump(std::sync::Arc::new(super::dump::tests::fixture_store()));
+
+    let keywords: Vec<_> = client.keywords_stream().try_collect().await.unwrap();
+    let page = client.keywords(None, None).await.unwrap();
+
+    assert_eq!(keywords.len() as u64, page.meta.total);
+}
+
+// ── custom backend ──────────────────────────────────────────────────────────
+
+#[tokio::test]
+async fn requests_go_through_a_custom_backend() {
+    use std::sync::Arc;
+
+    use super::http::{HttpResponse, MemoryBackend, Method};
+
+    let backend = Arc::new(MemoryBackend::new());
+    backend.route(
+        Method::GET,
+        "http://memory.test/crates",
+        HttpResponse::json(&search_page("tower", 1, None)),
+    );
+    let client = test_client("http://memory.test").with_backend(backend.clone());
+
+    let page = client
+        .crates(super::CratesQuery::builder().search("tower").build())
+        .await
+        .unwrap();
+    assert_eq!(page.crates[0].name, "tower");
+
+    let requests = backend.requests();
+    assert_eq!(requests.len(), 1);
+    assert!(requests[0].url.starts_with("http://memory.test/crates?"));
+    assert_eq!(requests[0].headers["user-agent"], "test-agent");
+}
+
+#[tokio::test]
+async fn custom_backend_status_errors_are_mapped() {
+    use std::sync::Arc;
+
+    use super::http::{HttpResponse, MemoryBackend, Method, StatusCode};
+
+    let backend = Arc::new(MemoryBackend::new());
+    backend.route(
+        Method::GET,
+        "http://memory.test/crates/missing",
+        HttpResponse::new(StatusCode::NOT_FOUND),
+    );
+    let client = test_client("http://memory.test").with_backend(backend);
+
+    let err = client.get_crate("missing").await.unwrap_err();
+    assert!(matches!(err, super::Error::NotFound(_)), "{err:?}");
+}
+
 // ── publish ─────────────────────────────────────────────────────────────────
 
 #[tokio::test]
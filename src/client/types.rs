@@ -95,6 +95,35 @@ pub struct Dependency {
     pub optional: bool,
     #[serde(default)]
     pub version_id: u64,
+    /// Features the dependent enables on this dependency.
+    #[serde(default)]
+    pub features: Vec<String>,
+    /// Whether the dependency's `default` feature is enabled.
+    #[serde(default = "default_true")]
+    pub default_features: bool,
+    /// Target triple or `cfg(...)` expression the dependency is limited to.
+    #[serde(default)]
+    pub target: Option<String>,
+    /// Index URL of the registry the dependency comes from, when it differs
+    /// from the dependent's registry.
+    #[serde(default)]
+    pub registry: Option<String>,
+    /// The name the dependent gives a renamed dependency
+    /// (`alias = { package = "..." }`), which its feature table refers to.
+    #[serde(default)]
+    pub explicit_name: Option<String>,
+}
+
+impl Dependency {
+    /// The dependency's key in the dependent's manifest: its rename if it
+    /// has one, else the crate name.
+    pub fn key(&self) -> &str {
+        self.explicit_name.as_deref().unwrap_or(&self.crate_id)
+    }
+}
+
+fn default_true() -> bool {
+    true
 }
 
 /// A keyword from crates.io.
@@ -124,6 +153,9 @@ pub struct Category {
 #[cfg_attr(feature = "mcp", derive(JsonSchema))]
 pub struct Meta {
     pub total: u64,
+    /// Query string for the next page, when the endpoint provides one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_page: Option<String>,
 }
 
 /// Authors listed in a crate version's Cargo.toml.
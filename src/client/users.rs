@@ -1,7 +1,10 @@
 //! User-related API endpoints.
 
+use futures::Stream;
+
 use super::CratesIoClient;
 use super::error::Error;
+use super::pagination;
 use super::types::{User, UserStats, Version};
 use super::wire::{
     MyUpdatesResponse, UpdateUserData, UpdateUserRequest, UserResponse, UserStatsResponse,
@@ -60,4 +63,16 @@ impl CratesIoClient {
         let resp: MyUpdatesResponse = self.get_json_query_auth("/me/updates", &params).await?;
         Ok((resp.versions, resp.meta.more))
     }
+
+    /// Stream every update to crates the authenticated user follows,
+    /// fetching pages as they're read.
+    ///
+    /// Requires authentication.
+    pub fn my_updates_stream(&self) -> impl Stream<Item = Result<Version, Error>> {
+        let params = vec![("per_page".to_string(), pagination::MAX_PER_PAGE.to_string())];
+        pagination::items(pagination::pages(params, move |params| async move {
+            self.get_json_query_auth::<MyUpdatesResponse>("/me/updates", &params)
+                .await
+        }))
+    }
 }
@@ -2,8 +2,11 @@
 
 use std::collections::HashMap;
 
+use futures::Stream;
+
 use super::CratesIoClient;
 use super::error::Error;
+use super::pagination;
 use super::types::{
     Authors, CrateDownloads, Dependency, OkResponse, Version, VersionSettings, VersionsPage,
 };
@@ -28,6 +31,15 @@ impl CratesIoClient {
             .await
     }
 
+    /// Stream every version of a crate, fetching pages as they're read.
+    pub fn versions_stream(&self, name: &str) -> impl Stream<Item = Result<Version, Error>> {
+        let path = format!("/crates/{name}/versions");
+        pagination::items(pagination::pages(Vec::new(), move |params| {
+            let path = path.clone();
+            async move { self.get_json_query::<VersionsPage>(&path, &params).await }
+        }))
+    }
+
     /// Get metadata for a specific crate version.
     pub async fn crate_version(&self, name: &str, version: &str) -> Result<Version, Error> {
         let resp: VersionResponse = self.get_json(&format!("/crates/{name}/{version}")).await?;
@@ -60,6 +72,11 @@ impl CratesIoClient {
         name: &str,
         version: &str,
     ) -> Result<Vec<Dependency>, Error> {
+        if let Some(dump) = self.dump() {
+            return dump
+                .crate_dependencies(name, version)
+                .ok_or_else(|| Error::NotFound(format!("/crates/{name}/{version}/dependencies")));
+        }
         let resp: DependenciesResponse = self
             .get_json(&format!("/crates/{name}/{version}/dependencies"))
             .await?;
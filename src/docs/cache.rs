@@ -1,4 +1,4 @@
-//! In-memory LRU cache for parsed rustdoc JSON.
+//! In-memory LRU cache for parsed rustdoc JSON and its full-text indexes.
 
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -8,9 +8,13 @@ use rustdoc_types::Crate;
 use tokio::sync::RwLock;
 
 use crate::client::docsrs::{DocsRsClient, DocsRsError};
+use crate::client::single_flight::SingleFlight;
+use crate::docs::search::DocIndex;
 
 struct CacheEntry {
     krate: Arc<Crate>,
+    /// Full-text index of `krate`, built on first use.
+    search_index: Option<Arc<DocIndex>>,
     fetched_at: Instant,
     /// Tracks last access for LRU eviction.
     last_accessed: Instant,
@@ -23,6 +27,10 @@ pub struct DocsCache {
     entries: RwLock<HashMap<(String, String), CacheEntry>>,
     max_entries: usize,
     ttl: Duration,
+    /// Fetches in progress, so concurrent misses download and parse once
+    in_flight: SingleFlight<Arc<Crate>, DocsRsError>,
+    /// Index builds in progress, so concurrent searches build each index once
+    index_builds: SingleFlight<Arc<DocIndex>, DocsRsError>,
 }
 
 impl DocsCache {
@@ -32,6 +40,8 @@ impl DocsCache {
             entries: RwLock::new(HashMap::new()),
             max_entries,
             ttl,
+            in_flight: SingleFlight::default(),
+            index_builds: SingleFlight::default(),
         }
     }
 
@@ -72,6 +82,7 @@ impl DocsCache {
             key,
             CacheEntry {
                 krate,
+                search_index: None,
                 fetched_at: now,
                 last_accessed: now,
             },
@@ -79,6 +90,8 @@ impl DocsCache {
     }
 
     /// Get a cached crate, or fetch and cache it on miss.
+    ///
+    /// Concurrent misses for the same crate version share one fetch.
     pub async fn get_or_fetch(
         &self,
         client: &DocsRsClient,
@@ -89,10 +102,62 @@ impl DocsCache {
             return Ok(krate);
         }
 
-        let krate = client.fetch_rustdoc(name, version).await?;
-        let krate = Arc::new(krate);
-        self.insert(name, version, Arc::clone(&krate)).await;
-        Ok(krate)
+        self.in_flight
+            .run(&format!("{name}@{version}"), || async {
+                let krate = Arc::new(client.fetch_rustdoc(name, version).await?);
+                self.insert(name, version, Arc::clone(&krate)).await;
+                Ok(krate)
+            })
+            .await
+    }
+
+    /// Get a crate and its full-text index, fetching the crate on miss.
+    ///
+    /// The index is built the first time it is asked for and kept with the
+    /// cached crate, so it lives and expires with it. Building runs on the
+    /// blocking pool, and concurrent requests for the same crate share one
+    /// build.
+    pub async fn get_or_fetch_with_index(
+        &self,
+        client: &DocsRsClient,
+        name: &str,
+        version: &str,
+    ) -> Result<(Arc<Crate>, Arc<DocIndex>), DocsRsError> {
+        let krate = self.get_or_fetch(client, name, version).await?;
+        let key = (name.to_string(), version.to_string());
+        let cached_index = || async {
+            self.entries.read().await.get(&key).and_then(|entry| {
+                Arc::ptr_eq(&entry.krate, &krate)
+                    .then(|| entry.search_index.clone())
+                    .flatten()
+            })
+        };
+        if let Some(index) = cached_index().await {
+            return Ok((krate, index));
+        }
+
+        // Keyed by the crate's address too, so a replaced entry gets its own build
+        let flight = format!("{name}@{version}@{:p}", Arc::as_ptr(&krate));
+        let index = self
+            .index_builds
+            .run(&flight, || async {
+                if let Some(index) = cached_index().await {
+                    return Ok(index);
+                }
+                let source = Arc::clone(&krate);
+                let index = tokio::task::spawn_blocking(move || DocIndex::build(&source))
+                    .await
+                    .unwrap_or_else(|e| std::panic::resume_unwind(e.into_panic()));
+                let index = Arc::new(index);
+                if let Some(entry) = self.entries.write().await.get_mut(&key)
+                    && Arc::ptr_eq(&entry.krate, &krate)
+                {
+                    entry.search_index = Some(Arc::clone(&index));
+                }
+                Ok(index)
+            })
+            .await?;
+        Ok((krate, index))
     }
 }
 
@@ -159,4 +224,90 @@ mod tests {
         assert!(cache.get("b", "1.0.0").await.is_none());
         assert!(cache.get("c", "1.0.0").await.is_some());
     }
+
+    #[tokio::test]
+    async fn concurrent_misses_fetch_once() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/crate/serde/1.0.0/json.gz"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(synthetic_crate())
+                    .set_delay(Duration::from_millis(100)),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client =
+            DocsRsClient::with_base_url("test", Duration::from_secs(30), &server.uri()).unwrap();
+        let cache = DocsCache::new(10, Duration::from_secs(3600));
+        let (a, b, c) = tokio::join!(
+            cache.get_or_fetch(&client, "serde", "1.0.0"),
+            cache.get_or_fetch(&client, "serde", "1.0.0"),
+            cache.get_or_fetch(&client, "serde", "1.0.0"),
+        );
+
+        let a = a.unwrap();
+        assert!(Arc::ptr_eq(&a, &b.unwrap()));
+        assert!(Arc::ptr_eq(&a, &c.unwrap()));
+    }
+
+    #[tokio::test]
+    async fn search_index_is_built_once_per_entry() {
+        use crate::client::http::{HttpResponse, MemoryBackend, Method};
+
+        let backend = Arc::new(MemoryBackend::new());
+        backend.route(
+            Method::GET,
+            "http://docs.test/crate/serde/1.0.0/json.gz",
+            HttpResponse::json(&synthetic_crate()),
+        );
+        let client =
+            DocsRsClient::with_base_url("test", Duration::from_secs(30), "http://docs.test")
+                .unwrap()
+                .with_backend(backend.clone());
+        let cache = DocsCache::new(10, Duration::from_secs(3600));
+
+        let (krate, a) = cache
+            .get_or_fetch_with_index(&client, "serde", "1.0.0")
+            .await
+            .unwrap();
+        let (_, b) = cache
+            .get_or_fetch_with_index(&client, "serde", "1.0.0")
+            .await
+            .unwrap();
+        assert!(Arc::ptr_eq(&a, &b));
+        assert!(Arc::ptr_eq(
+            &krate,
+            &cache.get("serde", "1.0.0").await.unwrap()
+        ));
+        assert_eq!(backend.requests().len(), 1);
+
+        // Concurrent searches share one build
+        cache
+            .insert("serde", "1.0.0", Arc::new(synthetic_crate()))
+            .await;
+        let (x, y, z) = tokio::join!(
+            cache.get_or_fetch_with_index(&client, "serde", "1.0.0"),
+            cache.get_or_fetch_with_index(&client, "serde", "1.0.0"),
+            cache.get_or_fetch_with_index(&client, "serde", "1.0.0"),
+        );
+        let x = x.unwrap().1;
+        assert!(Arc::ptr_eq(&x, &y.unwrap().1));
+        assert!(Arc::ptr_eq(&x, &z.unwrap().1));
+
+        // Replacing the crate drops its index
+        cache
+            .insert("serde", "1.0.0", Arc::new(synthetic_crate()))
+            .await;
+        let (_, c) = cache
+            .get_or_fetch_with_index(&client, "serde", "1.0.0")
+            .await
+            .unwrap();
+        assert!(!Arc::ptr_eq(&a, &c));
+    }
 }
@@ -0,0 +1,1142 @@
+//! Public API differences between two versions of a crate.
+//!
+//! [`diff`] collects every public item reachable from each version's root
+//! module -- following `pub use` re-exports -- along with struct fields, enum
+//! variants, inherent methods, trait items and trait impls, matches them by
+//! path and reports what was added, removed or changed. Each change is
+//! classified as breaking or not, in the spirit of `cargo-semver-checks`:
+//! removals, signature changes, new trait bounds, new required trait items
+//! and new variants of exhaustive enums break downstream code; new items,
+//! relaxed bounds, new default methods and new defaulted generic parameters
+//! don't, and neither does renaming a generic parameter.
+
+use std::collections::{BTreeMap, HashSet};
+
+use rustdoc_types::{
+    Attribute, Crate, Function, GenericParamDefKind, Generics, Id, Item, ItemEnum, StructKind,
+    VariantKind, Visibility, WherePredicate,
+};
+
+use super::format::{self, format_type};
+
+/// How an item differs between the two versions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ChangeKind {
+    Removed,
+    Changed,
+    Added,
+}
+
+impl ChangeKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Removed => "removed",
+            Self::Changed => "changed",
+            Self::Added => "added",
+        }
+    }
+}
+
+/// One difference in the public API.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiChange {
+    /// Path of the item, e.g. `url::Url::join`. Trait impls are written
+    /// `<Type as Trait>`.
+    pub path: String,
+    /// Kind of item, e.g. `fn`, `field`, `variant`, `impl`.
+    pub kind: &'static str,
+    pub change: ChangeKind,
+    /// Whether the change can break code using the old version.
+    pub breaking: bool,
+    /// What changed, e.g. "new bounds: `T: Send`".
+    pub reason: String,
+    /// The item's declaration in the old version.
+    pub before: Option<String>,
+    /// The item's declaration in the new version.
+    pub after: Option<String>,
+}
+
+/// Differences between the public APIs of `old` and `new`: breaking changes
+/// first, then by path.
+pub fn diff(old: &Crate, new: &Crate) -> Vec<ApiChange> {
+    let old_api = public_api(old);
+    let new_api = public_api(new);
+
+    let mut changes = Vec::new();
+    for ((path, _), before) in &old_api {
+        match new_api.get(&member_key(path, before.kind)) {
+            None => changes.push(ApiChange {
+                path: path.clone(),
+                kind: before.kind,
+                change: ChangeKind::Removed,
+                breaking: true,
+                reason: format!("{} removed", before.kind),
+                before: Some(before.signature.clone()),
+                after: None,
+            }),
+            Some(after) => {
+                if let Some((breaking, reason)) = compare(before, after) {
+                    changes.push(ApiChange {
+                        path: path.clone(),
+                        kind: after.kind,
+                        change: ChangeKind::Changed,
+                        breaking,
+                        reason,
+                        before: Some(before.signature.clone()),
+                        after: Some(after.signature.clone()),
+                    });
+                }
+            }
+        }
+    }
+    for (key, after) in &new_api {
+        if old_api.contains_key(key) {
+            continue;
+        }
+        let path = &key.0;
+        // Members of a new enum, struct or trait break nothing
+        let old_parent = path
+            .rsplit_once("::")
+            .and_then(|(parent, _)| old_api.get(&member_key(parent, "")));
+        let breaks = match after.kind {
+            // Struct literals written against the old version stop compiling
+            "field" => old_parent
+                .filter(|p| p.kind == "struct" && p.constructible && !p.non_exhaustive)
+                .map(|_| "new field of a struct that could be built with a literal"),
+            _ => after.breaks_when_added.filter(|_| old_parent.is_some()),
+        };
+        changes.push(ApiChange {
+            path: path.clone(),
+            kind: after.kind,
+            change: ChangeKind::Added,
+            breaking: breaks.is_some(),
+            reason: breaks.map_or_else(|| format!("{} added", after.kind), str::to_string),
+            before: None,
+            after: Some(after.signature.clone()),
+        });
+    }
+
+    changes.sort_by(|a, b| (!a.breaking, a.change, &a.path).cmp(&(!b.breaking, b.change, &b.path)));
+    changes
+}
+
+/// An item's path, plus its kind for members of a type or trait. Fields,
+/// variants, methods and associated items can share a name, so each kind of
+/// member gets its own key; module-level items use `""` so a change of kind
+/// is reported as such.
+type ApiKey = (String, &'static str);
+
+fn member_key(path: &str, kind: &'static str) -> ApiKey {
+    let kind = match kind {
+        "field" | "variant" | "method" | "trait fn" | "assoc type" | "assoc const" => kind,
+        _ => "",
+    };
+    (path.to_string(), kind)
+}
+
+/// A public item, reduced to what matters for compatibility.
+#[derive(Debug, Clone, Default)]
+struct ApiItem {
+    kind: &'static str,
+    /// Declaration shown in the report.
+    signature: String,
+    /// What must stay the same for existing users: the declaration without
+    /// parameter names, generics or `const`, with generic parameters named
+    /// by position.
+    shape: String,
+    /// Generic parameters, without bounds.
+    params: Vec<GenericParam>,
+    /// Trait bounds on the generics, one `T: Bound` per entry: keyed with
+    /// generic parameters named by position, mapped to the bound as written.
+    bounds: BTreeMap<String, String>,
+    non_exhaustive: bool,
+    /// A struct whose fields are all visible, so it can be built with a
+    /// struct literal (unless it is also `#[non_exhaustive]`).
+    constructible: bool,
+    /// A trait item without a default.
+    required: bool,
+    is_const: bool,
+    /// Why adding this item breaks downstream code, if it does.
+    breaks_when_added: Option<&'static str>,
+}
+
+/// One generic parameter as users see it: its kind and default, if any.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct GenericParam {
+    /// `lifetime`, `type` or `const: T`, followed by ` = default`.
+    decl: String,
+    has_default: bool,
+}
+
+/// Whether `before` and `after` differ in a way users can notice, and if
+/// so whether it breaks them and why.
+fn compare(before: &ApiItem, after: &ApiItem) -> Option<(bool, String)> {
+    let mut breaking = Vec::new();
+    let mut compatible = Vec::new();
+
+    if before.kind != after.kind {
+        breaking.push(format!("changed from {} to {}", before.kind, after.kind));
+    } else if before.shape != after.shape {
+        breaking.push(match after.kind {
+            "field" => "type changed".to_string(),
+            _ => "signature changed".to_string(),
+        });
+    }
+    if before.params != after.params {
+        // Defaults let existing uses keep naming the type without the new
+        // parameters
+        let extended = after.params.starts_with(&before.params)
+            && after.params[before.params.len()..]
+                .iter()
+                .all(|p| p.has_default);
+        if extended {
+            compatible.push("new generic parameters with defaults".to_string());
+        } else {
+            breaking.push("generic parameters changed".to_string());
+        }
+    }
+    let added: Vec<String> = after
+        .bounds
+        .iter()
+        .filter(|(key, _)| !before.bounds.contains_key(*key))
+        .map(|(_, b)| format!("`{b}`"))
+        .collect();
+    if !added.is_empty() {
+        breaking.push(format!("new bounds: {}", added.join(", ")));
+    }
+    let removed: Vec<String> = before
+        .bounds
+        .iter()
+        .filter(|(key, _)| !after.bounds.contains_key(*key))
+        .map(|(_, b)| format!("`{b}`"))
+        .collect();
+    if !removed.is_empty() {
+        compatible.push(format!("bounds relaxed: {}", removed.join(", ")));
+    }
+    match (before.required, after.required) {
+        (false, true) => breaking.push("default implementation removed".to_string()),
+        (true, false) => compatible.push("default implementation added".to_string()),
+        _ => {}
+    }
+    match (before.constructible, after.constructible) {
+        (true, false) => breaking.push("can no longer be built with a struct literal".to_string()),
+        (false, true) => compatible.push("can now be built with a struct literal".to_string()),
+        _ => {}
+    }
+    match (before.non_exhaustive, after.non_exhaustive) {
+        (false, true) => breaking.push("now #[non_exhaustive]".to_string()),
+        (true, false) => compatible.push("no longer #[non_exhaustive]".to_string()),
+        _ => {}
+    }
+    match (before.is_const, after.is_const) {
+        (true, false) => breaking.push("no longer const".to_string()),
+        (false, true) => compatible.push("now const".to_string()),
+        _ => {}
+    }
+
+    if !breaking.is_empty() {
+        breaking.extend(compatible);
+        Some((true, breaking.join("; ")))
+    } else if !compatible.is_empty() {
+        Some((false, compatible.join("; ")))
+    } else {
+        None
+    }
+}
+
+// ── Collecting the public API ──────────────────────────────────────────
+
+/// Every public item of `krate` by path, starting from the crate root.
+fn public_api(krate: &Crate) -> BTreeMap<ApiKey, ApiItem> {
+    let mut api = BTreeMap::new();
+    let root = krate
+        .index
+        .get(&krate.root)
+        .and_then(|item| item.name.clone())
+        .unwrap_or_else(|| "crate".to_string());
+    let mut visited = HashSet::new();
+    walk_module(krate, &krate.root, &root, &mut api, &mut visited);
+    api
+}
+
+fn walk_module(
+    krate: &Crate,
+    module_id: &Id,
+    prefix: &str,
+    api: &mut BTreeMap<ApiKey, ApiItem>,
+    visited: &mut HashSet<Id>,
+) {
+    let Some(ItemEnum::Module(module)) = krate.index.get(module_id).map(|item| &item.inner) else {
+        return;
+    };
+    if !visited.insert(*module_id) {
+        return;
+    }
+    for child_id in &module.items {
+        let Some(child) = krate.index.get(child_id) else {
+            continue;
+        };
+        match &child.inner {
+            ItemEnum::Use(u) => {
+                // Re-exports of items the docs include; other crates' items
+                // aren't in the index
+                let Some(target_id) = &u.id else {
+                    continue;
+                };
+                if u.is_glob {
+                    walk_module(krate, target_id, prefix, api, visited);
+                } else if let Some(target) = krate.index.get(target_id) {
+                    let path = format!("{prefix}::{}", u.name);
+                    add_item(krate, &path, target, api, visited);
+                }
+            }
+            _ => {
+                let Some(name) = &child.name else {
+                    continue;
+                };
+                let path = format!("{prefix}::{name}");
+                add_item(krate, &path, child, api, visited);
+            }
+        }
+    }
+}
+
+fn add_item(
+    krate: &Crate,
+    path: &str,
+    item: &Item,
+    api: &mut BTreeMap<ApiKey, ApiItem>,
+    visited: &mut HashSet<Id>,
+) {
+    let name = path.rsplit("::").next().unwrap_or(path);
+    let non_exhaustive = item.attrs.contains(&Attribute::NonExhaustive);
+    let entry = match &item.inner {
+        ItemEnum::Module(_) => {
+            insert(
+                api,
+                path,
+                ApiItem {
+                    kind: "mod",
+                    signature: format!("mod {name}"),
+                    shape: format!("mod {name}"),
+                    ..ApiItem::default()
+                },
+            );
+            walk_module(krate, &item.id, path, api, visited);
+            return;
+        }
+        ItemEnum::Function(f) => function_item("fn", name, f),
+        ItemEnum::Struct(s) => {
+            let (shape, fields, stripped): (_, Vec<(String, Id)>, _) = match &s.kind {
+                StructKind::Unit => (";", Vec::new(), false),
+                StructKind::Tuple(fields) => {
+                    let stripped = fields.iter().any(Option::is_none);
+                    let fields = fields
+                        .iter()
+                        .enumerate()
+                        .filter_map(|(i, id)| Some((i.to_string(), (*id)?)))
+                        .collect();
+                    ("(..)", fields, stripped)
+                }
+                StructKind::Plain {
+                    fields,
+                    has_stripped_fields,
+                } => {
+                    let fields = fields
+                        .iter()
+                        .filter_map(|id| Some((krate.index.get(id)?.name.clone()?, *id)))
+                        .collect();
+                    (" { .. }", fields, *has_stripped_fields)
+                }
+            };
+            // Documented private fields block struct literals as much as
+            // stripped ones
+            let constructible = !stripped
+                && fields.iter().all(|(_, id)| {
+                    krate
+                        .index
+                        .get(id)
+                        .is_some_and(|f| matches!(f.visibility, Visibility::Public))
+                });
+            for (field, id) in fields {
+                add_field(krate, &format!("{path}::{field}"), &id, api);
+            }
+            add_impls(krate, path, &s.impls, api);
+            ApiItem {
+                constructible,
+                ..type_item("struct", name, &s.generics, shape)
+            }
+        }
+        ItemEnum::Union(u) => {
+            for id in &u.fields {
+                if let Some(field) = krate.index.get(id).and_then(|f| f.name.as_ref()) {
+                    add_field(krate, &format!("{path}::{field}"), id, api);
+                }
+            }
+            add_impls(krate, path, &u.impls, api);
+            type_item("union", name, &u.generics, "")
+        }
+        ItemEnum::Enum(e) => {
+            let breaks = (!non_exhaustive)
+                .then_some("new variant of an exhaustive enum; matches on it stop compiling");
+            for id in &e.variants {
+                if let Some(variant) = krate.index.get(id)
+                    && let ItemEnum::Variant(v) = &variant.inner
+                {
+                    let vname = variant.name.as_deref().unwrap_or("_");
+                    let signature = format!("{vname}{}", variant_fields(krate, &v.kind));
+                    insert(
+                        api,
+                        &format!("{path}::{vname}"),
+                        ApiItem {
+                            kind: "variant",
+                            shape: signature.clone(),
+                            signature,
+                            non_exhaustive: variant.attrs.contains(&Attribute::NonExhaustive),
+                            breaks_when_added: breaks,
+                            ..ApiItem::default()
+                        },
+                    );
+                }
+            }
+            add_impls(krate, path, &e.impls, api);
+            type_item("enum", name, &e.generics, "")
+        }
+        ItemEnum::Trait(t) => {
+            for id in &t.items {
+                let Some(member) = krate.index.get(id) else {
+                    continue;
+                };
+                let mname = member.name.as_deref().unwrap_or("_");
+                let member_path = format!("{path}::{mname}");
+                let entry = match &member.inner {
+                    ItemEnum::Function(f) => ApiItem {
+                        required: !f.has_body,
+                        ..function_item("trait fn", mname, f)
+                    },
+                    ItemEnum::AssocType {
+                        bounds,
+                        type_: default,
+                        ..
+                    } => {
+                        let mut shape = format!("type {mname}");
+                        if !bounds.is_empty() {
+                            shape.push_str(&format!(": {}", format::format_bounds(bounds)));
+                        }
+                        ApiItem {
+                            kind: "assoc type",
+                            signature: shape.clone(),
+                            shape,
+                            required: default.is_none(),
+                            ..ApiItem::default()
+                        }
+                    }
+                    ItemEnum::AssocConst { type_, value } => {
+                        let shape = format!("const {mname}: {}", format_type(type_));
+                        ApiItem {
+                            kind: "assoc const",
+                            signature: shape.clone(),
+                            shape,
+                            required: value.is_none(),
+                            ..ApiItem::default()
+                        }
+                    }
+                    _ => continue,
+                };
+                let breaks = entry
+                    .required
+                    .then_some("new required trait item; implementors must add it");
+                insert(
+                    api,
+                    &member_path,
+                    ApiItem {
+                        breaks_when_added: breaks,
+                        ..entry
+                    },
+                );
+            }
+            let mut shape = String::new();
+            if !t.bounds.is_empty() {
+                shape.push_str(&format!(": {}", format::format_bounds(&t.bounds)));
+            }
+            let mut entry = type_item("trait", name, &t.generics, &shape);
+            if t.is_unsafe {
+                entry.signature.insert_str(0, "unsafe ");
+                entry.shape.insert_str(0, "unsafe ");
+            }
+            entry
+        }
+        ItemEnum::TypeAlias(ta) => {
+            let shape = format!(" = {}", format_type(&ta.type_));
+            type_item("type", name, &ta.generics, &shape)
+        }
+        ItemEnum::Constant { type_, .. } => {
+            plain_item("const", format!("const {name}: {}", format_type(type_)))
+        }
+        ItemEnum::Static(s) => {
+            let mutability = if s.is_mutable { "mut " } else { "" };
+            plain_item(
+                "static",
+                format!("static {mutability}{name}: {}", format_type(&s.type_)),
+            )
+        }
+        other => {
+            let kind = format::item_kind_label(other);
+            plain_item(kind, format!("{kind} {name}"))
+        }
+    };
+    insert(
+        api,
+        path,
+        ApiItem {
+            non_exhaustive,
+            ..entry
+        },
+    );
+}
+
+fn insert(api: &mut BTreeMap<ApiKey, ApiItem>, path: &str, item: ApiItem) {
+    api.insert(member_key(path, item.kind), item);
+}
+
+fn plain_item(kind: &'static str, signature: String) -> ApiItem {
+    ApiItem {
+        kind,
+        shape: signature.clone(),
+        signature,
+        ..ApiItem::default()
+    }
+}
+
+/// A struct, enum, union, trait or type alias: `shape` is the part of the
+/// declaration after its name and generics.
+fn type_item(kind: &'static str, name: &str, generics: &Generics, shape: &str) -> ApiItem {
+    let generic = generic_params(generics);
+    let header = format!("{kind} {name}{}{shape}", format::format_generics(generics));
+    ApiItem {
+        kind,
+        signature: header,
+        shape: generic.positional(&format!("{kind} {name}{shape}")),
+        params: generic.params,
+        bounds: generic.bounds,
+        ..ApiItem::default()
+    }
+}
+
+fn function_item(kind: &'static str, name: &str, f: &Function) -> ApiItem {
+    let generic = generic_params(&f.generics);
+    let mut shape = String::new();
+    if f.header.is_async {
+        shape.push_str("async ");
+    }
+    if f.header.is_unsafe {
+        shape.push_str("unsafe ");
+    }
+    let inputs: Vec<String> = f.sig.inputs.iter().map(|(_, ty)| format_type(ty)).collect();
+    shape.push_str(&format!("fn {name}({})", inputs.join(", ")));
+    if let Some(output) = &f.sig.output {
+        shape.push_str(&format!(" -> {}", format_type(output)));
+    }
+    ApiItem {
+        kind,
+        signature: format::format_function_signature(name, f),
+        shape: generic.positional(&shape),
+        params: generic.params,
+        bounds: generic.bounds,
+        is_const: f.header.is_const,
+        ..ApiItem::default()
+    }
+}
+
+fn add_field(krate: &Crate, path: &str, id: &Id, api: &mut BTreeMap<ApiKey, ApiItem>) {
+    let Some(field) = krate.index.get(id) else {
+        return;
+    };
+    let ItemEnum::StructField(ty) = &field.inner else {
+        return;
+    };
+    if !matches!(field.visibility, Visibility::Public) {
+        return;
+    }
+    let name = path.rsplit("::").next().unwrap_or(path);
+    let signature = format!("{name}: {}", format_type(ty));
+    insert(
+        api,
+        path,
+        ApiItem {
+            kind: "field",
+            shape: signature.clone(),
+            signature,
+            ..ApiItem::default()
+        },
+    );
+}
+
+/// Public inherent methods, and the traits the type implements.
+fn add_impls(krate: &Crate, path: &str, impls: &[Id], api: &mut BTreeMap<ApiKey, ApiItem>) {
+    for id in impls {
+        let Some(ItemEnum::Impl(imp)) = krate.index.get(id).map(|item| &item.inner) else {
+            continue;
+        };
+        match &imp.trait_ {
+            // Blanket impls follow from the others
+            Some(_) if imp.blanket_impl.is_some() => {}
+            Some(trait_) => {
+                let mut trait_name = format::format_path(trait_);
+                if imp.is_negative {
+                    trait_name.insert(0, '!');
+                }
+                let generic = generic_params(&imp.generics);
+                insert(
+                    api,
+                    &format!("<{path} as {trait_name}>"),
+                    ApiItem {
+                        kind: "impl",
+                        signature: format::format_impl_header(imp),
+                        shape: generic.positional(&format!("impl {trait_name}")),
+                        params: generic.params,
+                        bounds: generic.bounds,
+                        ..ApiItem::default()
+                    },
+                );
+            }
+            None => {
+                for method_id in &imp.items {
+                    let Some(method) = krate.index.get(method_id) else {
+                        continue;
+                    };
+                    let ItemEnum::Function(f) = &method.inner else {
+                        continue;
+                    };
+                    if !matches!(method.visibility, Visibility::Public) {
+                        continue;
+                    }
+                    let name = method.name.as_deref().unwrap_or("_");
+                    insert(
+                        api,
+                        &format!("{path}::{name}"),
+                        function_item("method", name, f),
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// A variant's fields as written after its name: `(T, U)`, ` { a: T }` or
+/// nothing.
+fn variant_fields(krate: &Crate, kind: &VariantKind) -> String {
+    let field = |id: &Id| {
+        krate.index.get(id).and_then(|item| match &item.inner {
+            ItemEnum::StructField(ty) => Some((item.name.clone(), format_type(ty))),
+            _ => None,
+        })
+    };
+    match kind {
+        VariantKind::Plain => String::new(),
+        VariantKind::Tuple(fields) => {
+            let types: Vec<String> = fields
+                .iter()
+                .map(|id| {
+                    id.as_ref()
+                        .and_then(field)
+                        .map_or_else(|| "_".to_string(), |(_, ty)| ty)
+                })
+                .collect();
+            format!("({})", types.join(", "))
+        }
+        VariantKind::Struct {
+            fields,
+            has_stripped_fields,
+        } => {
+            let mut parts: Vec<String> = fields
+                .iter()
+                .filter_map(field)
+                .map(|(name, ty)| format!("{}: {ty}", name.unwrap_or_default()))
+                .collect();
+            if *has_stripped_fields {
+                parts.push("..".to_string());
+            }
+            format!(" {{ {} }}", parts.join(", "))
+        }
+    }
+}
+
+/// A generic list reduced for comparison, with each parameter named by its
+/// position (`#0`, `'#1`, ...) so that renaming one is not a change.
+struct GenericSig {
+    params: Vec<GenericParam>,
+    /// Each bound on its own as `T: Bound`, from the parameters and the
+    /// where clause alike, in positional form and as written.
+    bounds: BTreeMap<String, String>,
+    /// Declared names and their positional stand-ins.
+    names: Vec<(String, String)>,
+}
+
+impl GenericSig {
+    /// `text` with every declared parameter name replaced by its stand-in.
+    fn positional(&self, text: &str) -> String {
+        let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+        let mut out = String::with_capacity(text.len());
+        let mut rest = text;
+        while let Some(c) = rest.chars().next() {
+            let first = c.len_utf8();
+            // Identifiers, and lifetimes with their leading quote
+            let starts_name = is_ident(c) || (c == '\'' && rest[first..].starts_with(is_ident));
+            if !starts_name {
+                out.push(c);
+                rest = &rest[first..];
+                continue;
+            }
+            let end = rest[first..]
+                .find(|c: char| !is_ident(c))
+                .map_or(rest.len(), |i| i + first);
+            let token = &rest[..end];
+            match self.names.iter().find(|(name, _)| name == token) {
+                Some((_, stand_in)) => out.push_str(stand_in),
+                None => out.push_str(token),
+            }
+            rest = &rest[end..];
+        }
+        out
+    }
+}
+
+fn generic_params(generics: &Generics) -> GenericSig {
+    let declared = generics.params.iter().filter(|param| {
+        !matches!(
+            param.kind,
+            GenericParamDefKind::Type {
+                is_synthetic: true,
+                ..
+            }
+        )
+    });
+    let mut sig = GenericSig {
+        params: Vec::new(),
+        bounds: BTreeMap::new(),
+        names: declared
+            .clone()
+            .enumerate()
+            .map(|(i, param)| {
+                let stand_in = match param.kind {
+                    GenericParamDefKind::Lifetime { .. } => format!("'#{i}"),
+                    _ => format!("#{i}"),
+                };
+                (param.name.clone(), stand_in)
+            })
+            .collect(),
+    };
+    let mut bounds = Vec::new();
+    for param in declared {
+        let (decl, default) = match &param.kind {
+            GenericParamDefKind::Type {
+                bounds: list,
+                default,
+                ..
+            } => {
+                bounds.push((param.name.clone(), list.as_slice()));
+                ("type".to_string(), default.as_ref().map(format_type))
+            }
+            GenericParamDefKind::Lifetime { .. } => ("lifetime".to_string(), None),
+            GenericParamDefKind::Const { type_, default } => {
+                (format!("const: {}", format_type(type_)), default.clone())
+            }
+        };
+        let decl = match &default {
+            Some(default) => format!("{decl} = {default}"),
+            None => decl,
+        };
+        sig.params.push(GenericParam {
+            decl: sig.positional(&decl),
+            has_default: default.is_some(),
+        });
+    }
+    for predicate in &generics.where_predicates {
+        if let WherePredicate::BoundPredicate {
+            type_,
+            bounds: list,
+            ..
+        } = predicate
+        {
+            bounds.push((format_type(type_), list.as_slice()));
+        }
+    }
+    for (ty, list) in bounds {
+        for bound in list {
+            let bound = format!(
+                "{ty}: {}",
+                format::format_bounds(std::slice::from_ref(bound))
+            );
+            sig.bounds.insert(sig.positional(&bound), bound);
+        }
+    }
+    sig
+}
+
+/// Format changes as markdown, breaking changes first.
+pub fn format_changes(changes: &[ApiChange]) -> String {
+    let mut output = String::new();
+    for (heading, breaking) in [("Breaking Changes", true), ("Non-breaking Changes", false)] {
+        let section: Vec<&ApiChange> = changes.iter().filter(|c| c.breaking == breaking).collect();
+        if section.is_empty() {
+            continue;
+        }
+        output.push_str(&format!("## {} ({})\n\n", heading, section.len()));
+        for change in section {
+            output.push_str(&format!(
+                "- **{}** {} `{}` -- {}\n",
+                change.change.label(),
+                change.kind,
+                change.path,
+                change.reason
+            ));
+            match (&change.before, &change.after) {
+                (Some(before), Some(after)) if before != after => {
+                    output.push_str(&format!("  - before: `{}`\n", before.trim()));
+                    output.push_str(&format!("  - after: `{}`\n", after.trim()));
+                }
+                (_, Some(decl)) | (Some(decl), None) => {
+                    output.push_str(&format!("  - `{}`\n", decl.trim()));
+                }
+                _ => {}
+            }
+        }
+        output.push('\n');
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::format::tests::{function_inner, item_json, synthetic_crate};
+    use super::*;
+    use serde_json::{Value, json};
+
+    /// A function taking `inputs`, generic over `T: bounds` if given.
+    fn fn_with(inputs: &[&str], bounds: Option<&[&str]>) -> Value {
+        let mut inner = function_inner();
+        let inputs: Vec<Value> = inputs
+            .iter()
+            .map(|ty| json!(["arg", { "primitive": ty }]))
+            .collect();
+        inner["function"]["sig"]["inputs"] = json!(inputs);
+        if let Some(bounds) = bounds {
+            let bounds: Vec<Value> = bounds
+                .iter()
+                .map(|bound| {
+                    json!({ "trait_bound": {
+                        "trait": { "path": bound, "id": 99, "args": null },
+                        "generic_params": [],
+                        "modifier": "none"
+                    } })
+                })
+                .collect();
+            inner["function"]["generics"]["params"] = json!([{
+                "name": "T",
+                "kind": { "type": { "bounds": bounds, "default": null, "is_synthetic": false } }
+            }]);
+        }
+        inner
+    }
+
+    fn field(id: u32, name: &str) -> Value {
+        item_json(
+            id,
+            name,
+            None,
+            json!({ "struct_field": { "primitive": "u64" } }),
+        )
+    }
+
+    fn variant(id: u32, name: &str) -> Value {
+        item_json(
+            id,
+            name,
+            None,
+            json!({ "variant": { "kind": "plain", "discriminant": null } }),
+        )
+    }
+
+    /// Version 1 of a small crate, or version 2 with one change of each kind.
+    fn krate(v2: bool) -> Crate {
+        let generics = json!({ "params": [], "where_predicates": [] });
+        let variants: &[u32] = if v2 { &[20, 21, 22] } else { &[20, 21] };
+        let fields: &[u32] = if v2 { &[30, 31] } else { &[30] };
+        let trait_items: &[u32] = if v2 { &[50, 51] } else { &[50] };
+        let mut required = function_inner();
+        required["function"]["has_body"] = json!(false);
+
+        let mut items = vec![
+            item_json(
+                1,
+                "connect",
+                None,
+                fn_with(if v2 { &["str", "u16"] } else { &["str"] }, None),
+            ),
+            item_json(
+                2,
+                "Method",
+                None,
+                json!({ "enum": {
+                    "generics": generics,
+                    "has_stripped_variants": false,
+                    "variants": variants,
+                    "impls": []
+                } }),
+            ),
+            variant(20, "Get"),
+            variant(21, "Post"),
+            item_json(
+                3,
+                "Config",
+                None,
+                json!({ "struct": {
+                    "kind": { "plain": { "fields": fields, "has_stripped_fields": false } },
+                    "generics": generics,
+                    "impls": []
+                } }),
+            ),
+            field(30, "timeout"),
+            item_json(
+                5,
+                "Handler",
+                None,
+                json!({ "trait": {
+                    "is_auto": false,
+                    "is_unsafe": false,
+                    "is_dyn_compatible": true,
+                    "items": trait_items,
+                    "generics": generics,
+                    "bounds": [],
+                    "implementations": []
+                } }),
+            ),
+            item_json(50, "handle", None, required),
+            item_json(
+                6,
+                "spawn",
+                None,
+                fn_with(&[], Some(if v2 { &[] } else { &["Send"] })),
+            ),
+        ];
+        let mut root = vec![1, 2, 3, 5, 6];
+        if v2 {
+            items.push(variant(22, "Put"));
+            items.push(field(31, "retries"));
+            items.push(item_json(51, "handle_all", None, function_inner()));
+            items.push(item_json(7, "added", None, function_inner()));
+            root.push(7);
+        } else {
+            items.push(item_json(4, "removed", None, function_inner()));
+            root.push(4);
+        }
+        synthetic_crate(&root, items)
+    }
+
+    fn summary(changes: &[ApiChange]) -> Vec<(String, ChangeKind, bool)> {
+        changes
+            .iter()
+            .map(|c| (c.path.clone(), c.change, c.breaking))
+            .collect()
+    }
+
+    #[test]
+    fn classifies_changes() {
+        let changes = diff(&krate(false), &krate(true));
+        let expected = [
+            ("root_mod::removed", ChangeKind::Removed, true),
+            ("root_mod::connect", ChangeKind::Changed, true),
+            ("root_mod::Config::retries", ChangeKind::Added, true),
+            ("root_mod::Method::Put", ChangeKind::Added, true),
+            ("root_mod::spawn", ChangeKind::Changed, false),
+            ("root_mod::Handler::handle_all", ChangeKind::Added, false),
+            ("root_mod::added", ChangeKind::Added, false),
+        ];
+        assert_eq!(
+            summary(&changes),
+            expected.map(|(path, change, breaking)| (path.to_string(), change, breaking))
+        );
+        assert_eq!(changes[1].reason, "signature changed");
+        assert_eq!(changes[4].reason, "bounds relaxed: `T: Send`");
+    }
+
+    #[test]
+    fn reverse_diff_flips_bounds_and_required_items() {
+        let changes = diff(&krate(true), &krate(false));
+        let spawn = changes
+            .iter()
+            .find(|c| c.path == "root_mod::spawn")
+            .unwrap();
+        assert!(spawn.breaking);
+        assert_eq!(spawn.reason, "new bounds: `T: Send`");
+        // Removing a provided method still breaks callers
+        let handle_all = changes
+            .iter()
+            .find(|c| c.path == "root_mod::Handler::handle_all")
+            .unwrap();
+        assert_eq!(handle_all.change, ChangeKind::Removed);
+        assert!(handle_all.breaking);
+    }
+
+    #[test]
+    fn identical_versions_have_no_changes() {
+        assert!(diff(&krate(true), &krate(true)).is_empty());
+        let out = format_changes(&diff(&krate(false), &krate(true)));
+        assert!(out.contains("## Breaking Changes (4)"));
+        assert!(out.contains("## Non-breaking Changes (3)"));
+        assert!(out.contains("- **removed** fn `root_mod::removed` -- fn removed"));
+    }
+
+    fn type_param(name: &str, default: Option<&str>) -> Value {
+        json!({
+            "name": name,
+            "kind": { "type": {
+                "bounds": [],
+                "default": default.map(|ty| json!({ "primitive": ty })),
+                "is_synthetic": false
+            } }
+        })
+    }
+
+    /// `fn id<{param}: Send>(x: {param}) -> {param}`
+    fn generic_fn(param: &str) -> Crate {
+        let mut inner = fn_with(&[], Some(&["Send"]));
+        inner["function"]["generics"]["params"][0]["name"] = json!(param);
+        inner["function"]["sig"]["inputs"] = json!([["x", { "generic": param }]]);
+        inner["function"]["sig"]["output"] = json!({ "generic": param });
+        synthetic_crate(&[1], vec![item_json(1, "id", None, inner)])
+    }
+
+    #[test]
+    fn renamed_generics_are_not_a_change() {
+        assert!(diff(&generic_fn("T"), &generic_fn("U")).is_empty());
+    }
+
+    /// `struct Wrapper<T, ..extra>` with no visible fields.
+    fn wrapper(extra: &[Value]) -> Crate {
+        let mut params = vec![type_param("T", None)];
+        params.extend_from_slice(extra);
+        synthetic_crate(
+            &[1],
+            vec![item_json(
+                1,
+                "Wrapper",
+                None,
+                json!({ "struct": {
+                    "kind": { "plain": { "fields": [], "has_stripped_fields": true } },
+                    "generics": { "params": params, "where_predicates": [] },
+                    "impls": []
+                } }),
+            )],
+        )
+    }
+
+    #[test]
+    fn defaulted_generic_parameters_are_compatible() {
+        let defaulted = diff(&wrapper(&[]), &wrapper(&[type_param("A", Some("u8"))]));
+        assert_eq!(
+            summary(&defaulted),
+            [("root_mod::Wrapper".to_string(), ChangeKind::Changed, false)]
+        );
+        assert_eq!(defaulted[0].reason, "new generic parameters with defaults");
+
+        let required = diff(&wrapper(&[]), &wrapper(&[type_param("A", None)]));
+        assert!(required[0].breaking);
+        assert_eq!(required[0].reason, "generic parameters changed");
+    }
+
+    /// `struct Point` with public fields `x` (id 30) and `y` (id 31) as
+    /// listed, and private fields if `stripped`.
+    fn point(fields: &[u32], stripped: bool) -> Crate {
+        let mut items = vec![item_json(
+            3,
+            "Point",
+            None,
+            json!({ "struct": {
+                "kind": { "plain": { "fields": fields, "has_stripped_fields": stripped } },
+                "generics": { "params": [], "where_predicates": [] },
+                "impls": []
+            } }),
+        )];
+        items.push(field(30, "x"));
+        items.push(field(31, "y"));
+        synthetic_crate(&[3], items)
+    }
+
+    #[test]
+    fn new_private_fields_break_struct_literals() {
+        let changes = diff(&point(&[30], false), &point(&[30, 31], true));
+        assert_eq!(
+            summary(&changes),
+            [
+                ("root_mod::Point".to_string(), ChangeKind::Changed, true),
+                ("root_mod::Point::y".to_string(), ChangeKind::Added, true),
+            ]
+        );
+        assert_eq!(
+            changes[0].reason,
+            "can no longer be built with a struct literal"
+        );
+    }
+
+    #[test]
+    fn new_fields_are_judged_by_the_old_struct() {
+        // The old struct had private fields, so no literal can break
+        let changes = diff(&point(&[30], true), &point(&[30, 31], false));
+        assert_eq!(
+            summary(&changes),
+            [
+                ("root_mod::Point".to_string(), ChangeKind::Changed, false),
+                ("root_mod::Point::y".to_string(), ChangeKind::Added, false),
+            ]
+        );
+    }
+
+    /// `struct Buf { pub len: u64 }`, with an inherent `fn len()` if `method`.
+    fn buf(method: bool) -> Crate {
+        let methods: &[u32] = if method { &[41] } else { &[] };
+        synthetic_crate(
+            &[3],
+            vec![
+                item_json(
+                    3,
+                    "Buf",
+                    None,
+                    json!({ "struct": {
+                        "kind": { "plain": { "fields": [30], "has_stripped_fields": false } },
+                        "generics": { "params": [], "where_predicates": [] },
+                        "impls": [40]
+                    } }),
+                ),
+                field(30, "len"),
+                item_json(
+                    40,
+                    "",
+                    None,
+                    json!({ "impl": {
+                        "is_unsafe": false,
+                        "generics": { "params": [], "where_predicates": [] },
+                        "provided_trait_methods": [],
+                        "trait": null,
+                        "for": { "resolved_path": { "path": "Buf", "id": 3, "args": null } },
+                        "items": methods,
+                        "is_negative": false,
+                        "is_synthetic": false,
+                        "blanket_impl": null
+                    } }),
+                ),
+                item_json(41, "len", None, function_inner()),
+            ],
+        )
+    }
+
+    #[test]
+    fn fields_and_methods_with_one_name_are_kept_apart() {
+        let changes = diff(&buf(true), &buf(false));
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].path, "root_mod::Buf::len");
+        assert_eq!(changes[0].kind, "method");
+        assert_eq!(changes[0].change, ChangeKind::Removed);
+    }
+}
@@ -3,7 +3,7 @@
 use rustdoc_types::{
     AssocItemConstraint, AssocItemConstraintKind, Crate, DynTrait, Enum, Function, FunctionPointer,
     GenericArg, GenericArgs, GenericBound, GenericParamDef, GenericParamDefKind, Generics, Id,
-    Item, ItemEnum, Struct, StructKind, Term, Trait, Type, VariantKind, Visibility,
+    Impl, Item, ItemEnum, Path, Struct, StructKind, Term, Trait, Type, VariantKind, Visibility,
 };
 
 /// Format a module listing showing grouped children with summaries.
@@ -317,13 +317,7 @@ pub fn format_type(ty: &Type) -> String {
     match ty {
         Type::Primitive(name) => name.clone(),
         Type::Generic(name) => name.clone(),
-        Type::ResolvedPath(path) => {
-            let mut s = path.path.clone();
-            if let Some(args) = &path.args {
-                s.push_str(&format_generic_args(args));
-            }
-            s
-        }
+        Type::ResolvedPath(path) => format_path(path),
         Type::BorrowedRef {
             lifetime,
             is_mutable,
@@ -377,6 +371,15 @@ pub fn format_type(ty: &Type) -> String {
     }
 }
 
+/// Format a path with its generic arguments, e.g. `Result<T, Error>`.
+pub(crate) fn format_path(path: &Path) -> String {
+    let mut s = path.path.clone();
+    if let Some(args) = &path.args {
+        s.push_str(&format_generic_args(args));
+    }
+    s
+}
+
 fn format_generic_args(args: &GenericArgs) -> String {
     match args {
         GenericArgs::AngleBracketed {
@@ -428,17 +431,11 @@ fn format_assoc_constraint(c: &AssocItemConstraint) -> String {
     }
 }
 
-fn format_bounds(bounds: &[GenericBound]) -> String {
+pub(crate) fn format_bounds(bounds: &[GenericBound]) -> String {
     let parts: Vec<String> = bounds
         .iter()
         .map(|b| match b {
-            GenericBound::TraitBound { trait_, .. } => {
-                let mut s = trait_.path.clone();
-                if let Some(args) = &trait_.args {
-                    s.push_str(&format_generic_args(args));
-                }
-                s
-            }
+            GenericBound::TraitBound { trait_, .. } => format_path(trait_),
             GenericBound::Outlives(lt) => lt.clone(),
             GenericBound::Use(_) => "use<..>".to_string(),
         })
@@ -480,7 +477,7 @@ fn format_fn_pointer(fp: &FunctionPointer) -> String {
 
 // ── Signature formatting ───────────────────────────────────────────────
 
-fn format_function_signature(name: &str, f: &Function) -> String {
+pub(crate) fn format_function_signature(name: &str, f: &Function) -> String {
     let mut s = String::new();
     if f.header.is_const {
         s.push_str("const ");
@@ -510,7 +507,32 @@ fn format_function_signature(name: &str, f: &Function) -> String {
     s
 }
 
-fn format_generics(g: &Generics) -> String {
+/// Format an impl header on one line, e.g.
+/// `impl<T: Display> ToString for T`.
+pub(crate) fn format_impl_header(imp: &Impl) -> String {
+    let mut s = format!("impl{} ", format_generics(&imp.generics));
+    if let Some(trait_) = &imp.trait_ {
+        if imp.is_negative {
+            s.push('!');
+        }
+        s.push_str(&format_path(trait_));
+        s.push_str(" for ");
+    }
+    s.push_str(&format_type(&imp.for_));
+    let where_clause = format_where_clause(&imp.generics);
+    if !where_clause.is_empty() {
+        s.push(' ');
+        s.push_str(
+            &where_clause
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" "),
+        );
+    }
+    s
+}
+
+pub(crate) fn format_generics(g: &Generics) -> String {
     if g.params.is_empty() {
         return String::new();
     }
@@ -535,7 +557,7 @@ fn format_generics(g: &Generics) -> String {
     }
 }
 
-fn format_generic_param(p: &GenericParamDef) -> String {
+pub(crate) fn format_generic_param(p: &GenericParamDef) -> String {
     match &p.kind {
         GenericParamDefKind::Lifetime { .. } => p.name.clone(),
         GenericParamDefKind::Type {
@@ -807,7 +829,7 @@ pub(crate) fn first_sentence(docs: &str) -> String {
 }
 
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
     use super::*;
     use rustdoc_types::*;
 
@@ -897,7 +919,7 @@ mod tests {
     // adding new struct fields with serde defaults.
 
     /// Build JSON for an `Item` with the given id, name, docs, and inner.
-    fn item_json(
+    pub(crate) fn item_json(
         id: u32,
         name: &str,
         docs: Option<&str>,
@@ -918,7 +940,7 @@ mod tests {
     }
 
     /// Build JSON for a no-argument, unit-returning function inner.
-    fn function_inner() -> serde_json::Value {
+    pub(crate) fn function_inner() -> serde_json::Value {
         serde_json::json!({
             "function": {
                 "sig": { "inputs": [], "output": null, "is_c_variadic": false },
@@ -935,7 +957,7 @@ mod tests {
     }
 
     /// Build JSON for a unit-struct inner.
-    fn struct_inner() -> serde_json::Value {
+    pub(crate) fn struct_inner() -> serde_json::Value {
         serde_json::json!({
             "struct": {
                 "kind": "unit",
@@ -946,7 +968,7 @@ mod tests {
     }
 
     /// Build a `Crate` from a root module plus a set of item JSON values.
-    fn synthetic_crate(root_children: &[u32], items: Vec<serde_json::Value>) -> Crate {
+    pub(crate) fn synthetic_crate(root_children: &[u32], items: Vec<serde_json::Value>) -> Crate {
         let mut index = serde_json::Map::new();
 
         // Root module (id 0) holding the listed children.
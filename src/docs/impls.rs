@@ -0,0 +1,289 @@
+//! Trait implementations in a rustdoc crate, in both directions.
+//!
+//! [`implementors`] lists the types implementing a trait; [`trait_impls`]
+//! lists the traits a type implements. Either way each impl is classified as
+//! written in the crate, a blanket impl (`impl<T: Display> ToString for T`),
+//! or a synthetic auto-trait impl (`Send`, `Sync`, `Unpin`, ...) that
+//! rustdoc derived itself.
+
+use std::collections::HashSet;
+
+use rustdoc_types::{Crate, Id, ItemEnum, Trait, Type};
+
+use super::format;
+
+/// Where an impl comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImplKind {
+    /// An impl for a concrete type, as written in the source.
+    Direct,
+    /// A generic impl covering this type among others.
+    Blanket,
+    /// An auto-trait impl derived by rustdoc.
+    Synthetic,
+}
+
+/// One trait impl.
+#[derive(Debug, Clone)]
+pub struct ImplSummary<'a> {
+    pub id: &'a Id,
+    pub kind: ImplKind,
+    /// The trait with its arguments, e.g. `From<&str>`; `!Send` for
+    /// negative impls.
+    pub trait_name: String,
+    /// The implementing type.
+    pub for_type: String,
+    /// The whole header, e.g. `impl<T> From<T> for Wrapper<T>`.
+    pub header: String,
+}
+
+/// A trait's methods, as signatures.
+#[derive(Debug, Clone, Default)]
+pub struct TraitMethods {
+    /// Methods without a default body.
+    pub required: Vec<String>,
+    /// Methods with a default body.
+    pub provided: Vec<String>,
+}
+
+/// Impls of a trait in `krate`. A local trait's impls come from its own
+/// list; a trait defined elsewhere is matched by `trait_id` if the crate
+/// knows its path, or else by `trait_name`.
+pub fn implementors<'a>(
+    krate: &'a Crate,
+    trait_id: Option<&Id>,
+    trait_name: &str,
+) -> Vec<ImplSummary<'a>> {
+    if let Some(id) = trait_id
+        && let Some(ItemEnum::Trait(t)) = krate.index.get(id).map(|item| &item.inner)
+    {
+        return summarize(krate, t.implementations.iter().collect());
+    }
+    let ids = krate
+        .index
+        .iter()
+        .filter(|(_, item)| match &item.inner {
+            ItemEnum::Impl(imp) => imp.trait_.as_ref().is_some_and(|t| match trait_id {
+                Some(id) => t.id == *id,
+                None => t.path.rsplit("::").next() == Some(trait_name),
+            }),
+            _ => false,
+        })
+        .map(|(id, _)| id)
+        .collect();
+    summarize(krate, ids)
+}
+
+/// Trait impls among a type's `impls`; inherent impls are skipped.
+pub fn trait_impls<'a>(krate: &'a Crate, impls: &'a [Id]) -> Vec<ImplSummary<'a>> {
+    summarize(krate, impls.iter().collect())
+}
+
+/// The trait's required and provided methods.
+pub fn trait_methods(krate: &Crate, t: &Trait) -> TraitMethods {
+    let mut methods = TraitMethods::default();
+    for id in &t.items {
+        let Some(item) = krate.index.get(id) else {
+            continue;
+        };
+        let ItemEnum::Function(f) = &item.inner else {
+            continue;
+        };
+        let sig = format::format_function_signature(item.name.as_deref().unwrap_or("_"), f);
+        if f.has_body {
+            methods.provided.push(sig);
+        } else {
+            methods.required.push(sig);
+        }
+    }
+    methods
+}
+
+/// Summaries of the trait impls among `ids`, each header listed once, in
+/// trait then type order.
+fn summarize<'a>(krate: &'a Crate, ids: Vec<&'a Id>) -> Vec<ImplSummary<'a>> {
+    let mut seen = HashSet::new();
+    let mut impls: Vec<ImplSummary<'a>> = ids
+        .into_iter()
+        .filter_map(|id| {
+            let ItemEnum::Impl(imp) = &krate.index.get(id)?.inner else {
+                return None;
+            };
+            let trait_ = imp.trait_.as_ref()?;
+            let kind = if imp.is_synthetic {
+                ImplKind::Synthetic
+            } else if imp.blanket_impl.is_some() || matches!(imp.for_, Type::Generic(_)) {
+                ImplKind::Blanket
+            } else {
+                ImplKind::Direct
+            };
+            let mut trait_name = format::format_path(trait_);
+            if imp.is_negative {
+                trait_name.insert(0, '!');
+            }
+            Some(ImplSummary {
+                id,
+                kind,
+                trait_name,
+                for_type: format::format_type(&imp.for_),
+                header: format::format_impl_header(imp),
+            })
+        })
+        .filter(|summary| seen.insert(summary.header.clone()))
+        .collect();
+    impls.sort_by(|a, b| {
+        (a.kind as u8, &a.trait_name, &a.for_type).cmp(&(b.kind as u8, &b.trait_name, &b.for_type))
+    });
+    impls
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::format::tests::{function_inner, item_json, synthetic_crate};
+    use super::*;
+    use serde_json::{Value, json};
+
+    fn path(name: &str, id: u32) -> Value {
+        json!({ "path": name, "id": id, "args": null })
+    }
+
+    fn impl_json(
+        id: u32,
+        trait_: Value,
+        for_: Value,
+        blanket: Option<Value>,
+        synthetic: bool,
+    ) -> Value {
+        let generics = if blanket.is_some() {
+            json!({
+                "params": [{
+                    "name": "T",
+                    "kind": { "type": { "bounds": [], "default": null, "is_synthetic": false } }
+                }],
+                "where_predicates": []
+            })
+        } else {
+            json!({ "params": [], "where_predicates": [] })
+        };
+        item_json(
+            id,
+            "",
+            None,
+            json!({
+                "impl": {
+                    "is_unsafe": false,
+                    "generics": generics,
+                    "provided_trait_methods": [],
+                    "trait": trait_,
+                    "for": for_,
+                    "items": [],
+                    "is_negative": false,
+                    "is_synthetic": synthetic,
+                    "blanket_impl": blanket
+                }
+            }),
+        )
+    }
+
+    fn krate() -> Crate {
+        let bytes = json!({ "resolved_path": path("Bytes", 1) });
+        let mut required = function_inner();
+        required["function"]["has_body"] = json!(false);
+        synthetic_crate(
+            &[1, 2],
+            vec![
+                item_json(
+                    1,
+                    "Bytes",
+                    None,
+                    json!({
+                        "struct": {
+                            "kind": "unit",
+                            "generics": { "params": [], "where_predicates": [] },
+                            "impls": [10, 11, 12, 13]
+                        }
+                    }),
+                ),
+                item_json(
+                    2,
+                    "Buf",
+                    None,
+                    json!({
+                        "trait": {
+                            "is_auto": false,
+                            "is_unsafe": false,
+                            "is_dyn_compatible": true,
+                            "items": [3, 4],
+                            "generics": { "params": [], "where_predicates": [] },
+                            "bounds": [],
+                            "implementations": [10]
+                        }
+                    }),
+                ),
+                item_json(3, "remaining", None, required),
+                item_json(4, "has_remaining", None, function_inner()),
+                impl_json(10, path("Buf", 2), bytes.clone(), None, false),
+                impl_json(11, path("Send", 90), bytes.clone(), None, true),
+                impl_json(
+                    12,
+                    path("From", 91),
+                    bytes.clone(),
+                    Some(json!({ "generic": "T" })),
+                    false,
+                ),
+                // Inherent impls are not trait impls
+                impl_json(13, Value::Null, bytes, None, false),
+            ],
+        )
+    }
+
+    fn headers(impls: &[ImplSummary<'_>]) -> Vec<(ImplKind, String)> {
+        impls
+            .iter()
+            .map(|summary| (summary.kind, summary.header.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn type_impls_are_grouped_by_kind() {
+        let krate = krate();
+        let ItemEnum::Struct(s) = &krate.index[&Id(1)].inner else {
+            panic!("Bytes is a struct");
+        };
+        assert_eq!(
+            headers(&trait_impls(&krate, &s.impls)),
+            [
+                (ImplKind::Direct, "impl Buf for Bytes".to_string()),
+                (ImplKind::Blanket, "impl<T> From for Bytes".to_string()),
+                (ImplKind::Synthetic, "impl Send for Bytes".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn implementors_of_local_and_external_traits() {
+        let krate = krate();
+        assert_eq!(
+            headers(&implementors(&krate, Some(&Id(2)), "Buf")),
+            [(ImplKind::Direct, "impl Buf for Bytes".to_string())]
+        );
+        // Traits from other crates, by id or by name
+        assert_eq!(
+            headers(&implementors(&krate, Some(&Id(90)), "Send")),
+            [(ImplKind::Synthetic, "impl Send for Bytes".to_string())]
+        );
+        assert_eq!(implementors(&krate, None, "From").len(), 1);
+        assert!(implementors(&krate, None, "Service").is_empty());
+    }
+
+    #[test]
+    fn trait_methods_split_required_and_provided() {
+        let krate = krate();
+        let ItemEnum::Trait(t) = &krate.index[&Id(2)].inner else {
+            panic!("Buf is a trait");
+        };
+        let methods = trait_methods(&krate, t);
+        assert_eq!(methods.required, ["fn remaining()"]);
+        assert_eq!(methods.provided, ["fn has_remaining()"]);
+    }
+}
@@ -1,4 +1,8 @@
 //! docs.rs integration: cache and formatting for rustdoc JSON.
 
 pub mod cache;
+pub mod diff;
 pub mod format;
+pub mod impls;
+pub mod search;
+pub mod signature;
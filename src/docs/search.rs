@@ -0,0 +1,247 @@
+//! Ranked full-text search over a crate's documentation.
+//!
+//! [`DocIndex`] is a BM25 index over every local item of a rustdoc crate,
+//! built from the item's name, its path and its doc comment. Names weigh
+//! more than paths, and paths more than doc text, so a query like "set a
+//! timeout" puts a `timeout` method ahead of items that only mention
+//! timeouts in passing.
+
+use std::collections::HashMap;
+
+use rustdoc_types::{Crate, Id};
+
+use super::format;
+
+/// BM25 term-frequency saturation.
+const K1: f32 = 1.2;
+/// BM25 document-length normalization.
+const B: f32 = 0.75;
+
+/// How much one occurrence of a term counts in each field.
+const NAME_WEIGHT: f32 = 3.0;
+const PATH_WEIGHT: f32 = 1.5;
+const DOCS_WEIGHT: f32 = 1.0;
+
+/// Words too common in questions and prose to say anything about an item.
+const STOP_WORDS: &[&str] = &[
+    "a", "an", "and", "are", "as", "at", "be", "by", "can", "do", "does", "for", "from", "how",
+    "i", "if", "in", "is", "it", "its", "me", "my", "of", "on", "or", "that", "the", "this", "to",
+    "what", "when", "which", "with", "you",
+];
+
+/// A search result: an item and its relevance score.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DocHit {
+    pub id: Id,
+    pub score: f32,
+}
+
+/// BM25 index over a crate's local items.
+#[derive(Debug, Default)]
+pub struct DocIndex {
+    /// Indexed items and their weighted lengths, in id order.
+    docs: Vec<(Id, f32)>,
+    /// For each term, the documents containing it and its weighted frequency.
+    postings: HashMap<String, Vec<(u32, f32)>>,
+    avg_len: f32,
+}
+
+impl DocIndex {
+    /// Index every named local item of `krate`.
+    pub fn build(krate: &Crate) -> Self {
+        let mut items: Vec<_> = krate
+            .index
+            .iter()
+            .filter(|(_, item)| item.crate_id == 0 && item.name.is_some())
+            .collect();
+        items.sort_by_key(|(id, _)| id.0);
+
+        let mut index = Self::default();
+        let mut total_len = 0.0;
+        for (doc, (id, item)) in items.into_iter().enumerate() {
+            let mut freqs: HashMap<String, f32> = HashMap::new();
+            let mut add = |text: &str, weight: f32| {
+                for token in tokens(text) {
+                    *freqs.entry(token).or_default() += weight;
+                }
+            };
+            add(item.name.as_deref().unwrap_or_default(), NAME_WEIGHT);
+            add(&format::item_path(krate, id), PATH_WEIGHT);
+            add(item.docs.as_deref().unwrap_or_default(), DOCS_WEIGHT);
+
+            let len: f32 = freqs.values().sum();
+            total_len += len;
+            index.docs.push((*id, len));
+            for (term, tf) in freqs {
+                index
+                    .postings
+                    .entry(term)
+                    .or_default()
+                    .push((doc as u32, tf));
+            }
+        }
+        if !index.docs.is_empty() {
+            index.avg_len = total_len / index.docs.len() as f32;
+        }
+        index
+    }
+
+    /// Number of indexed items.
+    pub fn len(&self) -> usize {
+        self.docs.len()
+    }
+
+    /// Whether no items were indexed.
+    pub fn is_empty(&self) -> bool {
+        self.docs.is_empty()
+    }
+
+    /// Items matching any term of `query`, best first. Ties keep id order.
+    pub fn search(&self, query: &str) -> Vec<DocHit> {
+        let mut terms: Vec<String> = tokens(query).collect();
+        terms.sort();
+        terms.dedup();
+
+        let n = self.docs.len() as f32;
+        let mut scores: HashMap<u32, f32> = HashMap::new();
+        for term in &terms {
+            let Some(postings) = self.postings.get(term) else {
+                continue;
+            };
+            let df = postings.len() as f32;
+            let idf = (1.0 + (n - df + 0.5) / (df + 0.5)).ln();
+            for &(doc, tf) in postings {
+                let len = self.docs[doc as usize].1;
+                let norm = K1 * (1.0 - B + B * len / self.avg_len);
+                *scores.entry(doc).or_default() += idf * tf * (K1 + 1.0) / (tf + norm);
+            }
+        }
+
+        let mut hits: Vec<(u32, f32)> = scores.into_iter().collect();
+        hits.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+        hits.into_iter()
+            .map(|(doc, score)| DocHit {
+                id: self.docs[doc as usize].0,
+                score,
+            })
+            .collect()
+    }
+}
+
+/// Lower-cased search terms in `text`. Identifiers are split at `_`, `::`
+/// and camel-case boundaries (`ClientBuilder` gives `client` and
+/// `builder`), stop words are dropped and plurals are reduced to the
+/// singular.
+fn tokens(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric())
+        .flat_map(split_camel_case)
+        .map(|word| normalize(&word.to_lowercase()))
+        .filter(|word| !STOP_WORDS.contains(&word.as_str()))
+}
+
+fn split_camel_case(word: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut start = 0;
+    let mut prev_lower = false;
+    for (i, c) in word.char_indices() {
+        if c.is_uppercase() && prev_lower {
+            parts.push(&word[start..i]);
+            start = i;
+        }
+        prev_lower = c.is_lowercase() || c.is_ascii_digit();
+    }
+    if start < word.len() {
+        parts.push(&word[start..]);
+    }
+    parts
+}
+
+fn normalize(word: &str) -> String {
+    if let Some(stem) = word.strip_suffix("ies")
+        && stem.len() >= 2
+    {
+        return format!("{stem}y");
+    }
+    match word.strip_suffix('s') {
+        Some(stem)
+            if stem.len() >= 3
+                && !stem.ends_with('s')
+                && !stem.ends_with('u')
+                && !stem.ends_with('i') =>
+        {
+            stem.to_string()
+        }
+        _ => word.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::format::tests::{function_inner, item_json, struct_inner, synthetic_crate};
+    use super::*;
+
+    fn krate() -> Crate {
+        synthetic_crate(
+            &[1, 2, 3, 4],
+            vec![
+                item_json(
+                    1,
+                    "ClientBuilder",
+                    Some("A builder to configure a `Client`, e.g. its timeouts and proxies."),
+                    struct_inner(),
+                ),
+                item_json(
+                    2,
+                    "timeout",
+                    Some("Set a timeout for the whole request.\n\nDefault is no timeout."),
+                    function_inner(),
+                ),
+                item_json(
+                    3,
+                    "proxy",
+                    Some("Add a proxy to the client."),
+                    function_inner(),
+                ),
+                item_json(4, "get", None, function_inner()),
+            ],
+        )
+    }
+
+    fn ids(hits: &[DocHit]) -> Vec<u32> {
+        hits.iter().map(|hit| hit.id.0).collect()
+    }
+
+    #[test]
+    fn tokens_split_identifiers_and_drop_stop_words() {
+        let words: Vec<String> =
+            tokens("How do I set_timeout on a ClientBuilder? HTTP2 headers").collect();
+        assert_eq!(
+            words,
+            ["set", "timeout", "client", "builder", "http2", "header"]
+        );
+        assert_eq!(
+            tokens("status process is").collect::<Vec<_>>(),
+            ["status", "process"]
+        );
+    }
+
+    #[test]
+    fn name_matches_outrank_mentions() {
+        let index = DocIndex::build(&krate());
+        assert_eq!(index.len(), 5);
+
+        let hits = index.search("how do I set a timeout");
+        assert_eq!(ids(&hits)[..2], [2, 1]);
+        assert!(hits[0].score > hits[1].score);
+
+        assert_eq!(ids(&index.search("proxies")), [3, 1]);
+    }
+
+    #[test]
+    fn unknown_terms_match_nothing() {
+        let index = DocIndex::build(&krate());
+        assert!(index.search("websocket").is_empty());
+        assert!(index.search("the").is_empty());
+        assert!(DocIndex::default().search("timeout").is_empty());
+    }
+}
@@ -0,0 +1,972 @@
+//! Hoogle-style search for functions by type signature.
+//!
+//! A query such as `&str -> Result<Url, _>` is matched against every local
+//! function and method of a rustdoc crate. Item types are rendered with
+//! [`format_type`] and parsed back into the same shape as the query, so both
+//! sides are compared in one normal form: paths are reduced to their last
+//! segment, lifetimes are dropped, and `Self` in a method stands for the type
+//! of its `impl`.
+//!
+//! Generics are wildcards on both sides: a single capital letter or `_` in
+//! the query matches any type, and an item's type parameters (or
+//! `impl Trait` arguments) match any type in the query. Query inputs may be
+//! in any order, and functions may take extra arguments, at a cost.
+//!
+//! A query without `->` is matched against return types only; `&str ->`
+//! matches inputs only.
+
+use std::collections::HashMap;
+
+use rustdoc_types::{Crate, Function, GenericParamDefKind, Generics, Id, Item, ItemEnum, Type};
+
+use super::format::{self, format_type};
+
+/// Cost of each function argument the query doesn't mention.
+const EXTRA_INPUT_COST: u32 = 1;
+/// Cost of matching a query wildcard against a concrete type.
+const QUERY_WILDCARD_COST: u32 = 1;
+/// Cost of matching a concrete query type against an item's generic.
+const ITEM_WILDCARD_COST: u32 = 2;
+/// Cost of ignoring a reference on one side (`str` against `&str`).
+const AUTO_REF_COST: u32 = 2;
+
+/// Most argument types a query may list; matching is exponential in it.
+const MAX_QUERY_INPUTS: usize = 8;
+/// Deepest nesting of types the parser follows, so a hostile query can't
+/// exhaust the stack.
+const MAX_DEPTH: usize = 32;
+
+/// A type, normalised for matching.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Shape {
+    /// A generic or `_`, matching any type.
+    Any,
+    Ref {
+        mutable: bool,
+        inner: Box<Shape>,
+    },
+    /// A named type with its type arguments. Slices, arrays, tuples, pointers
+    /// and function pointers use the names `[]`, `[;]`, `()`, `*const` /
+    /// `*mut` and `fn`.
+    Named {
+        name: String,
+        args: Vec<Shape>,
+    },
+}
+
+impl Shape {
+    fn named(name: &str, args: Vec<Shape>) -> Self {
+        Self::Named {
+            name: name.to_string(),
+            args,
+        }
+    }
+
+    fn unit() -> Self {
+        Self::named("()", Vec::new())
+    }
+
+    /// Replace the item's generic parameters with wildcards and `Self` with
+    /// the implementing type.
+    fn bind(self, generics: &[String], self_type: &Shape) -> Self {
+        match self {
+            Self::Named { name, args } if args.is_empty() && name == "Self" => self_type.clone(),
+            Self::Named { name, args } if args.is_empty() && generics.contains(&name) => Self::Any,
+            Self::Named { name, args } => Self::Named {
+                name,
+                args: args
+                    .into_iter()
+                    .map(|arg| arg.bind(generics, self_type))
+                    .collect(),
+            },
+            Self::Ref { mutable, inner } => Self::Ref {
+                mutable,
+                inner: Box::new(inner.bind(generics, self_type)),
+            },
+            Self::Any => Self::Any,
+        }
+    }
+}
+
+/// A parsed signature query.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignatureQuery {
+    inputs: Vec<Shape>,
+    output: Option<Shape>,
+}
+
+/// A function matching a signature query.
+#[derive(Debug, Clone)]
+pub struct SignatureMatch<'a> {
+    pub id: &'a Id,
+    pub item: &'a Item,
+    pub function: &'a Function,
+    /// `Type::method` for methods, the item path otherwise.
+    pub path: String,
+    /// How loosely the function matches; 0 is exact.
+    pub cost: u32,
+}
+
+impl SignatureQuery {
+    /// Parse a query like `&str, usize -> Option<T>`.
+    pub fn parse(query: &str) -> Result<Self, String> {
+        let tokens = tokenize(query)?;
+        let mut parser = Parser {
+            tokens: &tokens,
+            pos: 0,
+            depth: 0,
+            query: true,
+        };
+
+        let mut inputs = Vec::new();
+        while !matches!(parser.peek(), None | Some(Token::Arrow)) {
+            inputs.push(parser.parse_type()?);
+            if !parser.eat(&Token::Punct(',')) {
+                break;
+            }
+        }
+        let output = if parser.eat(&Token::Arrow) {
+            if parser.peek().is_none() {
+                None
+            } else {
+                Some(parser.parse_type()?)
+            }
+        } else if inputs.len() == 1 {
+            // No arrow: the query is a return type
+            inputs.pop()
+        } else {
+            return Err("expected `->` between argument and return types".to_string());
+        };
+        if let Some(token) = parser.peek() {
+            return Err(format!("unexpected `{token}` in query"));
+        }
+        if inputs.is_empty() && output.is_none() {
+            return Err("empty signature query".to_string());
+        }
+
+        // `(A, B) -> C` lists arguments rather than taking one tuple, and
+        // `() -> C` takes none
+        if let [Shape::Named { name, args }] = inputs.as_slice()
+            && name == "()"
+        {
+            inputs = args.clone();
+        }
+        if inputs.len() > MAX_QUERY_INPUTS {
+            return Err(format!(
+                "too many argument types ({}, at most {MAX_QUERY_INPUTS})",
+                inputs.len()
+            ));
+        }
+        Ok(Self { inputs, output })
+    }
+
+    /// Local functions and methods of `krate` matching this query, best
+    /// first.
+    pub fn search<'a>(&self, krate: &'a Crate) -> Vec<SignatureMatch<'a>> {
+        let mut matches: Vec<SignatureMatch<'a>> = functions(krate)
+            .into_iter()
+            .filter_map(|candidate| {
+                let cost = self.match_cost(&candidate.inputs, &candidate.output)?;
+                Some(SignatureMatch {
+                    id: candidate.id,
+                    item: candidate.item,
+                    function: candidate.function,
+                    path: candidate.path,
+                    cost,
+                })
+            })
+            .collect();
+        matches.sort_by(|a, b| a.cost.cmp(&b.cost).then_with(|| a.path.cmp(&b.path)));
+        matches
+    }
+
+    fn match_cost(&self, inputs: &[Shape], output: &Shape) -> Option<u32> {
+        let mut cost = match &self.output {
+            Some(query) => unify(query, output)?,
+            None => 0,
+        };
+        if self.inputs.len() > inputs.len() {
+            return None;
+        }
+        cost += assign(&self.inputs, inputs)?;
+        Some(cost + (inputs.len() - self.inputs.len()) as u32 * EXTRA_INPUT_COST)
+    }
+}
+
+/// Cost of matching query type `query` against item type `item`, if they
+/// match at all.
+fn unify(query: &Shape, item: &Shape) -> Option<u32> {
+    match (query, item) {
+        (Shape::Any, Shape::Any) => Some(0),
+        (Shape::Any, _) => Some(QUERY_WILDCARD_COST),
+        (_, Shape::Any) => Some(ITEM_WILDCARD_COST),
+        (
+            Shape::Ref {
+                mutable: m1,
+                inner: i1,
+            },
+            Shape::Ref {
+                mutable: m2,
+                inner: i2,
+            },
+        ) if m1 == m2 => unify(i1, i2),
+        (Shape::Ref { .. }, Shape::Ref { .. }) => None,
+        (_, Shape::Ref { inner, .. }) => Some(unify(query, inner)? + AUTO_REF_COST),
+        (Shape::Ref { inner, .. }, _) => Some(unify(inner, item)? + AUTO_REF_COST),
+        (Shape::Named { name: n1, args: a1 }, Shape::Named { name: n2, args: a2 }) if n1 == n2 => {
+            if a1.is_empty() {
+                // `Result` matches `Result<T, E>`
+                Some(0)
+            } else if a1.len() == a2.len() {
+                a1.iter().zip(a2).map(|(q, i)| unify(q, i)).sum()
+            } else {
+                None
+            }
+        }
+        _ => None,
+    }
+}
+
+/// The cheapest way to match each query input to a different item input.
+///
+/// Item inputs are taken one at a time; `best[set]` is the cheapest way to
+/// match the query inputs in `set` to item inputs seen so far.
+fn assign(queries: &[Shape], inputs: &[Shape]) -> Option<u32> {
+    let full = (1usize << queries.len()) - 1;
+    let mut best: Vec<Option<u32>> = vec![None; full + 1];
+    best[0] = Some(0);
+    for input in inputs {
+        let costs: Vec<Option<u32>> = queries.iter().map(|query| unify(query, input)).collect();
+        // Larger sets first, so each input is used at most once
+        for set in (0..full).rev() {
+            let Some(base) = best[set] else {
+                continue;
+            };
+            for (q, cost) in costs.iter().enumerate() {
+                if set & (1 << q) != 0 {
+                    continue;
+                }
+                if let Some(cost) = cost {
+                    let next = &mut best[set | (1 << q)];
+                    *next = Some(next.map_or(base + cost, |n| n.min(base + cost)));
+                }
+            }
+        }
+    }
+    best[full]
+}
+
+/// A function or method with its normalised signature.
+struct Candidate<'a> {
+    id: &'a Id,
+    item: &'a Item,
+    function: &'a Function,
+    path: String,
+    inputs: Vec<Shape>,
+    output: Shape,
+}
+
+/// Every local function and method, with methods' `Self` and impl generics
+/// resolved from their `impl` or trait.
+fn functions(krate: &Crate) -> Vec<Candidate<'_>> {
+    // Methods are reachable only through their impl or trait
+    let mut owners: HashMap<&Id, (String, Shape, Vec<String>)> = HashMap::new();
+    for item in krate.index.values() {
+        if item.crate_id != 0 {
+            continue;
+        }
+        let (owner, self_type, generics, items) = match &item.inner {
+            ItemEnum::Impl(imp) if !imp.is_synthetic && imp.blanket_impl.is_none() => {
+                let rendered = format_type(&imp.for_);
+                let owner = rendered.split('<').next().unwrap_or(&rendered).to_string();
+                let generics = type_params(&imp.generics);
+                let self_type = parse_item_type(&imp.for_, &generics, &Shape::Any);
+                (owner, self_type, generics, &imp.items)
+            }
+            ItemEnum::Trait(t) => (
+                item.name.clone().unwrap_or_default(),
+                Shape::Any,
+                type_params(&t.generics),
+                &t.items,
+            ),
+            _ => continue,
+        };
+        for id in items {
+            owners.insert(id, (owner.clone(), self_type.clone(), generics.clone()));
+        }
+    }
+
+    let mut candidates = Vec::new();
+    for (id, item) in &krate.index {
+        let ItemEnum::Function(function) = &item.inner else {
+            continue;
+        };
+        if item.crate_id != 0 {
+            continue;
+        }
+        let name = item.name.as_deref().unwrap_or("_");
+        let mut generics = type_params(&function.generics);
+        let (path, self_type) = match owners.get(id) {
+            Some((owner, self_type, impl_generics)) => {
+                generics.extend(impl_generics.iter().cloned());
+                (format!("{owner}::{name}"), self_type.clone())
+            }
+            None => (format::item_path(krate, id), Shape::Any),
+        };
+        let inputs = function
+            .sig
+            .inputs
+            .iter()
+            .map(|(_, ty)| parse_item_type(ty, &generics, &self_type))
+            .collect();
+        let output = function
+            .sig
+            .output
+            .as_ref()
+            .map_or_else(Shape::unit, |ty| parse_item_type(ty, &generics, &self_type));
+        candidates.push(Candidate {
+            id,
+            item,
+            function,
+            path,
+            inputs,
+            output,
+        });
+    }
+    candidates
+}
+
+fn type_params(generics: &Generics) -> Vec<String> {
+    generics
+        .params
+        .iter()
+        .filter(|p| matches!(p.kind, GenericParamDefKind::Type { .. }))
+        .map(|p| p.name.clone())
+        .collect()
+}
+
+/// Normalise an item's type through its rendered form. Types the parser
+/// can't follow match anything.
+fn parse_item_type(ty: &Type, generics: &[String], self_type: &Shape) -> Shape {
+    let rendered = format_type(ty);
+    let Ok(tokens) = tokenize(&rendered) else {
+        return Shape::Any;
+    };
+    let mut parser = Parser {
+        tokens: &tokens,
+        pos: 0,
+        depth: 0,
+        query: false,
+    };
+    match parser.parse_type() {
+        Ok(shape) if parser.peek().is_none() => shape.bind(generics, self_type),
+        _ => Shape::Any,
+    }
+}
+
+// ── Parsing ────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Ident(String),
+    Lifetime,
+    Arrow,
+    PathSep,
+    Punct(char),
+}
+
+impl std::fmt::Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Ident(s) => f.write_str(s),
+            Self::Lifetime => f.write_str("'_"),
+            Self::Arrow => f.write_str("->"),
+            Self::PathSep => f.write_str("::"),
+            Self::Punct(c) => write!(f, "{c}"),
+        }
+    }
+}
+
+fn tokenize(s: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = s.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {}
+            '-' if chars.peek() == Some(&'>') => {
+                chars.next();
+                tokens.push(Token::Arrow);
+            }
+            ':' if chars.peek() == Some(&':') => {
+                chars.next();
+                tokens.push(Token::PathSep);
+            }
+            '\'' => {
+                while chars
+                    .next_if(|c| c.is_alphanumeric() || *c == '_')
+                    .is_some()
+                {}
+                tokens.push(Token::Lifetime);
+            }
+            c if c.is_alphanumeric() || c == '_' => {
+                let mut ident = c.to_string();
+                while let Some(c) = chars.next_if(|c| c.is_alphanumeric() || *c == '_') {
+                    ident.push(c);
+                }
+                tokens.push(Token::Ident(ident));
+            }
+            '&' | '*' | '[' | ']' | '(' | ')' | '<' | '>' | ',' | ';' | ':' | '=' | '+' | '{'
+            | '}' | '!' | '?' => tokens.push(Token::Punct(c)),
+            c => return Err(format!("unexpected `{c}` in query")),
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser<'t> {
+    tokens: &'t [Token],
+    pos: usize,
+    /// Types currently being parsed, one inside the other.
+    depth: usize,
+    /// Whether this is a query, where single capital letters are generics.
+    query: bool,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn bump(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn eat(&mut self, token: &Token) -> bool {
+        if self.peek() == Some(token) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), String> {
+        if self.eat(&Token::Punct(c)) {
+            Ok(())
+        } else {
+            Err(match self.peek() {
+                Some(token) => format!("expected `{c}`, found `{token}`"),
+                None => format!("expected `{c}`"),
+            })
+        }
+    }
+
+    fn ident(&mut self) -> Result<String, String> {
+        match self.bump() {
+            Some(Token::Ident(ident)) => Ok(ident),
+            Some(token) => Err(format!("expected a type, found `{token}`")),
+            None => Err("expected a type".to_string()),
+        }
+    }
+
+    /// Run `parse` one nesting level deeper.
+    fn nested<T>(
+        &mut self,
+        parse: impl FnOnce(&mut Self) -> Result<T, String>,
+    ) -> Result<T, String> {
+        if self.depth >= MAX_DEPTH {
+            return Err("query nested too deeply".to_string());
+        }
+        self.depth += 1;
+        let result = parse(self);
+        self.depth -= 1;
+        result
+    }
+
+    fn parse_type(&mut self) -> Result<Shape, String> {
+        self.nested(Self::parse_type_inner)
+    }
+
+    fn parse_type_inner(&mut self) -> Result<Shape, String> {
+        match self.peek() {
+            Some(Token::Punct('&')) => {
+                self.pos += 1;
+                self.eat(&Token::Lifetime);
+                let mutable = self.eat(&Token::Ident("mut".to_string()));
+                let inner = Box::new(self.parse_type()?);
+                Ok(Shape::Ref { mutable, inner })
+            }
+            Some(Token::Punct('*')) => {
+                self.pos += 1;
+                let kind = self.ident()?;
+                if kind != "const" && kind != "mut" {
+                    return Err(format!("expected `const` or `mut`, found `{kind}`"));
+                }
+                Ok(Shape::named(&format!("*{kind}"), vec![self.parse_type()?]))
+            }
+            Some(Token::Punct('[')) => {
+                self.pos += 1;
+                let inner = self.parse_type()?;
+                if self.eat(&Token::Punct(';')) {
+                    self.skip_until(']');
+                    self.expect(']')?;
+                    return Ok(Shape::named("[;]", vec![inner]));
+                }
+                self.expect(']')?;
+                Ok(Shape::named("[]", vec![inner]))
+            }
+            Some(Token::Punct('(')) => {
+                self.pos += 1;
+                let (types, trailing_comma) = self.parse_list(')')?;
+                if types.len() == 1 && !trailing_comma {
+                    return Ok(types.into_iter().next().unwrap_or(Shape::Any));
+                }
+                Ok(Shape::named("()", types))
+            }
+            Some(Token::Punct('<')) => {
+                // `<T as Trait>::Name`: an associated type, unknown here
+                self.pos += 1;
+                self.skip_until('>');
+                self.expect('>')?;
+                while self.eat(&Token::PathSep) {
+                    self.ident()?;
+                }
+                Ok(Shape::Any)
+            }
+            Some(Token::Punct('!')) => {
+                self.pos += 1;
+                Ok(Shape::named("!", Vec::new()))
+            }
+            Some(Token::Ident(ident)) if ident == "impl" => {
+                self.pos += 1;
+                self.parse_bounds()?;
+                Ok(Shape::Any)
+            }
+            Some(Token::Ident(ident)) if ident == "dyn" => {
+                self.pos += 1;
+                // `dyn Trait + Send` is named after its first trait
+                match self.parse_bounds()?.into_iter().next() {
+                    Some(Shape::Named { name, args }) => Ok(Shape::Named {
+                        name: format!("dyn {name}"),
+                        args,
+                    }),
+                    _ => Ok(Shape::Any),
+                }
+            }
+            Some(Token::Ident(ident)) if ident == "fn" => {
+                self.pos += 1;
+                self.expect('(')?;
+                let (mut types, _) = self.parse_list(')')?;
+                types.push(self.parse_return()?);
+                Ok(Shape::named("fn", types))
+            }
+            _ => self.parse_path(),
+        }
+    }
+
+    /// `a::b::Name<Args>`, reduced to `Name<Args>`. `Fn(A) -> B` sugar keeps
+    /// its inputs and output as arguments.
+    fn parse_path(&mut self) -> Result<Shape, String> {
+        let mut name = self.ident()?;
+        while self.eat(&Token::PathSep) {
+            name = self.ident()?;
+        }
+        if name == "_" || (self.query && is_generic_name(&name)) {
+            return Ok(Shape::Any);
+        }
+
+        let mut args = Vec::new();
+        if self.eat(&Token::Punct('<')) {
+            loop {
+                match self.peek() {
+                    Some(Token::Punct('>')) => {
+                        self.pos += 1;
+                        break;
+                    }
+                    Some(Token::Lifetime) => self.pos += 1,
+                    Some(Token::Punct('{')) => {
+                        self.skip_until('}');
+                        self.expect('}')?;
+                    }
+                    // `Item = T` / `Item: Bound` constraints
+                    Some(Token::Ident(_))
+                        if matches!(
+                            self.tokens.get(self.pos + 1),
+                            Some(Token::Punct('=') | Token::Punct(':'))
+                        ) =>
+                    {
+                        self.pos += 2;
+                        self.parse_bounds()?;
+                    }
+                    Some(_) => args.push(self.parse_type()?),
+                    None => return Err("expected `>`".to_string()),
+                }
+                if !self.eat(&Token::Punct(',')) {
+                    self.expect('>')?;
+                    break;
+                }
+            }
+        } else if self.peek() == Some(&Token::Punct('(')) && is_fn_trait(&name) {
+            self.pos += 1;
+            let (types, _) = self.parse_list(')')?;
+            args = types;
+            args.push(self.parse_return()?);
+        }
+        Ok(Shape::Named { name, args })
+    }
+
+    /// `Trait + Trait + 'a`, returning the trait shapes.
+    fn parse_bounds(&mut self) -> Result<Vec<Shape>, String> {
+        self.nested(Self::parse_bounds_inner)
+    }
+
+    fn parse_bounds_inner(&mut self) -> Result<Vec<Shape>, String> {
+        let mut bounds = Vec::new();
+        loop {
+            if self.eat(&Token::Lifetime) {
+            } else if self.eat(&Token::Punct('?')) {
+                self.parse_path()?;
+            } else {
+                bounds.push(self.parse_type()?);
+            }
+            if !self.eat(&Token::Punct('+')) {
+                return Ok(bounds);
+            }
+        }
+    }
+
+    fn parse_return(&mut self) -> Result<Shape, String> {
+        if self.eat(&Token::Arrow) {
+            self.parse_type()
+        } else {
+            Ok(Shape::unit())
+        }
+    }
+
+    /// Comma-separated types up to `close`, and whether a comma came last.
+    fn parse_list(&mut self, close: char) -> Result<(Vec<Shape>, bool), String> {
+        let mut types = Vec::new();
+        let mut trailing_comma = false;
+        while !self.eat(&Token::Punct(close)) {
+            types.push(self.parse_type()?);
+            trailing_comma = self.eat(&Token::Punct(','));
+            if !trailing_comma {
+                self.expect(close)?;
+                break;
+            }
+        }
+        Ok((types, trailing_comma))
+    }
+
+    /// Skip to the `close` that ends the current group.
+    fn skip_until(&mut self, close: char) {
+        let open = match close {
+            '>' => '<',
+            ']' => '[',
+            '}' => '{',
+            _ => '(',
+        };
+        let mut depth = 0;
+        while let Some(token) = self.peek() {
+            match token {
+                Token::Punct(c) if *c == open => depth += 1,
+                Token::Punct(c) if *c == close => {
+                    if depth == 0 {
+                        return;
+                    }
+                    depth -= 1;
+                }
+                _ => {}
+            }
+            self.pos += 1;
+        }
+    }
+}
+
+/// `T`, `U`, `E`, `T2`: the names a query uses for "any type".
+fn is_generic_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c.is_ascii_uppercase()) && chars.all(|c| c.is_ascii_digit())
+}
+
+fn is_fn_trait(name: &str) -> bool {
+    matches!(
+        name,
+        "Fn" | "FnMut" | "FnOnce" | "AsyncFn" | "AsyncFnMut" | "AsyncFnOnce"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::format::tests::{item_json, struct_inner, synthetic_crate};
+    use super::*;
+    use serde_json::{Value, json};
+
+    fn named(name: &str, args: Vec<Shape>) -> Shape {
+        Shape::named(name, args)
+    }
+
+    fn reference(inner: Shape) -> Shape {
+        Shape::Ref {
+            mutable: false,
+            inner: Box::new(inner),
+        }
+    }
+
+    /// Parse a rendered item type, as `parse_item_type` does.
+    fn item_shape(rendered: &str) -> Shape {
+        let tokens = tokenize(rendered).unwrap();
+        let mut parser = Parser {
+            tokens: &tokens,
+            pos: 0,
+            depth: 0,
+            query: false,
+        };
+        let shape = parser.parse_type().unwrap();
+        assert!(parser.peek().is_none(), "trailing tokens in {rendered}");
+        shape
+    }
+
+    fn prim(name: &str) -> Value {
+        json!({ "primitive": name })
+    }
+
+    fn borrowed(ty: Value) -> Value {
+        json!({ "borrowed_ref": { "lifetime": null, "is_mutable": false, "type": ty } })
+    }
+
+    fn path(name: &str, args: Vec<Value>) -> Value {
+        let args = if args.is_empty() {
+            Value::Null
+        } else {
+            let args: Vec<Value> = args.into_iter().map(|ty| json!({ "type": ty })).collect();
+            json!({ "angle_bracketed": { "args": args, "constraints": [] } })
+        };
+        json!({ "resolved_path": { "path": name, "id": 99, "args": args } })
+    }
+
+    fn generics(params: &[&str]) -> Value {
+        let params: Vec<Value> = params
+            .iter()
+            .map(|name| {
+                json!({
+                    "name": name,
+                    "kind": { "type": { "bounds": [], "default": null, "is_synthetic": false } }
+                })
+            })
+            .collect();
+        json!({ "params": params, "where_predicates": [] })
+    }
+
+    fn function(inputs: Vec<(&str, Value)>, output: Option<Value>, params: &[&str]) -> Value {
+        let inputs: Vec<Value> = inputs
+            .into_iter()
+            .map(|(name, ty)| json!([name, ty]))
+            .collect();
+        json!({
+            "function": {
+                "sig": { "inputs": inputs, "output": output, "is_c_variadic": false },
+                "generics": generics(params),
+                "header": {
+                    "is_const": false,
+                    "is_unsafe": false,
+                    "is_async": false,
+                    "abi": "Rust"
+                },
+                "has_body": true
+            }
+        })
+    }
+
+    fn krate() -> Crate {
+        let self_ref = borrowed(json!({ "generic": "Self" }));
+        let result = |ok: Value| path("Result", vec![ok, path("crate::ParseError", vec![])]);
+        synthetic_crate(
+            &[1, 2, 3, 4],
+            vec![
+                item_json(
+                    1,
+                    "parse",
+                    Some("Parse a URL."),
+                    function(
+                        vec![("input", borrowed(prim("str")))],
+                        Some(result(path("Url", vec![]))),
+                        &[],
+                    ),
+                ),
+                item_json(2, "Url", None, struct_inner()),
+                item_json(
+                    10,
+                    "",
+                    None,
+                    json!({
+                        "impl": {
+                            "is_unsafe": false,
+                            "generics": generics(&[]),
+                            "provided_trait_methods": [],
+                            "trait": null,
+                            "for": path("Url", vec![]),
+                            "items": [11, 12],
+                            "is_negative": false,
+                            "is_synthetic": false,
+                            "blanket_impl": null
+                        }
+                    }),
+                ),
+                item_json(
+                    11,
+                    "as_str",
+                    None,
+                    function(
+                        vec![("self", self_ref.clone())],
+                        Some(borrowed(prim("str"))),
+                        &[],
+                    ),
+                ),
+                item_json(
+                    12,
+                    "join",
+                    None,
+                    function(
+                        vec![("self", self_ref), ("input", borrowed(prim("str")))],
+                        Some(result(json!({ "generic": "Self" }))),
+                        &[],
+                    ),
+                ),
+                item_json(
+                    3,
+                    "first",
+                    None,
+                    function(
+                        vec![("items", borrowed(json!({ "slice": { "generic": "T" } })))],
+                        Some(path("Option", vec![borrowed(json!({ "generic": "T" }))])),
+                        &["T"],
+                    ),
+                ),
+                item_json(
+                    4,
+                    "len",
+                    None,
+                    function(vec![("s", borrowed(prim("str")))], Some(prim("usize")), &[]),
+                ),
+            ],
+        )
+    }
+
+    fn results(krate: &Crate, query: &str) -> Vec<(String, u32)> {
+        SignatureQuery::parse(query)
+            .unwrap()
+            .search(krate)
+            .into_iter()
+            .map(|m| (m.path, m.cost))
+            .collect()
+    }
+
+    #[test]
+    fn parses_queries() {
+        let query = SignatureQuery::parse("&str -> Result<Url, _>").unwrap();
+        assert_eq!(query.inputs, [reference(named("str", vec![]))]);
+        assert_eq!(
+            query.output,
+            Some(named("Result", vec![named("Url", vec![]), Shape::Any]))
+        );
+
+        let query = SignatureQuery::parse("(&'a mut [u8], usize) -> T").unwrap();
+        assert_eq!(query.inputs.len(), 2);
+        assert_eq!(query.output, Some(Shape::Any));
+
+        let query = SignatureQuery::parse("std::vec::Vec<u8>").unwrap();
+        assert!(query.inputs.is_empty());
+        assert_eq!(query.output, Some(named("Vec", vec![named("u8", vec![])])));
+
+        let query = SignatureQuery::parse("&str ->").unwrap();
+        assert_eq!(query.inputs.len(), 1);
+        assert_eq!(query.output, None);
+
+        for bad in ["", "->", "&str, u32", "Result<Url", "&str -> $", "u8 u8"] {
+            assert!(SignatureQuery::parse(bad).is_err(), "{bad}");
+        }
+    }
+
+    #[test]
+    fn normalises_rendered_item_types() {
+        assert_eq!(
+            item_shape("std::borrow::Cow<'static, str>"),
+            named("Cow", vec![named("str", vec![])])
+        );
+        assert_eq!(item_shape("impl Iterator<Item = &'a str> + 'a"), Shape::Any);
+        assert_eq!(item_shape("<Self as FromStr>::Err"), Shape::Any);
+        assert_eq!(
+            item_shape("Box<dyn Fn(u8) -> bool + Send>"),
+            named(
+                "Box",
+                vec![named(
+                    "dyn Fn",
+                    vec![named("u8", vec![]), named("bool", vec![])]
+                )]
+            )
+        );
+        assert_eq!(
+            item_shape("[u8; 32]"),
+            named("[;]", vec![named("u8", vec![])])
+        );
+        // Item types keep single-letter names until bound to their generics
+        assert_eq!(item_shape("T"), named("T", vec![]));
+    }
+
+    #[test]
+    fn ranks_functions_and_methods() {
+        let krate = krate();
+        assert_eq!(
+            results(&krate, "&str -> Result<Url, _>"),
+            [("parse".to_string(), 1), ("Url::join".to_string(), 2)]
+        );
+        assert_eq!(
+            results(&krate, "&Url -> &str"),
+            [("Url::as_str".to_string(), 0)]
+        );
+        assert_eq!(results(&krate, "-> usize"), [("len".to_string(), 1)]);
+        assert!(results(&krate, "&str -> bool").is_empty());
+    }
+
+    #[test]
+    fn generics_and_references_match_loosely() {
+        let krate = krate();
+        // `T` in `first<T>` matches u8; `_` matches `&T`
+        assert_eq!(
+            results(&krate, "&[u8] -> Option<_>"),
+            [("first".to_string(), 3)]
+        );
+        // A query without `&` still finds `&str` arguments
+        assert_eq!(results(&krate, "str -> usize"), [("len".to_string(), 2)]);
+        // Arguments match in any order
+        assert_eq!(
+            results(&krate, "&str, &Url -> Result"),
+            [("Url::join".to_string(), 0)]
+        );
+    }
+
+    #[test]
+    fn oversized_queries_are_rejected() {
+        let deep = format!("{}str", "&".repeat(1000));
+        assert_eq!(
+            SignatureQuery::parse(&deep).unwrap_err(),
+            "query nested too deeply"
+        );
+        let deep = format!("{}u8{}", "Vec<".repeat(100), ">".repeat(100));
+        assert_eq!(
+            SignatureQuery::parse(&deep).unwrap_err(),
+            "query nested too deeply"
+        );
+        let wide = format!("{} -> ()", ["_"; 9].join(", "));
+        assert!(
+            SignatureQuery::parse(&wide)
+                .unwrap_err()
+                .contains("at most 8")
+        );
+        let widest = format!("{} -> ()", ["_"; 8].join(", "));
+        assert!(SignatureQuery::parse(&widest).is_ok());
+    }
+}
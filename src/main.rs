@@ -1,7 +1,13 @@
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
 
 use clap::{Parser, ValueEnum};
+use cratesio_mcp::client::cassette::{RecordingBackend, ReplayBackend};
+use cratesio_mcp::client::credentials::CargoCredentials;
+use cratesio_mcp::client::disk_cache::DiskCache;
+use cratesio_mcp::client::dump::DumpStore;
+use cratesio_mcp::client::http::ReqwestBackend;
 use cratesio_mcp::{prompts, resources, state::AppState, tools};
 use tower::ServiceBuilder;
 use tower::timeout::TimeoutLayer;
@@ -21,6 +27,25 @@ enum Transport {
     Http,
 }
 
+/// Where crate data comes from.
+#[derive(Debug, Clone)]
+enum DataSource {
+    /// The live crates.io API
+    Api,
+    /// A crates.io database dump (`db-dump.tar.gz` or its extracted directory)
+    Dump(PathBuf),
+}
+
+fn parse_data_source(value: &str) -> Result<DataSource, String> {
+    match value.split_once(':') {
+        _ if value == "api" => Ok(DataSource::Api),
+        Some(("dump", path)) if !path.is_empty() => Ok(DataSource::Dump(PathBuf::from(path))),
+        _ => Err(format!(
+            "invalid data source `{value}`, expected `api` or `dump:<path>`"
+        )),
+    }
+}
+
 #[derive(Parser, Debug)]
 #[command(name = "cratesio-mcp")]
 #[command(about = "MCP server for querying crates.io", long_about = None)]
@@ -84,6 +109,67 @@ struct Args {
     #[arg(long, default_value = "3600")]
     docs_cache_ttl_secs: u64,
 
+    /// Directory for a persistent cache of crates.io responses and docs.rs
+    /// rustdoc JSON, reused across restarts. Disabled when not set.
+    #[arg(long)]
+    disk_cache_dir: Option<PathBuf>,
+
+    /// Size budget for the disk cache (in megabytes)
+    #[arg(long, default_value = "512")]
+    disk_cache_max_mb: u64,
+
+    /// TTL for crate metadata, listings and owners in the disk cache (in
+    /// seconds). A specific version's dependencies and rustdoc JSON never expire.
+    #[arg(long, default_value = "300")]
+    disk_cache_metadata_ttl_secs: u64,
+
+    /// TTL for rustdoc JSON of `latest` and other non-exact versions in the
+    /// disk cache (in seconds)
+    #[arg(long, default_value = "3600")]
+    disk_cache_docs_ttl_secs: u64,
+
+    /// Read versions, dependencies and features from the crates.io sparse
+    /// index (index.crates.io) where possible. The index is not subject to the
+    /// API rate limit, so dependency trees and timelines need far fewer API calls.
+    #[arg(long, default_value = "false")]
+    sparse_index: bool,
+
+    /// Alternative registry as NAME=INDEX_URL (repeatable), e.g.
+    /// `company=sparse+https://registry.example.com/index/`, or just NAME for a
+    /// registry defined in cargo's configuration. Only sparse indexes are
+    /// supported. The API URL is read from the index's config.json; with --cargo-credentials, its token is found the
+    /// way cargo finds it. Tools select it with their `registry` argument.
+    #[arg(long = "registry", value_name = "NAME[=INDEX_URL]")]
+    registries: Vec<String>,
+
+    /// Authenticate with registry tokens found the way cargo finds them:
+    /// CARGO_REGISTRY_TOKEN and CARGO_REGISTRIES_<NAME>_TOKEN,
+    /// $CARGO_HOME/credentials.toml, and the credential providers configured
+    /// in $CARGO_HOME/config.toml (which runs those providers' commands).
+    /// Tokens are only sent with registry index and API requests, e.g. to
+    /// reach a private registry; no tool exposes account endpoints.
+    #[arg(long, default_value = "false")]
+    cargo_credentials: bool,
+
+    /// Where crate data comes from: `api` for live crates.io, or
+    /// `dump:<path>` to answer search, crate info, dependency, owner, category
+    /// and keyword queries offline from a crates.io database dump
+    /// (https://static.crates.io/db-dump.tar.gz, as downloaded or extracted).
+    #[arg(long, default_value = "api", value_parser = parse_data_source)]
+    data_source: DataSource,
+
+    /// Record every outbound request and its response (crates.io, docs.rs,
+    /// OSV.dev, the sparse index and alternative registries) into this
+    /// directory, for later use with --replay.
+    #[arg(long, value_name = "DIR", conflicts_with = "replay")]
+    record: Option<PathBuf>,
+
+    /// Answer every outbound request from exchanges recorded with --record
+    /// instead of the network, for reproducible offline runs. Requests that
+    /// were not recorded fail. Disables the crates.io rate limit.
+    #[arg(long, value_name = "DIR")]
+    replay: Option<PathBuf>,
+
     /// Log the client IP and User-Agent of a sampled subset of HTTP requests
     /// (HTTP transport only). Off by default; enable for diagnosing the source
     /// of unexpected traffic. Logs end-user IP addresses when on.
@@ -113,18 +199,122 @@ async fn main() -> Result<(), tower_mcp::BoxError> {
     );
 
     // Create shared state with rate limiting for crates.io API
-    let rate_limit = Duration::from_millis(args.rate_limit_ms);
+    // Replayed responses come from disk, so there is nothing to be polite to.
+    let rate_limit = if args.replay.is_some() {
+        Duration::ZERO
+    } else {
+        Duration::from_millis(args.rate_limit_ms)
+    };
     let http_timeout = Duration::from_secs(args.http_timeout_secs);
     let docs_cache_ttl = Duration::from_secs(args.docs_cache_ttl_secs);
-    let state = Arc::new(
-        AppState::new(
-            rate_limit,
-            http_timeout,
-            args.docs_cache_max_entries,
-            docs_cache_ttl,
-        )
-        .map_err(|e| format!("Failed to create state: {}", e))?,
-    );
+    let mut state = AppState::new(
+        rate_limit,
+        http_timeout,
+        args.docs_cache_max_entries,
+        docs_cache_ttl,
+        args.sparse_index,
+    )
+    .map_err(|e| format!("Failed to create state: {}", e))?;
+
+    if let Some(dir) = &args.record {
+        let inner = ReqwestBackend::new(http_timeout)
+            .map_err(|e| format!("Failed to create HTTP client: {e}"))?;
+        let recorder = RecordingBackend::new(Arc::new(inner), dir)
+            .map_err(|e| format!("Failed to open record directory {}: {e}", dir.display()))?;
+        tracing::info!(dir = %dir.display(), "Recording outbound HTTP exchanges");
+        state = state.with_http_backend(Arc::new(recorder));
+    }
+    if let Some(dir) = &args.replay {
+        let replay = ReplayBackend::open(dir)
+            .map_err(|e| format!("Failed to open replay directory {}: {e}", dir.display()))?;
+        tracing::info!(
+            dir = %dir.display(),
+            exchanges = replay.len(),
+            "Replaying recorded HTTP exchanges"
+        );
+        state = state.with_http_backend(Arc::new(replay));
+    }
+
+    if let Some(dir) = &args.disk_cache_dir {
+        let cache = DiskCache::open(dir, args.disk_cache_max_mb * 1024 * 1024)
+            .map_err(|e| format!("Failed to open disk cache {}: {e}", dir.display()))?
+            .with_metadata_ttl(Duration::from_secs(args.disk_cache_metadata_ttl_secs))
+            .with_docs_ttl(Duration::from_secs(args.disk_cache_docs_ttl_secs));
+        let cache = Arc::new(cache);
+        tracing::info!(dir = %dir.display(), max_mb = args.disk_cache_max_mb, "Using disk cache");
+        state.client = state.client.with_disk_cache(cache.clone());
+        state.docsrs_client = state.docsrs_client.with_disk_cache(cache);
+    }
+
+    if let DataSource::Dump(path) = args.data_source.clone() {
+        tracing::info!(path = %path.display(), "Loading crates.io database dump");
+        let dump = tokio::task::spawn_blocking(move || DumpStore::open(&path))
+            .await?
+            .map_err(|e| format!("Failed to load database dump: {e}"))?;
+        tracing::info!(
+            crates = dump.crate_count(),
+            "Loaded crates.io database dump"
+        );
+        state.client = state.client.with_dump(Arc::new(dump));
+    }
+
+    // Cargo's configuration also names the registries given as `--registry NAME`
+    let credentials = if args.cargo_credentials || args.registries.iter().any(|s| !s.contains('='))
+    {
+        CargoCredentials::load()
+            .inspect_err(|e| tracing::warn!(error = %e, "Failed to read cargo configuration"))
+            .ok()
+    } else {
+        None
+    };
+    let token_for = |registry: Option<(&str, &str)>| {
+        if !args.cargo_credentials {
+            return None;
+        }
+        let credentials = credentials.as_ref()?;
+        let token = match registry {
+            None => credentials.crates_io_token(),
+            Some((name, index_url)) => credentials.registry_token(name, index_url),
+        };
+        token
+            .inspect_err(|e| tracing::warn!(error = %e, "Failed to get registry token"))
+            .ok()
+            .flatten()
+    };
+
+    if let Some(token) = token_for(None) {
+        tracing::info!("Using crates.io token from cargo credentials");
+        state.client = state.client.with_auth(token);
+    }
+
+    for spec in &args.registries {
+        let (name, index_url) = match spec.split_once('=') {
+            Some((name, index_url)) => (name, index_url.to_string()),
+            None => {
+                let index_url = credentials
+                    .as_ref()
+                    .and_then(|c| c.registry_index(spec))
+                    .ok_or_else(|| {
+                        format!(
+                            "Invalid --registry `{spec}`: expected NAME=INDEX_URL, or the name \
+                             of a registry in cargo's configuration"
+                        )
+                    })?;
+                (spec.as_str(), index_url)
+            }
+        };
+        let index_url = index_url.as_str();
+        let token = token_for(Some((name, index_url)));
+        state
+            .add_registry(name, index_url, token, rate_limit, http_timeout)
+            .await?;
+        tracing::info!(
+            registry = name,
+            index_url,
+            "Registered alternative registry"
+        );
+    }
+    let state = Arc::new(state);
 
     // Build all tools
     let search_tool = tools::search::build(state.clone());
@@ -147,6 +337,10 @@ async fn main() -> Result<(), tower_mcp::BoxError> {
     let get_crate_docs_tool = tools::crate_docs::build(state.clone());
     let get_doc_item_tool = tools::doc_item::build(state.clone());
     let search_docs_tool = tools::search_docs::build(state.clone());
+    let search_signatures_tool = tools::search_signatures::build(state.clone());
+    let trait_impls_tool = tools::trait_impls::build(state.clone());
+    let api_diff_tool = tools::api_diff::build(state.clone());
+    let semver_check_tool = tools::semver_check::build(state.clone());
     let audit_tool = tools::audit::build(state.clone());
     let features_tool = tools::features::build(state.clone());
     let user_stats_tool = tools::user_stats::build(state.clone());
@@ -156,12 +350,15 @@ async fn main() -> Result<(), tower_mcp::BoxError> {
     let get_alternatives_tool = tools::alternatives::build(state.clone());
     let changelog_tool = tools::changelog::build(state.clone());
     let release_timeline_tool = tools::release_timeline::build(state.clone());
+    let audit_lockfile_tool = tools::audit_lockfile::build(state.clone());
+    let duplicates_tool = tools::duplicates::build(state.clone());
+    let analyze_manifest_tool = tools::analyze_manifest::build(state.clone());
 
     // Create base router with tools (always registered)
     let instructions = if args.minimal {
         "MCP server for querying crates.io - the Rust package registry.\n\n\
          Available tools:\n\
-         - search_crates: Find crates by name/keywords\n\
+         - search_crates: Find crates by name/keywords, or filter by category, keyword, owner\n\
          - get_crate_info: Get detailed crate information\n\
          - get_crate_versions: Get version history\n\
          - get_crate_readme: Get README content for a crate\n\
@@ -180,7 +377,11 @@ async fn main() -> Result<(), tower_mcp::BoxError> {
          - get_keyword: Details about a specific keyword\n\
          - get_crate_docs: Browse crate documentation structure from docs.rs\n\
          - get_doc_item: Get full documentation for a specific item from docs.rs\n\
-         - search_docs: Search for items by name within a crate's docs\n\
+         - search_docs: Search a crate's docs by item name or full text\n\
+         - search_signatures: Find functions by type signature, e.g. \"&str -> Result<Url, _>\"\n\
+         - get_trait_impls: List a trait's implementors, or the traits a type implements\n\
+         - get_api_diff: Public API changes between two versions, classified as breaking or not\n\
+         - check_semver: Flag breaking API changes in a minor or patch release\n\
          - audit_dependencies: Check deps against OSV.dev vulnerability database\n\
          - get_crate_features: Get feature flags for a crate version\n\
          - get_user_stats: Get download statistics for a crates.io user\n\
@@ -189,12 +390,15 @@ async fn main() -> Result<(), tower_mcp::BoxError> {
          - get_crate_health: Comprehensive health report for a crate\n\
          - get_alternatives: Find and compare alternative crates for a given crate\n\
          - get_crate_changelog: Fetch changelog from a crate's GitHub repository\n\
-         - get_release_timeline: Registry-metadata version diff (features, MSRV, yanked, cadence)\n\n\
+         - get_release_timeline: Registry-metadata version diff (features, MSRV, yanked, cadence)\n\
+         - audit_lockfile: Check every package in a Cargo.lock at its locked version against OSV.dev\n\
+         - find_duplicate_versions: Find crates resolved at several incompatible versions across one or more roots\n\
+         - analyze_manifest: Check every dependency in a Cargo.toml for newer and yanked versions\n\n\
          (Running in minimal mode - resources, prompts, and completions disabled)"
     } else {
         "MCP server for querying crates.io - the Rust package registry.\n\n\
          Available tools:\n\
-         - search_crates: Find crates by name/keywords\n\
+         - search_crates: Find crates by name/keywords, or filter by category, keyword, owner\n\
          - get_crate_info: Get detailed crate information\n\
          - get_crate_versions: Get version history\n\
          - get_crate_readme: Get README content for a crate\n\
@@ -213,7 +417,11 @@ async fn main() -> Result<(), tower_mcp::BoxError> {
          - get_keyword: Details about a specific keyword\n\
          - get_crate_docs: Browse crate documentation structure from docs.rs\n\
          - get_doc_item: Get full documentation for a specific item from docs.rs\n\
-         - search_docs: Search for items by name within a crate's docs\n\
+         - search_docs: Search a crate's docs by item name or full text\n\
+         - search_signatures: Find functions by type signature, e.g. \"&str -> Result<Url, _>\"\n\
+         - get_trait_impls: List a trait's implementors, or the traits a type implements\n\
+         - get_api_diff: Public API changes between two versions, classified as breaking or not\n\
+         - check_semver: Flag breaking API changes in a minor or patch release\n\
          - audit_dependencies: Check deps against OSV.dev vulnerability database\n\
          - get_crate_features: Get feature flags for a crate version\n\
          - get_user_stats: Get download statistics for a crates.io user\n\
@@ -222,7 +430,10 @@ async fn main() -> Result<(), tower_mcp::BoxError> {
          - get_crate_health: Comprehensive health report for a crate\n\
          - get_alternatives: Find and compare alternative crates for a given crate\n\
          - get_crate_changelog: Fetch changelog from a crate's GitHub repository\n\
-         - get_release_timeline: Registry-metadata version diff (features, MSRV, yanked, cadence)\n\n\
+         - get_release_timeline: Registry-metadata version diff (features, MSRV, yanked, cadence)\n\
+         - audit_lockfile: Check every package in a Cargo.lock at its locked version against OSV.dev\n\
+         - find_duplicate_versions: Find crates resolved at several incompatible versions across one or more roots\n\
+         - analyze_manifest: Check every dependency in a Cargo.toml for newer and yanked versions\n\n\
          Resources:\n\
          - crates://{name}/info: Get crate info as a resource\n\
          - crates://{name}/readme: Get README content for a crate\n\
@@ -272,6 +483,10 @@ async fn main() -> Result<(), tower_mcp::BoxError> {
         .tool(get_crate_docs_tool)
         .tool(get_doc_item_tool)
         .tool(search_docs_tool)
+        .tool(search_signatures_tool)
+        .tool(trait_impls_tool)
+        .tool(api_diff_tool)
+        .tool(semver_check_tool)
         .tool(audit_tool)
         .tool(features_tool)
         .tool(user_stats_tool)
@@ -280,7 +495,10 @@ async fn main() -> Result<(), tower_mcp::BoxError> {
         .tool(get_crate_health_tool)
         .tool(get_alternatives_tool)
         .tool(changelog_tool)
-        .tool(release_timeline_tool);
+        .tool(release_timeline_tool)
+        .tool(audit_lockfile_tool)
+        .tool(duplicates_tool)
+        .tool(analyze_manifest_tool);
 
     // Add resources, prompts, and completions unless in minimal mode
     // Minimal mode works around Claude Code MCP tool discovery issues
@@ -443,9 +661,10 @@ async fn main() -> Result<(), tower_mcp::BoxError> {
             // the layer's own errors and the inner service error, making them
             // compatible with tower-mcp's Infallible error type.
             //
-            // Note: CircuitBreakerLayer could be added for downstream service failures
-            // (e.g., crates.io API), but McpRouter returns Infallible so the breaker
-            // would need a custom failure classifier to inspect response content.
+            // Upstream failures (crates.io, docs.rs, OSV.dev) are handled by the
+            // circuit breakers inside each API client rather than a layer here:
+            // McpRouter returns Infallible, so a breaker at this level could not
+            // tell which upstream failed.
             let rate_limiter = RateLimiterLayer::builder()
                 .limit_for_period(10) // 10 requests per second
                 .refresh_period(Duration::from_secs(1))
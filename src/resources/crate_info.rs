@@ -90,6 +90,9 @@ mod tests {
             docsrs_client: DocsRsClient::new("test", Duration::from_secs(30)).unwrap(),
             osv_client: OsvClient::new("test", Duration::from_secs(30)).unwrap(),
             docs_cache: DocsCache::new(10, Duration::from_secs(3600)),
+            index_client: None,
+            registries: Default::default(),
+            http_backend: None,
         })
     }
 
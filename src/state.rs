@@ -1,12 +1,88 @@
 //! Shared application state
 
+use std::collections::HashMap;
+use std::sync::Arc;
 use std::time::Duration;
 
 use crate::client::CratesIoClient;
 use crate::client::docsrs::DocsRsClient;
+use crate::client::http::HttpBackend;
+use crate::client::index::{IndexError, SparseIndexClient};
 use crate::client::osv::OsvClient;
+use crate::client::resilience::CircuitBreaker;
 use crate::docs::cache::DocsCache;
 
+/// Index URLs that refer to crates.io (sparse and git protocols).
+const CRATES_IO_INDEXES: &[&str] = &[
+    "https://index.crates.io",
+    "https://github.com/rust-lang/crates.io-index",
+];
+
+/// A named alternative registry.
+pub struct Registry {
+    /// API client pointed at the registry's `api` URL
+    pub client: CratesIoClient,
+    /// Sparse index client for the registry's index
+    pub index_client: SparseIndexClient,
+}
+
+/// The clients for one registry, as selected by a tool's `registry` argument.
+#[derive(Clone, Copy)]
+pub struct RegistryRef<'a> {
+    /// Configured name, or `None` for crates.io
+    pub name: Option<&'a str>,
+    pub client: &'a CratesIoClient,
+    pub index_client: Option<&'a SparseIndexClient>,
+}
+
+impl RegistryRef<'_> {
+    /// Whether this is crates.io (the only registry docs.rs and OSV.dev cover).
+    pub fn is_crates_io(&self) -> bool {
+        self.name.is_none()
+    }
+}
+
+/// Errors selecting or configuring a registry.
+#[derive(Debug, thiserror::Error)]
+pub enum RegistryError {
+    /// No registry with this name is configured.
+    #[error("unknown registry `{name}` (configured: {configured})")]
+    Unknown { name: String, configured: String },
+
+    /// The registry's index could not be read or has no API.
+    #[error("registry `{name}`: {message}")]
+    Config { name: String, message: String },
+}
+
+/// Normalize an index URL for comparison: drop the `sparse+`/`registry+`
+/// protocol prefix and any trailing slash.
+fn normalize_index_url(url: &str) -> &str {
+    url.trim_start_matches("sparse+")
+        .trim_start_matches("registry+")
+        .trim_end_matches('/')
+}
+
+/// The URL to read a sparse index from, given a registry's `index` setting:
+/// `sparse+https://...`, or a bare `https://` URL. Git indexes
+/// (`registry+...`) and other schemes are rejected with a reason.
+fn sparse_index_url(url: &str) -> Result<&str, String> {
+    let base = match url.strip_prefix("sparse+") {
+        Some(base) if base.starts_with("https://") || base.starts_with("http://") => base,
+        None if url.starts_with("https://") => url,
+        _ if url.starts_with("registry+") => {
+            return Err(format!(
+                "`{url}` is a git index; only sparse indexes (`sparse+https://...`) are supported"
+            ));
+        }
+        _ => {
+            return Err(format!(
+                "`{url}` is not a sparse index URL (expected `sparse+https://...`)"
+            ));
+        }
+    };
+    Ok(base.trim_end_matches('/'))
+}
+
 /// Shared state for the MCP server
 pub struct AppState {
     /// Crates.io API client (already rate-limited internally)
@@ -17,6 +93,15 @@ pub struct AppState {
     pub osv_client: OsvClient,
     /// Cache for parsed rustdoc JSON
     pub docs_cache: DocsCache,
+    /// Sparse index client, when enabled; tools that walk dependencies or
+    /// read per-version metadata prefer it over the rate-limited API
+    pub index_client: Option<SparseIndexClient>,
+    /// Alternative registries by name, selected with a tool's `registry` argument
+    pub registries: HashMap<String, Registry>,
+    /// Transport shared by every client, when set with
+    /// [`with_http_backend`](Self::with_http_backend); registries added later
+    /// use it too
+    pub http_backend: Option<Arc<dyn HttpBackend>>,
 }
 
 impl AppState {
@@ -27,11 +112,14 @@ impl AppState {
     /// * `http_timeout` - Per-request timeout for all outbound HTTP calls
     /// * `docs_cache_max_entries` - Maximum cached rustdoc JSON entries
     /// * `docs_cache_ttl` - TTL for cached rustdoc JSON entries
+    /// * `sparse_index` - Read versions, dependencies and features from the
+    ///   crates.io sparse index where possible
     pub fn new(
         rate_limit: Duration,
         http_timeout: Duration,
         docs_cache_max_entries: usize,
         docs_cache_ttl: Duration,
+        sparse_index: bool,
     ) -> Result<Self, tower_mcp::BoxError> {
         let user_agent = "cratesio-mcp (https://github.com/joshrotenberg/cratesio-mcp)";
         let client = CratesIoClient::new(user_agent, rate_limit, http_timeout)
@@ -41,12 +129,23 @@ impl AppState {
         let osv_client = OsvClient::new(user_agent, http_timeout)
             .map_err(|e| format!("Failed to create OSV client: {e}"))?;
         let docs_cache = DocsCache::new(docs_cache_max_entries, docs_cache_ttl);
+        let index_client = if sparse_index {
+            Some(
+                SparseIndexClient::new(user_agent, http_timeout)
+                    .map_err(|e| format!("Failed to create sparse index client: {e}"))?,
+            )
+        } else {
+            None
+        };
 
         Ok(Self {
             client,
             docsrs_client,
             osv_client,
             docs_cache,
+            index_client,
+            registries: HashMap::new(),
+            http_backend: None,
         })
     }
 
@@ -82,6 +181,9 @@ impl AppState {
             docsrs_client,
             osv_client,
             docs_cache,
+            index_client: None,
+            registries: HashMap::new(),
+            http_backend: None,
         })
     }
 
@@ -113,8 +215,136 @@ impl AppState {
             docsrs_client,
             osv_client,
             docs_cache,
+            index_client: None,
+            registries: HashMap::new(),
+            http_backend: None,
         })
     }
+
+    /// Send every client's requests through `backend`, e.g. to record or
+    /// replay a session.
+    pub fn with_http_backend(mut self, backend: Arc<dyn HttpBackend>) -> Self {
+        self.client = self.client.with_backend(backend.clone());
+        self.docsrs_client = self.docsrs_client.with_backend(backend.clone());
+        self.osv_client = self.osv_client.with_backend(backend.clone());
+        self.index_client = self
+            .index_client
+            .map(|index| index.with_backend(backend.clone()));
+        self.http_backend = Some(backend);
+        self
+    }
+
+    /// Register a named registry from its sparse index URL.
+    ///
+    /// Reads the index's `config.json` to find the API. `token` (if any) is
+    /// sent to the API, and to the index only when the registry requires it:
+    /// its `config.json` sets `auth-required`, or is refused without a token.
+    pub async fn add_registry(
+        &mut self,
+        name: &str,
+        index_url: &str,
+        token: Option<String>,
+        rate_limit: Duration,
+        http_timeout: Duration,
+    ) -> Result<(), RegistryError> {
+        let config_error = |message: String| RegistryError::Config {
+            name: name.to_string(),
+            message,
+        };
+        let user_agent = "cratesio-mcp (https://github.com/joshrotenberg/cratesio-mcp)";
+
+        let mut index_client = SparseIndexClient::with_base_url(
+            user_agent,
+            http_timeout,
+            sparse_index_url(index_url).map_err(config_error)?,
+        )
+        .map_err(|e| config_error(e.to_string()))?;
+        if let Some(backend) = &self.http_backend {
+            index_client = index_client.with_backend(backend.clone());
+        }
+        let mut config = index_client.config().await;
+        if matches!(config, Err(IndexError::Api { status: 401, .. }))
+            && let Some(token) = &token
+        {
+            index_client = index_client.with_auth(token.clone());
+            config = index_client.config().await;
+        }
+        let config = config.map_err(|e| config_error(e.to_string()))?;
+        if config.auth_required
+            && let Some(token) = &token
+        {
+            index_client = index_client.with_auth(token.clone());
+        }
+        let api = config
+            .api
+            .ok_or_else(|| config_error("config.json has no `api` URL".to_string()))?;
+
+        let mut client = CratesIoClient::with_base_url(
+            user_agent,
+            rate_limit,
+            http_timeout,
+            &format!("{}/api/v1", api.trim_end_matches('/')),
+        )
+        .map_err(|e| config_error(e.to_string()))?
+        .with_circuit_breaker(Arc::new(CircuitBreaker::new(format!("registry `{name}`"))));
+        if let Some(backend) = &self.http_backend {
+            client = client.with_backend(backend.clone());
+        }
+        if let Some(token) = token {
+            client = client.with_auth(token);
+        }
+
+        self.registries.insert(
+            name.to_string(),
+            Registry {
+                client,
+                index_client,
+            },
+        );
+        Ok(())
+    }
+
+    /// The registry a tool's `registry` argument names: crates.io for `None`
+    /// or `"crates-io"` (cargo's name for it), otherwise a configured one.
+    pub fn registry(&self, name: Option<&str>) -> Result<RegistryRef<'_>, RegistryError> {
+        match name {
+            None | Some("crates-io") => Ok(RegistryRef {
+                name: None,
+                client: &self.client,
+                index_client: self.index_client.as_ref(),
+            }),
+            Some(name) => {
+                let (name, registry) = self.registries.get_key_value(name).ok_or_else(|| {
+                    let mut configured: Vec<&str> =
+                        self.registries.keys().map(String::as_str).collect();
+                    configured.sort();
+                    configured.insert(0, "crates-io");
+                    RegistryError::Unknown {
+                        name: name.to_string(),
+                        configured: configured.join(", "),
+                    }
+                })?;
+                Ok(RegistryRef {
+                    name: Some(name),
+                    client: &registry.client,
+                    index_client: Some(&registry.index_client),
+                })
+            }
+        }
+    }
+
+    /// The registry whose index is at `index_url`, as given in a
+    /// dependency's `registry` field. `None` if it isn't configured.
+    pub fn registry_for_index(&self, index_url: &str) -> Option<RegistryRef<'_>> {
+        let url = normalize_index_url(index_url);
+        if CRATES_IO_INDEXES.contains(&url) {
+            return self.registry(None).ok();
+        }
+        self.registries
+            .iter()
+            .find(|(_, r)| r.index_client.base_url() == url)
+            .and_then(|(name, _)| self.registry(Some(name)).ok())
+    }
 }
 
 /// Helper to format large numbers in a human-readable way.
@@ -165,4 +395,236 @@ mod tests {
         assert_eq!(format_number(2_500_000), "2.5M");
         assert_eq!(format_number(50_000_000_000), "50000.0M");
     }
+
+    #[tokio::test]
+    async fn add_registry_discovers_api() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/index/config.json"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "dl": format!("{}/dl", server.uri()),
+                "api": server.uri(),
+            })))
+            .mount(&server)
+            .await;
+
+        let mut state = AppState::with_base_url("http://127.0.0.1:1").unwrap();
+        let index_url = format!("sparse+{}/index/", server.uri());
+        state
+            .add_registry(
+                "company",
+                &index_url,
+                None,
+                Duration::from_millis(0),
+                Duration::from_secs(30),
+            )
+            .await
+            .unwrap();
+
+        let registry = state.registry(Some("company")).unwrap();
+        assert_eq!(registry.name, Some("company"));
+        assert!(!registry.is_crates_io());
+        assert!(state.registry(Some("crates-io")).unwrap().is_crates_io());
+
+        // Dependencies name registries by index URL
+        let by_index = state.registry_for_index(&index_url).unwrap();
+        assert_eq!(by_index.name, Some("company"));
+        let crates_io = state
+            .registry_for_index("sparse+https://index.crates.io/")
+            .unwrap();
+        assert!(crates_io.is_crates_io());
+        assert!(
+            state
+                .registry_for_index("https://example.com/index")
+                .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn add_registry_requires_api() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/config.json"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({"dl": "https://example.com/dl"})),
+            )
+            .mount(&server)
+            .await;
+
+        let mut state = AppState::with_base_url("http://127.0.0.1:1").unwrap();
+        let err = state
+            .add_registry(
+                "mirror",
+                &format!("sparse+{}", server.uri()),
+                None,
+                Duration::from_millis(0),
+                Duration::from_secs(30),
+            )
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("no `api` URL"), "got: {err}");
+    }
+
+    #[tokio::test]
+    async fn http_backend_covers_registries_added_later() {
+        use crate::client::http::{HttpResponse, MemoryBackend, Method};
+
+        let backend = Arc::new(MemoryBackend::new());
+        backend.route(
+            Method::GET,
+            "https://cargo.example.com/index/config.json",
+            HttpResponse::json(&serde_json::json!({
+                "dl": "https://cargo.example.com/dl",
+                "api": "https://cargo.example.com",
+            })),
+        );
+
+        let mut state = AppState::with_base_url("http://127.0.0.1:1")
+            .unwrap()
+            .with_http_backend(backend.clone());
+        state
+            .add_registry(
+                "company",
+                "sparse+https://cargo.example.com/index/",
+                None,
+                Duration::from_millis(0),
+                Duration::from_secs(30),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(backend.requests().len(), 1);
+        assert!(state.registry(Some("company")).is_ok());
+    }
+
+    #[tokio::test]
+    async fn add_registry_rejects_non_sparse_indexes() {
+        let mut state = AppState::with_base_url("http://127.0.0.1:1").unwrap();
+        for (url, reason) in [
+            (
+                "registry+https://github.com/company/index",
+                "is a git index",
+            ),
+            ("http://cargo.example.com/index", "not a sparse index URL"),
+            ("sparse+file:///srv/index", "not a sparse index URL"),
+        ] {
+            let err = state
+                .add_registry(
+                    "company",
+                    url,
+                    None,
+                    Duration::from_millis(0),
+                    Duration::from_secs(30),
+                )
+                .await
+                .unwrap_err();
+            assert!(err.to_string().contains(reason), "{url}: {err}");
+        }
+    }
+
+    #[tokio::test]
+    async fn index_token_is_only_sent_when_required() {
+        use crate::client::http::{HttpResponse, MemoryBackend, Method};
+
+        let backend = Arc::new(MemoryBackend::new());
+        for (host, auth_required) in [("open", false), ("private", true)] {
+            backend.route(
+                Method::GET,
+                &format!("https://{host}.example.com/index/config.json"),
+                HttpResponse::json(&serde_json::json!({
+                    "dl": format!("https://{host}.example.com/dl"),
+                    "api": format!("https://{host}.example.com"),
+                    "auth-required": auth_required,
+                })),
+            );
+            backend.route(
+                Method::GET,
+                &format!("https://{host}.example.com/index/3/s/syn"),
+                HttpResponse::new(reqwest::StatusCode::OK),
+            );
+        }
+
+        let mut state = AppState::with_base_url("http://127.0.0.1:1")
+            .unwrap()
+            .with_http_backend(backend.clone());
+        for name in ["open", "private"] {
+            state
+                .add_registry(
+                    name,
+                    &format!("sparse+https://{name}.example.com/index/"),
+                    Some("secret".to_string()),
+                    Duration::from_millis(0),
+                    Duration::from_secs(30),
+                )
+                .await
+                .unwrap();
+            let index = state.registry(Some(name)).unwrap().index_client.unwrap();
+            index.crate_versions("syn").await.unwrap();
+        }
+
+        let authorized: Vec<String> = backend
+            .requests()
+            .iter()
+            .filter(|r| r.headers.contains_key(reqwest::header::AUTHORIZATION))
+            .map(|r| r.url.clone())
+            .collect();
+        assert_eq!(
+            authorized,
+            vec!["https://private.example.com/index/3/s/syn"]
+        );
+    }
+
+    #[tokio::test]
+    async fn refused_config_is_retried_with_the_token() {
+        use wiremock::matchers::{header, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/config.json"))
+            .and(header("authorization", "secret"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "dl": format!("{}/dl", server.uri()),
+                "api": server.uri(),
+                "auth-required": true,
+            })))
+            .with_priority(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/config.json"))
+            .respond_with(ResponseTemplate::new(401))
+            .mount(&server)
+            .await;
+
+        let mut state = AppState::with_base_url("http://127.0.0.1:1").unwrap();
+        state
+            .add_registry(
+                "company",
+                &format!("sparse+{}", server.uri()),
+                Some("secret".to_string()),
+                Duration::from_millis(0),
+                Duration::from_secs(30),
+            )
+            .await
+            .unwrap();
+        assert!(state.registry(Some("company")).is_ok());
+    }
+
+    #[test]
+    fn unknown_registry_lists_configured() {
+        let state = AppState::with_base_url("http://127.0.0.1:1").unwrap();
+        let err = state.registry(Some("nope")).err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "unknown registry `nope` (configured: crates-io)"
+        );
+    }
 }
@@ -21,6 +21,9 @@ pub struct FindAlternativesInput {
     /// Maximum number of alternatives to return (default: 5)
     #[serde(default = "default_max_results")]
     max_results: usize,
+    /// Registry to query, as configured with `--registry` (default: crates.io)
+    #[serde(default)]
+    registry: Option<String>,
 }
 
 /// Target crate, search terms, and discovered alternative crates.
@@ -50,8 +53,11 @@ pub fn build(state: Arc<AppState>) -> Tool {
         .extractor_handler(
             state,
             |State(state): State<Arc<AppState>>, Json(input): Json<FindAlternativesInput>| async move {
+                let registry = state
+                    .registry(input.registry.as_deref())
+                    .tool_context("Registry error")?;
                 // 1. Get target crate info to extract keywords
-                let target = state
+                let target = registry
                     .client
                     .get_crate(&input.name)
                     .await
@@ -92,7 +98,7 @@ pub fn build(state: Arc<AppState>) -> Tool {
                     .per_page(25)
                     .build();
 
-                let search_results = state
+                let search_results = registry
                     .client
                     .crates(query)
                     .await
@@ -168,7 +174,7 @@ pub fn build(state: Arc<AppState>) -> Tool {
                 // 5. Get basic info for each alternative and add to table
                 let mut alternatives = Vec::with_capacity(candidates.len());
                 for candidate in &candidates {
-                    match state.client.get_crate(&candidate.name).await {
+                    match registry.client.get_crate(&candidate.name).await {
                         Ok(alt) => {
                             let c = &alt.crate_data;
                             alternatives.push(c.clone());
@@ -254,6 +260,9 @@ mod tests {
                 .unwrap(),
             osv_client: OsvClient::with_base_url("test", Duration::from_secs(30), osv_url).unwrap(),
             docs_cache: DocsCache::new(10, Duration::from_secs(3600)),
+            index_client: None,
+            registries: Default::default(),
+            http_backend: None,
         })
     }
 
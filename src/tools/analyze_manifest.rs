@@ -0,0 +1,535 @@
+//! Cargo.toml dependency analysis tool
+
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+use std::sync::Arc;
+
+use schemars::JsonSchema;
+use semver::Version;
+use serde::{Deserialize, Serialize};
+use tower_mcp::{
+    ResultExt, Tool, ToolBuilder,
+    extract::{Json, State},
+};
+
+use crate::client::manifest::{DependencyKind, DependencySource, Manifest, ManifestDependency};
+use crate::client::resolve::parse_req;
+use crate::client::{CrateResponse, Error};
+use crate::state::AppState;
+use crate::tools::output::{schema, structured};
+
+/// Input for analyzing a manifest
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct AnalyzeManifestInput {
+    /// Full text of a Cargo.toml file
+    manifest: String,
+}
+
+/// Registry status of one declared dependency.
+#[derive(Debug, Serialize, JsonSchema)]
+struct DependencyReport {
+    /// Name the dependency is declared under
+    name: String,
+    /// Crate name on crates.io (differs from `name` when renamed)
+    package: String,
+    kind: DependencyKind,
+    target: Option<String>,
+    req: String,
+    /// Newest non-yanked version matching `req`
+    latest_compatible: Option<String>,
+    /// Newest non-yanked stable version
+    latest: Option<String>,
+    /// `req` does not allow `latest`
+    outdated: bool,
+    /// Lowest published version matching `req`
+    minimum: Option<String>,
+    /// Whether `minimum` has been yanked
+    minimum_yanked: bool,
+    /// License of `latest_compatible` (or `latest`)
+    license: Option<String>,
+    /// Why the dependency could not be fully checked
+    note: Option<String>,
+}
+
+/// A dependency that was not looked up on crates.io.
+#[derive(Debug, Serialize, JsonSchema)]
+struct SkippedDependency {
+    name: String,
+    kind: DependencyKind,
+    reason: String,
+}
+
+/// Machine-readable manifest analysis.
+#[derive(Debug, Serialize, JsonSchema)]
+struct AnalyzeManifestOutput {
+    package: Option<String>,
+    dependencies: Vec<DependencyReport>,
+    skipped: Vec<SkippedDependency>,
+    outdated: u64,
+    api_calls: u64,
+}
+
+/// Why a dependency is not checked against crates.io.
+fn skip_reason(dep: &ManifestDependency) -> String {
+    match &dep.source {
+        DependencySource::Registry {
+            registry: Some(registry),
+        } => format!("registry `{}` is not configured", registry),
+        DependencySource::Path { .. } => "path dependency".to_string(),
+        DependencySource::Git { .. } => "git dependency".to_string(),
+        DependencySource::Workspace => {
+            "inherited from a workspace not in this manifest".to_string()
+        }
+        DependencySource::Registry { registry: None } => "no version requirement".to_string(),
+    }
+}
+
+/// Compare a requirement against the published versions of its crate, or
+/// note why they could not be looked up.
+fn check(
+    dep: &ManifestDependency,
+    req: &str,
+    crate_response: Result<&CrateResponse, &str>,
+) -> DependencyReport {
+    let mut report = DependencyReport {
+        name: dep.name.clone(),
+        package: dep.package.clone(),
+        kind: dep.kind,
+        target: dep.target.clone(),
+        req: req.to_string(),
+        latest_compatible: None,
+        latest: None,
+        outdated: false,
+        minimum: None,
+        minimum_yanked: false,
+        license: None,
+        note: None,
+    };
+    let crate_response = match crate_response {
+        Ok(crate_response) => crate_response,
+        Err(reason) => {
+            report.note = Some(reason.to_string());
+            return report;
+        }
+    };
+    let Some(parsed) = parse_req(req) else {
+        report.note = Some("requirement could not be parsed".to_string());
+        return report;
+    };
+
+    let published: Vec<(Version, &crate::client::Version)> = crate_response
+        .versions
+        .iter()
+        .filter_map(|v| Some((Version::parse(&v.num).ok()?, v)))
+        .collect();
+
+    let latest = published
+        .iter()
+        .filter(|(v, meta)| !meta.yanked && v.pre.is_empty())
+        .max_by(|a, b| a.0.cmp(&b.0));
+    let compatible = published
+        .iter()
+        .filter(|(v, meta)| !meta.yanked && parsed.matches(v))
+        .max_by(|a, b| a.0.cmp(&b.0));
+    let minimum = published
+        .iter()
+        .filter(|(v, _)| parsed.matches(v))
+        .min_by(|a, b| a.0.cmp(&b.0));
+
+    report.latest = latest.map(|(v, _)| v.to_string());
+    report.latest_compatible = compatible.map(|(v, _)| v.to_string());
+    report.outdated = latest.is_some_and(|(v, _)| !parsed.matches(v));
+    report.minimum = minimum.map(|(v, _)| v.to_string());
+    report.minimum_yanked = minimum.is_some_and(|(_, meta)| meta.yanked);
+    report.license = compatible
+        .or(latest)
+        .and_then(|(_, meta)| meta.license.clone());
+    if compatible.is_none() {
+        report.note = Some("no published, non-yanked version matches".to_string());
+    }
+    report
+}
+
+fn format_analysis(result: &AnalyzeManifestOutput) -> String {
+    let mut output = format!(
+        "# Manifest Analysis: {}\n\n",
+        result.package.as_deref().unwrap_or("workspace")
+    );
+
+    if !result.dependencies.is_empty() {
+        output.push_str(
+            "| Dependency | Kind | Requirement | Latest compatible | Latest | Status | License |\n\
+             |------------|------|-------------|-------------------|--------|--------|---------|\n",
+        );
+    }
+    for dep in &result.dependencies {
+        let name = if dep.name == dep.package {
+            dep.name.clone()
+        } else {
+            format!("{} ({})", dep.name, dep.package)
+        };
+        let kind = match &dep.target {
+            Some(target) => format!("{:?} ({})", dep.kind, target).to_lowercase(),
+            None => format!("{:?}", dep.kind).to_lowercase(),
+        };
+        let mut status = Vec::new();
+        if let Some(note) = &dep.note {
+            status.push(note.clone());
+        } else if dep.outdated {
+            status.push("outdated".to_string());
+        } else {
+            status.push("up to date".to_string());
+        }
+        if dep.minimum_yanked {
+            status.push(format!(
+                "minimum v{} yanked",
+                dep.minimum.as_deref().unwrap_or("?")
+            ));
+        }
+        output.push_str(&format!(
+            "| {} | {} | {} | {} | {} | {} | {} |\n",
+            name,
+            kind,
+            dep.req,
+            dep.latest_compatible.as_deref().unwrap_or("-"),
+            dep.latest.as_deref().unwrap_or("-"),
+            status.join("; "),
+            dep.license.as_deref().unwrap_or("-"),
+        ));
+    }
+
+    if !result.skipped.is_empty() {
+        output.push_str("\n## Skipped\n\n");
+        for dep in &result.skipped {
+            output.push_str(&format!("- `{}`: {}\n", dep.name, dep.reason));
+        }
+    }
+
+    let yanked = result
+        .dependencies
+        .iter()
+        .filter(|d| d.minimum_yanked)
+        .count();
+    output.push_str(&format!(
+        "\n## Summary\n\n\
+         - **Dependencies checked**: {}\n\
+         - **Outdated requirements**: {}\n\
+         - **Yanked minimum versions**: {}\n\
+         - **Skipped**: {}\n\
+         - **API calls made**: {}\n",
+        result.dependencies.len(),
+        result.outdated,
+        yanked,
+        result.skipped.len(),
+        result.api_calls
+    ));
+
+    output
+}
+
+pub fn build(state: Arc<AppState>) -> Tool {
+    ToolBuilder::new("analyze_manifest")
+        .title("Analyze Manifest")
+        .description(
+            "Analyze the dependencies declared in a Cargo.toml, like `cargo outdated`. Accepts \
+             the full manifest text, including dev/build sections, `[target.'cfg(..)'.*]` tables, \
+             renamed `package = ...` dependencies and `[workspace.dependencies]`. For each \
+             crates.io dependency, reports the newest version its requirement allows, the newest \
+             version overall, whether the requirement is outdated, whether the lowest matching \
+             version is yanked, and the license. As in Cargo, dependencies without a `registry` \
+             key come from crates.io; `registry = \"...\"` dependencies are checked when that \
+             registry is configured with `--registry`. Path, git and unknown-registry \
+             dependencies are listed as skipped.",
+        )
+        .read_only_safe()
+        .output_schema(schema::<AnalyzeManifestOutput>())
+        .icon("https://crates.io/assets/cargo.png")
+        .extractor_handler(
+            state,
+            |State(state): State<Arc<AppState>>, Json(input): Json<AnalyzeManifestInput>| async move {
+                let manifest =
+                    Manifest::parse(&input.manifest).tool_context("Cargo.toml parse error")?;
+
+                // One lookup per crate, shared by every section that uses it
+                let mut crates: HashMap<(Option<&str>, &str), Result<CrateResponse, String>> =
+                    HashMap::new();
+                let mut dependencies = Vec::new();
+                let mut skipped = Vec::new();
+                for dep in &manifest.dependencies {
+                    // As in Cargo, dependencies without a `registry` key come
+                    // from crates.io; `registry = "name"` ones are checked
+                    // when that registry is configured
+                    let dep_registry = match &dep.source {
+                        DependencySource::Registry {
+                            registry: Some(name),
+                        } => state.registry(Some(name)).ok(),
+                        _ if dep.is_crates_io() => state.registry(None).ok(),
+                        _ => None,
+                    };
+                    let (Some(req), Some(dep_registry)) = (dep.req.as_deref(), dep_registry) else {
+                        skipped.push(SkippedDependency {
+                            name: dep.name.clone(),
+                            kind: dep.kind,
+                            reason: skip_reason(dep),
+                        });
+                        continue;
+                    };
+                    let key = (dep_registry.name, dep.package.as_str());
+                    if let Entry::Vacant(entry) = crates.entry(key) {
+                        // Only a 404 means the crate is missing; rate limits
+                        // and outages are reported as such
+                        let response = match dep_registry.client.get_crate(&dep.package).await {
+                            Ok(response) => Ok(response),
+                            Err(Error::NotFound(_)) => Err(format!(
+                                "not found on {}",
+                                dep_registry.name.unwrap_or("crates.io")
+                            )),
+                            Err(e) => Err(format!("lookup failed: {e}")),
+                        };
+                        entry.insert(response);
+                    }
+                    let response = crates[&key].as_ref().map_err(String::as_str);
+                    dependencies.push(check(dep, req, response));
+                }
+
+                let result = AnalyzeManifestOutput {
+                    package: manifest.package.clone(),
+                    outdated: dependencies.iter().filter(|d| d.outdated).count() as u64,
+                    dependencies,
+                    skipped,
+                    api_calls: crates.len() as u64,
+                };
+                structured(format_analysis(&result), &result)
+            },
+        )
+        .build()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::time::Duration;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use crate::client::CratesIoClient;
+    use crate::client::docsrs::DocsRsClient;
+    use crate::client::osv::OsvClient;
+    use crate::docs::cache::DocsCache;
+    use crate::state::AppState;
+
+    fn test_state(base_url: &str) -> Arc<AppState> {
+        Arc::new(AppState {
+            client: CratesIoClient::with_base_url(
+                "test",
+                Duration::from_millis(0),
+                Duration::from_secs(30),
+                base_url,
+            )
+            .unwrap(),
+            docsrs_client: DocsRsClient::with_base_url("test", Duration::from_secs(30), base_url)
+                .unwrap(),
+            osv_client: OsvClient::with_base_url("test", Duration::from_secs(30), base_url)
+                .unwrap(),
+            docs_cache: DocsCache::new(10, Duration::from_secs(3600)),
+            index_client: None,
+            registries: Default::default(),
+            http_backend: None,
+        })
+    }
+
+    /// Mount a crate whose versions are `(num, yanked)` pairs.
+    async fn mount_crate(server: &MockServer, name: &str, versions: &[(&str, bool)]) {
+        let versions: Vec<serde_json::Value> = versions
+            .iter()
+            .map(|(num, yanked)| {
+                serde_json::json!({
+                    "num": num,
+                    "yanked": yanked,
+                    "created_at": "2026-01-01T00:00:00.000000Z",
+                    "downloads": 1,
+                    "license": "MIT OR Apache-2.0"
+                })
+            })
+            .collect();
+        Mock::given(method("GET"))
+            .and(path(format!("/crates/{}", name)))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "crate": {
+                    "name": name,
+                    "max_version": "0.0.0",
+                    "description": null,
+                    "downloads": 1,
+                    "created_at": "2026-01-01T00:00:00.000000Z",
+                    "updated_at": "2026-01-01T00:00:00.000000Z"
+                },
+                "versions": versions
+            })))
+            .expect(1)
+            .mount(server)
+            .await;
+    }
+
+    const MANIFEST: &str = r#"
+[package]
+name = "my-app"
+
+[dependencies]
+serde = "1.0.100"
+json = { package = "serde_json", version = "1" }
+rand = "0.7"
+local = { path = "../local" }
+
+[target.'cfg(unix)'.dev-dependencies]
+serde = "1"
+"#;
+
+    #[tokio::test]
+    async fn reports_outdated_and_yanked_requirements() {
+        let server = MockServer::start().await;
+        mount_crate(
+            &server,
+            "serde",
+            &[("1.0.100", true), ("1.0.101", false), ("1.0.219", false)],
+        )
+        .await;
+        mount_crate(&server, "serde_json", &[("1.0.140", false)]).await;
+        mount_crate(
+            &server,
+            "rand",
+            &[("0.7.3", false), ("0.8.5", false), ("0.9.0-beta.1", false)],
+        )
+        .await;
+
+        let tool = super::build(test_state(&server.uri()));
+        let result = tool.call(serde_json::json!({"manifest": MANIFEST})).await;
+        let text = result.all_text();
+
+        assert!(text.contains("Manifest Analysis: my-app"), "{text}");
+        assert!(text.contains(
+            "| serde | normal | 1.0.100 | 1.0.219 | 1.0.219 | up to date; minimum v1.0.100 yanked | MIT OR Apache-2.0 |"
+        ));
+        assert!(
+            text.contains("| json (serde_json) | normal | 1 | 1.0.140 | 1.0.140 | up to date |")
+        );
+        assert!(text.contains("| rand | normal | 0.7 | 0.7.3 | 0.8.5 | outdated |"));
+        assert!(text.contains("| serde | dev (cfg(unix)) | 1 |"));
+        assert!(text.contains("- `local`: path dependency"));
+        assert!(text.contains("Outdated requirements**: 1"));
+        // serde is looked up once for both sections
+        assert!(text.contains("API calls made**: 3"));
+    }
+
+    #[tokio::test]
+    async fn unknown_crate_is_noted() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/crates/nope"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&server)
+            .await;
+
+        let tool = super::build(test_state(&server.uri()));
+        let result = tool
+            .call(serde_json::json!({"manifest": "[dependencies]\nnope = \"1\"\n"}))
+            .await;
+        let text = result.all_text();
+
+        assert!(text.contains("Manifest Analysis: workspace"));
+        assert!(text.contains("not found on crates.io"));
+    }
+
+    #[tokio::test]
+    async fn failed_lookup_is_not_reported_as_missing() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/crates/serde"))
+            .respond_with(ResponseTemplate::new(500))
+            .mount(&server)
+            .await;
+
+        let mut state = Arc::into_inner(test_state(&server.uri())).unwrap();
+        state.client = state.client.with_max_retries(0);
+        let tool = super::build(Arc::new(state));
+        let result = tool
+            .call(serde_json::json!({"manifest": "[dependencies]\nserde = \"1\"\n"}))
+            .await;
+        let text = result.all_text();
+
+        assert!(text.contains("lookup failed: API error (500)"), "{text}");
+        assert!(!text.contains("not found"), "{text}");
+    }
+
+    #[tokio::test]
+    async fn registry_key_selects_the_registry() {
+        let server = MockServer::start().await;
+        let private = MockServer::start().await;
+        mount_crate(&server, "serde", &[("1.0.219", false)]).await;
+        Mock::given(method("GET"))
+            .and(path("/api/v1/crates/internal-lib"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "crate": {
+                    "name": "internal-lib",
+                    "max_version": "1.2.0",
+                    "downloads": 1,
+                    "created_at": "2026-01-01T00:00:00.000000Z",
+                    "updated_at": "2026-01-01T00:00:00.000000Z"
+                },
+                "versions": [{"num": "1.2.0", "yanked": false, "created_at": "2026-01-01T00:00:00.000000Z", "downloads": 1}]
+            })))
+            .expect(1)
+            .mount(&private)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/config.json"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "dl": format!("{}/dl", private.uri()),
+                "api": private.uri(),
+            })))
+            .mount(&private)
+            .await;
+
+        let mut state = Arc::into_inner(test_state(&server.uri())).unwrap();
+        state
+            .add_registry(
+                "company",
+                &format!("sparse+{}", private.uri()),
+                None,
+                Duration::from_millis(0),
+                Duration::from_secs(30),
+            )
+            .await
+            .unwrap();
+        let tool = super::build(Arc::new(state));
+        let manifest = r#"
+[dependencies]
+serde = "1"
+internal-lib = { version = "1", registry = "company" }
+vendored = { version = "1", registry = "elsewhere" }
+"#;
+        let result = tool.call(serde_json::json!({"manifest": manifest})).await;
+        let text = result.all_text();
+
+        // Without a `registry` key, dependencies come from crates.io
+        assert!(
+            text.contains("| serde | normal | 1 | 1.0.219 | 1.0.219 | up to date |"),
+            "{text}"
+        );
+        assert!(
+            text.contains("| internal-lib | normal | 1 | 1.2.0 | 1.2.0 | up to date | - |"),
+            "{text}"
+        );
+        assert!(text.contains("- `vendored`: registry `elsewhere` is not configured"));
+    }
+
+    #[tokio::test]
+    async fn invalid_manifest_is_an_error() {
+        let server = MockServer::start().await;
+        let tool = super::build(test_state(&server.uri()));
+        let result = tool
+            .call(serde_json::json!({"manifest": "[dependencies\n"}))
+            .await;
+        assert!(result.is_error);
+        assert!(result.all_text().contains("Cargo.toml parse error"));
+    }
+}
@@ -0,0 +1,105 @@
+//! Compare the public API of two versions of a crate.
+
+use std::sync::Arc;
+
+use schemars::JsonSchema;
+use serde::Deserialize;
+use tower_mcp::{
+    ResultExt, Tool, ToolBuilder,
+    extract::{Json, State},
+};
+
+use crate::docs::diff;
+use crate::state::AppState;
+use crate::tools::output::{ApiDiffOutput, schema, structured};
+
+/// Input for diffing the public API of two versions
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GetApiDiffInput {
+    /// Crate name (e.g. "serde", "tokio")
+    name: String,
+    /// Old version (e.g. "0.11.27")
+    from_version: String,
+    /// New version (default: "latest")
+    #[serde(default = "default_version")]
+    to_version: String,
+    /// Maximum number of changes to list, breaking changes first (default: 100)
+    #[serde(default = "default_limit")]
+    limit: usize,
+}
+
+fn default_version() -> String {
+    "latest".to_string()
+}
+
+fn default_limit() -> usize {
+    100
+}
+
+pub fn build(state: Arc<AppState>) -> Tool {
+    ToolBuilder::new("get_api_diff")
+        .title("Get API Diff")
+        .description(
+            "Compare the public API of two versions of a crate using docs.rs rustdoc JSON. \
+             Matches items by path and reports added, removed and changed items -- function \
+             signatures, trait bounds, struct fields, enum variants, trait items and trait \
+             impls -- each classified as breaking or non-breaking, in the spirit of \
+             cargo-semver-checks.",
+        )
+        .read_only_safe()
+        .output_schema(schema::<ApiDiffOutput>())
+        .extractor_handler(
+            state,
+            |State(state): State<Arc<AppState>>, Json(input): Json<GetApiDiffInput>| async move {
+                let (old, new) = tokio::try_join!(
+                    state.docs_cache.get_or_fetch(
+                        &state.docsrs_client,
+                        &input.name,
+                        &input.from_version
+                    ),
+                    state.docs_cache.get_or_fetch(
+                        &state.docsrs_client,
+                        &input.name,
+                        &input.to_version
+                    ),
+                )
+                .tool_context("docs.rs fetch error")?;
+
+                let mut changes = diff::diff(&old, &new);
+                let breaking = changes.iter().filter(|c| c.breaking).count();
+                let non_breaking = changes.len() - breaking;
+                let total = changes.len();
+                changes.truncate(input.limit.min(500));
+
+                let result = ApiDiffOutput {
+                    name: input.name.clone(),
+                    from_version: input.from_version.clone(),
+                    to_version: input.to_version.clone(),
+                    breaking: breaking as u64,
+                    non_breaking: non_breaking as u64,
+                    changes: changes.iter().map(Into::into).collect(),
+                };
+
+                let mut output = format!(
+                    "# API diff for {} {} -> {}\n\n",
+                    input.name, input.from_version, input.to_version
+                );
+                if total == 0 {
+                    output.push_str("No public API changes.\n");
+                    return structured(output, &result);
+                }
+                output.push_str(&format!(
+                    "{} breaking, {} non-breaking changes",
+                    breaking, non_breaking
+                ));
+                if changes.len() < total {
+                    output.push_str(&format!(" (showing {})", changes.len()));
+                }
+                output.push_str(".\n\n");
+                output.push_str(&diff::format_changes(&changes));
+
+                structured(output, &result)
+            },
+        )
+        .build()
+}
@@ -1,5 +1,6 @@
 //! Dependency security audit tool via OSV.dev
 
+use std::collections::BTreeSet;
 use std::sync::Arc;
 
 use schemars::JsonSchema;
@@ -9,8 +10,15 @@ use tower_mcp::{
     extract::{Json, State},
 };
 
-use crate::client::osv::OsvVulnerability;
+use semver::Version;
+
+use crate::client::CrateResponse;
+use crate::client::osv::{Exposure, OsvVulnerability, PackageQuery};
+use crate::client::resolve::parse_req;
 use crate::state::AppState;
+use crate::tools::dep_graph::{
+    DEFAULT_MAX_CRATES, MAX_CRATES_LIMIT, NodeKey, WalkOptions, format_path, walk,
+};
 use crate::tools::output::{schema, structured};
 
 /// Input for auditing dependencies
@@ -24,15 +32,86 @@ pub struct AuditInput {
     /// Include dev dependencies in audit
     #[serde(default)]
     include_dev: bool,
+    /// Walk the whole dependency tree instead of only direct dependencies
+    #[serde(default)]
+    transitive: bool,
+    /// Transitive mode: maximum depth to walk (default: 3, max: 10)
+    #[serde(default)]
+    max_depth: Option<u32>,
+    /// Transitive mode: maximum unique crates to check (default: 50, max: 500)
+    #[serde(default)]
+    max_crates: Option<usize>,
+    /// Registry to query, as configured with `--registry` (default: crates.io)
+    #[serde(default)]
+    registry: Option<String>,
 }
 
+/// Maximum chains shown per vulnerable crate in transitive mode.
+const MAX_PATHS_PER_CRATE: usize = 3;
+
 /// A vulnerability finding associated with a dependency.
 #[derive(Debug, Serialize, JsonSchema)]
 struct Finding {
     dep_name: String,
+    /// Version or requirement the advisory was evaluated against
+    checked: String,
+    /// Whether the advisory affects `checked` (false: historical, already fixed)
+    affects: bool,
+    /// Minimal fixed version to upgrade to, if one exists
+    upgrade_to: Option<String>,
+    /// Transitive mode: dependency chains from the root that pull this crate in
+    paths: Vec<String>,
     vuln: OsvVulnerability,
 }
 
+impl Finding {
+    /// Classify a vulnerability against the exact version of the audited crate.
+    fn for_version(name: &str, version: &str, vuln: OsvVulnerability) -> Self {
+        let (affects, upgrade_to) = match Version::parse(version) {
+            Ok(v) => (
+                vuln.affects(name, &v),
+                vuln.fixed_version(name, &v).map(|f| f.to_string()),
+            ),
+            // Unparseable version: report it rather than hide it.
+            Err(_) => (true, None),
+        };
+        Self {
+            dep_name: name.to_string(),
+            checked: version.to_string(),
+            affects,
+            upgrade_to,
+            paths: Vec::new(),
+            vuln,
+        }
+    }
+
+    /// Classify a vulnerability against a dependency requirement.
+    fn for_requirement(name: &str, req: &str, vuln: OsvVulnerability) -> Self {
+        let (affects, upgrade_to) = match parse_req(req).map(|r| vuln.exposure(name, &r)) {
+            Some(Exposure::Historical) => (false, None),
+            Some(Exposure::Affected { fixed }) => (true, fixed.map(|f| f.to_string())),
+            None => (true, None),
+        };
+        Self {
+            dep_name: name.to_string(),
+            checked: req.to_string(),
+            affects,
+            upgrade_to,
+            paths: Vec::new(),
+            vuln,
+        }
+    }
+}
+
+/// Traversal limits of a transitive audit.
+#[derive(Debug, Serialize, JsonSchema)]
+struct TransitiveScope {
+    max_depth: u32,
+    max_crates: u64,
+    /// Whether the crate cap was hit before the tree was exhausted
+    truncated: bool,
+}
+
 /// Security findings for a crate and the dependencies that were checked.
 #[derive(Debug, Serialize, JsonSchema)]
 struct AuditOutput {
@@ -40,93 +119,274 @@ struct AuditOutput {
     version: String,
     dependencies_checked: u64,
     findings: Vec<Finding>,
+    /// Set when the whole dependency tree was audited
+    transitive: Option<TransitiveScope>,
 }
 
-fn format_findings(
-    crate_name: &str,
-    version: &str,
-    findings: &[Finding],
-    deps_checked: usize,
-) -> String {
-    let mut output = format!("# Security Audit: {} v{}\n\n", crate_name, version);
-
-    if findings.is_empty() {
-        output.push_str("No known vulnerabilities found.\n\n");
-    } else {
-        output.push_str("## Vulnerabilities Found\n\n");
-        for f in findings {
-            output.push_str(&format!("### {} -- {}\n\n", f.dep_name, f.vuln.id));
-
-            if let Some(summary) = &f.vuln.summary {
-                output.push_str(&format!("- **Summary**: {}\n", summary));
-            }
+/// Append the summary, severity, fixed versions, and advisory link of a
+/// vulnerability as Markdown list items.
+pub(crate) fn push_vuln_details(output: &mut String, vuln: &OsvVulnerability) {
+    if let Some(summary) = &vuln.summary {
+        output.push_str(&format!("- **Summary**: {}\n", summary));
+    }
 
-            // Show CVSS severity if available
-            if let Some(severity) = &f.vuln.severity
-                && let Some(s) = severity.first()
-            {
-                output.push_str(&format!(
-                    "- **Severity**: {} ({})\n",
-                    s.severity_type, s.score
-                ));
-            }
+    // Show CVSS severity if available
+    if let Some(severity) = &vuln.severity
+        && let Some(s) = severity.first()
+    {
+        output.push_str(&format!(
+            "- **Severity**: {} ({})\n",
+            s.severity_type, s.score
+        ));
+    }
 
-            // Show fixed version if available
-            if let Some(affected) = &f.vuln.affected {
-                for a in affected {
-                    if let Some(ranges) = &a.ranges {
-                        for range in ranges {
-                            for event in &range.events {
-                                if let Some(fixed) = &event.fixed {
-                                    output.push_str(&format!("- **Fixed in**: {}\n", fixed));
-                                }
-                            }
+    // Show fixed version if available
+    if let Some(affected) = &vuln.affected {
+        for a in affected {
+            if let Some(ranges) = &a.ranges {
+                for range in ranges {
+                    for event in &range.events {
+                        if let Some(fixed) = &event.fixed {
+                            output.push_str(&format!("- **Fixed in**: {}\n", fixed));
                         }
                     }
                 }
             }
+        }
+    }
 
-            // Show first advisory reference
-            if let Some(refs) = &f.vuln.references {
-                if let Some(r) = refs.iter().find(|r| r.ref_type == "ADVISORY") {
-                    output.push_str(&format!("- **Advisory**: {}\n", r.url));
-                } else if let Some(r) = refs.first() {
-                    output.push_str(&format!("- **Reference**: {}\n", r.url));
-                }
+    // Show first advisory reference
+    if let Some(refs) = &vuln.references {
+        if let Some(r) = refs.iter().find(|r| r.ref_type == "ADVISORY") {
+            output.push_str(&format!("- **Advisory**: {}\n", r.url));
+        } else if let Some(r) = refs.first() {
+            output.push_str(&format!("- **Reference**: {}\n", r.url));
+        }
+    }
+}
+
+fn format_findings(audit: &AuditOutput) -> String {
+    let findings = &audit.findings;
+    let mut output = format!("# Security Audit: {} v{}\n\n", audit.name, audit.version);
+
+    let (affecting, historical): (Vec<&Finding>, Vec<&Finding>) =
+        findings.iter().partition(|f| f.affects);
+
+    if affecting.is_empty() {
+        output.push_str("No known vulnerabilities found.\n\n");
+    } else {
+        output.push_str("## Vulnerabilities Found\n\n");
+        for f in &affecting {
+            output.push_str(&format!("### {} -- {}\n\n", f.dep_name, f.vuln.id));
+            output.push_str(&format!("- **Checked**: {}\n", f.checked));
+            push_vuln_details(&mut output, &f.vuln);
+            match &f.upgrade_to {
+                Some(target) => output.push_str(&format!("- **Upgrade to**: {}\n", target)),
+                None => output.push_str("- **Upgrade to**: no fixed version available\n"),
+            }
+            for path in &f.paths {
+                output.push_str(&format!("- **Pulled in by**: {}\n", path));
             }
+            output.push('\n');
+        }
+    }
 
+    if !historical.is_empty() {
+        output.push_str("## Historical Advisories\n\n");
+        output.push_str("Not affecting the newest versions the requirements allow:\n\n");
+        for f in &historical {
+            output.push_str(&format!(
+                "- {} ({}) -- {}",
+                f.dep_name, f.checked, f.vuln.id
+            ));
+            if let Some(summary) = &f.vuln.summary {
+                output.push_str(&format!(": {}", summary));
+            }
             output.push('\n');
         }
+        output.push('\n');
     }
 
     // Summary
     let affected_deps: Vec<&str> = {
-        let mut names: Vec<&str> = findings.iter().map(|f| f.dep_name.as_str()).collect();
+        let mut names: Vec<&str> = affecting.iter().map(|f| f.dep_name.as_str()).collect();
         names.sort();
         names.dedup();
         names
     };
 
     output.push_str("## Summary\n\n");
-    output.push_str(&format!("- **Dependencies checked**: {}\n", deps_checked));
+    if let Some(scope) = &audit.transitive {
+        output.push_str(&format!(
+            "- **Scope**: transitive (depth {}, up to {} crates)\n",
+            scope.max_depth, scope.max_crates
+        ));
+    }
+    output.push_str(&format!(
+        "- **Dependencies checked**: {}\n",
+        audit.dependencies_checked
+    ));
     output.push_str(&format!(
         "- **Vulnerabilities found**: {}\n",
-        findings.len()
+        affecting.len()
+    ));
+    output.push_str(&format!(
+        "- **Historical advisories**: {}\n",
+        historical.len()
     ));
     output.push_str(&format!(
         "- **Affected dependencies**: {}\n",
         affected_deps.len()
     ));
 
+    if let Some(scope) = &audit.transitive
+        && scope.truncated
+    {
+        output.push_str(&format!(
+            "\n> Note: audit stopped at {} unique crates (graph is larger); raise `max_crates` to check more.\n",
+            scope.max_crates
+        ));
+    }
+
     output
 }
 
+/// Audit the crate and its direct dependencies, evaluating each dependency's
+/// advisories against its version requirement.
+async fn audit_direct(
+    state: &AppState,
+    input: &AuditInput,
+    version: &str,
+) -> tower_mcp::Result<AuditOutput> {
+    let deps = state
+        .client
+        .crate_dependencies(&input.name, version)
+        .await
+        .tool_context("Crates.io API error")?;
+
+    // Filter out dev deps unless requested
+    let deps_to_check: Vec<_> = deps
+        .iter()
+        .filter(|d| input.include_dev || d.kind != "dev")
+        .collect();
+
+    // Check the crate itself and every dependency in one batch
+    let queries: Vec<PackageQuery<'_>> = std::iter::once(input.name.as_str())
+        .chain(deps_to_check.iter().map(|d| d.crate_id.as_str()))
+        .map(PackageQuery::any)
+        .collect();
+    let mut results = state
+        .osv_client
+        .query_batch(&queries)
+        .await
+        .tool_context("OSV.dev API error")?
+        .into_iter();
+
+    let mut findings = Vec::new();
+    for vuln in results.next().unwrap_or_default() {
+        findings.push(Finding::for_version(&input.name, version, vuln));
+    }
+    for (dep, vulns) in deps_to_check.iter().zip(results) {
+        for vuln in vulns {
+            findings.push(Finding::for_requirement(&dep.crate_id, &dep.req, vuln));
+        }
+    }
+
+    Ok(AuditOutput {
+        name: input.name.clone(),
+        version: version.to_string(),
+        dependencies_checked: deps_to_check.len() as u64,
+        findings,
+        transitive: None,
+    })
+}
+
+/// Walk the dependency tree and check every reached crate at the version its
+/// requirement resolves to, recording the chains that pull in each vulnerable
+/// crate.
+async fn audit_transitive(
+    state: &AppState,
+    input: &AuditInput,
+    root: &CrateResponse,
+    version: &str,
+) -> tower_mcp::Result<AuditOutput> {
+    let options = WalkOptions {
+        max_depth: input.max_depth.unwrap_or(3).min(10),
+        max_crates: input
+            .max_crates
+            .unwrap_or(DEFAULT_MAX_CRATES)
+            .clamp(1, MAX_CRATES_LIMIT),
+        include_build: true,
+        include_dev_root: input.include_dev,
+    };
+    // Audit what a default build pulls in
+    let features = BTreeSet::from(["default".to_string()]);
+    let registry = state.registry(None).tool_context("Registry error")?;
+    let graph = walk(state, registry, root, version, features, options)
+        .await
+        .tool_context("Crates.io API error")?;
+
+    // Root first, then the rest in a stable order
+    let mut crates: Vec<&NodeKey> = graph.nodes.keys().filter(|k| **k != graph.root).collect();
+    crates.sort();
+    crates.insert(0, &graph.root);
+
+    let queries: Vec<PackageQuery<'_>> = crates
+        .iter()
+        .map(|(name, version)| PackageQuery::at(name, version))
+        .collect();
+    let results = state
+        .osv_client
+        .query_batch(&queries)
+        .await
+        .tool_context("OSV.dev API error")?;
+
+    let mut findings = Vec::new();
+    for (key, vulns) in crates.iter().zip(results) {
+        if vulns.is_empty() {
+            continue;
+        }
+        let paths: Vec<String> = if **key == graph.root {
+            Vec::new()
+        } else {
+            graph
+                .paths_to(key, MAX_PATHS_PER_CRATE)
+                .iter()
+                .map(|p| format_path(p))
+                .collect()
+        };
+        for vuln in vulns {
+            let mut finding = Finding::for_version(&key.0, &key.1, vuln);
+            finding.paths = paths.clone();
+            findings.push(finding);
+        }
+    }
+
+    Ok(AuditOutput {
+        name: input.name.clone(),
+        version: version.to_string(),
+        dependencies_checked: (crates.len() - 1) as u64,
+        findings,
+        transitive: Some(TransitiveScope {
+            max_depth: options.max_depth,
+            max_crates: options.max_crates as u64,
+            truncated: graph.truncated,
+        }),
+    })
+}
+
 pub fn build(state: Arc<AppState>) -> Tool {
     ToolBuilder::new("audit_dependencies")
         .title("Audit Dependencies")
         .description(
             "Check a crate's dependencies against the OSV.dev vulnerability database \
-             (RustSec + GHSA + NVD). Returns known vulnerabilities for each dependency.",
+             (RustSec + GHSA + NVD). Advisories are evaluated against the audited version \
+             and each dependency's version requirement: those that still apply are listed \
+             with the minimal fixed version to upgrade to, and advisories already fixed \
+             within the requirement are reported separately as historical. Set `transitive` \
+             to walk the whole dependency tree instead: every reached crate is checked at its \
+             resolved version and reported with the chains from the root that pull it in, \
+             so you can tell which direct dependency to upgrade.",
         )
         .read_only_safe()
         .output_schema(schema::<AuditOutput>())
@@ -134,8 +394,18 @@ pub fn build(state: Arc<AppState>) -> Tool {
         .extractor_handler(
             state,
             |State(state): State<Arc<AppState>>, Json(input): Json<AuditInput>| async move {
+                let registry = state
+                    .registry(input.registry.as_deref())
+                    .tool_context("Registry error")?;
+                if !registry.is_crates_io() {
+                    return Err(tower_mcp::ToolError::new(
+                        "OSV.dev advisories only cover crates.io; audits can't use another registry",
+                    )
+                    .into());
+                }
+
                 // Resolve crate version
-                let crate_response = state
+                let crate_response = registry
                     .client
                     .get_crate(&input.name)
                     .await
@@ -146,63 +416,12 @@ pub fn build(state: Arc<AppState>) -> Tool {
                     .as_deref()
                     .unwrap_or(&crate_response.crate_data.max_version);
 
-                // Fetch dependencies
-                let deps = state
-                    .client
-                    .crate_dependencies(&input.name, version)
-                    .await
-                    .tool_context("Crates.io API error")?;
-
-                // Filter out dev deps unless requested
-                let deps_to_check: Vec<_> = deps
-                    .iter()
-                    .filter(|d| input.include_dev || d.kind != "dev")
-                    .collect();
-
-                let deps_checked = deps_to_check.len();
-                let mut findings = Vec::new();
-
-                // Check the crate itself
-                let self_resp = state
-                    .osv_client
-                    .query_package_any(&input.name)
-                    .await
-                    .tool_context("OSV.dev API error")?;
-
-                if let Some(vulns) = self_resp.vulns {
-                    for vuln in vulns {
-                        findings.push(Finding {
-                            dep_name: input.name.clone(),
-                            vuln,
-                        });
-                    }
-                }
-
-                // Check each dependency
-                for dep in &deps_to_check {
-                    let resp = state
-                        .osv_client
-                        .query_package_any(&dep.crate_id)
-                        .await
-                        .tool_context("OSV.dev API error")?;
-
-                    if let Some(vulns) = resp.vulns {
-                        for vuln in vulns {
-                            findings.push(Finding {
-                                dep_name: dep.crate_id.clone(),
-                                vuln,
-                            });
-                        }
-                    }
-                }
-
-                let output = format_findings(&input.name, version, &findings, deps_checked);
-                let result = AuditOutput {
-                    name: input.name,
-                    version: version.to_string(),
-                    dependencies_checked: deps_checked as u64,
-                    findings,
+                let result = if input.transitive {
+                    audit_transitive(&state, &input, &crate_response, version).await?
+                } else {
+                    audit_direct(&state, &input, version).await?
                 };
+                let output = format_findings(&result);
                 structured(output, &result)
             },
         )
@@ -235,6 +454,9 @@ mod tests {
                 .unwrap(),
             osv_client: OsvClient::with_base_url("test", Duration::from_secs(30), osv_url).unwrap(),
             docs_cache: DocsCache::new(10, Duration::from_secs(3600)),
+            index_client: None,
+            registries: Default::default(),
+            http_backend: None,
         })
     }
 
@@ -268,15 +490,19 @@ mod tests {
             .await;
 
         Mock::given(method("POST"))
-            .and(path("/query"))
+            .and(path("/querybatch"))
             .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
-                "vulns": [
-                    {
-                        "id": "RUSTSEC-2024-0001",
-                        "summary": "Use-after-free in vuln-crate",
-                        "references": [{"type": "ADVISORY", "url": "https://rustsec.org/advisories/RUSTSEC-2024-0001.html"}]
-                    }
-                ]
+                "results": [{"vulns": [{"id": "RUSTSEC-2024-0001"}]}]
+            })))
+            .mount(&osv_server)
+            .await;
+
+        Mock::given(method("GET"))
+            .and(path("/vulns/RUSTSEC-2024-0001"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "id": "RUSTSEC-2024-0001",
+                "summary": "Use-after-free in vuln-crate",
+                "references": [{"type": "ADVISORY", "url": "https://rustsec.org/advisories/RUSTSEC-2024-0001.html"}]
             })))
             .mount(&osv_server)
             .await;
@@ -294,6 +520,174 @@ mod tests {
         assert!(text.contains("Vulnerabilities Found"));
     }
 
+    #[tokio::test]
+    async fn audit_separates_historical_advisories() {
+        let crates_server = MockServer::start().await;
+        let osv_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/crates/my-crate"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "crate": {
+                    "name": "my-crate",
+                    "max_version": "1.0.0",
+                    "description": "Test crate",
+                    "downloads": 100,
+                    "created_at": "2026-01-01T00:00:00.000000Z",
+                    "updated_at": "2026-01-01T00:00:00.000000Z"
+                },
+                "versions": [{"num": "1.0.0", "yanked": false, "created_at": "2026-01-01T00:00:00.000000Z", "downloads": 100}]
+            })))
+            .mount(&crates_server)
+            .await;
+
+        Mock::given(method("GET"))
+            .and(path("/crates/my-crate/1.0.0/dependencies"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "dependencies": [
+                    {"crate_id": "pinned-dep", "req": "=0.3.1", "kind": "normal", "optional": false, "version_id": 1},
+                    {"crate_id": "fresh-dep", "req": "^2.4", "kind": "normal", "optional": false, "version_id": 2}
+                ]
+            })))
+            .mount(&crates_server)
+            .await;
+
+        Mock::given(method("POST"))
+            .and(path("/querybatch"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "results": [
+                    {},
+                    {"vulns": [{"id": "RUSTSEC-2024-0010"}]},
+                    {"vulns": [{"id": "RUSTSEC-2022-0020"}]}
+                ]
+            })))
+            .expect(1)
+            .mount(&osv_server)
+            .await;
+
+        Mock::given(method("GET"))
+            .and(path("/vulns/RUSTSEC-2024-0010"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "id": "RUSTSEC-2024-0010",
+                "affected": [{
+                    "package": {"name": "pinned-dep", "ecosystem": "crates.io"},
+                    "ranges": [{"type": "SEMVER", "events": [{"introduced": "0"}, {"fixed": "0.3.4"}]}]
+                }]
+            })))
+            .mount(&osv_server)
+            .await;
+
+        Mock::given(method("GET"))
+            .and(path("/vulns/RUSTSEC-2022-0020"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "id": "RUSTSEC-2022-0020",
+                "affected": [{
+                    "package": {"name": "fresh-dep", "ecosystem": "crates.io"},
+                    "ranges": [{"type": "SEMVER", "events": [{"introduced": "2.0.0"}, {"fixed": "2.3.0"}]}]
+                }]
+            })))
+            .mount(&osv_server)
+            .await;
+
+        let state = test_state(&crates_server.uri(), &osv_server.uri());
+        let tool = super::build(state);
+        let result = tool.call(serde_json::json!({"name": "my-crate"})).await;
+
+        let text = result.all_text();
+        assert!(!result.is_error);
+        assert!(text.contains("### pinned-dep -- RUSTSEC-2024-0010"));
+        assert!(text.contains("Upgrade to**: 0.3.4"), "got: {text}");
+        assert!(text.contains("## Historical Advisories"));
+        assert!(text.contains("- fresh-dep (^2.4) -- RUSTSEC-2022-0020"));
+        assert!(text.contains("Vulnerabilities found**: 1"));
+        assert!(text.contains("Historical advisories**: 1"));
+    }
+
+    #[tokio::test]
+    async fn audit_transitive_reports_paths() {
+        let crates_server = MockServer::start().await;
+        let osv_server = MockServer::start().await;
+
+        // my-crate 1.0.0 -> dep-a 1.2.0 -> dep-b 1.0.0 (vulnerable)
+        for (name, version, deps) in [
+            (
+                "my-crate",
+                "1.0.0",
+                serde_json::json!([
+                    {"crate_id": "dep-a", "req": "^1", "kind": "normal", "optional": false, "version_id": 1}
+                ]),
+            ),
+            (
+                "dep-a",
+                "1.2.0",
+                serde_json::json!([
+                    {"crate_id": "dep-b", "req": "^1", "kind": "normal", "optional": false, "version_id": 2}
+                ]),
+            ),
+            ("dep-b", "1.0.0", serde_json::json!([])),
+        ] {
+            Mock::given(method("GET"))
+                .and(path(format!("/crates/{name}")))
+                .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                    "crate": {
+                        "name": name,
+                        "max_version": version,
+                        "downloads": 100,
+                        "created_at": "2026-01-01T00:00:00.000000Z",
+                        "updated_at": "2026-01-01T00:00:00.000000Z"
+                    },
+                    "versions": [{"num": version, "yanked": false, "created_at": "2026-01-01T00:00:00.000000Z", "downloads": 100}]
+                })))
+                .mount(&crates_server)
+                .await;
+
+            Mock::given(method("GET"))
+                .and(path(format!("/crates/{name}/{version}/dependencies")))
+                .respond_with(
+                    ResponseTemplate::new(200)
+                        .set_body_json(serde_json::json!({ "dependencies": deps })),
+                )
+                .mount(&crates_server)
+                .await;
+        }
+
+        // Queries go out root first, then the remaining crates by name
+        Mock::given(method("POST"))
+            .and(path("/querybatch"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "results": [{}, {}, {"vulns": [{"id": "RUSTSEC-2025-0100"}]}]
+            })))
+            .expect(1)
+            .mount(&osv_server)
+            .await;
+
+        Mock::given(method("GET"))
+            .and(path("/vulns/RUSTSEC-2025-0100"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "id": "RUSTSEC-2025-0100",
+                "affected": [{
+                    "package": {"name": "dep-b", "ecosystem": "crates.io"},
+                    "ranges": [{"type": "SEMVER", "events": [{"introduced": "0"}, {"fixed": "1.0.1"}]}]
+                }]
+            })))
+            .mount(&osv_server)
+            .await;
+
+        let state = test_state(&crates_server.uri(), &osv_server.uri());
+        let tool = super::build(state);
+        let result = tool
+            .call(serde_json::json!({"name": "my-crate", "transitive": true}))
+            .await;
+
+        let text = result.all_text();
+        assert!(!result.is_error, "got: {text}");
+        assert!(text.contains("### dep-b -- RUSTSEC-2025-0100"));
+        assert!(text.contains("Upgrade to**: 1.0.1"));
+        assert!(text.contains("Pulled in by**: my-crate v1.0.0 -> dep-a v1.2.0 -> dep-b v1.0.0"));
+        assert!(text.contains("Scope**: transitive"));
+        assert!(text.contains("Dependencies checked**: 2"));
+    }
+
     #[tokio::test]
     async fn audit_include_dev_deps() {
         let crates_server = MockServer::start().await;
@@ -326,11 +720,16 @@ mod tests {
             .mount(&crates_server)
             .await;
 
+        // One empty result per query, however many deps are included
         Mock::given(method("POST"))
-            .and(path("/query"))
-            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
-                "vulns": []
-            })))
+            .and(path("/querybatch"))
+            .respond_with(|req: &wiremock::Request| {
+                let body: serde_json::Value = serde_json::from_slice(&req.body).unwrap();
+                let queries = body["queries"].as_array().unwrap().len();
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                    "results": vec![serde_json::json!({}); queries]
+                }))
+            })
             .mount(&osv_server)
             .await;
 
@@ -390,10 +789,11 @@ mod tests {
             .mount(&crates_server)
             .await;
 
+        // No dependencies, so only the crate itself is looked up
         Mock::given(method("POST"))
-            .and(path("/query"))
+            .and(path("/querybatch"))
             .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
-                "vulns": []
+                "results": [{}]
             })))
             .mount(&osv_server)
             .await;
@@ -443,7 +843,7 @@ mod tests {
 
         // OSV returns a server error -- should surface as a tool error
         Mock::given(method("POST"))
-            .and(path("/query"))
+            .and(path("/querybatch"))
             .respond_with(ResponseTemplate::new(500))
             .mount(&osv_server)
             .await;
@@ -0,0 +1,220 @@
+//! Cargo.lock security audit tool via OSV.dev
+
+use std::sync::Arc;
+
+use schemars::JsonSchema;
+use serde::Deserialize;
+use tower_mcp::{
+    ResultExt, Tool, ToolBuilder,
+    extract::{Json, State},
+};
+
+use crate::client::lockfile::Lockfile;
+use crate::client::osv::LockfileAudit;
+use crate::state::AppState;
+use crate::tools::audit::push_vuln_details;
+use crate::tools::output::{schema, structured};
+
+/// Input for auditing a lockfile
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct AuditLockfileInput {
+    /// Full text of a Cargo.lock file
+    lockfile: String,
+}
+
+fn format_audit(audit: &LockfileAudit) -> String {
+    let mut output = String::from("# Security Audit: Cargo.lock\n\n");
+
+    if audit.affected.is_empty() {
+        output.push_str("No known vulnerabilities affect the locked versions.\n\n");
+    } else {
+        output.push_str("## Vulnerabilities Found\n\n");
+        for entry in &audit.affected {
+            for vuln in &entry.vulns {
+                output.push_str(&format!(
+                    "### {} v{} -- {}\n\n",
+                    entry.package.name, entry.package.version, vuln.id
+                ));
+                push_vuln_details(&mut output, vuln);
+                output.push('\n');
+            }
+        }
+    }
+
+    let vuln_count: usize = audit.affected.iter().map(|e| e.vulns.len()).sum();
+
+    output.push_str("## Summary\n\n");
+    output.push_str(&format!(
+        "- **Packages checked**: {}\n",
+        audit.packages_checked
+    ));
+    if audit.packages_skipped > 0 {
+        output.push_str(&format!(
+            "- **Packages skipped** (path, git, or other registry): {}\n",
+            audit.packages_skipped
+        ));
+    }
+    output.push_str(&format!("- **Vulnerabilities found**: {}\n", vuln_count));
+    output.push_str(&format!(
+        "- **Affected packages**: {}\n",
+        audit.affected.len()
+    ));
+
+    output
+}
+
+pub fn build(state: Arc<AppState>) -> Tool {
+    ToolBuilder::new("audit_lockfile")
+        .title("Audit Cargo.lock")
+        .description(
+            "Audit a resolved dependency graph: pass the text of a Cargo.lock and every \
+             crates.io package is checked at its exact locked version against the OSV.dev \
+             vulnerability database (RustSec + GHSA + NVD). Only advisories whose affected \
+             ranges include the locked version are reported.",
+        )
+        .read_only_safe()
+        .output_schema(schema::<LockfileAudit>())
+        .icon("https://crates.io/assets/cargo.png")
+        .extractor_handler(
+            state,
+            |State(state): State<Arc<AppState>>, Json(input): Json<AuditLockfileInput>| async move {
+                let lockfile =
+                    Lockfile::parse(&input.lockfile).tool_context("Cargo.lock parse error")?;
+
+                let audit = state
+                    .osv_client
+                    .audit_lockfile(&lockfile)
+                    .await
+                    .tool_context("OSV.dev API error")?;
+
+                structured(format_audit(&audit), &audit)
+            },
+        )
+        .build()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::time::Duration;
+    use wiremock::matchers::{body_partial_json, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use crate::client::CratesIoClient;
+    use crate::client::docsrs::DocsRsClient;
+    use crate::client::osv::OsvClient;
+    use crate::docs::cache::DocsCache;
+    use crate::state::AppState;
+
+    fn test_state(osv_url: &str) -> Arc<AppState> {
+        Arc::new(AppState {
+            client: CratesIoClient::with_base_url(
+                "test",
+                Duration::from_millis(0),
+                Duration::from_secs(30),
+                "http://localhost:1",
+            )
+            .unwrap(),
+            docsrs_client: DocsRsClient::with_base_url(
+                "test",
+                Duration::from_secs(30),
+                "http://localhost:1",
+            )
+            .unwrap(),
+            osv_client: OsvClient::with_base_url("test", Duration::from_secs(30), osv_url).unwrap(),
+            docs_cache: DocsCache::new(10, Duration::from_secs(3600)),
+            index_client: None,
+            registries: Default::default(),
+            http_backend: None,
+        })
+    }
+
+    const LOCKFILE: &str = r#"
+version = 4
+
+[[package]]
+name = "my-app"
+version = "0.1.0"
+dependencies = ["smallvec", "time"]
+
+[[package]]
+name = "smallvec"
+version = "1.13.2"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+
+[[package]]
+name = "time"
+version = "0.1.45"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+"#;
+
+    #[tokio::test]
+    async fn audit_lockfile_reports_locked_version() {
+        let osv_server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/querybatch"))
+            .and(body_partial_json(serde_json::json!({
+                "queries": [
+                    { "package": { "name": "smallvec" }, "version": "1.13.2" },
+                    { "package": { "name": "time" }, "version": "0.1.45" }
+                ]
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "results": [{}, { "vulns": [{ "id": "RUSTSEC-2020-0071" }] }]
+            })))
+            .mount(&osv_server)
+            .await;
+
+        Mock::given(method("GET"))
+            .and(path("/vulns/RUSTSEC-2020-0071"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "id": "RUSTSEC-2020-0071",
+                "summary": "Potential segfault in the time crate"
+            })))
+            .mount(&osv_server)
+            .await;
+
+        let tool = super::build(test_state(&osv_server.uri()));
+        let result = tool.call(serde_json::json!({"lockfile": LOCKFILE})).await;
+
+        let text = result.all_text();
+        assert!(!result.is_error);
+        assert!(
+            text.contains("time v0.1.45 -- RUSTSEC-2020-0071"),
+            "finding should name the locked version, got: {text}"
+        );
+        assert!(text.contains("Packages checked**: 2"));
+        assert!(text.contains("Packages skipped** (path, git, or other registry): 1"));
+        assert!(text.contains("Affected packages**: 1"));
+    }
+
+    #[tokio::test]
+    async fn audit_lockfile_clean() {
+        let osv_server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/querybatch"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "results": [{}, {}]
+            })))
+            .mount(&osv_server)
+            .await;
+
+        let tool = super::build(test_state(&osv_server.uri()));
+        let result = tool.call(serde_json::json!({"lockfile": LOCKFILE})).await;
+
+        assert!(!result.is_error);
+        assert!(result.all_text().contains("No known vulnerabilities"));
+    }
+
+    #[tokio::test]
+    async fn audit_lockfile_invalid_lockfile() {
+        let tool = super::build(test_state("http://localhost:1"));
+        let result = tool
+            .call(serde_json::json!({"lockfile": "[[package]\nname ="}))
+            .await;
+
+        assert!(result.is_error);
+    }
+}
@@ -21,6 +21,9 @@ pub struct AuthorsInput {
     /// Version (defaults to latest)
     #[serde(default)]
     version: Option<String>,
+    /// Registry to query, as configured with `--registry` (default: crates.io)
+    #[serde(default)]
+    registry: Option<String>,
 }
 
 pub fn build(state: Arc<AppState>) -> Tool {
@@ -36,11 +39,14 @@ pub fn build(state: Arc<AppState>) -> Tool {
         .extractor_handler(
             state,
             |State(state): State<Arc<AppState>>, Json(input): Json<AuthorsInput>| async move {
+                let registry = state
+                    .registry(input.registry.as_deref())
+                    .tool_context("Registry error")?;
                 // If no version specified, get the latest
                 let version = match input.version {
                     Some(v) => v,
                     None => {
-                        let crate_info = state
+                        let crate_info = registry
                             .client
                             .get_crate(&input.name)
                             .await
@@ -49,7 +55,7 @@ pub fn build(state: Arc<AppState>) -> Tool {
                     }
                 };
 
-                let authors = state
+                let authors = registry
                     .client
                     .crate_authors(&input.name, &version)
                     .await
@@ -102,6 +108,9 @@ mod tests {
             )
             .unwrap(),
             docs_cache: DocsCache::new(10, Duration::from_secs(3600)),
+            index_client: None,
+            registries: Default::default(),
+            http_backend: None,
         })
     }
 
@@ -22,6 +22,9 @@ pub struct CategoriesInput {
     /// Results per page (default: 20, max: 100)
     #[serde(default = "default_per_page")]
     per_page: u64,
+    /// Registry to query, as configured with `--registry` (default: crates.io)
+    #[serde(default)]
+    registry: Option<String>,
 }
 
 fn default_page() -> u64 {
@@ -46,7 +49,10 @@ pub fn build(state: Arc<AppState>) -> Tool {
         .extractor_handler(
             state,
             |State(state): State<Arc<AppState>>, Json(input): Json<CategoriesInput>| async move {
-                let response = state
+                let registry = state
+                    .registry(input.registry.as_deref())
+                    .tool_context("Registry error")?;
+                let response = registry
                     .client
                     .categories(Some(input.page), Some(input.per_page))
                     .await
@@ -101,6 +107,9 @@ mod tests {
             )
             .unwrap(),
             docs_cache: DocsCache::new(10, Duration::from_secs(3600)),
+            index_client: None,
+            registries: Default::default(),
+            http_backend: None,
         })
     }
 
@@ -18,6 +18,9 @@ use crate::tools::output::{schema, structured};
 pub struct CategoryInput {
     /// Category slug (e.g. "command-line-utilities", "web-programming", "cryptography")
     slug: String,
+    /// Registry to query, as configured with `--registry` (default: crates.io)
+    #[serde(default)]
+    registry: Option<String>,
 }
 
 pub fn build(state: Arc<AppState>) -> Tool {
@@ -33,7 +36,10 @@ pub fn build(state: Arc<AppState>) -> Tool {
         .extractor_handler(
             state,
             |State(state): State<Arc<AppState>>, Json(input): Json<CategoryInput>| async move {
-                let cat = state
+                let registry = state
+                    .registry(input.registry.as_deref())
+                    .tool_context("Registry error")?;
+                let cat = registry
                     .client
                     .category(&input.slug)
                     .await
@@ -91,6 +97,9 @@ mod tests {
             )
             .unwrap(),
             docs_cache: DocsCache::new(10, Duration::from_secs(3600)),
+            index_client: None,
+            registries: Default::default(),
+            http_backend: None,
         })
     }
 
@@ -22,6 +22,9 @@ pub struct ChangelogInput {
     /// for that version is returned from the changelog.
     #[serde(default)]
     version: Option<String>,
+    /// Registry to query, as configured with `--registry` (default: crates.io)
+    #[serde(default)]
+    registry: Option<String>,
 }
 
 /// Result of locating and optionally filtering a crate changelog.
@@ -61,7 +64,10 @@ pub fn build(state: Arc<AppState>) -> Tool {
         .extractor_handler(
             state,
             |State(state): State<Arc<AppState>>, Json(input): Json<ChangelogInput>| async move {
-                let result = state
+                let registry = state
+                    .registry(input.registry.as_deref())
+                    .tool_context("Registry error")?;
+                let result = registry
                     .client
                     .fetch_changelog(&input.name)
                     .await
@@ -223,6 +229,7 @@ mod tests {
         let input = ChangelogInput {
             name: "testcrate".to_string(),
             version: None,
+            registry: None,
         };
 
         let tool = build(state);
@@ -5,7 +5,7 @@ use std::{collections::BTreeMap, sync::Arc};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use tower_mcp::{
-    Tool, ToolBuilder,
+    ResultExt, Tool, ToolBuilder,
     extract::{Json, State},
 };
 
@@ -17,6 +17,9 @@ use crate::tools::output::{schema, structured};
 pub struct CompareInput {
     /// List of crate names to compare (2-5 crates)
     crates: Vec<String>,
+    /// Registry to query, as configured with `--registry` (default: crates.io)
+    #[serde(default)]
+    registry: Option<String>,
 }
 
 /// One metric in a side-by-side crate comparison.
@@ -47,6 +50,9 @@ pub fn build(state: Arc<AppState>) -> Tool {
         .extractor_handler(
             state,
             |State(state): State<Arc<AppState>>, Json(input): Json<CompareInput>| async move {
+                let registry = state
+                    .registry(input.registry.as_deref())
+                    .tool_context("Registry error")?;
                 let names: Vec<&str> = input.crates.iter().map(|s| s.trim()).collect();
 
                 if names.len() < 2 {
@@ -96,8 +102,8 @@ pub fn build(state: Arc<AppState>) -> Tool {
                 let mut description_row = vec![];
 
                 for name in &names {
-                    let info = state.client.get_crate(name).await;
-                    let rev_deps = state.client.crate_reverse_dependencies(name).await;
+                    let info = registry.client.get_crate(name).await;
+                    let rev_deps = registry.client.crate_reverse_dependencies(name).await;
 
                     match info {
                         Ok(resp) => {
@@ -115,7 +121,7 @@ pub fn build(state: Arc<AppState>) -> Tool {
 
                             // Get deps and version details from the latest version
                             let version = &c.max_version;
-                            match state.client.crate_dependencies(name, version).await {
+                            match registry.client.crate_dependencies(name, version).await {
                                 Ok(deps) => {
                                     let normal: Vec<_> = deps
                                         .iter()
@@ -126,7 +132,7 @@ pub fn build(state: Arc<AppState>) -> Tool {
                                 Err(_) => deps_row.push("-".to_string()),
                             }
 
-                            match state.client.crate_version(name, version).await {
+                            match registry.client.crate_version(name, version).await {
                                 Ok(v) => {
                                     license_row.push(v.license.unwrap_or_else(|| "-".to_string()));
                                     msrv_row
@@ -231,6 +237,9 @@ mod tests {
             )
             .unwrap(),
             docs_cache: DocsCache::new(10, Duration::from_secs(3600)),
+            index_client: None,
+            registries: Default::default(),
+            http_backend: None,
         })
     }
 
@@ -0,0 +1,585 @@
+//! Breadth-first walk of a crate's dependency graph on crates.io.
+//!
+//! Shared by the tools that need more than one level of dependencies
+//! (`get_dependency_tree`, the transitive mode of `audit_dependencies`).
+
+use std::collections::{BTreeSet, HashMap, VecDeque};
+
+use semver::{Version, VersionReq};
+
+use crate::client::index::IndexVersion;
+use crate::client::resolve::{activate_features, parse_req};
+use crate::client::types::Dependency;
+use crate::client::{CrateResponse, Error};
+use crate::state::{AppState, RegistryRef};
+
+/// Default number of unique crates (including the root) a walk resolves.
+pub(crate) const DEFAULT_MAX_CRATES: usize = 50;
+
+/// Hard ceiling on `max_crates`, whatever the caller asks for.
+pub(crate) const MAX_CRATES_LIMIT: usize = 500;
+
+/// Which edges to follow and how far.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct WalkOptions {
+    /// Levels below the root to expand.
+    pub max_depth: u32,
+    /// Stop once this many unique crates (including the root) are queued.
+    pub max_crates: usize,
+    /// Follow build dependencies as well as normal ones.
+    pub include_build: bool,
+    /// Follow the root's dev dependencies (never those of other crates).
+    pub include_dev_root: bool,
+}
+
+impl Default for WalkOptions {
+    fn default() -> Self {
+        Self {
+            max_depth: 3,
+            max_crates: DEFAULT_MAX_CRATES,
+            include_build: false,
+            include_dev_root: false,
+        }
+    }
+}
+
+/// A node in the graph: a crate name at one concrete version.
+pub(crate) type NodeKey = (String, String);
+
+/// A dependency edge and the version it resolved to.
+#[derive(Debug, Clone)]
+pub(crate) struct ResolvedDep {
+    pub dep: Dependency,
+    /// Whether the edge is in effect: always for required dependencies, and
+    /// for optional ones only once a feature of the dependent switches it on.
+    pub active: bool,
+    /// Newest non-yanked version satisfying `dep.req`, once the edge has been
+    /// resolved. `None` for edges the walk did not reach or could not look up.
+    pub version: Option<String>,
+    /// No published, non-yanked version satisfies `dep.req`.
+    pub unsatisfiable: bool,
+}
+
+impl ResolvedDep {
+    /// The node this edge points at, if it resolved.
+    pub fn target(&self) -> Option<NodeKey> {
+        self.version
+            .as_ref()
+            .map(|v| (self.dep.crate_id.clone(), v.clone()))
+    }
+
+    /// Features this edge asks for on its target, before any `dep/feature`
+    /// additions from the dependent's own features.
+    fn requested_features(&self) -> BTreeSet<String> {
+        let mut features: BTreeSet<String> = self.dep.features.iter().cloned().collect();
+        if self.dep.default_features {
+            features.insert("default".to_string());
+        }
+        features
+    }
+}
+
+/// A crate version reached by the walk.
+pub(crate) struct ResolvedCrate {
+    pub deps: Vec<ResolvedDep>,
+    /// Features requested by the root input or by dependents.
+    requested: BTreeSet<String>,
+    /// Every enabled feature after expanding `requested` against the
+    /// version's feature table.
+    pub features: BTreeSet<String>,
+}
+
+/// The crate versions reached from a root.
+pub(crate) struct DepGraph {
+    pub root: NodeKey,
+    pub nodes: HashMap<NodeKey, ResolvedCrate>,
+    /// Registry requests (JSON API and sparse index) made by the walk.
+    pub api_calls: u32,
+    /// Whether the walk stopped at `max_crates` before exhausting the graph.
+    pub truncated: bool,
+    options: WalkOptions,
+}
+
+impl DepGraph {
+    /// Whether the walk follows `dep` when it is declared by `from`.
+    pub fn follows(&self, from: &NodeKey, dep: &Dependency) -> bool {
+        match dep.kind.as_str() {
+            "normal" => true,
+            "build" => self.options.include_build,
+            "dev" => self.options.include_dev_root && *from == self.root,
+            _ => false,
+        }
+    }
+
+    /// Followed, active dependencies of a node.
+    pub fn edges<'a>(&'a self, key: &'a NodeKey) -> impl Iterator<Item = &'a ResolvedDep> {
+        self.nodes
+            .get(key)
+            .into_iter()
+            .flat_map(|c| c.deps.iter())
+            .filter(move |d| d.active && self.follows(key, &d.dep))
+    }
+
+    /// Up to `limit` dependency chains from the root to `target`, shortest
+    /// first. Each chain starts with the root and ends with `target`.
+    pub fn paths_to(&self, target: &NodeKey, limit: usize) -> Vec<Vec<NodeKey>> {
+        // Bound the search on dense graphs; shortest paths are found first.
+        const MAX_EXPANSIONS: usize = 10_000;
+
+        let mut found = Vec::new();
+        let mut queue: VecDeque<Vec<NodeKey>> = VecDeque::new();
+        queue.push_back(vec![self.root.clone()]);
+        let mut expansions = 0;
+
+        while let Some(path) = queue.pop_front() {
+            let last = path.last().expect("paths are never empty");
+            if last == target {
+                found.push(path);
+                if found.len() >= limit {
+                    break;
+                }
+                continue;
+            }
+            expansions += 1;
+            if expansions > MAX_EXPANSIONS {
+                break;
+            }
+            for next in self.edges(last).filter_map(ResolvedDep::target) {
+                if self.nodes.contains_key(&next) && !path.contains(&next) {
+                    let mut extended = path.clone();
+                    extended.push(next);
+                    queue.push_back(extended);
+                }
+            }
+        }
+
+        found
+    }
+}
+
+/// Render a chain as `root v1.0.0 -> dep v2.3.1`.
+pub(crate) fn format_path(path: &[NodeKey]) -> String {
+    path.iter()
+        .map(|(name, version)| format!("{name} v{version}"))
+        .collect::<Vec<_>>()
+        .join(" -> ")
+}
+
+/// A published release and its feature table.
+struct Published {
+    version: Version,
+    yanked: bool,
+    features: HashMap<String, Vec<String>>,
+    /// Dependencies, when the source lists them with the version (the
+    /// sparse index does; the JSON API needs a request per version).
+    deps: Option<Vec<Dependency>>,
+}
+
+impl Published {
+    fn from_index(versions: &[IndexVersion]) -> Vec<Self> {
+        versions
+            .iter()
+            .filter_map(|v| {
+                Some(Self {
+                    version: Version::parse(&v.vers).ok()?,
+                    yanked: v.yanked,
+                    features: v.all_features(),
+                    deps: Some(v.dependencies()),
+                })
+            })
+            .collect()
+    }
+
+    fn from_versions(versions: &[crate::client::Version]) -> Vec<Self> {
+        versions
+            .iter()
+            .filter_map(|v| {
+                Some(Self {
+                    version: Version::parse(&v.num).ok()?,
+                    yanked: v.yanked,
+                    features: v.features.clone(),
+                    deps: None,
+                })
+            })
+            .collect()
+    }
+}
+
+/// The newest non-yanked version in `versions` that satisfies `req`.
+fn select_version(versions: &[Published], req: &VersionReq) -> Option<Version> {
+    versions
+        .iter()
+        .filter(|p| !p.yanked && req.matches(&p.version))
+        .map(|p| &p.version)
+        .max()
+        .cloned()
+}
+
+/// Published versions of `name` in `registry`, from its sparse index when
+/// there is one and the JSON API otherwise (or if the index lookup fails).
+/// `None` if neither knows the crate.
+async fn fetch_published(
+    registry: RegistryRef<'_>,
+    name: &str,
+    api_calls: &mut u32,
+) -> Option<Vec<Published>> {
+    if let Some(index) = registry.index_client {
+        *api_calls += 1;
+        if let Ok(versions) = index.crate_versions(name).await {
+            return Some(Published::from_index(&versions));
+        }
+    }
+    *api_calls += 1;
+    let response = registry.client.get_crate(name).await.ok()?;
+    Some(Published::from_versions(&response.versions))
+}
+
+/// Dependencies of `version` if `versions` already lists them.
+fn listed_deps(versions: &[Published], version: &Version) -> Option<Vec<Dependency>> {
+    versions
+        .iter()
+        .find(|p| p.version == *version)
+        .and_then(|p| p.deps.clone())
+}
+
+/// Walk the dependency graph of `root` at `root_version` with the given
+/// root features enabled (include `"default"` for cargo's default set).
+///
+/// Every followed edge is resolved the way cargo would pick a fresh version:
+/// the newest non-yanked release satisfying the requirement, taken from the
+/// crate's version list (from the sparse index when the registry has one,
+/// which also lists every version's dependencies in the same response).
+/// Edges no release satisfies are flagged
+/// [`unsatisfiable`](ResolvedDep::unsatisfiable).
+///
+/// `root` is looked up in `registry`. A dependency is looked up in the same
+/// registry as its dependent unless its `registry` field names another
+/// index, which must be crates.io or one of the configured registries; edges
+/// into unknown registries are left unresolved.
+///
+/// Features are propagated along the way: each node's requested features are
+/// expanded against its feature table, optional dependencies are only
+/// followed once switched on, and `dep/feature` entries add features to the
+/// dependency. A node whose feature set grows after it was first visited is
+/// revisited so newly enabled optional dependencies are picked up.
+///
+/// Failing to fetch the root's dependencies is an error; crates that cannot
+/// be looked up further down are skipped.
+pub(crate) async fn walk<'a>(
+    state: &'a AppState,
+    registry: RegistryRef<'a>,
+    root: &CrateResponse,
+    root_version: &str,
+    root_features: BTreeSet<String>,
+    options: WalkOptions,
+) -> Result<DepGraph, Error> {
+    let root_name = root.crate_data.name.as_str();
+    let mut api_calls = 0;
+
+    let root_published = match registry.index_client {
+        Some(index) => {
+            api_calls += 1;
+            index.crate_versions(root_name).await.ok()
+        }
+        None => None,
+    }
+    .map(|versions| Published::from_index(&versions))
+    .unwrap_or_else(|| Published::from_versions(&root.versions));
+    let root_deps = match Version::parse(root_version)
+        .ok()
+        .and_then(|v| listed_deps(&root_published, &v))
+    {
+        Some(deps) => deps,
+        None => {
+            api_calls += 1;
+            registry
+                .client
+                .crate_dependencies(root_name, root_version)
+                .await?
+        }
+    };
+
+    let root_key: NodeKey = (root_name.to_string(), root_version.to_string());
+    let mut graph = DepGraph {
+        root: root_key.clone(),
+        nodes: HashMap::new(),
+        api_calls,
+        truncated: false,
+        options,
+    };
+    graph
+        .nodes
+        .insert(root_key.clone(), unresolved(root_deps, root_features));
+
+    // Published versions per crate; `None` if the lookup failed
+    let mut published: HashMap<String, Option<Vec<Published>>> = HashMap::new();
+    published.insert(root_name.to_string(), Some(root_published));
+
+    // Registry each crate was looked up in
+    let mut registries: HashMap<String, RegistryRef<'a>> = HashMap::new();
+    registries.insert(root_name.to_string(), registry);
+
+    // Depth at which each node was first reached
+    let mut depths: HashMap<NodeKey, u32> = HashMap::new();
+    depths.insert(root_key.clone(), 0);
+
+    // Worklist: nodes to (re)visit, with their depth
+    let mut queue: VecDeque<(NodeKey, u32)> = VecDeque::new();
+    queue.push_back((root_key, 0));
+
+    'bfs: while let Some((key, depth)) = queue.pop_front() {
+        // Expand this node's features and switch on its optional deps
+        let activation = {
+            let node = &graph.nodes[&key];
+            let optional: BTreeSet<&str> = node
+                .deps
+                .iter()
+                .filter(|d| d.dep.optional)
+                .map(|d| d.dep.key())
+                .collect();
+            let empty = HashMap::new();
+            let table = published
+                .get(&key.0)
+                .and_then(Option::as_ref)
+                .and_then(|versions| versions.iter().find(|p| p.version.to_string() == key.1))
+                .map_or(&empty, |p| &p.features);
+            activate_features(table, &optional, node.requested.iter().map(String::as_str))
+        };
+        let node = graph.nodes.get_mut(&key).expect("queued nodes exist");
+        for edge in &mut node.deps {
+            if edge.dep.optional && activation.optional_deps.contains(edge.dep.key()) {
+                edge.active = true;
+            }
+        }
+        node.features = activation.features;
+
+        if depth >= options.max_depth {
+            continue;
+        }
+
+        let followed: Vec<(usize, ResolvedDep)> = graph.nodes[&key]
+            .deps
+            .iter()
+            .enumerate()
+            .filter(|(_, d)| d.active && graph.follows(&key, &d.dep))
+            .map(|(i, d)| (i, d.clone()))
+            .collect();
+
+        for (idx, edge) in followed {
+            let dep = &edge.dep;
+            let dep_registry = match &dep.registry {
+                None => registries[&key.0],
+                Some(index_url) => match state.registry_for_index(index_url) {
+                    Some(registry) => registry,
+                    None => continue,
+                },
+            };
+            if !published.contains_key(&dep.crate_id) {
+                // A new crate can only add nodes; don't spend requests past the cap
+                if graph.nodes.len() >= options.max_crates {
+                    graph.truncated = true;
+                    break 'bfs;
+                }
+                // Unresolvable deps are skipped
+                let versions =
+                    fetch_published(dep_registry, &dep.crate_id, &mut graph.api_calls).await;
+                published.insert(dep.crate_id.clone(), versions);
+                registries.insert(dep.crate_id.clone(), dep_registry);
+            }
+            let Some(versions) = &published[&dep.crate_id] else {
+                continue;
+            };
+            let Some(req) = parse_req(&dep.req) else {
+                continue;
+            };
+
+            let slot = &mut graph.nodes.get_mut(&key).expect("node is queued").deps[idx];
+            let Some(chosen) = select_version(versions, &req) else {
+                slot.unsatisfiable = true;
+                continue;
+            };
+            let child: NodeKey = (dep.crate_id.clone(), chosen.to_string());
+            slot.version = Some(child.1.clone());
+
+            let mut requested = edge.requested_features();
+            if let Some(extra) = activation.dep_features.get(dep.key()) {
+                requested.extend(extra.iter().cloned());
+            }
+
+            if let Some(existing) = graph.nodes.get_mut(&child) {
+                // Revisit a known node only if this edge enables new features
+                if !requested.is_subset(&existing.requested) {
+                    existing.requested.extend(requested);
+                    queue.push_back((child.clone(), depths[&child]));
+                }
+                continue;
+            }
+            if graph.nodes.len() >= options.max_crates {
+                graph.truncated = true;
+                break 'bfs;
+            }
+
+            // The chosen version's own dependencies, fetched unless listed
+            let child_deps = match listed_deps(versions, &chosen) {
+                Some(deps) => deps,
+                None => {
+                    graph.api_calls += 1;
+                    registries[&child.0]
+                        .client
+                        .crate_dependencies(&child.0, &child.1)
+                        .await
+                        .unwrap_or_default()
+                }
+            };
+
+            graph
+                .nodes
+                .insert(child.clone(), unresolved(child_deps, requested));
+            depths.insert(child.clone(), depth + 1);
+            queue.push_back((child, depth + 1));
+        }
+    }
+
+    Ok(graph)
+}
+
+/// Wrap freshly fetched dependencies as not-yet-resolved edges.
+fn unresolved(deps: Vec<Dependency>, requested: BTreeSet<String>) -> ResolvedCrate {
+    ResolvedCrate {
+        deps: deps
+            .into_iter()
+            .map(|dep| ResolvedDep {
+                active: !dep.optional,
+                dep,
+                version: None,
+                unsatisfiable: false,
+            })
+            .collect(),
+        requested,
+        features: BTreeSet::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dep(name: &str, kind: &str) -> Dependency {
+        Dependency {
+            crate_id: name.to_string(),
+            req: "^1".to_string(),
+            kind: kind.to_string(),
+            optional: false,
+            version_id: 0,
+            features: Vec::new(),
+            default_features: true,
+            target: None,
+            registry: None,
+            explicit_name: None,
+        }
+    }
+
+    fn key(name: &str) -> NodeKey {
+        (name.to_string(), "1.0.0".to_string())
+    }
+
+    /// A graph where every crate is at 1.0.0 and every edge is resolved.
+    fn graph(edges: &[(&str, &[&str])]) -> DepGraph {
+        DepGraph {
+            root: key(edges[0].0),
+            nodes: edges
+                .iter()
+                .map(|(name, deps)| {
+                    let deps = deps
+                        .iter()
+                        .map(|d| ResolvedDep {
+                            dep: dep(d, "normal"),
+                            active: true,
+                            version: Some("1.0.0".to_string()),
+                            unsatisfiable: false,
+                        })
+                        .collect();
+                    let node = ResolvedCrate {
+                        deps,
+                        requested: BTreeSet::new(),
+                        features: BTreeSet::new(),
+                    };
+                    (key(name), node)
+                })
+                .collect(),
+            api_calls: 0,
+            truncated: false,
+            options: WalkOptions::default(),
+        }
+    }
+
+    fn names(paths: Vec<Vec<NodeKey>>) -> Vec<Vec<String>> {
+        paths
+            .into_iter()
+            .map(|p| p.into_iter().map(|(name, _)| name).collect())
+            .collect()
+    }
+
+    #[test]
+    fn paths_to_returns_shortest_chains_first() {
+        let g = graph(&[
+            ("root", &["a", "b"]),
+            ("a", &["c"]),
+            ("b", &["a"]),
+            ("c", &[]),
+        ]);
+        assert_eq!(
+            names(g.paths_to(&key("c"), 5)),
+            vec![vec!["root", "a", "c"], vec!["root", "b", "a", "c"]]
+        );
+        assert_eq!(g.paths_to(&key("c"), 1).len(), 1);
+    }
+
+    #[test]
+    fn paths_to_survives_cycles() {
+        let g = graph(&[("root", &["a"]), ("a", &["b"]), ("b", &["a"])]);
+        assert_eq!(
+            names(g.paths_to(&key("b"), 10)),
+            vec![vec!["root", "a", "b"]]
+        );
+        assert_eq!(
+            format_path(&g.paths_to(&key("b"), 1)[0]),
+            "root v1.0.0 -> a v1.0.0 -> b v1.0.0"
+        );
+    }
+
+    #[test]
+    fn dev_dependencies_only_followed_from_root() {
+        let mut g = graph(&[("root", &[]), ("a", &[])]);
+        g.options.include_dev_root = true;
+        assert!(g.follows(&key("root"), &dep("x", "dev")));
+        assert!(!g.follows(&key("a"), &dep("x", "dev")));
+        assert!(!g.follows(&key("a"), &dep("x", "build")));
+    }
+
+    #[test]
+    fn select_version_picks_newest_match() {
+        let published: Vec<Published> = [
+            ("1.0.0", false),
+            ("1.4.2", false),
+            ("1.5.0", true),
+            ("2.0.0", false),
+            ("1.5.1-beta.1", false),
+        ]
+        .iter()
+        .map(|(v, yanked)| Published {
+            version: Version::parse(v).unwrap(),
+            yanked: *yanked,
+            features: HashMap::new(),
+            deps: None,
+        })
+        .collect();
+        let pick = |req: &str| {
+            select_version(&published, &VersionReq::parse(req).unwrap()).map(|v| v.to_string())
+        };
+        assert_eq!(pick("^1").as_deref(), Some("1.4.2"));
+        assert_eq!(pick("~1.0").as_deref(), Some("1.0.0"));
+        assert_eq!(pick("*").as_deref(), Some("2.0.0"));
+        assert_eq!(pick("^3"), None);
+    }
+}
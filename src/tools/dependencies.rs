@@ -24,6 +24,9 @@ pub struct DependenciesInput {
     /// Include dev dependencies
     #[serde(default)]
     include_dev: bool,
+    /// Registry to query, as configured with `--registry` (default: crates.io)
+    #[serde(default)]
+    registry: Option<String>,
 }
 
 pub fn build(state: Arc<AppState>) -> Tool {
@@ -39,8 +42,11 @@ pub fn build(state: Arc<AppState>) -> Tool {
         .extractor_handler(
             state,
             |State(state): State<Arc<AppState>>, Json(input): Json<DependenciesInput>| async move {
+                let registry = state
+                    .registry(input.registry.as_deref())
+                    .tool_context("Registry error")?;
                 // Get crate info first to find version
-                let crate_response = state
+                let crate_response = registry
                     .client
                     .get_crate(&input.name)
                     .await
@@ -51,7 +57,7 @@ pub fn build(state: Arc<AppState>) -> Tool {
                     .as_deref()
                     .unwrap_or(&crate_response.crate_data.max_version);
 
-                let deps = state
+                let deps = registry
                     .client
                     .crate_dependencies(&input.name, version)
                     .await
@@ -146,6 +152,9 @@ mod tests {
             )
             .unwrap(),
             docs_cache: DocsCache::new(10, Duration::from_secs(3600)),
+            index_client: None,
+            registries: Default::default(),
+            http_backend: None,
         })
     }
 
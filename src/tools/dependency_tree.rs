@@ -1,6 +1,6 @@
 //! Recursive dependency tree tool
 
-use std::collections::{HashMap, HashSet, VecDeque};
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::sync::Arc;
 
 use schemars::JsonSchema;
@@ -10,8 +10,9 @@ use tower_mcp::{
     extract::{Json, State},
 };
 
-use crate::client::types::Dependency;
+use crate::client::osv::PackageQuery;
 use crate::state::AppState;
+use crate::tools::dep_graph::{DEFAULT_MAX_CRATES, DepGraph, NodeKey, WalkOptions, walk};
 use crate::tools::output::{schema, structured};
 
 /// Input for getting a dependency tree
@@ -24,6 +25,18 @@ pub struct DependencyTreeInput {
     version: Option<String>,
     /// Maximum depth to recurse (default: 3, max: 5)
     max_depth: Option<u32>,
+    /// Annotate each resolved crate with OSV advisories affecting its version
+    #[serde(default)]
+    vulnerabilities: bool,
+    /// Features to enable on the root crate (like `cargo tree --features`)
+    #[serde(default)]
+    features: Vec<String>,
+    /// Don't enable the root crate's default features
+    #[serde(default)]
+    no_default_features: bool,
+    /// Registry to query, as configured with `--registry` (default: crates.io)
+    #[serde(default)]
+    registry: Option<String>,
 }
 
 /// A node in the dependency tree used during BFS traversal.
@@ -32,6 +45,10 @@ struct TreeNode {
     name: String,
     version: String,
     deps: Vec<TreeChild>,
+    /// Features enabled on this version
+    features: Vec<String>,
+    /// Advisory IDs affecting this version (only when `vulnerabilities` is set)
+    vulnerabilities: Vec<String>,
 }
 
 /// A child reference in the formatted tree.
@@ -40,18 +57,18 @@ struct TreeChild {
     name: String,
     req: String,
     optional: bool,
+    /// Platform the dependency is limited to, e.g. `cfg(windows)`
+    target: Option<String>,
+    /// Newest non-yanked version matching `req`, if the edge was resolved
+    version: Option<String>,
+    /// No published, non-yanked version satisfies `req`
+    unsatisfiable: bool,
     /// None = not yet expanded (depth exceeded), Some = index into nodes vec
     node_idx: Option<usize>,
     seen: bool,
     circular: bool,
 }
 
-/// Cached info about a resolved crate.
-struct ResolvedCrate {
-    version: String,
-    deps: Vec<Dependency>,
-}
-
 /// Machine-readable dependency graph and traversal statistics.
 #[derive(Debug, Serialize, JsonSchema)]
 struct DependencyTreeOutput {
@@ -63,9 +80,22 @@ struct DependencyTreeOutput {
     tree_depth: u32,
     api_calls: u64,
     truncated: bool,
+    /// Edges (`crate vX -> dep req`) that no published, non-yanked version satisfies
+    unsatisfiable_requirements: Vec<String>,
+    /// Number of crates with advisories, when `vulnerabilities` was requested
+    vulnerable_crates: Option<u64>,
+    /// Why advisories could not be checked, if OSV.dev failed
+    advisories_unavailable: Option<String>,
 }
 
-const MAX_UNIQUE_CRATES: usize = 50;
+/// Advisory marker appended to a tree line, e.g. ` [RUSTSEC-2020-0071]`.
+fn vuln_marker(node: &TreeNode) -> String {
+    if node.vulnerabilities.is_empty() {
+        String::new()
+    } else {
+        format!(" [{}]", node.vulnerabilities.join(", "))
+    }
+}
 
 /// Format the tree output recursively.
 fn format_tree(
@@ -79,7 +109,12 @@ fn format_tree(
     let node = &nodes[node_idx];
 
     if is_root {
-        output.push_str(&format!("{} v{}\n", node.name, node.version));
+        output.push_str(&format!(
+            "{} v{}{}\n",
+            node.name,
+            node.version,
+            vuln_marker(node)
+        ));
     }
 
     for (i, child) in node.deps.iter().enumerate() {
@@ -100,11 +135,25 @@ fn format_tree(
         } else {
             ""
         };
+        let resolved = match &child.version {
+            Some(version) => format!(" -> v{}", version),
+            None if child.unsatisfiable => " (unsatisfiable)".to_string(),
+            None => String::new(),
+        };
         let opt = if child.optional { " (optional)" } else { "" };
+        let target = child
+            .target
+            .as_ref()
+            .map(|t| format!(" ({})", t))
+            .unwrap_or_default();
+        let vulns = child
+            .node_idx
+            .map(|idx| vuln_marker(&nodes[idx]))
+            .unwrap_or_default();
 
         output.push_str(&format!(
-            "{}{}{} {}{}{}\n",
-            child_prefix, connector, child.name, child.req, opt, suffix
+            "{}{}{} {}{}{}{}{}{}\n",
+            child_prefix, connector, child.name, child.req, resolved, opt, target, suffix, vulns
         ));
 
         // Recurse into children that have been expanded
@@ -122,8 +171,13 @@ pub fn build(state: Arc<AppState>) -> Tool {
         .title("Get Dependency Tree")
         .description(
             "Get the full transitive dependency tree for a crate, recursively resolving \
-             dependencies to a configurable depth. Shows the complete dependency footprint \
-             with version requirements and deduplication markers.",
+             dependencies to a configurable depth. Each requirement is resolved to the newest \
+             non-yanked version that satisfies it, as cargo would for a fresh lockfile, and \
+             requirements no published version satisfies are flagged. Root `features` (and \
+             `no_default_features`) are propagated through each crate's feature table, so only \
+             optional dependencies that are actually switched on appear. Shows the complete \
+             dependency footprint with version requirements and deduplication markers. Set `vulnerabilities` to \
+             annotate each crate with OSV.dev advisories affecting its resolved version.",
         )
         .read_only_safe()
         .output_schema(schema::<DependencyTreeOutput>())
@@ -132,10 +186,13 @@ pub fn build(state: Arc<AppState>) -> Tool {
             state,
             |State(state): State<Arc<AppState>>,
              Json(input): Json<DependencyTreeInput>| async move {
+                let registry = state
+                    .registry(input.registry.as_deref())
+                    .tool_context("Registry error")?;
                 let max_depth = input.max_depth.unwrap_or(3).min(5);
 
                 // Resolve root crate version
-                let crate_response = state
+                let crate_response = registry
                     .client
                     .get_crate(&input.name)
                     .await
@@ -147,206 +204,109 @@ pub fn build(state: Arc<AppState>) -> Tool {
                     .unwrap_or(&crate_response.crate_data.max_version)
                     .to_string();
 
-                // Cache: crate_name -> ResolvedCrate
-                let mut cache: HashMap<String, ResolvedCrate> = HashMap::new();
-
-                // Fetch root deps
-                let root_deps = state
-                    .client
-                    .crate_dependencies(&input.name, &root_version)
-                    .await
-                    .tool_context("Crates.io API error")?;
-
-                let mut api_calls: u32 = 2; // get_crate + crate_dependencies for root
+                let mut root_features: BTreeSet<String> = input.features.iter().cloned().collect();
+                if !input.no_default_features {
+                    root_features.insert("default".to_string());
+                }
 
-                cache.insert(
-                    input.name.clone(),
-                    ResolvedCrate {
-                        version: root_version.clone(),
-                        deps: root_deps,
+                let graph = walk(
+                    &state,
+                    registry,
+                    &crate_response,
+                    &root_version,
+                    root_features,
+                    WalkOptions {
+                        max_depth,
+                        ..WalkOptions::default()
                     },
-                );
-
-                // BFS queue: (crate_name, depth)
-                // We process each crate's normal deps and resolve their versions
-                let mut queue: VecDeque<(String, u32)> = VecDeque::new();
-                queue.push_back((input.name.clone(), 0));
-
-                // Track which crates we've queued to avoid re-processing
-                let mut queued: HashSet<String> = HashSet::new();
-                queued.insert(input.name.clone());
-
-                let mut truncated = false;
-
-                'bfs: while let Some((crate_name, depth)) = queue.pop_front() {
-                    if depth >= max_depth {
-                        continue;
-                    }
+                )
+                .await
+                .tool_context("Crates.io API error")?;
 
-                    let deps = {
-                        let resolved = cache.get(&crate_name).expect("crate should be cached");
-                        resolved
-                            .deps
-                            .iter()
-                            .filter(|d| d.kind == "normal")
-                            .cloned()
-                            .collect::<Vec<_>>()
-                    };
-
-                    for dep in &deps {
-                        if queued.contains(&dep.crate_id) {
-                            continue;
-                        }
-                        queued.insert(dep.crate_id.clone());
-
-                        if queued.len() > MAX_UNIQUE_CRATES {
-                            truncated = true;
-                            break 'bfs;
-                        }
+                let api_calls = graph.api_calls + 1; // plus get_crate for the root
+                let truncated = graph.truncated;
 
-                        // Resolve the dep's actual version via get_crate
-                        let dep_crate = match state.client.get_crate(&dep.crate_id).await {
-                            Ok(c) => c,
-                            Err(_) => continue, // skip unresolvable deps
-                        };
-                        api_calls += 1;
-
-                        let dep_version = dep_crate.crate_data.max_version.clone();
-
-                        // Fetch the dep's own dependencies
-                        let dep_deps: Vec<Dependency> = state
-                            .client
-                            .crate_dependencies(&dep.crate_id, &dep_version)
-                            .await
-                            .unwrap_or_default();
-                        api_calls += 1;
-
-                        cache.insert(
-                            dep.crate_id.clone(),
-                            ResolvedCrate {
-                                version: dep_version,
-                                deps: dep_deps,
-                            },
-                        );
-
-                        queue.push_back((dep.crate_id.clone(), depth + 1));
-                    }
-                }
-
-                // Build tree structure from cache
+                // Build tree structure from the graph
                 // We build nodes bottom-up via a recursive function
                 let mut nodes: Vec<TreeNode> = Vec::new();
-                let mut node_map: HashMap<String, usize> = HashMap::new();
-                let mut building: HashSet<String> = HashSet::new();
+                let mut node_map: HashMap<NodeKey, usize> = HashMap::new();
+                let mut building: HashSet<NodeKey> = HashSet::new();
 
                 fn build_node(
-                    crate_name: &str,
-                    cache: &HashMap<String, ResolvedCrate>,
+                    key: &NodeKey,
+                    graph: &DepGraph,
                     nodes: &mut Vec<TreeNode>,
-                    node_map: &mut HashMap<String, usize>,
-                    building: &mut HashSet<String>,
+                    node_map: &mut HashMap<NodeKey, usize>,
+                    building: &mut HashSet<NodeKey>,
                     depth: u32,
                     max_depth: u32,
                 ) -> usize {
-                    if let Some(&idx) = node_map.get(crate_name) {
+                    if let Some(&idx) = node_map.get(key) {
                         return idx;
                     }
 
-                    let resolved = match cache.get(crate_name) {
-                        Some(r) => r,
-                        None => {
-                            // Crate not in cache (couldn't resolve)
-                            let idx = nodes.len();
-                            nodes.push(TreeNode {
-                                name: crate_name.to_string(),
-                                version: "?".to_string(),
-                                deps: Vec::new(),
-                            });
-                            node_map.insert(crate_name.to_string(), idx);
-                            return idx;
-                        }
-                    };
-
                     // Mark as being built (circular detection)
-                    building.insert(crate_name.to_string());
-
-                    let normal_deps: Vec<Dependency> = resolved
-                        .deps
-                        .iter()
-                        .filter(|d| d.kind == "normal")
-                        .cloned()
-                        .collect();
+                    building.insert(key.clone());
 
                     let mut children = Vec::new();
 
-                    for dep in &normal_deps {
-                        if building.contains(&dep.crate_id) {
+                    for edge in graph.edges(key) {
+                        let mut child = TreeChild {
+                            name: edge.dep.crate_id.clone(),
+                            req: edge.dep.req.clone(),
+                            optional: edge.dep.optional,
+                            target: edge.dep.target.clone(),
+                            version: edge.version.clone(),
+                            unsatisfiable: edge.unsatisfiable,
+                            node_idx: None,
+                            seen: false,
+                            circular: false,
+                        };
+                        match edge.target() {
                             // Circular dependency
-                            children.push(TreeChild {
-                                name: dep.crate_id.clone(),
-                                req: dep.req.clone(),
-                                optional: dep.optional,
-                                node_idx: None,
-                                seen: false,
-                                circular: true,
-                            });
-                        } else if node_map.contains_key(&dep.crate_id) {
+                            Some(target) if building.contains(&target) => child.circular = true,
                             // Already seen at a different point in the tree
-                            children.push(TreeChild {
-                                name: dep.crate_id.clone(),
-                                req: dep.req.clone(),
-                                optional: dep.optional,
-                                node_idx: Some(node_map[&dep.crate_id]),
-                                seen: true,
-                                circular: false,
-                            });
-                        } else if depth + 1 > max_depth || !cache.contains_key(&dep.crate_id) {
-                            // Depth exceeded or not resolved
-                            children.push(TreeChild {
-                                name: dep.crate_id.clone(),
-                                req: dep.req.clone(),
-                                optional: dep.optional,
-                                node_idx: None,
-                                seen: false,
-                                circular: false,
-                            });
-                        } else {
+                            Some(target) if node_map.contains_key(&target) => {
+                                child.node_idx = Some(node_map[&target]);
+                                child.seen = true;
+                            }
                             // Recurse
-                            let child_idx = build_node(
-                                &dep.crate_id,
-                                cache,
-                                nodes,
-                                node_map,
-                                building,
-                                depth + 1,
-                                max_depth,
-                            );
-                            children.push(TreeChild {
-                                name: dep.crate_id.clone(),
-                                req: dep.req.clone(),
-                                optional: dep.optional,
-                                node_idx: Some(child_idx),
-                                seen: false,
-                                circular: false,
-                            });
+                            Some(target)
+                                if depth < max_depth && graph.nodes.contains_key(&target) =>
+                            {
+                                child.node_idx = Some(build_node(
+                                    &target,
+                                    graph,
+                                    nodes,
+                                    node_map,
+                                    building,
+                                    depth + 1,
+                                    max_depth,
+                                ));
+                            }
+                            // Depth exceeded or not resolved
+                            _ => {}
                         }
+                        children.push(child);
                     }
 
-                    building.remove(crate_name);
+                    building.remove(key);
 
                     let idx = nodes.len();
                     nodes.push(TreeNode {
-                        name: crate_name.to_string(),
-                        version: resolved.version.clone(),
+                        name: key.0.clone(),
+                        version: key.1.clone(),
                         deps: children,
+                        features: graph.nodes[key].features.iter().cloned().collect(),
+                        vulnerabilities: Vec::new(),
                     });
-                    node_map.insert(crate_name.to_string(), idx);
+                    node_map.insert(key.clone(), idx);
                     idx
                 }
 
                 let root_idx = build_node(
-                    &input.name,
-                    &cache,
+                    &graph.root,
+                    &graph,
                     &mut nodes,
                     &mut node_map,
                     &mut building,
@@ -354,6 +314,36 @@ pub fn build(state: Arc<AppState>) -> Tool {
                     max_depth,
                 );
 
+                // Optionally check every resolved crate at its version, in one
+                // batch. If OSV.dev is down the tree is still returned.
+                let mut advisories_unavailable = None;
+                let vulnerable_crates = if input.vulnerabilities {
+                    let queries: Vec<PackageQuery<'_>> = nodes
+                        .iter()
+                        .map(|n| PackageQuery::at(&n.name, &n.version))
+                        .collect();
+                    match state.osv_client.query_batch_ids(&queries).await {
+                        Ok(ids) => {
+                            for (node, ids) in nodes.iter_mut().zip(ids) {
+                                node.vulnerabilities = ids;
+                            }
+                            Some(
+                                nodes
+                                    .iter()
+                                    .filter(|n| !n.vulnerabilities.is_empty())
+                                    .count() as u64,
+                            )
+                        }
+                        Err(e) => {
+                            tracing::warn!(error = %e, "dependency tree without advisories");
+                            advisories_unavailable = Some(e.to_string());
+                            None
+                        }
+                    }
+                } else {
+                    None
+                };
+
                 // Format tree output
                 let mut output =
                     format!("# Dependency Tree: {} v{}\n\n", input.name, root_version);
@@ -361,11 +351,20 @@ pub fn build(state: Arc<AppState>) -> Tool {
                 format_tree(&nodes, root_idx, "", true, true, &mut output);
 
                 // Count stats
-                let direct_deps = cache
-                    .get(&input.name)
-                    .map(|r| r.deps.iter().filter(|d| d.kind == "normal").count())
-                    .unwrap_or(0);
-                let unique_crates = cache.len() - 1; // exclude root
+                let direct_deps = graph.edges(&graph.root).count();
+                let unique_crates = graph.nodes.len() - 1; // exclude root
+                let unsatisfiable: Vec<String> = graph
+                    .nodes
+                    .iter()
+                    .flat_map(|((name, version), node)| {
+                        node.deps
+                            .iter()
+                            .filter(|d| d.unsatisfiable)
+                            .map(move |d| {
+                                format!("{} v{} -> {} {}", name, version, d.dep.crate_id, d.dep.req)
+                            })
+                    })
+                    .collect();
 
                 // Calculate max depth reached
                 fn calc_depth(
@@ -402,11 +401,38 @@ pub fn build(state: Arc<AppState>) -> Tool {
                      - **API calls made**: {}\n",
                     direct_deps, unique_crates, tree_depth, api_calls
                 ));
+                let root_features = &nodes[root_idx].features;
+                output.push_str(&format!(
+                    "- **Enabled features**: {}\n",
+                    if root_features.is_empty() {
+                        "(none)".to_string()
+                    } else {
+                        root_features.join(", ")
+                    }
+                ));
+                if !unsatisfiable.is_empty() {
+                    output.push_str(&format!(
+                        "- **Unsatisfiable requirements**: {}\n",
+                        unsatisfiable.len()
+                    ));
+                    for edge in &unsatisfiable {
+                        output.push_str(&format!("  - {}\n", edge));
+                    }
+                }
+                if let Some(count) = vulnerable_crates {
+                    output.push_str(&format!("- **Crates with advisories**: {}\n", count));
+                }
+                if let Some(reason) = &advisories_unavailable {
+                    output.push_str(&format!(
+                        "- **Crates with advisories**: Unavailable ({})\n",
+                        reason
+                    ));
+                }
 
                 if truncated {
                     output.push_str(&format!(
                         "\n> Note: tree truncated at {} unique crates (graph is larger).\n",
-                        MAX_UNIQUE_CRATES
+                        DEFAULT_MAX_CRATES
                     ));
                 }
 
@@ -419,6 +445,9 @@ pub fn build(state: Arc<AppState>) -> Tool {
                     tree_depth,
                     api_calls: api_calls as u64,
                     truncated,
+                    unsatisfiable_requirements: unsatisfiable,
+                    vulnerable_crates,
+                    advisories_unavailable,
                 };
                 structured(output, &result)
             },
@@ -435,6 +464,7 @@ mod tests {
 
     use crate::client::CratesIoClient;
     use crate::client::docsrs::DocsRsClient;
+    use crate::client::index::SparseIndexClient;
     use crate::client::osv::OsvClient;
     use crate::docs::cache::DocsCache;
     use crate::state::AppState;
@@ -450,8 +480,12 @@ mod tests {
             .unwrap(),
             docsrs_client: DocsRsClient::with_base_url("test", Duration::from_secs(30), base_url)
                 .unwrap(),
-            osv_client: OsvClient::new("test", Duration::from_secs(30)).unwrap(),
+            osv_client: OsvClient::with_base_url("test", Duration::from_secs(30), base_url)
+                .unwrap(),
             docs_cache: DocsCache::new(10, Duration::from_secs(3600)),
+            index_client: None,
+            registries: Default::default(),
+            http_backend: None,
         })
     }
 
@@ -550,6 +584,175 @@ mod tests {
         assert!(text.contains("Total unique crates in tree"));
     }
 
+    #[tokio::test]
+    async fn dependency_tree_annotates_vulnerabilities() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/crates/my-crate"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "crate": {
+                    "name": "my-crate",
+                    "max_version": "1.0.0",
+                    "description": "Test crate",
+                    "downloads": 100,
+                    "created_at": "2026-01-01T00:00:00.000000Z",
+                    "updated_at": "2026-01-01T00:00:00.000000Z"
+                },
+                "versions": [{"num": "1.0.0", "yanked": false, "created_at": "2026-01-01T00:00:00.000000Z", "downloads": 100}]
+            })))
+            .mount(&server)
+            .await;
+
+        Mock::given(method("GET"))
+            .and(path("/crates/my-crate/1.0.0/dependencies"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "dependencies": [
+                    {"crate_id": "dep-a", "req": "^0.1", "kind": "normal", "optional": false, "version_id": 1}
+                ]
+            })))
+            .mount(&server)
+            .await;
+
+        Mock::given(method("GET"))
+            .and(path("/crates/dep-a"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "crate": {
+                    "name": "dep-a",
+                    "max_version": "0.1.45",
+                    "description": "Dep A",
+                    "downloads": 50,
+                    "created_at": "2026-01-01T00:00:00.000000Z",
+                    "updated_at": "2026-01-01T00:00:00.000000Z"
+                },
+                "versions": [{"num": "0.1.45", "yanked": false, "created_at": "2026-01-01T00:00:00.000000Z", "downloads": 50}]
+            })))
+            .mount(&server)
+            .await;
+
+        Mock::given(method("GET"))
+            .and(path("/crates/dep-a/0.1.45/dependencies"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "dependencies": []
+            })))
+            .mount(&server)
+            .await;
+
+        // dep-a is built first (post-order), then the root
+        Mock::given(method("POST"))
+            .and(path("/querybatch"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "results": [{"vulns": [{"id": "RUSTSEC-2020-0071"}]}, {}]
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let state = test_state(&server.uri());
+        let tool = super::build(state);
+        let result = tool
+            .call(serde_json::json!({"name": "my-crate", "vulnerabilities": true}))
+            .await;
+
+        let text = result.all_text();
+        assert!(!result.is_error);
+        assert!(
+            text.contains("dep-a ^0.1 -> v0.1.45 [RUSTSEC-2020-0071]"),
+            "dep line should carry the advisory, got: {text}"
+        );
+        assert!(text.contains("Crates with advisories**: 1"));
+    }
+
+    #[tokio::test]
+    async fn dependency_tree_resolves_requirements() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/crates/my-crate"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "crate": {
+                    "name": "my-crate",
+                    "max_version": "1.0.0",
+                    "downloads": 100,
+                    "created_at": "2026-01-01T00:00:00.000000Z",
+                    "updated_at": "2026-01-01T00:00:00.000000Z"
+                },
+                "versions": [{"num": "1.0.0", "yanked": false, "created_at": "2026-01-01T00:00:00.000000Z", "downloads": 100}]
+            })))
+            .mount(&server)
+            .await;
+
+        Mock::given(method("GET"))
+            .and(path("/crates/my-crate/1.0.0/dependencies"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "dependencies": [
+                    {"crate_id": "dep-a", "req": "^1.1", "kind": "normal", "optional": false, "version_id": 1},
+                    {"crate_id": "dep-b", "req": "^5", "kind": "normal", "optional": false, "version_id": 2}
+                ]
+            })))
+            .mount(&server)
+            .await;
+
+        // max_version is 2.0.0 and 1.4.0 is yanked, so ^1.1 resolves to 1.3.0
+        Mock::given(method("GET"))
+            .and(path("/crates/dep-a"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "crate": {
+                    "name": "dep-a",
+                    "max_version": "2.0.0",
+                    "downloads": 50,
+                    "created_at": "2026-01-01T00:00:00.000000Z",
+                    "updated_at": "2026-01-01T00:00:00.000000Z"
+                },
+                "versions": [
+                    {"num": "2.0.0", "yanked": false, "created_at": "2026-04-01T00:00:00.000000Z", "downloads": 10},
+                    {"num": "1.4.0", "yanked": true, "created_at": "2026-03-01T00:00:00.000000Z", "downloads": 10},
+                    {"num": "1.3.0", "yanked": false, "created_at": "2026-02-01T00:00:00.000000Z", "downloads": 10},
+                    {"num": "1.0.0", "yanked": false, "created_at": "2026-01-01T00:00:00.000000Z", "downloads": 10}
+                ]
+            })))
+            .mount(&server)
+            .await;
+
+        Mock::given(method("GET"))
+            .and(path("/crates/dep-a/1.3.0/dependencies"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "dependencies": []
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        Mock::given(method("GET"))
+            .and(path("/crates/dep-b"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "crate": {
+                    "name": "dep-b",
+                    "max_version": "4.2.0",
+                    "downloads": 50,
+                    "created_at": "2026-01-01T00:00:00.000000Z",
+                    "updated_at": "2026-01-01T00:00:00.000000Z"
+                },
+                "versions": [{"num": "4.2.0", "yanked": false, "created_at": "2026-01-01T00:00:00.000000Z", "downloads": 50}]
+            })))
+            .mount(&server)
+            .await;
+
+        let state = test_state(&server.uri());
+        let tool = super::build(state);
+        let result = tool.call(serde_json::json!({"name": "my-crate"})).await;
+
+        let text = result.all_text();
+        assert!(!result.is_error);
+        assert!(
+            text.contains("dep-a ^1.1 -> v1.3.0"),
+            "dep-a should resolve to the newest non-yanked match, got: {text}"
+        );
+        assert!(text.contains("dep-b ^5 (unsatisfiable)"));
+        assert!(text.contains("Unsatisfiable requirements**: 1"));
+        assert!(text.contains("my-crate v1.0.0 -> dep-b ^5"));
+    }
+
     #[tokio::test]
     async fn dependency_tree_with_seen_deps() {
         let server = MockServer::start().await;
@@ -894,6 +1097,296 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn dependency_tree_propagates_features() {
+        let server = MockServer::start().await;
+
+        async fn mount_crate(
+            server: &MockServer,
+            name: &str,
+            features: serde_json::Value,
+            deps: serde_json::Value,
+        ) {
+            Mock::given(method("GET"))
+                .and(path(format!("/crates/{}", name)))
+                .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                    "crate": {
+                        "name": name,
+                        "max_version": "1.0.0",
+                        "description": null,
+                        "downloads": 1,
+                        "created_at": "2026-01-01T00:00:00.000000Z",
+                        "updated_at": "2026-01-01T00:00:00.000000Z"
+                    },
+                    "versions": [{"num": "1.0.0", "yanked": false, "created_at": "2026-01-01T00:00:00.000000Z", "downloads": 1, "features": features}]
+                })))
+                .mount(server)
+                .await;
+            Mock::given(method("GET"))
+                .and(path(format!("/crates/{}/1.0.0/dependencies", name)))
+                .respond_with(
+                    ResponseTemplate::new(200)
+                        .set_body_json(serde_json::json!({ "dependencies": deps })),
+                )
+                .mount(server)
+                .await;
+        }
+
+        // `json` switches on serde_json and helper's `extra`, which in turn
+        // switches on helper's optional rayon dependency
+        mount_crate(
+            &server,
+            "my-crate",
+            serde_json::json!({
+                "default": ["std"],
+                "std": [],
+                "json": ["dep:serde_json", "helper/extra"]
+            }),
+            serde_json::json!([
+                {"crate_id": "helper", "req": "^1", "kind": "normal", "optional": false, "version_id": 1},
+                {"crate_id": "serde_json", "req": "^1", "kind": "normal", "optional": true, "version_id": 1},
+                {"crate_id": "winapi", "req": "^1", "kind": "normal", "optional": false, "version_id": 1, "target": "cfg(windows)"}
+            ]),
+        )
+        .await;
+        mount_crate(
+            &server,
+            "helper",
+            serde_json::json!({"extra": ["dep:rayon"]}),
+            serde_json::json!([
+                {"crate_id": "rayon", "req": "^1", "kind": "normal", "optional": true, "version_id": 2}
+            ]),
+        )
+        .await;
+        for leaf in ["serde_json", "rayon", "winapi"] {
+            mount_crate(&server, leaf, serde_json::json!({}), serde_json::json!([])).await;
+        }
+
+        let state = test_state(&server.uri());
+        let tool = super::build(state);
+
+        let text = tool
+            .call(serde_json::json!({"name": "my-crate"}))
+            .await
+            .all_text();
+        assert!(text.contains("helper ^1"));
+        assert!(text.contains("winapi ^1 -> v1.0.0 (cfg(windows))"));
+        assert!(!text.contains("serde_json"), "optional dep is off: {text}");
+        assert!(!text.contains("rayon"), "optional dep is off: {text}");
+        assert!(text.contains("Enabled features**: default, std"));
+
+        let text = tool
+            .call(serde_json::json!({
+                "name": "my-crate",
+                "features": ["json"],
+                "no_default_features": true
+            }))
+            .await
+            .all_text();
+        assert!(text.contains("serde_json ^1 -> v1.0.0 (optional)"));
+        assert!(text.contains("rayon ^1 -> v1.0.0 (optional)"));
+        assert!(text.contains("Enabled features**: json\n"));
+    }
+
+    #[tokio::test]
+    async fn dependency_tree_reads_sparse_index() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/crates/my-crate"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "crate": {
+                    "name": "my-crate",
+                    "max_version": "1.0.0",
+                    "downloads": 1,
+                    "created_at": "2026-01-01T00:00:00.000000Z",
+                    "updated_at": "2026-01-01T00:00:00.000000Z"
+                },
+                "versions": [{"num": "1.0.0", "yanked": false, "created_at": "2026-01-01T00:00:00.000000Z", "downloads": 1}]
+            })))
+            .mount(&server)
+            .await;
+
+        // No per-version dependency endpoints: everything comes from the index
+        Mock::given(method("GET"))
+            .and(path("/my/-c/my-crate"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                r#"{"name":"my-crate","vers":"1.0.0","deps":[{"name":"a","req":"^2","package":"dep-a"}],"cksum":"x","features":{}}"#,
+            ))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/de/p-/dep-a"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(concat!(
+                r#"{"name":"dep-a","vers":"2.0.0","deps":[],"cksum":"y","features":{}}"#,
+                "\n",
+                r#"{"name":"dep-a","vers":"2.1.0","deps":[],"cksum":"z","features":{}}"#,
+            )))
+            .mount(&server)
+            .await;
+
+        let state = Arc::new(AppState {
+            index_client: Some(
+                SparseIndexClient::with_base_url("test", Duration::from_secs(30), &server.uri())
+                    .unwrap(),
+            ),
+            ..Arc::into_inner(test_state(&server.uri())).unwrap()
+        });
+        let tool = super::build(state);
+        let text = tool
+            .call(serde_json::json!({"name": "my-crate"}))
+            .await
+            .all_text();
+
+        assert!(text.contains("dep-a ^2 -> v2.1.0"), "got: {text}");
+        assert!(text.contains("API calls made**: 3"), "got: {text}");
+    }
+
+    #[tokio::test]
+    async fn dependency_tree_activates_renamed_optional_deps() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/crates/my-crate"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "crate": {
+                    "name": "my-crate",
+                    "max_version": "1.0.0",
+                    "downloads": 1,
+                    "created_at": "2026-01-01T00:00:00.000000Z",
+                    "updated_at": "2026-01-01T00:00:00.000000Z"
+                },
+                "versions": [{"num": "1.0.0", "yanked": false, "created_at": "2026-01-01T00:00:00.000000Z", "downloads": 1}]
+            })))
+            .mount(&server)
+            .await;
+
+        // Feature tables name renamed deps by their key, not their package
+        let index = [
+            (
+                "/my/-c/my-crate",
+                r#"{"name":"my-crate","vers":"1.0.0","deps":[{"name":"serde_crate","req":"^1","optional":true,"package":"serde"},{"name":"json","req":"^1","optional":true,"package":"serde_json"}],"cksum":"x","features":{"default":["dep:serde_crate","json/parallel"]}}"#,
+            ),
+            (
+                "/se/rd/serde",
+                r#"{"name":"serde","vers":"1.0.0","deps":[],"cksum":"x","features":{}}"#,
+            ),
+            (
+                "/se/rd/serde_json",
+                r#"{"name":"serde_json","vers":"1.0.0","deps":[{"name":"rayon","req":"^1","optional":true}],"cksum":"x","features":{"parallel":["dep:rayon"]}}"#,
+            ),
+            (
+                "/ra/yo/rayon",
+                r#"{"name":"rayon","vers":"1.0.0","deps":[],"cksum":"x","features":{}}"#,
+            ),
+        ];
+        for (index_path, body) in index {
+            Mock::given(method("GET"))
+                .and(path(index_path))
+                .respond_with(ResponseTemplate::new(200).set_body_string(body))
+                .mount(&server)
+                .await;
+        }
+
+        let state = Arc::new(AppState {
+            index_client: Some(
+                SparseIndexClient::with_base_url("test", Duration::from_secs(30), &server.uri())
+                    .unwrap(),
+            ),
+            ..Arc::into_inner(test_state(&server.uri())).unwrap()
+        });
+        let tool = super::build(state);
+        let text = tool
+            .call(serde_json::json!({"name": "my-crate"}))
+            .await
+            .all_text();
+
+        assert!(
+            text.contains("serde ^1 -> v1.0.0 (optional)"),
+            "got: {text}"
+        );
+        assert!(
+            text.contains("serde_json ^1 -> v1.0.0 (optional)"),
+            "got: {text}"
+        );
+        assert!(
+            text.contains("rayon ^1 -> v1.0.0 (optional)"),
+            "got: {text}"
+        );
+    }
+
+    #[tokio::test]
+    async fn dependency_tree_follows_other_registries() {
+        let server = MockServer::start().await;
+        let private = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/crates/app"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "crate": {
+                    "name": "app",
+                    "max_version": "1.0.0",
+                    "downloads": 1,
+                    "created_at": "2026-01-01T00:00:00.000000Z",
+                    "updated_at": "2026-01-01T00:00:00.000000Z"
+                },
+                "versions": [{"num": "1.0.0", "yanked": false, "created_at": "2026-01-01T00:00:00.000000Z", "downloads": 1}]
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/3/a/app"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(format!(
+                r#"{{"name":"app","vers":"1.0.0","deps":[{{"name":"internal-lib","req":"^1","registry":"sparse+{}/"}},{{"name":"elsewhere","req":"^1","registry":"https://example.com/index"}}],"cksum":"x","features":{{}}}}"#,
+                private.uri()
+            )))
+            .mount(&server)
+            .await;
+
+        // The private registry serves its own index and API
+        Mock::given(method("GET"))
+            .and(path("/config.json"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "dl": format!("{}/dl", private.uri()),
+                "api": private.uri(),
+            })))
+            .mount(&private)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/in/te/internal-lib"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                r#"{"name":"internal-lib","vers":"1.2.0","deps":[],"cksum":"y","features":{}}"#,
+            ))
+            .mount(&private)
+            .await;
+
+        let mut state = AppState {
+            index_client: Some(
+                SparseIndexClient::with_base_url("test", Duration::from_secs(30), &server.uri())
+                    .unwrap(),
+            ),
+            ..Arc::into_inner(test_state(&server.uri())).unwrap()
+        };
+        state
+            .add_registry(
+                "company",
+                &format!("sparse+{}", private.uri()),
+                None,
+                Duration::from_millis(0),
+                Duration::from_secs(30),
+            )
+            .await
+            .unwrap();
+        let tool = super::build(Arc::new(state));
+        let text = tool
+            .call(serde_json::json!({"name": "app"}))
+            .await
+            .all_text();
+
+        assert!(text.contains("internal-lib ^1 -> v1.2.0"), "got: {text}");
+        assert!(!text.contains("elsewhere ^1 ->"), "got: {text}");
+    }
+
     #[test]
     fn input_deserializes_without_version_key() {
         let input: super::DependencyTreeInput =
@@ -907,7 +1400,7 @@ mod tests {
 
         let server = MockServer::start().await;
 
-        // Root has 51 direct deps (dep-0 through dep-50), which exceeds MAX_UNIQUE_CRATES=50
+        // Root has 51 direct deps (dep-0 through dep-50), which exceeds DEFAULT_MAX_CRATES=50
         let deps: Vec<serde_json::Value> = (0..=50)
             .map(|i| {
                 serde_json::json!({
@@ -19,6 +19,9 @@ use crate::tools::output::{schema, structured};
 pub struct DownloadsInput {
     /// Crate name
     name: String,
+    /// Registry to query, as configured with `--registry` (default: crates.io)
+    #[serde(default)]
+    registry: Option<String>,
 }
 
 pub fn build(state: Arc<AppState>) -> Tool {
@@ -34,7 +37,10 @@ pub fn build(state: Arc<AppState>) -> Tool {
         .extractor_handler(
             state,
             |State(state): State<Arc<AppState>>, Json(input): Json<DownloadsInput>| async move {
-                let response = state
+                let registry = state
+                    .registry(input.registry.as_deref())
+                    .tool_context("Registry error")?;
+                let response = registry
                     .client
                     .crate_downloads(&input.name)
                     .await
@@ -108,6 +114,9 @@ mod tests {
             )
             .unwrap(),
             docs_cache: DocsCache::new(10, Duration::from_secs(3600)),
+            index_client: None,
+            registries: Default::default(),
+            http_backend: None,
         })
     }
 
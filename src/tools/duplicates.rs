@@ -0,0 +1,440 @@
+//! Duplicate crate version detection tool
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Arc;
+
+use schemars::JsonSchema;
+use semver::Version;
+use serde::{Deserialize, Serialize};
+use tower_mcp::{
+    ResultExt, Tool, ToolBuilder,
+    extract::{Json, State},
+};
+
+use crate::client::resolve::compatibility_key;
+use crate::state::AppState;
+use crate::tools::dep_graph::{
+    DEFAULT_MAX_CRATES, DepGraph, MAX_CRATES_LIMIT, NodeKey, WalkOptions, format_path, walk,
+};
+use crate::tools::output::{schema, structured};
+
+/// Most root crates checked together.
+const MAX_ROOTS: usize = 10;
+
+/// Most chains listed per duplicated version.
+const MAX_PATHS: usize = 3;
+
+/// Input for finding duplicate versions
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct DuplicatesInput {
+    /// Root crates as `name` or `name@version` (1-10). Several roots are
+    /// checked together, like the members of a workspace.
+    crates: Vec<String>,
+    /// Maximum depth to walk from each root (default: 5, max: 10)
+    max_depth: Option<u32>,
+    /// Maximum unique crates to visit per root (default: 50, max: 500)
+    max_crates: Option<usize>,
+    /// Also follow build dependencies
+    #[serde(default)]
+    include_build: bool,
+    /// Registry to query, as configured with `--registry` (default: crates.io)
+    #[serde(default)]
+    registry: Option<String>,
+}
+
+/// One resolved version of a duplicated crate.
+#[derive(Debug, Serialize, JsonSchema)]
+struct DuplicateVersion {
+    version: String,
+    /// Chains from a root to this version (`a v1.0.0 -> b v2.1.0`)
+    paths: Vec<String>,
+}
+
+/// A crate resolved at two or more semver-incompatible versions.
+#[derive(Debug, Serialize, JsonSchema)]
+struct DuplicateCrate {
+    name: String,
+    versions: Vec<DuplicateVersion>,
+}
+
+/// Machine-readable duplicate version report.
+#[derive(Debug, Serialize, JsonSchema)]
+struct DuplicatesOutput {
+    /// Roots as resolved (`name vX`)
+    roots: Vec<String>,
+    message: Option<String>,
+    duplicates: Vec<DuplicateCrate>,
+    /// Unique crate versions reached across all roots
+    crates_checked: u64,
+    api_calls: u64,
+    /// Whether any walk stopped at `max_crates`
+    truncated: bool,
+}
+
+impl DuplicatesOutput {
+    fn message(roots: Vec<String>, message: String) -> Self {
+        Self {
+            roots,
+            message: Some(message),
+            duplicates: Vec::new(),
+            crates_checked: 0,
+            api_calls: 0,
+            truncated: false,
+        }
+    }
+}
+
+/// Group every crate reached from `graphs` by name and keep the ones
+/// resolved into more than one compatibility bucket.
+fn find_duplicates(graphs: &[DepGraph]) -> Vec<DuplicateCrate> {
+    let mut versions: BTreeMap<&str, BTreeSet<Version>> = BTreeMap::new();
+    for graph in graphs {
+        for (name, version) in graph.nodes.keys() {
+            if let Ok(parsed) = Version::parse(version) {
+                versions.entry(name).or_default().insert(parsed);
+            }
+        }
+    }
+
+    versions
+        .into_iter()
+        .filter(|(_, versions)| {
+            versions
+                .iter()
+                .map(compatibility_key)
+                .collect::<BTreeSet<_>>()
+                .len()
+                > 1
+        })
+        .map(|(name, versions)| DuplicateCrate {
+            name: name.to_string(),
+            versions: versions
+                .into_iter()
+                .map(|version| {
+                    let key: NodeKey = (name.to_string(), version.to_string());
+                    let paths = graphs
+                        .iter()
+                        .flat_map(|graph| graph.paths_to(&key, MAX_PATHS))
+                        .take(MAX_PATHS)
+                        .map(|path| format_path(&path))
+                        .collect();
+                    DuplicateVersion {
+                        version: key.1,
+                        paths,
+                    }
+                })
+                .collect(),
+        })
+        .collect()
+}
+
+fn format_duplicates(result: &DuplicatesOutput) -> String {
+    let mut output = format!("# Duplicate Versions: {}\n\n", result.roots.join(", "));
+
+    if result.duplicates.is_empty() {
+        output.push_str("No crate is resolved at more than one incompatible version.\n\n");
+    }
+    for dup in &result.duplicates {
+        output.push_str(&format!("## {}\n\n", dup.name));
+        for version in &dup.versions {
+            output.push_str(&format!("- **v{}**\n", version.version));
+            for path in &version.paths {
+                output.push_str(&format!("  - {}\n", path));
+            }
+        }
+        output.push('\n');
+    }
+
+    output.push_str(&format!(
+        "## Summary\n\n\
+         - **Crates checked**: {}\n\
+         - **Duplicated crates**: {}\n\
+         - **API calls made**: {}\n",
+        result.crates_checked,
+        result.duplicates.len(),
+        result.api_calls
+    ));
+
+    if result.truncated {
+        output.push_str(
+            "\n> Note: the walk stopped at `max_crates` for at least one root; \
+             raise it to check the whole graph.\n",
+        );
+    }
+
+    output
+}
+
+pub fn build(state: Arc<AppState>) -> Tool {
+    ToolBuilder::new("find_duplicate_versions")
+        .title("Find Duplicate Versions")
+        .description(
+            "Find crates that would be compiled more than once because the dependency graph \
+             resolves them at semver-incompatible versions (like `cargo tree -d`). Accepts one \
+             or more root crates (`name` or `name@version`) treated as one stack, resolves each \
+             requirement to the newest matching version with default features, and lists the \
+             dependency chains that pull in each copy.",
+        )
+        .read_only_safe()
+        .output_schema(schema::<DuplicatesOutput>())
+        .icon("https://crates.io/assets/cargo.png")
+        .extractor_handler(
+            state,
+            |State(state): State<Arc<AppState>>, Json(input): Json<DuplicatesInput>| async move {
+                let registry = state
+                    .registry(input.registry.as_deref())
+                    .tool_context("Registry error")?;
+                let specs: Vec<&str> = input
+                    .crates
+                    .iter()
+                    .map(|s| s.trim())
+                    .filter(|s| !s.is_empty())
+                    .collect();
+                let names: Vec<String> = specs.iter().map(|s| s.to_string()).collect();
+
+                if specs.is_empty() {
+                    let message = "Please provide at least one root crate.".to_string();
+                    return structured(message.clone(), &DuplicatesOutput::message(names, message));
+                }
+                if specs.len() > MAX_ROOTS {
+                    let message = format!("Please provide at most {} root crates.", MAX_ROOTS);
+                    return structured(message.clone(), &DuplicatesOutput::message(names, message));
+                }
+
+                let options = WalkOptions {
+                    max_depth: input.max_depth.unwrap_or(5).min(10),
+                    max_crates: input
+                        .max_crates
+                        .unwrap_or(DEFAULT_MAX_CRATES)
+                        .clamp(1, MAX_CRATES_LIMIT),
+                    include_build: input.include_build,
+                    include_dev_root: false,
+                };
+
+                let mut graphs = Vec::with_capacity(specs.len());
+                let mut roots = Vec::with_capacity(specs.len());
+                let mut api_calls = 0;
+                for spec in specs {
+                    let (name, version) = match spec.split_once('@') {
+                        Some((name, version)) => (name, Some(version)),
+                        None => (spec, None),
+                    };
+                    let crate_response = registry
+                        .client
+                        .get_crate(name)
+                        .await
+                        .tool_context("Crates.io API error")?;
+                    let version = version
+                        .unwrap_or(&crate_response.crate_data.max_version)
+                        .to_string();
+
+                    let features = BTreeSet::from(["default".to_string()]);
+                    let graph = walk(
+                        &state,
+                        registry,
+                        &crate_response,
+                        &version,
+                        features,
+                        options,
+                    )
+                    .await
+                    .tool_context("Crates.io API error")?;
+                    api_calls += graph.api_calls + 1; // plus get_crate for the root
+                    roots.push(format!("{} v{}", name, version));
+                    graphs.push(graph);
+                }
+
+                let crates_checked = graphs
+                    .iter()
+                    .flat_map(|g| g.nodes.keys())
+                    .collect::<BTreeSet<_>>()
+                    .len();
+
+                let result = DuplicatesOutput {
+                    roots,
+                    message: None,
+                    duplicates: find_duplicates(&graphs),
+                    crates_checked: crates_checked as u64,
+                    api_calls: api_calls as u64,
+                    truncated: graphs.iter().any(|g| g.truncated),
+                };
+                structured(format_duplicates(&result), &result)
+            },
+        )
+        .build()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::time::Duration;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use crate::client::CratesIoClient;
+    use crate::client::docsrs::DocsRsClient;
+    use crate::client::osv::OsvClient;
+    use crate::docs::cache::DocsCache;
+    use crate::state::AppState;
+
+    fn test_state(base_url: &str) -> Arc<AppState> {
+        Arc::new(AppState {
+            client: CratesIoClient::with_base_url(
+                "test",
+                Duration::from_millis(0),
+                Duration::from_secs(30),
+                base_url,
+            )
+            .unwrap(),
+            docsrs_client: DocsRsClient::with_base_url("test", Duration::from_secs(30), base_url)
+                .unwrap(),
+            osv_client: OsvClient::with_base_url("test", Duration::from_secs(30), base_url)
+                .unwrap(),
+            docs_cache: DocsCache::new(10, Duration::from_secs(3600)),
+            index_client: None,
+            registries: Default::default(),
+            http_backend: None,
+        })
+    }
+
+    /// Mount a crate with the given versions, each depending on `deps`
+    /// (`(name, req)` pairs).
+    async fn mount_crate(
+        server: &MockServer,
+        name: &str,
+        versions: &[&str],
+        deps: &[(&str, &str)],
+    ) {
+        let version_list: Vec<serde_json::Value> = versions
+            .iter()
+            .map(|v| {
+                serde_json::json!({
+                    "num": v,
+                    "yanked": false,
+                    "created_at": "2026-01-01T00:00:00.000000Z",
+                    "downloads": 1
+                })
+            })
+            .collect();
+        Mock::given(method("GET"))
+            .and(path(format!("/crates/{}", name)))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "crate": {
+                    "name": name,
+                    "max_version": versions.last().unwrap(),
+                    "description": null,
+                    "downloads": 1,
+                    "created_at": "2026-01-01T00:00:00.000000Z",
+                    "updated_at": "2026-01-01T00:00:00.000000Z"
+                },
+                "versions": version_list
+            })))
+            .mount(server)
+            .await;
+
+        let deps: Vec<serde_json::Value> = deps
+            .iter()
+            .map(|(dep, req)| {
+                serde_json::json!({
+                    "crate_id": dep,
+                    "req": req,
+                    "kind": "normal",
+                    "optional": false,
+                    "version_id": 1
+                })
+            })
+            .collect();
+        for version in versions {
+            Mock::given(method("GET"))
+                .and(path(format!("/crates/{}/{}/dependencies", name, version)))
+                .respond_with(
+                    ResponseTemplate::new(200)
+                        .set_body_json(serde_json::json!({ "dependencies": deps })),
+                )
+                .mount(server)
+                .await;
+        }
+    }
+
+    #[tokio::test]
+    async fn reports_incompatible_versions_with_paths() {
+        let server = MockServer::start().await;
+        mount_crate(&server, "app", &["1.0.0"], &[("a", "^1"), ("b", "^1")]).await;
+        mount_crate(
+            &server,
+            "a",
+            &["1.0.0"],
+            &[("syn", "^1"), ("log", "^0.4.1")],
+        )
+        .await;
+        mount_crate(
+            &server,
+            "b",
+            &["1.0.0"],
+            &[("syn", "^2"), ("log", "=0.4.0")],
+        )
+        .await;
+        mount_crate(&server, "syn", &["1.0.109", "2.0.50"], &[]).await;
+        mount_crate(&server, "log", &["0.4.0", "0.4.20"], &[]).await;
+
+        let tool = super::build(test_state(&server.uri()));
+        let result = tool.call(serde_json::json!({"crates": ["app"]})).await;
+        let text = result.all_text();
+
+        assert!(text.contains("## syn"), "{text}");
+        assert!(text.contains("app v1.0.0 -> a v1.0.0 -> syn v1.0.109"));
+        assert!(text.contains("app v1.0.0 -> b v1.0.0 -> syn v2.0.50"));
+        // 0.4.0 and 0.4.20 are semver-compatible, so not reported
+        assert!(!text.contains("## log"));
+        assert!(text.contains("Duplicated crates**: 1"));
+    }
+
+    #[tokio::test]
+    async fn checks_several_roots_together() {
+        let server = MockServer::start().await;
+        mount_crate(
+            &server,
+            "web",
+            &["1.0.0", "2.0.0"],
+            &[("hashbrown", "^0.14")],
+        )
+        .await;
+        mount_crate(&server, "db", &["3.1.0"], &[("hashbrown", "^0.15")]).await;
+        mount_crate(&server, "hashbrown", &["0.14.5", "0.15.2"], &[]).await;
+
+        let tool = super::build(test_state(&server.uri()));
+        let result = tool
+            .call(serde_json::json!({"crates": ["web@1.0.0", "db"]}))
+            .await;
+        let text = result.all_text();
+
+        assert!(
+            text.contains("Duplicate Versions: web v1.0.0, db v3.1.0"),
+            "{text}"
+        );
+        assert!(text.contains("web v1.0.0 -> hashbrown v0.14.5"));
+        assert!(text.contains("db v3.1.0 -> hashbrown v0.15.2"));
+    }
+
+    #[tokio::test]
+    async fn no_duplicates() {
+        let server = MockServer::start().await;
+        mount_crate(&server, "app", &["1.0.0"], &[("a", "^1")]).await;
+        mount_crate(&server, "a", &["1.2.0"], &[]).await;
+
+        let tool = super::build(test_state(&server.uri()));
+        let result = tool.call(serde_json::json!({"crates": ["app"]})).await;
+        let text = result.all_text();
+
+        assert!(text.contains("No crate is resolved at more than one incompatible version"));
+        assert!(text.contains("Crates checked**: 2"));
+    }
+
+    #[tokio::test]
+    async fn rejects_empty_input() {
+        let server = MockServer::start().await;
+        let tool = super::build(test_state(&server.uri()));
+        let result = tool.call(serde_json::json!({"crates": []})).await;
+        assert!(result.all_text().contains("at least one root crate"));
+    }
+}
@@ -1,5 +1,6 @@
 //! Get feature flags for a crate version
 
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use schemars::JsonSchema;
@@ -9,6 +10,7 @@ use tower_mcp::{
     extract::{Json, State},
 };
 
+use crate::client::index::{SparseIndexClient, latest_version};
 use crate::state::AppState;
 use crate::tools::output::{FeaturesOutput, schema, structured};
 
@@ -20,6 +22,24 @@ pub struct FeaturesInput {
     /// Version string (e.g. "1.0.0"). Defaults to latest version.
     #[serde(default)]
     version: Option<String>,
+    /// Registry to query, as configured with `--registry` (default: crates.io)
+    #[serde(default)]
+    registry: Option<String>,
+}
+
+/// Look the features up in the sparse index: one request, no API rate limit.
+/// `None` if the index doesn't have the crate or version.
+async fn index_features(
+    index: &SparseIndexClient,
+    name: &str,
+    version: Option<&str>,
+) -> Option<(String, HashMap<String, Vec<String>>)> {
+    let versions = index.crate_versions(name).await.ok()?;
+    let entry = match version {
+        Some(version) => versions.iter().find(|v| v.vers == version)?,
+        None => latest_version(&versions)?,
+    };
+    Some((entry.vers.clone(), entry.all_features()))
 }
 
 pub fn build(state: Arc<AppState>) -> Tool {
@@ -36,24 +56,39 @@ pub fn build(state: Arc<AppState>) -> Tool {
         .extractor_handler(
             state,
             |State(state): State<Arc<AppState>>, Json(input): Json<FeaturesInput>| async move {
-                let version = match &input.version {
-                    Some(v) => v.clone(),
+                let registry = state
+                    .registry(input.registry.as_deref())
+                    .tool_context("Registry error")?;
+                let from_index = match &registry.index_client {
+                    Some(index) => {
+                        index_features(index, &input.name, input.version.as_deref()).await
+                    }
+                    None => None,
+                };
+
+                let (version, features) = match from_index {
+                    Some(found) => found,
                     None => {
-                        let crate_resp = state
+                        let version = match &input.version {
+                            Some(v) => v.clone(),
+                            None => {
+                                let crate_resp = registry
+                                    .client
+                                    .get_crate(&input.name)
+                                    .await
+                                    .tool_context("Crates.io API error")?;
+                                crate_resp.crate_data.max_version
+                            }
+                        };
+                        let features = registry
                             .client
-                            .get_crate(&input.name)
+                            .crate_features(&input.name, &version)
                             .await
                             .tool_context("Crates.io API error")?;
-                        crate_resp.crate_data.max_version
+                        (version, features)
                     }
                 };
 
-                let features = state
-                    .client
-                    .crate_features(&input.name, &version)
-                    .await
-                    .tool_context("Crates.io API error")?;
-
                 let mut output = format!("# {} v{} - Feature Flags\n\n", input.name, version);
 
                 if features.is_empty() {
@@ -10,6 +10,8 @@ use tower_mcp::{
     extract::{Json, State},
 };
 
+use crate::client::osv::{Exposure, PackageQuery};
+use crate::client::resolve::parse_req;
 use crate::client::{Crate, Version};
 use crate::state::{AppState, format_number};
 use crate::tools::output::{schema, structured};
@@ -22,6 +24,9 @@ pub struct HealthCheckInput {
     /// Version to check (default: latest)
     #[serde(default)]
     version: Option<String>,
+    /// Registry to query, as configured with `--registry` (default: crates.io)
+    #[serde(default)]
+    registry: Option<String>,
 }
 
 /// Registry metadata and derived signals used by the crate health report.
@@ -35,7 +40,16 @@ struct HealthCheckOutput {
     average_release_cadence_days: Option<i64>,
     yanked_versions: u64,
     reverse_dependencies: u64,
+    /// Advisories affecting the checked version
     known_vulnerabilities: u64,
+    /// Advisories that only affect other versions, older or newer
+    historical_vulnerabilities: u64,
+    /// Minimal version that fixes every advisory affecting the checked version
+    upgrade_to: Option<String>,
+    /// Normal dependencies whose version requirement admits an affected version
+    vulnerable_dependencies: Vec<String>,
+    /// Why advisories could not be checked, if OSV.dev failed
+    advisories_unavailable: Option<String>,
     maintenance_status: String,
     required_dependencies: u64,
     optional_dependencies: u64,
@@ -56,8 +70,11 @@ pub fn build(state: Arc<AppState>) -> Tool {
         .extractor_handler(
             state,
             |State(state): State<Arc<AppState>>, Json(input): Json<HealthCheckInput>| async move {
+                let registry = state
+                    .registry(input.registry.as_deref())
+                    .tool_context("Registry error")?;
                 // 1. Get crate info (basic metadata + version list)
-                let crate_response = state
+                let crate_response = registry
                     .client
                     .get_crate(&input.name)
                     .await
@@ -71,14 +88,14 @@ pub fn build(state: Arc<AppState>) -> Tool {
                     .to_string();
 
                 // 2. Get version details (license, MSRV)
-                let version_detail = state
+                let version_detail = registry
                     .client
                     .crate_version(&input.name, &version)
                     .await
                     .tool_context("Crates.io API error")?;
 
                 // 3. Get dependencies
-                let deps = state
+                let deps = registry
                     .client
                     .crate_dependencies(&input.name, &version)
                     .await
@@ -90,20 +107,62 @@ pub fn build(state: Arc<AppState>) -> Tool {
                 let build_deps: Vec<_> = deps.iter().filter(|d| d.kind == "build").collect();
 
                 // 4. Get reverse dependencies (adoption signal)
-                let rev_deps = state
+                let rev_deps = registry
                     .client
                     .crate_reverse_dependencies(&input.name)
                     .await
                     .tool_context("Crates.io API error")?;
 
-                // 5. Check vulnerabilities via OSV
-                let self_vulns = state
-                    .osv_client
-                    .query_package_any(&input.name)
-                    .await
-                    .tool_context("OSV.dev API error")?;
-
-                let vuln_count = self_vulns.vulns.as_ref().map_or(0, |v| v.len());
+                // 5. Check vulnerabilities via OSV: the crate and its normal
+                //    dependencies in a single batch. OSV only tracks crates.io.
+                let queries: Vec<PackageQuery<'_>> = std::iter::once(input.name.as_str())
+                    .chain(normal_deps.iter().map(|d| d.crate_id.as_str()))
+                    .map(PackageQuery::any)
+                    .collect();
+                //    If OSV.dev is down, report everything else and say so.
+                let (osv_results, advisories_unavailable) = if registry.is_crates_io() {
+                    match state.osv_client.query_batch(&queries).await {
+                        Ok(results) => (results, None),
+                        Err(e) => {
+                            tracing::warn!(error = %e, "health check without advisories");
+                            (Vec::new(), Some(e.to_string()))
+                        }
+                    }
+                } else {
+                    (Vec::new(), None)
+                };
+                let mut osv_results = osv_results.into_iter();
+                let all_vulns = osv_results.next().unwrap_or_default();
+
+                // Dependencies whose requirement still admits an affected version
+                let vulnerable_deps: Vec<String> = normal_deps
+                    .iter()
+                    .zip(osv_results)
+                    .filter(|(dep, vulns)| {
+                        let req = parse_req(&dep.req);
+                        vulns.iter().any(|v| match &req {
+                            Some(req) => {
+                                matches!(v.exposure(&dep.crate_id, req), Exposure::Affected { .. })
+                            }
+                            None => true,
+                        })
+                    })
+                    .map(|(dep, _)| dep.crate_id.clone())
+                    .collect();
+
+                // Only count advisories whose ranges include this version
+                let checked = semver::Version::parse(&version).ok();
+                let (affecting, historical): (Vec<_>, Vec<_>) = all_vulns
+                    .iter()
+                    .partition(|v| checked.as_ref().is_none_or(|c| v.affects(&input.name, c)));
+                let vuln_count = affecting.len();
+                let upgrade_to = checked.as_ref().and_then(|c| {
+                    affecting
+                        .iter()
+                        .filter_map(|v| v.fixed_version(&input.name, c))
+                        .max()
+                        .map(|v| v.to_string())
+                });
 
                 // -- Compute derived metrics --
 
@@ -194,13 +253,43 @@ pub fn build(state: Arc<AppState>) -> Tool {
 
                 // Security
                 output.push_str("\n## Security\n\n");
-                if vuln_count == 0 {
+                if !registry.is_crates_io() {
+                    output.push_str(
+                        "- **Known vulnerabilities**: Not checked (advisories cover crates.io only)\n",
+                    );
+                } else if let Some(reason) = &advisories_unavailable {
+                    output.push_str(&format!(
+                        "- **Known vulnerabilities**: Unavailable ({})\n",
+                        reason
+                    ));
+                } else if vuln_count == 0 {
                     output.push_str("- **Known vulnerabilities**: None\n");
                 } else {
                     output.push_str(&format!(
                         "- **Known vulnerabilities**: {} (run `audit_dependencies` for details)\n",
                         vuln_count
                     ));
+                    if let Some(target) = &upgrade_to {
+                        output.push_str(&format!("- **Upgrade to**: {}\n", target));
+                    }
+                }
+                if !historical.is_empty() {
+                    output.push_str(&format!(
+                        "- **Advisories not affecting v{}**: {}\n",
+                        version,
+                        historical.len()
+                    ));
+                }
+                if advisories_unavailable.is_some() {
+                    output.push_str("- **Vulnerable dependencies**: Unavailable\n");
+                } else if vulnerable_deps.is_empty() {
+                    output.push_str("- **Vulnerable dependencies**: None\n");
+                } else {
+                    output.push_str(&format!(
+                        "- **Vulnerable dependencies**: {} ({})\n",
+                        vulnerable_deps.len(),
+                        vulnerable_deps.join(", ")
+                    ));
                 }
 
                 // Compatibility
@@ -255,6 +344,10 @@ pub fn build(state: Arc<AppState>) -> Tool {
                     yanked_versions: yanked_count as u64,
                     reverse_dependencies: rev_deps.meta.total,
                     known_vulnerabilities: vuln_count as u64,
+                    historical_vulnerabilities: historical.len() as u64,
+                    upgrade_to,
+                    vulnerable_dependencies: vulnerable_deps,
+                    advisories_unavailable,
                     maintenance_status: freshness.to_string(),
                     required_dependencies: normal_required.len() as u64,
                     optional_dependencies: normal_optional.len() as u64,
@@ -292,6 +385,9 @@ mod tests {
                 .unwrap(),
             osv_client: OsvClient::with_base_url("test", Duration::from_secs(30), osv_url).unwrap(),
             docs_cache: DocsCache::new(10, Duration::from_secs(3600)),
+            index_client: None,
+            registries: Default::default(),
+            http_backend: None,
         })
     }
 
@@ -363,11 +459,11 @@ mod tests {
             .mount(&crates_server)
             .await;
 
-        // OSV: no vulnerabilities
+        // OSV: no vulnerabilities for the crate, serde, or tokio
         Mock::given(method("POST"))
-            .and(path("/query"))
+            .and(path("/querybatch"))
             .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
-                "vulns": []
+                "results": [{}, {}, {}]
             })))
             .mount(&osv_server)
             .await;
@@ -390,7 +486,7 @@ mod tests {
         // Maintenance
         assert!(text.contains("Last updated"));
         // Security
-        assert!(text.contains("None"));
+        assert!(text.contains("Known vulnerabilities**: None"));
         // Compatibility
         assert!(text.contains("MIT OR Apache-2.0"));
         assert!(text.contains("1.75"));
@@ -441,7 +537,9 @@ mod tests {
         Mock::given(method("GET"))
             .and(path("/crates/vuln-crate/0.1.0/dependencies"))
             .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
-                "dependencies": []
+                "dependencies": [
+                    {"crate_id": "old-dep", "req": "^0.4", "kind": "normal", "optional": false, "version_id": 1}
+                ]
             })))
             .mount(&crates_server)
             .await;
@@ -456,30 +554,156 @@ mod tests {
             .mount(&crates_server)
             .await;
 
-        // OSV: has vulnerabilities
+        // OSV: the crate has vulnerabilities, its dependency one live advisory
         Mock::given(method("POST"))
-            .and(path("/query"))
+            .and(path("/querybatch"))
             .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
-                "vulns": [
-                    {"id": "RUSTSEC-2025-0001", "summary": "Memory safety issue"},
-                    {"id": "GHSA-xxxx-yyyy", "summary": "Another issue"}
+                "results": [
+                    {"vulns": [
+                        {"id": "RUSTSEC-2025-0001"},
+                        {"id": "GHSA-xxxx-yyyy"},
+                        {"id": "RUSTSEC-2024-0099"}
+                    ]},
+                    {"vulns": [{"id": "RUSTSEC-2025-0002"}]}
                 ]
             })))
+            .expect(1)
             .mount(&osv_server)
             .await;
 
+        let records = [
+            serde_json::json!({"id": "RUSTSEC-2025-0001", "summary": "Memory safety issue"}),
+            serde_json::json!({
+                "id": "GHSA-xxxx-yyyy",
+                "summary": "Another issue",
+                "affected": [{
+                    "package": {"name": "vuln-crate", "ecosystem": "crates.io"},
+                    "ranges": [{"type": "SEMVER", "events": [{"introduced": "0.1.0"}, {"fixed": "0.1.2"}]}]
+                }]
+            }),
+            serde_json::json!({
+                "id": "RUSTSEC-2024-0099",
+                "summary": "Fixed long ago",
+                "affected": [{
+                    "package": {"name": "vuln-crate", "ecosystem": "crates.io"},
+                    "ranges": [{"type": "SEMVER", "events": [{"introduced": "0"}, {"fixed": "0.0.5"}]}]
+                }]
+            }),
+            serde_json::json!({
+                "id": "RUSTSEC-2025-0002",
+                "affected": [{
+                    "package": {"name": "old-dep", "ecosystem": "crates.io"},
+                    "ranges": [{"type": "SEMVER", "events": [{"introduced": "0"}, {"fixed": "0.5.0"}]}]
+                }]
+            }),
+        ];
+        for record in records {
+            Mock::given(method("GET"))
+                .and(path(format!("/vulns/{}", record["id"].as_str().unwrap())))
+                .respond_with(ResponseTemplate::new(200).set_body_json(record))
+                .mount(&osv_server)
+                .await;
+        }
+
         let state = test_state(&crates_server.uri(), &osv_server.uri());
         let tool = super::build(state);
         let result = tool.call(serde_json::json!({"name": "vuln-crate"})).await;
 
         let text = result.all_text();
         assert!(text.contains("Health Check: vuln-crate"));
-        assert!(text.contains("2"));
+        assert!(text.contains("Known vulnerabilities**: 2"));
         assert!(text.contains("audit_dependencies"));
+        assert!(text.contains("Upgrade to**: 0.1.2"));
+        assert!(text.contains("Advisories not affecting v0.1.0**: 1"));
+        assert!(text.contains("Vulnerable dependencies**: 1 (old-dep)"));
         // Stale crate
         assert!(text.contains("Stale") || text.contains("Aging"));
     }
 
+    #[tokio::test]
+    async fn health_check_degrades_when_osv_is_down() {
+        use crate::client::resilience::{CircuitBreaker, RetryPolicy};
+
+        let crates_server = MockServer::start().await;
+        let osv_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/crates/my-crate"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "crate": {
+                    "name": "my-crate",
+                    "max_version": "1.0.0",
+                    "downloads": 100,
+                    "created_at": "2024-01-01T00:00:00.000000Z",
+                    "updated_at": "2026-02-01T00:00:00.000000Z"
+                },
+                "versions": [
+                    {"num": "1.0.0", "yanked": false, "created_at": "2026-02-01T00:00:00.000000Z", "downloads": 100}
+                ]
+            })))
+            .mount(&crates_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/crates/my-crate/1.0.0"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "version": {"num": "1.0.0", "yanked": false, "created_at": "2026-02-01T00:00:00.000000Z", "downloads": 100}
+            })))
+            .mount(&crates_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/crates/my-crate/1.0.0/dependencies"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({"dependencies": []})),
+            )
+            .mount(&crates_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/crates/my-crate/reverse_dependencies"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "dependencies": [], "versions": [], "meta": {"total": 0}
+            })))
+            .mount(&crates_server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/querybatch"))
+            .respond_with(ResponseTemplate::new(503))
+            .expect(1)
+            .mount(&osv_server)
+            .await;
+
+        let breaker = Arc::new(CircuitBreaker::with_policy(
+            "OSV.dev",
+            1,
+            Duration::from_secs(60),
+        ));
+        let mut state = Arc::into_inner(test_state(&crates_server.uri(), &osv_server.uri()))
+            .expect("fresh state");
+        state.osv_client = state
+            .osv_client
+            .with_retry_policy(RetryPolicy {
+                max_retries: 0,
+                initial_backoff: Duration::ZERO,
+            })
+            .with_circuit_breaker(breaker.clone());
+        let tool = super::build(Arc::new(state));
+
+        let text = tool
+            .call(serde_json::json!({"name": "my-crate"}))
+            .await
+            .all_text();
+        assert!(text.contains("Health Check: my-crate v1.0.0"));
+        assert!(text.contains("Known vulnerabilities**: Unavailable (OSV API error (503)"));
+        assert!(text.contains("Vulnerable dependencies**: Unavailable"));
+        assert!(breaker.is_open());
+
+        // With the breaker open, OSV is not asked again
+        let text = tool
+            .call(serde_json::json!({"name": "my-crate"}))
+            .await
+            .all_text();
+        assert!(text.contains("Unavailable (OSV.dev is unavailable"));
+    }
+
     #[test]
     fn input_deserializes_without_version_key() {
         let input: super::HealthCheckInput =
@@ -18,6 +18,9 @@ use crate::tools::output::{schema, structured};
 pub struct InfoInput {
     /// Crate name
     name: String,
+    /// Registry to query, as configured with `--registry` (default: crates.io)
+    #[serde(default)]
+    registry: Option<String>,
 }
 
 pub fn build(state: Arc<AppState>) -> Tool {
@@ -33,7 +36,10 @@ pub fn build(state: Arc<AppState>) -> Tool {
         .extractor_handler(
             state,
             |State(state): State<Arc<AppState>>, Json(input): Json<InfoInput>| async move {
-                let response = state
+                let registry = state
+                    .registry(input.registry.as_deref())
+                    .tool_context("Registry error")?;
+                let response = registry
                     .client
                     .get_crate(&input.name)
                     .await
@@ -126,6 +132,9 @@ mod tests {
             )
             .unwrap(),
             docs_cache: DocsCache::new(10, Duration::from_secs(3600)),
+            index_client: None,
+            registries: Default::default(),
+            http_backend: None,
         })
     }
 
@@ -3,17 +3,22 @@
 //! Each tool corresponds to a crates.io API endpoint.
 
 pub mod alternatives;
+pub mod analyze_manifest;
+pub mod api_diff;
 pub mod audit;
+pub mod audit_lockfile;
 pub mod authors;
 pub mod categories;
 pub mod category;
 pub mod changelog;
 pub mod compare;
 pub mod crate_docs;
+pub(crate) mod dep_graph;
 pub mod dependencies;
 pub mod dependency_tree;
 pub mod doc_item;
 pub mod downloads;
+pub mod duplicates;
 pub mod features;
 pub mod health_check;
 pub mod info;
@@ -26,7 +31,10 @@ pub mod release_timeline;
 pub mod reverse_deps;
 pub mod search;
 pub mod search_docs;
+pub mod search_signatures;
+pub mod semver_check;
 pub mod summary;
+pub mod trait_impls;
 pub mod user;
 pub mod user_stats;
 pub mod version_detail;
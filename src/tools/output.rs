@@ -8,6 +8,7 @@ use serde_json::Value;
 use tower_mcp::{CallToolResult, Result};
 
 use crate::client::{User, UserStats};
+use crate::docs::diff::ApiChange;
 
 /// A named, optionally versioned collection returned by a registry tool.
 #[derive(Debug, Serialize, JsonSchema)]
@@ -49,6 +50,8 @@ pub struct DocSearchMatch {
     pub path: String,
     pub kind: String,
     pub summary: String,
+    /// Full-text relevance, for matches found by full-text search.
+    pub score: Option<f64>,
 }
 
 /// Search results from a crate's rustdoc index.
@@ -57,10 +60,112 @@ pub struct SearchDocsOutput {
     pub name: String,
     pub version: String,
     pub query: String,
+    pub mode: String,
     pub total: u64,
     pub matches: Vec<DocSearchMatch>,
 }
 
+/// A function or method matching a type-signature query.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct SignatureSearchMatch {
+    pub name: String,
+    pub path: String,
+    pub signature: String,
+    pub summary: String,
+    /// How loosely the signature matches the query; 0 is exact.
+    pub cost: u32,
+}
+
+/// Type-signature search results from a crate's rustdoc index.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct SearchSignaturesOutput {
+    pub name: String,
+    pub version: String,
+    pub query: String,
+    pub total: u64,
+    pub matches: Vec<SignatureSearchMatch>,
+}
+
+/// A trait impl, with the implementing type.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct TraitImplEntry {
+    pub trait_name: String,
+    pub for_type: String,
+    pub header: String,
+}
+
+/// The implementors of a trait, or the trait impls of a type.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct TraitImplsOutput {
+    pub name: String,
+    pub version: String,
+    pub item_path: String,
+    /// "trait" for implementors of a trait; the type's kind otherwise.
+    pub kind: String,
+    /// Signatures of the trait's methods without a default body.
+    pub required_methods: Vec<String>,
+    /// Signatures of the trait's methods with a default body.
+    pub provided_methods: Vec<String>,
+    pub impls: Vec<TraitImplEntry>,
+    pub blanket_impls: Vec<TraitImplEntry>,
+    /// Auto-trait impls derived by rustdoc.
+    pub synthetic_impls: Vec<TraitImplEntry>,
+}
+
+/// One difference between two versions' public APIs.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ApiChangeEntry {
+    pub path: String,
+    pub kind: String,
+    /// "added", "removed" or "changed".
+    pub change: String,
+    pub breaking: bool,
+    pub reason: String,
+    pub before: Option<String>,
+    pub after: Option<String>,
+}
+
+impl From<&ApiChange> for ApiChangeEntry {
+    fn from(change: &ApiChange) -> Self {
+        Self {
+            path: change.path.clone(),
+            kind: change.kind.to_string(),
+            change: change.change.label().to_string(),
+            breaking: change.breaking,
+            reason: change.reason.clone(),
+            before: change.before.clone(),
+            after: change.after.clone(),
+        }
+    }
+}
+
+/// Public API differences between two versions of a crate.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ApiDiffOutput {
+    pub name: String,
+    pub from_version: String,
+    pub to_version: String,
+    pub breaking: u64,
+    pub non_breaking: u64,
+    pub changes: Vec<ApiChangeEntry>,
+}
+
+/// Semver compliance of a release against the version before it.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct SemverCheckOutput {
+    pub name: String,
+    pub version: String,
+    pub previous_version: String,
+    /// "major", "minor" or "patch", by Cargo's compatibility rules.
+    pub bump: String,
+    /// Whether the release has no breaking changes its bump doesn't allow.
+    pub compliant: bool,
+    pub breaking: u64,
+    pub non_breaking: u64,
+    /// Breaking changes in a release that promised compatibility.
+    pub violations: Vec<ApiChangeEntry>,
+}
+
 /// Generate the JSON Schema advertised by a tool for its structured result.
 pub fn schema<T: JsonSchema>() -> Value {
     serde_json::to_value(schemars::schema_for!(T))
@@ -20,6 +20,9 @@ pub struct ReadmeInput {
     /// Version (defaults to latest)
     #[serde(default)]
     version: Option<String>,
+    /// Registry to query, as configured with `--registry` (default: crates.io)
+    #[serde(default)]
+    registry: Option<String>,
 }
 
 pub fn build(state: Arc<AppState>) -> Tool {
@@ -35,11 +38,14 @@ pub fn build(state: Arc<AppState>) -> Tool {
         .extractor_handler(
             state,
             |State(state): State<Arc<AppState>>, Json(input): Json<ReadmeInput>| async move {
+                let registry = state
+                    .registry(input.registry.as_deref())
+                    .tool_context("Registry error")?;
                 // If no version specified, get the latest
                 let version = match input.version {
                     Some(v) => v,
                     None => {
-                        let crate_info = state
+                        let crate_info = registry
                             .client
                             .get_crate(&input.name)
                             .await
@@ -48,7 +54,7 @@ pub fn build(state: Arc<AppState>) -> Tool {
                     }
                 };
 
-                let readme = state
+                let readme = registry
                     .client
                     .crate_readme(&input.name, &version)
                     .await
@@ -112,6 +118,9 @@ mod tests {
             )
             .unwrap(),
             docs_cache: DocsCache::new(10, Duration::from_secs(3600)),
+            index_client: None,
+            registries: Default::default(),
+            http_backend: None,
         })
     }
 
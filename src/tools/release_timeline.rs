@@ -25,6 +25,9 @@ pub struct ReleaseTimelineInput {
     /// Include yanked versions in the timeline (default true)
     #[serde(default)]
     include_yanked: Option<bool>,
+    /// Registry to query, as configured with `--registry` (default: crates.io)
+    #[serde(default)]
+    registry: Option<String>,
 }
 
 /// Versions and derived change metrics in a release timeline window.
@@ -48,7 +51,8 @@ pub fn build(state: Arc<AppState>) -> Tool {
         .description(
             "Show a version-over-version registry-metadata diff for a crate: feature flag \
              changes, MSRV bumps, license changes, yanked status, and release cadence. \
-             Uses a single crates.io API call on the happy path.",
+             Uses a single crates.io API call on the happy path (two with the sparse index \
+             enabled, when per-version features are missing).",
         )
         .read_only_safe()
         .output_schema(schema::<ReleaseTimelineOutput>())
@@ -57,11 +61,14 @@ pub fn build(state: Arc<AppState>) -> Tool {
             state,
             |State(state): State<Arc<AppState>>,
              Json(input): Json<ReleaseTimelineInput>| async move {
+                let registry = state
+                    .registry(input.registry.as_deref())
+                    .tool_context("Registry error")?;
                 let name = input.name.trim().to_owned();
                 let limit = input.versions.unwrap_or(5).clamp(2, 10);
                 let include_yanked = input.include_yanked.unwrap_or(true);
 
-                let response = state
+                let response = registry
                     .client
                     .get_crate(&name)
                     .await
@@ -96,10 +103,28 @@ pub fn build(state: Arc<AppState>) -> Tool {
                 let needs_fallback = window.iter().all(|v| v.features.is_empty());
                 let mut api_calls = 1usize;
 
-                let versions = if needs_fallback {
+                // The sparse index has every version's features in one response
+                let listed = match &registry.index_client {
+                    Some(index) if needs_fallback => index.crate_versions(&name).await.ok(),
+                    _ => None,
+                };
+
+                let versions = if let Some(listed) = listed {
+                    api_calls += 1;
+                    window
+                        .into_iter()
+                        .map(|mut v| {
+                            if let Some(entry) = listed.iter().find(|e| e.vers == v.num) {
+                                v.features = entry.all_features();
+                                v.rust_version = v.rust_version.or(entry.rust_version.clone());
+                            }
+                            v
+                        })
+                        .collect()
+                } else if needs_fallback {
                     let mut enriched = Vec::with_capacity(window.len());
                     for v in window {
-                        match state.client.crate_version(&name, &v.num).await {
+                        match registry.client.crate_version(&name, &v.num).await {
                             Ok(detail) => {
                                 api_calls += 1;
                                 enriched.push(detail);
@@ -322,6 +347,7 @@ mod tests {
 
     use crate::client::CratesIoClient;
     use crate::client::docsrs::DocsRsClient;
+    use crate::client::index::SparseIndexClient;
     use crate::client::osv::OsvClient;
     use crate::docs::cache::DocsCache;
     use crate::state::AppState;
@@ -344,6 +370,9 @@ mod tests {
             )
             .unwrap(),
             docs_cache: DocsCache::new(10, Duration::from_secs(3600)),
+            index_client: None,
+            registries: Default::default(),
+            http_backend: None,
         })
     }
 
@@ -526,6 +555,58 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn release_timeline_features_from_index() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/crates/fallback-crate"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "crate": {
+                    "name": "fallback-crate",
+                    "max_version": "1.0.0",
+                    "downloads": 100,
+                    "created_at": "2024-01-01T00:00:00.000000Z",
+                    "updated_at": "2024-06-01T00:00:00.000000Z"
+                },
+                "versions": [
+                    {"num": "1.0.0", "yanked": false, "created_at": "2024-06-01T00:00:00.000000Z", "downloads": 80, "features": {}},
+                    {"num": "0.9.0", "yanked": false, "created_at": "2024-01-01T00:00:00.000000Z", "downloads": 20, "features": {}}
+                ]
+            })))
+            .mount(&server)
+            .await;
+
+        // One index fetch replaces the per-version API calls
+        Mock::given(method("GET"))
+            .and(path("/fa/ll/fallback-crate"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(concat!(
+                r#"{"name":"fallback-crate","vers":"0.9.0","deps":[],"cksum":"a","features":{"default":[]},"rust_version":"1.60"}"#,
+                "\n",
+                r#"{"name":"fallback-crate","vers":"1.0.0","deps":[],"cksum":"b","features":{"default":[]},"features2":{"extra":["dep:x"]},"rust_version":"1.70"}"#,
+                "\n",
+            )))
+            .mount(&server)
+            .await;
+
+        let state = Arc::new(AppState {
+            index_client: Some(
+                SparseIndexClient::with_base_url("test", Duration::from_secs(30), &server.uri())
+                    .unwrap(),
+            ),
+            ..Arc::into_inner(test_state(&server.uri())).unwrap()
+        });
+        let tool = super::build(state);
+        let result = tool
+            .call(serde_json::json!({"name": "fallback-crate", "versions": 2}))
+            .await;
+        let text = result.all_text();
+
+        assert!(text.contains("API calls made: 2"), "got: {text}");
+        assert!(text.contains("+`extra`"), "got: {text}");
+        assert!(text.contains("1.60"), "MSRV from the index, got: {text}");
+    }
+
     #[tokio::test]
     async fn release_timeline_not_found() {
         let server = MockServer::start().await;
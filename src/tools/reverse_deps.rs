@@ -1,7 +1,9 @@
 //! Get reverse dependencies tool
 
+use std::pin::pin;
 use std::sync::Arc;
 
+use futures::TryStreamExt;
 use schemars::JsonSchema;
 use serde::Deserialize;
 use tower_mcp::protocol::{LogLevel, LoggingMessageParams};
@@ -12,24 +14,41 @@ use tower_mcp::{
 
 use crate::state::AppState;
 use crate::{
-    client::ReverseDependencies,
+    client::{Meta, ReverseDependencies},
     tools::output::{schema, structured},
 };
 
+/// Most dependents a single call lists.
+const MAX_LIMIT: usize = 1000;
+
 /// Input for getting reverse dependencies
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct ReverseDepsInput {
     /// Crate name
     name: String,
+    #[schemars(description = format!(
+        "Maximum number of dependent crates to list, following further pages \
+         as needed (default: 100, max: {MAX_LIMIT})"
+    ))]
+    #[serde(default = "default_limit")]
+    limit: usize,
+    /// Registry to query, as configured with `--registry` (default: crates.io)
+    #[serde(default)]
+    registry: Option<String>,
+}
+
+fn default_limit() -> usize {
+    100
 }
 
 pub fn build(state: Arc<AppState>) -> Tool {
     ToolBuilder::new("get_reverse_dependencies")
         .title("Get Reverse Dependencies")
-        .description(
+        .description(format!(
             "Get crates that depend on the specified crate (reverse dependencies). \
-             Useful for understanding a crate's ecosystem impact.",
-        )
+             Useful for understanding a crate's ecosystem impact. Follows result pages \
+             up to `limit` dependents (at most {MAX_LIMIT})."
+        ))
         .read_only_safe()
         .output_schema(schema::<ReverseDependencies>())
         .icon("https://crates.io/assets/cargo.png")
@@ -38,6 +57,9 @@ pub fn build(state: Arc<AppState>) -> Tool {
             |State(state): State<Arc<AppState>>,
              ctx: Context,
              Json(input): Json<ReverseDepsInput>| async move {
+                let registry = state
+                    .registry(input.registry.as_deref())
+                    .tool_context("Registry error")?;
                 // Log the request
                 ctx.send_log(LoggingMessageParams {
                     level: LogLevel::Info,
@@ -53,11 +75,31 @@ pub fn build(state: Arc<AppState>) -> Tool {
                 ctx.report_progress(0.1, Some(1.0), Some("Fetching reverse dependencies..."))
                     .await;
 
-                let response = state
-                    .client
-                    .crate_reverse_dependencies(&input.name)
-                    .await
-                    .tool_context("Crates.io API error")?;
+                let limit = input.limit.min(MAX_LIMIT);
+                let mut pages = pin!(registry.client.reverse_dependencies_pages(&input.name));
+                let mut response = ReverseDependencies {
+                    dependencies: Vec::new(),
+                    meta: Meta {
+                        total: 0,
+                        next_page: None,
+                    },
+                };
+                while response.dependencies.len() < limit
+                    && let Some(page) =
+                        pages.try_next().await.tool_context("Crates.io API error")?
+                {
+                    response.dependencies.extend(page.dependencies);
+                    response.meta = page.meta;
+                    let wanted = limit.min(response.meta.total as usize).max(1);
+                    let fetched = response.dependencies.len().min(wanted);
+                    ctx.report_progress(
+                        0.1 + 0.7 * fetched as f64 / wanted as f64,
+                        Some(1.0),
+                        Some("Fetching reverse dependencies..."),
+                    )
+                    .await;
+                }
+                response.dependencies.truncate(limit);
 
                 // Update progress
                 ctx.report_progress(0.8, Some(1.0), Some("Processing results..."))
@@ -103,7 +145,7 @@ pub fn build(state: Arc<AppState>) -> Tool {
 mod tests {
     use std::sync::Arc;
     use std::time::Duration;
-    use wiremock::matchers::{method, path};
+    use wiremock::matchers::{method, path, query_param, query_param_is_missing};
     use wiremock::{Mock, MockServer, ResponseTemplate};
 
     use crate::client::CratesIoClient;
@@ -130,6 +172,9 @@ mod tests {
             )
             .unwrap(),
             docs_cache: DocsCache::new(10, Duration::from_secs(3600)),
+            index_client: None,
+            registries: Default::default(),
+            http_backend: None,
         })
     }
 
@@ -183,4 +228,39 @@ mod tests {
         let result = tool.call(serde_json::json!({"name": "my-crate"})).await;
         assert!(result.all_text().contains("0 crates depend"));
     }
+
+    #[tokio::test]
+    async fn reverse_deps_follows_pages_up_to_limit() {
+        let server = MockServer::start().await;
+        let page = |name: &str| {
+            ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "dependencies": [{ "crate_id": "my-crate", "req": "^1", "version_id": 1 }],
+                "versions": [{ "id": 1, "crate": name, "num": "1.0.0" }],
+                "meta": {"total": 3}
+            }))
+        };
+        Mock::given(method("GET"))
+            .and(path("/crates/my-crate/reverse_dependencies"))
+            .and(query_param_is_missing("page"))
+            .respond_with(page("first-dep"))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/crates/my-crate/reverse_dependencies"))
+            .and(query_param("page", "2"))
+            .respond_with(page("second-dep"))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let state = test_state(&server.uri());
+        let tool = super::build(state);
+        let result = tool
+            .call(serde_json::json!({"name": "my-crate", "limit": 2}))
+            .await;
+        let text = result.all_text();
+        assert!(text.contains("3 crates depend on this crate (showing first 2)"));
+        assert!(text.contains("first-dep") && text.contains("second-dep"));
+    }
 }
@@ -17,23 +17,111 @@ use crate::tools::output::{schema, structured};
 /// Input for searching crates
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct SearchInput {
-    /// Search query (crate name or keywords)
+    /// Search query (crate name or keywords); may be empty when filtering
+    #[serde(default)]
     query: String,
-    /// Sort order: relevance, downloads, recent-downloads, recent-updates, new
+    /// Sort order: relevance, downloads, recent-downloads, recent-updates, new, alpha
     #[serde(default = "default_sort")]
     sort: String,
+    /// Only crates in this category slug (e.g. "asynchronous"), including its subcategories
+    #[serde(default)]
+    category: Option<String>,
+    /// Only crates tagged with this keyword
+    #[serde(default)]
+    keyword: Option<String>,
+    /// Only crates tagged with every one of these keywords
+    #[serde(default)]
+    all_keywords: Vec<String>,
+    /// Only crates owned by this user ID (see get_user)
+    #[serde(default)]
+    user_id: Option<u64>,
+    /// Only crates owned by this team ID
+    #[serde(default)]
+    team_id: Option<u64>,
+    /// Only these crates, by exact name
+    #[serde(default)]
+    ids: Vec<String>,
+    /// Only crates whose name starts with this letter
+    #[serde(default)]
+    letter: Option<char>,
+    /// Registry to query, as configured with `--registry` (default: crates.io)
+    #[serde(default)]
+    registry: Option<String>,
 }
 
 fn default_sort() -> String {
     "relevance".to_string()
 }
 
+/// Build the crates.io query for a search's term and filters.
+fn build_query(input: &SearchInput) -> CratesQuery {
+    let mut query = CratesQuery::builder().sort(parse_sort(&input.sort));
+    if !input.query.is_empty() {
+        query = query.search(&input.query);
+    }
+    if let Some(category) = &input.category {
+        query = query.category(category);
+    }
+    if let Some(keyword) = &input.keyword {
+        query = query.keyword(keyword);
+    }
+    if !input.all_keywords.is_empty() {
+        let keywords: Vec<&str> = input.all_keywords.iter().map(String::as_str).collect();
+        query = query.all_keywords(&keywords);
+    }
+    if let Some(user_id) = input.user_id {
+        query = query.user_id(user_id);
+    }
+    if let Some(team_id) = input.team_id {
+        query = query.team_id(team_id);
+    }
+    if !input.ids.is_empty() {
+        let ids: Vec<&str> = input.ids.iter().map(String::as_str).collect();
+        query = query.ids(&ids);
+    }
+    if let Some(letter) = input.letter {
+        query = query.letter(letter);
+    }
+    query.build()
+}
+
+/// What the search asked for, e.g. " matching 'tokio' in category `asynchronous`",
+/// or nothing for an unfiltered listing.
+fn describe(input: &SearchInput) -> String {
+    let mut parts = Vec::new();
+    if !input.query.is_empty() {
+        parts.push(format!("matching '{}'", input.query));
+    }
+    if let Some(category) = &input.category {
+        parts.push(format!("in category `{category}`"));
+    }
+    let mut keywords: Vec<&str> = input.keyword.iter().map(String::as_str).collect();
+    keywords.extend(input.all_keywords.iter().map(String::as_str));
+    if !keywords.is_empty() {
+        parts.push(format!("with keywords `{}`", keywords.join("`, `")));
+    }
+    if let Some(user_id) = input.user_id {
+        parts.push(format!("owned by user {user_id}"));
+    }
+    if let Some(team_id) = input.team_id {
+        parts.push(format!("owned by team {team_id}"));
+    }
+    if !input.ids.is_empty() {
+        parts.push(format!("named {}", input.ids.join(", ")));
+    }
+    if let Some(letter) = input.letter {
+        parts.push(format!("starting with '{letter}'"));
+    }
+    parts.iter().map(|part| format!(" {part}")).collect()
+}
+
 fn parse_sort(s: &str) -> Sort {
     match s {
         "downloads" => Sort::Downloads,
         "recent-downloads" => Sort::RecentDownloads,
         "recent-updates" => Sort::RecentUpdates,
         "new" => Sort::NewlyAdded,
+        "alpha" => Sort::Alphabetical,
         _ => Sort::Relevance,
     }
 }
@@ -43,7 +131,8 @@ pub fn build(state: Arc<AppState>) -> Tool {
         .title("Search Crates")
         .description(
             "Search for Rust crates on crates.io. Returns crate names, descriptions, \
-             download counts, and repository links.",
+             download counts, and repository links. Results can be filtered by category, \
+             keyword, owner, name or first letter, with or without a search query.",
         )
         .read_only_safe()
         .output_schema(schema::<CratesPage>())
@@ -51,13 +140,12 @@ pub fn build(state: Arc<AppState>) -> Tool {
         .extractor_handler(
             state,
             |State(state): State<Arc<AppState>>, Json(input): Json<SearchInput>| async move {
-                let sort = parse_sort(&input.sort);
-                let query = CratesQuery::builder()
-                    .search(&input.query)
-                    .sort(sort)
-                    .build();
+                let registry = state
+                    .registry(input.registry.as_deref())
+                    .tool_context("Registry error")?;
+                let query = build_query(&input);
 
-                let response = state
+                let response = registry
                     .client
                     .crates(query)
                     .await
@@ -65,9 +153,9 @@ pub fn build(state: Arc<AppState>) -> Tool {
 
                 // Format results
                 let mut output = format!(
-                    "Found {} crates matching '{}' (showing {}):\n\n",
+                    "Found {} crates{} (showing {}):\n\n",
                     response.meta.total,
-                    input.query,
+                    describe(&input),
                     response.crates.len()
                 );
 
@@ -124,6 +212,9 @@ mod tests {
             )
             .unwrap(),
             docs_cache: DocsCache::new(10, Duration::from_secs(3600)),
+            index_client: None,
+            registries: Default::default(),
+            http_backend: None,
         })
     }
 
@@ -163,4 +254,62 @@ mod tests {
             .await;
         assert!(result.all_text().contains("Found 0 crates"));
     }
+
+    #[tokio::test]
+    async fn search_offline_dump() {
+        let dump = crate::client::dump::tests::fixture_store();
+        let state = Arc::new(AppState {
+            client: CratesIoClient::with_base_url(
+                "test",
+                Duration::from_millis(0),
+                Duration::from_secs(30),
+                "http://localhost:1",
+            )
+            .unwrap()
+            .with_dump(Arc::new(dump)),
+            ..Arc::into_inner(test_state("http://localhost:1")).unwrap()
+        });
+        let tool = super::build(state);
+        let text = tool
+            .call(serde_json::json!({"query": "serde"}))
+            .await
+            .all_text();
+        assert!(text.contains("Found 2 crates"), "got: {text}");
+        assert!(text.contains("serde_json"), "got: {text}");
+    }
+
+    #[tokio::test]
+    async fn search_filters_without_query() {
+        let dump = crate::client::dump::tests::fixture_store();
+        let state = Arc::new(AppState {
+            client: CratesIoClient::with_base_url(
+                "test",
+                Duration::from_millis(0),
+                Duration::from_secs(30),
+                "http://localhost:1",
+            )
+            .unwrap()
+            .with_dump(Arc::new(dump)),
+            ..Arc::into_inner(test_state("http://localhost:1")).unwrap()
+        });
+        let tool = super::build(state);
+        let text = tool
+            .call(serde_json::json!({
+                "category": "encoding",
+                "keyword": "serialization",
+                "sort": "downloads"
+            }))
+            .await
+            .all_text();
+        assert!(
+            text.contains(
+                "Found 2 crates in category `encoding` with keywords `serialization` (showing 2)"
+            ),
+            "got: {text}"
+        );
+        assert!(
+            text.find("**serde**") < text.find("**serde_json**"),
+            "got: {text}"
+        );
+    }
 }
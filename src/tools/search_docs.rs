@@ -1,7 +1,9 @@
 //! Search for items within a crate's documentation.
 
+use std::collections::HashSet;
 use std::sync::Arc;
 
+use rustdoc_types::{Crate, Id, Item};
 use schemars::JsonSchema;
 use serde::Deserialize;
 use tower_mcp::{
@@ -21,8 +23,14 @@ pub struct SearchDocsInput {
     /// Version (default: "latest")
     #[serde(default = "default_version")]
     version: String,
-    /// Search query (matched against item names, case-insensitive)
+    /// Search query: a name fragment in `name` mode, or words to rank items by
+    /// (e.g. "set a request timeout") in `fulltext` mode
     query: String,
+    /// How to match: "name" (case-insensitive substring of item names, the
+    /// default), "fulltext" (ranked search over item names, paths and doc
+    /// comments), or "both" (name matches first, then full-text results)
+    #[serde(default = "default_mode")]
+    mode: String,
     /// Maximum number of results (default: 20)
     #[serde(default = "default_limit")]
     limit: usize,
@@ -32,71 +40,117 @@ fn default_version() -> String {
     "latest".to_string()
 }
 
+fn default_mode() -> String {
+    "name".to_string()
+}
+
 fn default_limit() -> usize {
     20
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SearchMode {
+    Name,
+    Fulltext,
+    Both,
+}
+
+fn parse_mode(s: &str) -> Option<SearchMode> {
+    match s {
+        "name" => Some(SearchMode::Name),
+        "fulltext" => Some(SearchMode::Fulltext),
+        "both" => Some(SearchMode::Both),
+        _ => None,
+    }
+}
+
+/// Local items whose name contains `query` (case-insensitive): exact matches
+/// first, then prefix matches, then the rest, alphabetically within each.
+fn name_matches<'a>(krate: &'a Crate, query: &str) -> Vec<(&'a Id, &'a Item)> {
+    let query_lower = query.to_lowercase();
+    let mut matches: Vec<_> = krate
+        .index
+        .iter()
+        .filter(|(_, item)| {
+            item.crate_id == 0
+                && item
+                    .name
+                    .as_ref()
+                    .is_some_and(|n| n.to_lowercase().contains(&query_lower))
+        })
+        .collect();
+
+    matches.sort_by_cached_key(|(_, item)| {
+        let name = item.name.as_deref().unwrap_or("").to_lowercase();
+        (name != query_lower, !name.starts_with(&query_lower), name)
+    });
+    matches
+}
+
 pub fn build(state: Arc<AppState>) -> Tool {
     ToolBuilder::new("search_docs")
         .title("Search Docs")
         .description(
-            "Search for items by name within a crate's documentation on docs.rs. \
-             Returns matching functions, structs, traits, etc. with their paths \
-             and brief descriptions. Case-insensitive substring match.",
+            "Search within a crate's documentation on docs.rs. Returns matching \
+             functions, structs, traits, etc. with their paths and brief descriptions. \
+             mode=name (default) is a case-insensitive substring match on item names; \
+             mode=fulltext ranks items by how well their names, paths and doc comments \
+             match the query, for questions like \"set a timeout\"; mode=both combines them.",
         )
         .read_only_safe()
         .output_schema(schema::<SearchDocsOutput>())
         .extractor_handler(
             state,
             |State(state): State<Arc<AppState>>, Json(input): Json<SearchDocsInput>| async move {
-                let krate = state
-                    .docs_cache
-                    .get_or_fetch(&state.docsrs_client, &input.name, &input.version)
-                    .await
-                    .tool_context("docs.rs fetch error")?;
-
-                let query_lower = input.query.to_lowercase();
-                let limit = input.limit.min(100);
-
-                // Collect matches from local items only (crate_id == 0)
-                let mut matches: Vec<_> = krate
-                    .index
-                    .iter()
-                    .filter(|(_, item)| {
-                        item.crate_id == 0
-                            && item
-                                .name
-                                .as_ref()
-                                .is_some_and(|n| n.to_lowercase().contains(&query_lower))
-                    })
-                    .collect();
+                let mode = parse_mode(&input.mode).ok_or_else(|| {
+                    tower_mcp::ToolError::new(format!(
+                        "Unknown mode '{}', expected name, fulltext or both",
+                        input.mode
+                    ))
+                })?;
+
+                let (krate, index) = if mode == SearchMode::Name {
+                    let krate = state
+                        .docs_cache
+                        .get_or_fetch(&state.docsrs_client, &input.name, &input.version)
+                        .await
+                        .tool_context("docs.rs fetch error")?;
+                    (krate, None)
+                } else {
+                    let (krate, index) = state
+                        .docs_cache
+                        .get_or_fetch_with_index(&state.docsrs_client, &input.name, &input.version)
+                        .await
+                        .tool_context("docs.rs fetch error")?;
+                    (krate, Some(index))
+                };
 
-                // Sort: exact match first, then prefix, then substring
-                matches.sort_by(|(_, a), (_, b)| {
-                    let a_name = a.name.as_deref().unwrap_or("").to_lowercase();
-                    let b_name = b.name.as_deref().unwrap_or("").to_lowercase();
-                    let a_exact = a_name == query_lower;
-                    let b_exact = b_name == query_lower;
-                    let a_prefix = a_name.starts_with(&query_lower);
-                    let b_prefix = b_name.starts_with(&query_lower);
-
-                    match (a_exact, b_exact) {
-                        (true, false) => std::cmp::Ordering::Less,
-                        (false, true) => std::cmp::Ordering::Greater,
-                        _ => match (a_prefix, b_prefix) {
-                            (true, false) => std::cmp::Ordering::Less,
-                            (false, true) => std::cmp::Ordering::Greater,
-                            _ => a_name.cmp(&b_name),
-                        },
+                // Local items only (crate_id == 0), each listed once
+                let mut matches: Vec<(&Id, &Item, Option<f32>)> = Vec::new();
+                if mode != SearchMode::Fulltext {
+                    matches.extend(
+                        name_matches(&krate, &input.query)
+                            .into_iter()
+                            .map(|(id, item)| (id, item, None)),
+                    );
+                }
+                if let Some(index) = &index {
+                    let mut seen: HashSet<Id> = matches.iter().map(|(id, _, _)| **id).collect();
+                    for hit in index.search(&input.query) {
+                        if seen.insert(hit.id)
+                            && let Some((id, item)) = krate.index.get_key_value(&hit.id)
+                        {
+                            matches.push((id, item, Some(hit.score)));
+                        }
                     }
-                });
+                }
 
                 let total = matches.len();
-                matches.truncate(limit);
+                matches.truncate(input.limit.min(100));
 
                 let structured_matches = matches
                     .iter()
-                    .map(|(id, item)| DocSearchMatch {
+                    .map(|(id, item, score)| DocSearchMatch {
                         name: item.name.clone().unwrap_or_else(|| "_".to_string()),
                         path: format::item_path(&krate, id),
                         kind: format::item_kind_label(&item.inner).to_string(),
@@ -105,6 +159,7 @@ pub fn build(state: Arc<AppState>) -> Tool {
                             .as_deref()
                             .map(format::first_sentence)
                             .unwrap_or_default(),
+                        score: score.map(f64::from),
                     })
                     .collect();
 
@@ -112,6 +167,7 @@ pub fn build(state: Arc<AppState>) -> Tool {
                     name: input.name.clone(),
                     version: input.version.clone(),
                     query: input.query.clone(),
+                    mode: input.mode.clone(),
                     total: total as u64,
                     matches: structured_matches,
                 };
@@ -126,15 +182,23 @@ pub fn build(state: Arc<AppState>) -> Tool {
                     );
                 }
 
+                let how = match mode {
+                    SearchMode::Name => "",
+                    SearchMode::Fulltext => " (full-text)",
+                    SearchMode::Both => " (names, then full-text)",
+                };
                 let mut output = format!(
-                    "Found {} items matching '{}' in {} v{} (showing {}):\n\n",
+                    "Found {} items matching '{}'{} in {} v{} (showing {}):\n\n",
                     total,
                     input.query,
+                    how,
                     input.name,
                     input.version,
                     matches.len()
                 );
-                output.push_str(&format::format_search_results(&krate, &matches));
+                let listed: Vec<(&Id, &Item)> =
+                    matches.iter().map(|(id, item, _)| (*id, *item)).collect();
+                output.push_str(&format::format_search_results(&krate, &listed));
 
                 structured(output, &result)
             },
@@ -0,0 +1,151 @@
+//! Search for functions within a crate's documentation by type signature.
+
+use std::sync::Arc;
+
+use schemars::JsonSchema;
+use serde::Deserialize;
+use tower_mcp::{
+    ResultExt, Tool, ToolBuilder,
+    extract::{Json, State},
+};
+
+use crate::docs::format;
+use crate::docs::signature::SignatureQuery;
+use crate::state::AppState;
+use crate::tools::output::{SearchSignaturesOutput, SignatureSearchMatch, schema, structured};
+
+/// Input for searching crate documentation by type signature
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct SearchSignaturesInput {
+    /// Crate name (e.g. "serde", "tokio")
+    name: String,
+    /// Version (default: "latest")
+    #[serde(default = "default_version")]
+    version: String,
+    /// Signature to look for: argument types, `->`, return type (e.g.
+    /// "&str -> Result<Url, _>", "&Client, u64 -> bool"). Single capital
+    /// letters and `_` match any type. Without `->` the query is a return
+    /// type; "&str ->" matches arguments only. At most 256 characters and 8
+    /// argument types
+    query: String,
+    /// Maximum number of results (default: 20)
+    #[serde(default = "default_limit")]
+    limit: usize,
+}
+
+fn default_version() -> String {
+    "latest".to_string()
+}
+
+fn default_limit() -> usize {
+    20
+}
+
+/// Longest query accepted, in characters.
+const MAX_QUERY_LEN: usize = 256;
+
+pub fn build(state: Arc<AppState>) -> Tool {
+    ToolBuilder::new("search_signatures")
+        .title("Search Signatures")
+        .description(
+            "Search a crate's functions and methods by type signature, Hoogle-style. \
+             The query lists argument types, then `->` and the return type, e.g. \
+             \"&str -> Result<Url, _>\". Arguments may match in any order and functions \
+             may take extra ones; paths and lifetimes are ignored, `Self` stands for the \
+             implementing type, and generics (single capital letters or `_` in the query, \
+             type parameters and `impl Trait` in the crate) match any type. Exact matches \
+             are listed first.",
+        )
+        .read_only_safe()
+        .output_schema(schema::<SearchSignaturesOutput>())
+        .extractor_handler(
+            state,
+            |State(state): State<Arc<AppState>>, Json(input): Json<SearchSignaturesInput>| async move {
+                if input.query.chars().count() > MAX_QUERY_LEN {
+                    return Err(tower_mcp::ToolError::new(format!(
+                        "Signature query is too long (at most {MAX_QUERY_LEN} characters)"
+                    ))
+                    .into());
+                }
+                let query = SignatureQuery::parse(&input.query).map_err(|e| {
+                    tower_mcp::ToolError::new(format!(
+                        "Invalid signature query '{}': {e}",
+                        input.query
+                    ))
+                })?;
+
+                let krate = state
+                    .docs_cache
+                    .get_or_fetch(&state.docsrs_client, &input.name, &input.version)
+                    .await
+                    .tool_context("docs.rs fetch error")?;
+
+                // Matching visits every function in the crate: keep it off
+                // the async workers
+                let limit = input.limit.min(100);
+                let (total, structured_matches) = tokio::task::spawn_blocking(move || {
+                    let mut matches = query.search(&krate);
+                    let total = matches.len();
+                    matches.truncate(limit);
+                    let structured_matches: Vec<SignatureSearchMatch> = matches
+                        .iter()
+                        .map(|m| {
+                            let name = m.item.name.as_deref().unwrap_or("_");
+                            SignatureSearchMatch {
+                                name: name.to_string(),
+                                path: m.path.clone(),
+                                signature: format::format_function_signature(name, m.function),
+                                summary: m
+                                    .item
+                                    .docs
+                                    .as_deref()
+                                    .map(format::first_sentence)
+                                    .unwrap_or_default(),
+                                cost: m.cost,
+                            }
+                        })
+                        .collect();
+                    (total, structured_matches)
+                })
+                .await
+                .tool_context("Signature search failed")?;
+
+                let result = SearchSignaturesOutput {
+                    name: input.name.clone(),
+                    version: input.version.clone(),
+                    query: input.query.clone(),
+                    total: total as u64,
+                    matches: structured_matches,
+                };
+
+                if result.matches.is_empty() {
+                    return structured(
+                        format!(
+                            "No functions matching '{}' found in {} v{}.",
+                            input.query, input.name, input.version
+                        ),
+                        &result,
+                    );
+                }
+
+                let mut output = format!(
+                    "Found {} functions matching '{}' in {} v{} (showing {}):\n\n",
+                    total,
+                    input.query,
+                    input.name,
+                    input.version,
+                    result.matches.len()
+                );
+                for (i, m) in result.matches.iter().enumerate() {
+                    output.push_str(&format!("{}. `{}`", i + 1, m.path));
+                    if !m.summary.is_empty() {
+                        output.push_str(&format!(" -- {}", m.summary));
+                    }
+                    output.push_str(&format!("\n   `{}`\n", m.signature.trim()));
+                }
+
+                structured(output, &result)
+            },
+        )
+        .build()
+}
@@ -0,0 +1,226 @@
+//! Check a release for semver violations against the version before it.
+
+use std::sync::Arc;
+
+use schemars::JsonSchema;
+use semver::Version;
+use serde::Deserialize;
+use tower_mcp::{
+    ResultExt, Tool, ToolBuilder,
+    extract::{Json, State},
+};
+
+use crate::docs::diff;
+use crate::state::AppState;
+use crate::tools::output::{SemverCheckOutput, schema, structured};
+
+/// Input for checking a release for semver violations
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CheckSemverInput {
+    /// Crate name (e.g. "serde", "tokio")
+    name: String,
+    /// Release to check (e.g. "1.4.2")
+    version: String,
+}
+
+/// How far a release moves from the one before it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Bump {
+    /// An incompatible release: breaking changes are allowed.
+    Major,
+    Minor,
+    Patch,
+}
+
+impl Bump {
+    fn label(self) -> &'static str {
+        match self {
+            Self::Major => "major",
+            Self::Minor => "minor",
+            Self::Patch => "patch",
+        }
+    }
+}
+
+/// Classify a bump the way Cargo does: the first non-zero component is the
+/// major one, so 0.2.0 -> 0.3.0 is a major bump and 0.2.0 -> 0.2.1 a minor
+/// one.
+fn bump(old: &Version, new: &Version) -> Bump {
+    let parts = |v: &Version| [v.major, v.minor, v.patch];
+    let (old_parts, new_parts) = (parts(old), parts(new));
+    let first_nonzero = old_parts.iter().position(|&n| n != 0).unwrap_or(2);
+    match old_parts.iter().zip(&new_parts).position(|(a, b)| a != b) {
+        Some(i) if i <= first_nonzero => Bump::Major,
+        Some(i) if i == first_nonzero + 1 => Bump::Minor,
+        _ => Bump::Patch,
+    }
+}
+
+/// The release `version` follows: the newest earlier non-yanked version,
+/// skipping pre-releases unless `version` is one.
+fn predecessor(versions: &[(Version, bool)], version: &Version) -> Option<Version> {
+    versions
+        .iter()
+        .filter(|(v, yanked)| {
+            !yanked && v < version && (v.pre.is_empty() || !version.pre.is_empty())
+        })
+        .map(|(v, _)| v)
+        .max()
+        .cloned()
+}
+
+pub fn build(state: Arc<AppState>) -> Tool {
+    ToolBuilder::new("check_semver")
+        .title("Check Semver")
+        .description(
+            "Check a published crate version for semver violations. Compares its public API \
+             (from docs.rs rustdoc JSON) with the immediately preceding release and flags \
+             breaking changes -- removed items, changed signatures, new trait bounds, new \
+             required trait items, new variants of exhaustive enums -- in a minor or patch \
+             bump, by Cargo's rules (0.x.y -> 0.x.z is a minor bump). Useful to decide \
+             whether to pin a dependency with `=x.y.z`.",
+        )
+        .read_only_safe()
+        .output_schema(schema::<SemverCheckOutput>())
+        .extractor_handler(
+            state,
+            |State(state): State<Arc<AppState>>, Json(input): Json<CheckSemverInput>| async move {
+                let version = Version::parse(input.version.trim()).map_err(|e| {
+                    tower_mcp::ToolError::new(format!("Invalid version '{}': {e}", input.version))
+                })?;
+
+                // The sparse index lists every version in one response
+                let listed = match &state.index_client {
+                    Some(index) => index.crate_versions(&input.name).await.ok(),
+                    None => None,
+                };
+                let versions: Vec<(Version, bool)> = match listed {
+                    Some(listed) => listed
+                        .iter()
+                        .filter_map(|v| Some((Version::parse(&v.vers).ok()?, v.yanked)))
+                        .collect(),
+                    None => state
+                        .client
+                        .get_crate(&input.name)
+                        .await
+                        .tool_context("Crates.io API error")?
+                        .versions
+                        .iter()
+                        .filter_map(|v| Some((Version::parse(&v.num).ok()?, v.yanked)))
+                        .collect(),
+                };
+                if !versions.iter().any(|(v, _)| *v == version) {
+                    return Err(tower_mcp::ToolError::new(format!(
+                        "{} has no version {}",
+                        input.name, version
+                    ))
+                    .into());
+                }
+                let previous = predecessor(&versions, &version).ok_or_else(|| {
+                    tower_mcp::ToolError::new(format!(
+                        "{} {} has no earlier release to compare with",
+                        input.name, version
+                    ))
+                })?;
+
+                let (previous_num, version_num) = (previous.to_string(), version.to_string());
+                let (old, new) = tokio::try_join!(
+                    state
+                        .docs_cache
+                        .get_or_fetch(&state.docsrs_client, &input.name, &previous_num),
+                    state
+                        .docs_cache
+                        .get_or_fetch(&state.docsrs_client, &input.name, &version_num),
+                )
+                .tool_context("docs.rs fetch error")?;
+
+                let changes = diff::diff(&old, &new);
+                let breaking: Vec<_> = changes.iter().filter(|c| c.breaking).cloned().collect();
+                let bump = bump(&previous, &version);
+                let violations = if bump == Bump::Major {
+                    Vec::new()
+                } else {
+                    breaking.clone()
+                };
+
+                let result = SemverCheckOutput {
+                    name: input.name.clone(),
+                    version: version_num,
+                    previous_version: previous_num,
+                    bump: bump.label().to_string(),
+                    compliant: violations.is_empty(),
+                    breaking: breaking.len() as u64,
+                    non_breaking: (changes.len() - breaking.len()) as u64,
+                    violations: violations.iter().map(Into::into).collect(),
+                };
+
+                let mut output = format!(
+                    "# Semver check for {} {} ({} bump from {})\n\n",
+                    input.name,
+                    version,
+                    bump.label(),
+                    previous
+                );
+                if !violations.is_empty() {
+                    output.push_str(&format!(
+                        "**Violation:** {} breaking change(s) in a {} release.\n\n",
+                        violations.len(),
+                        bump.label()
+                    ));
+                    output.push_str(&diff::format_changes(&violations));
+                } else if bump == Bump::Major {
+                    output.push_str(&format!(
+                        "Compliant: a major release may break the API ({} breaking change(s)).\n",
+                        breaking.len()
+                    ));
+                } else {
+                    output.push_str(&format!(
+                        "Compliant: no breaking changes ({} non-breaking change(s)).\n",
+                        changes.len()
+                    ));
+                }
+
+                structured(output, &result)
+            },
+        )
+        .build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn v(s: &str) -> Version {
+        Version::parse(s).unwrap()
+    }
+
+    #[test]
+    fn bumps_follow_cargo_compatibility() {
+        assert_eq!(bump(&v("1.2.3"), &v("2.0.0")), Bump::Major);
+        assert_eq!(bump(&v("1.2.3"), &v("1.3.0")), Bump::Minor);
+        assert_eq!(bump(&v("1.2.3"), &v("1.2.4")), Bump::Patch);
+        assert_eq!(bump(&v("0.2.3"), &v("0.3.0")), Bump::Major);
+        assert_eq!(bump(&v("0.2.3"), &v("0.2.4")), Bump::Minor);
+        assert_eq!(bump(&v("0.0.3"), &v("0.0.4")), Bump::Major);
+        assert_eq!(bump(&v("1.0.0-rc.1"), &v("1.0.0")), Bump::Patch);
+    }
+
+    #[test]
+    fn predecessor_skips_yanked_and_prereleases() {
+        let versions = [
+            (v("1.0.0"), false),
+            (v("1.1.0"), false),
+            (v("1.1.1"), true),
+            (v("1.2.0-beta.1"), false),
+            (v("1.2.0"), false),
+            (v("2.0.0"), false),
+        ];
+        assert_eq!(predecessor(&versions, &v("1.2.0")), Some(v("1.1.0")));
+        assert_eq!(predecessor(&versions, &v("2.0.0")), Some(v("1.2.0")));
+        assert_eq!(
+            predecessor(&versions, &v("1.2.0-beta.2")),
+            Some(v("1.2.0-beta.1"))
+        );
+        assert_eq!(predecessor(&versions, &v("1.0.0")), None);
+    }
+}
@@ -0,0 +1,192 @@
+//! List the implementors of a trait, or the traits a type implements.
+
+use std::sync::Arc;
+
+use rustdoc_types::{Crate, Id, ItemEnum, ItemKind};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use tower_mcp::{
+    ResultExt, Tool, ToolBuilder,
+    extract::{Json, State},
+};
+
+use crate::docs::format;
+use crate::docs::impls::{self, ImplKind, ImplSummary, TraitMethods};
+use crate::state::AppState;
+use crate::tools::output::{TraitImplEntry, TraitImplsOutput, schema, structured};
+
+/// Input for listing trait implementations
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GetTraitImplsInput {
+    /// Crate name (e.g. "bytes", "tower")
+    name: String,
+    /// Version (default: "latest")
+    #[serde(default = "default_version")]
+    version: String,
+    /// A trait, to list the types implementing it (e.g. "Service", "Buf"),
+    /// or a struct, enum or union, to list the traits it implements (e.g.
+    /// "Bytes"). Traits from other crates are matched by name.
+    item_path: String,
+}
+
+fn default_version() -> String {
+    "latest".to_string()
+}
+
+/// A trait defined in another crate that `krate` refers to by `path`.
+fn external_trait(krate: &Crate, path: &str) -> Option<Id> {
+    let segments: Vec<&str> = path.split("::").collect();
+    krate
+        .paths
+        .iter()
+        .filter(|(_, summary)| summary.kind == ItemKind::Trait && summary.crate_id != 0)
+        .find(|(_, summary)| {
+            let summary_path: Vec<&str> = summary.path.iter().map(String::as_str).collect();
+            summary_path.ends_with(&segments)
+        })
+        .map(|(id, _)| *id)
+}
+
+fn entries(impls: &[ImplSummary<'_>], kind: ImplKind) -> Vec<TraitImplEntry> {
+    impls
+        .iter()
+        .filter(|summary| summary.kind == kind)
+        .map(|summary| TraitImplEntry {
+            trait_name: summary.trait_name.clone(),
+            for_type: summary.for_type.clone(),
+            header: summary.header.clone(),
+        })
+        .collect()
+}
+
+fn push_section(output: &mut String, heading: &str, entries: &[TraitImplEntry]) {
+    if entries.is_empty() {
+        return;
+    }
+    output.push_str(&format!("## {} ({})\n\n", heading, entries.len()));
+    for entry in entries {
+        output.push_str(&format!("- `{}`\n", entry.header));
+    }
+    output.push('\n');
+}
+
+fn push_methods(output: &mut String, heading: &str, signatures: &[String]) {
+    if signatures.is_empty() {
+        return;
+    }
+    output.push_str(&format!("## {}\n\n", heading));
+    for sig in signatures {
+        output.push_str(&format!("- `{}`\n", sig.trim()));
+    }
+    output.push('\n');
+}
+
+pub fn build(state: Arc<AppState>) -> Tool {
+    ToolBuilder::new("get_trait_impls")
+        .title("Get Trait Impls")
+        .description(
+            "List trait implementations from a crate's docs.rs rustdoc JSON. For a trait, \
+             lists the types in the crate implementing it, plus the trait's required and \
+             provided methods; traits from other crates (e.g. \"Service\") are matched by \
+             name. For a struct, enum or union, lists the traits it implements. Blanket \
+             impls and auto-trait impls (Send, Sync, Unpin, ...) are grouped separately.",
+        )
+        .read_only_safe()
+        .output_schema(schema::<TraitImplsOutput>())
+        .extractor_handler(
+            state,
+            |State(state): State<Arc<AppState>>, Json(input): Json<GetTraitImplsInput>| async move {
+                let krate = state
+                    .docs_cache
+                    .get_or_fetch(&state.docsrs_client, &input.name, &input.version)
+                    .await
+                    .tool_context("docs.rs fetch error")?;
+
+                let trait_name = input.item_path.rsplit("::").next().unwrap_or_default();
+                let no_methods = TraitMethods::default;
+                let (kind, found, methods) =
+                    match format::resolve_item_path(&krate, &input.item_path) {
+                        Some(item) => match &item.inner {
+                            ItemEnum::Trait(t) => (
+                                "trait",
+                                impls::implementors(&krate, Some(&item.id), trait_name),
+                                impls::trait_methods(&krate, t),
+                            ),
+                            ItemEnum::Struct(s) => {
+                                ("struct", impls::trait_impls(&krate, &s.impls), no_methods())
+                            }
+                            ItemEnum::Enum(e) => {
+                                ("enum", impls::trait_impls(&krate, &e.impls), no_methods())
+                            }
+                            ItemEnum::Union(u) => {
+                                ("union", impls::trait_impls(&krate, &u.impls), no_methods())
+                            }
+                            other => {
+                                return Err(tower_mcp::ToolError::new(format!(
+                                    "'{}' is a {}, not a trait, struct, enum or union",
+                                    input.item_path,
+                                    format::item_kind_label(other)
+                                ))
+                                .into());
+                            }
+                        },
+                        // Not defined here: a trait from another crate
+                        None => {
+                            let trait_id = external_trait(&krate, &input.item_path);
+                            let found = impls::implementors(&krate, trait_id.as_ref(), trait_name);
+                            if found.is_empty() {
+                                return Err(tower_mcp::ToolError::new(format!(
+                                    "Item '{}' not found in {} v{}",
+                                    input.item_path, input.name, input.version
+                                ))
+                                .into());
+                            }
+                            ("trait", found, no_methods())
+                        }
+                    };
+
+                let result = TraitImplsOutput {
+                    name: input.name.clone(),
+                    version: input.version.clone(),
+                    item_path: input.item_path.clone(),
+                    kind: kind.to_string(),
+                    required_methods: methods.required,
+                    provided_methods: methods.provided,
+                    impls: entries(&found, ImplKind::Direct),
+                    blanket_impls: entries(&found, ImplKind::Blanket),
+                    synthetic_impls: entries(&found, ImplKind::Synthetic),
+                };
+
+                let mut output = if kind == "trait" {
+                    format!(
+                        "# Implementors of `{}` in {} v{}\n\n",
+                        input.item_path, input.name, input.version
+                    )
+                } else {
+                    format!(
+                        "# Traits implemented by {} `{}` in {} v{}\n\n",
+                        kind, input.item_path, input.name, input.version
+                    )
+                };
+                push_methods(&mut output, "Required Methods", &result.required_methods);
+                push_methods(&mut output, "Provided Methods", &result.provided_methods);
+                push_section(&mut output, "Implementations", &result.impls);
+                push_section(
+                    &mut output,
+                    "Blanket Implementations",
+                    &result.blanket_impls,
+                );
+                push_section(
+                    &mut output,
+                    "Auto Trait Implementations",
+                    &result.synthetic_impls,
+                );
+                if found.is_empty() {
+                    output.push_str("No trait implementations found.\n");
+                }
+
+                structured(output, &result)
+            },
+        )
+        .build()
+}
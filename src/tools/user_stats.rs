@@ -17,6 +17,9 @@ use crate::tools::output::{UserStatsOutput, schema, structured};
 pub struct UserStatsInput {
     /// GitHub username
     username: String,
+    /// Registry to query, as configured with `--registry` (default: crates.io)
+    #[serde(default)]
+    registry: Option<String>,
 }
 
 pub fn build(state: Arc<AppState>) -> Tool {
@@ -32,13 +35,16 @@ pub fn build(state: Arc<AppState>) -> Tool {
         .extractor_handler(
             state,
             |State(state): State<Arc<AppState>>, Json(input): Json<UserStatsInput>| async move {
-                let user = state
+                let registry = state
+                    .registry(input.registry.as_deref())
+                    .tool_context("Registry error")?;
+                let user = registry
                     .client
                     .user(&input.username)
                     .await
                     .tool_context("Crates.io API error")?;
 
-                let stats = state
+                let stats = registry
                     .client
                     .user_stats(user.id)
                     .await
@@ -93,6 +99,9 @@ mod tests {
             )
             .unwrap(),
             docs_cache: DocsCache::new(10, Duration::from_secs(3600)),
+            index_client: None,
+            registries: Default::default(),
+            http_backend: None,
         })
     }
 
@@ -21,6 +21,9 @@ pub struct VersionDownloadsInput {
     /// Version (defaults to latest)
     #[serde(default)]
     version: Option<String>,
+    /// Registry to query, as configured with `--registry` (default: crates.io)
+    #[serde(default)]
+    registry: Option<String>,
 }
 
 pub fn build(state: Arc<AppState>) -> Tool {
@@ -37,10 +40,13 @@ pub fn build(state: Arc<AppState>) -> Tool {
             state,
             |State(state): State<Arc<AppState>>,
              Json(input): Json<VersionDownloadsInput>| async move {
+                let registry = state
+                    .registry(input.registry.as_deref())
+                    .tool_context("Registry error")?;
                 let version = match input.version {
                     Some(v) => v,
                     None => {
-                        let crate_info = state
+                        let crate_info = registry
                             .client
                             .get_crate(&input.name)
                             .await
@@ -49,7 +55,7 @@ pub fn build(state: Arc<AppState>) -> Tool {
                     }
                 };
 
-                let response = state
+                let response = registry
                     .client
                     .version_downloads(&input.name, &version)
                     .await
@@ -124,6 +130,9 @@ mod tests {
             )
             .unwrap(),
             docs_cache: DocsCache::new(10, Duration::from_secs(3600)),
+            index_client: None,
+            registries: Default::default(),
+            http_backend: None,
         })
     }
 
@@ -24,6 +24,9 @@ pub struct VersionsInput {
     /// Maximum number of versions (default: 10)
     #[serde(default = "default_limit")]
     limit: usize,
+    /// Registry to query, as configured with `--registry` (default: crates.io)
+    #[serde(default)]
+    registry: Option<String>,
 }
 
 fn default_limit() -> usize {
@@ -43,7 +46,10 @@ pub fn build(state: Arc<AppState>) -> Tool {
         .extractor_handler(
             state,
             |State(state): State<Arc<AppState>>, Json(input): Json<VersionsInput>| async move {
-                let response = state
+                let registry = state
+                    .registry(input.registry.as_deref())
+                    .tool_context("Registry error")?;
+                let response = registry
                     .client
                     .get_crate(&input.name)
                     .await
@@ -116,6 +122,9 @@ mod tests {
             )
             .unwrap(),
             docs_cache: DocsCache::new(10, Duration::from_secs(3600)),
+            index_client: None,
+            registries: Default::default(),
+            http_backend: None,
         })
     }
 
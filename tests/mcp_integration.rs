@@ -63,7 +63,14 @@ fn test_router(state: Arc<AppState>) -> McpRouter {
         .tool(tools::crate_docs::build(state.clone()))
         .tool(tools::doc_item::build(state.clone()))
         .tool(tools::search_docs::build(state.clone()))
+        .tool(tools::search_signatures::build(state.clone()))
+        .tool(tools::trait_impls::build(state.clone()))
+        .tool(tools::api_diff::build(state.clone()))
+        .tool(tools::semver_check::build(state.clone()))
         .tool(tools::audit::build(state.clone()))
+        .tool(tools::audit_lockfile::build(state.clone()))
+        .tool(tools::duplicates::build(state.clone()))
+        .tool(tools::analyze_manifest::build(state.clone()))
         .resource_template(resources::crate_info::build(state.clone()))
         .resource_template(resources::readme::build(state.clone()))
         .resource_template(resources::docs::build(state.clone()))
@@ -277,13 +284,13 @@ async fn mount_get_crate(server: &MockServer) {
 // ── Discovery tests ────────────────────────────────────────────────────────
 
 #[tokio::test]
-async fn list_tools_returns_all_29_with_safe_annotations() {
+async fn list_tools_returns_all_36_with_safe_annotations() {
     let server = MockServer::start().await;
     let mut client = initialized_client(&server).await;
 
     let tools = client.list_tools().await;
 
-    assert_eq!(tools.len(), 29);
+    assert_eq!(tools.len(), 36);
     let names: Vec<&str> = tools
         .iter()
         .filter_map(|t| t.get("name").and_then(|n| n.as_str()))
@@ -316,7 +323,14 @@ async fn list_tools_returns_all_29_with_safe_annotations() {
     assert!(names.contains(&"get_crate_docs"));
     assert!(names.contains(&"get_doc_item"));
     assert!(names.contains(&"search_docs"));
+    assert!(names.contains(&"search_signatures"));
+    assert!(names.contains(&"get_trait_impls"));
+    assert!(names.contains(&"get_api_diff"));
+    assert!(names.contains(&"check_semver"));
     assert!(names.contains(&"audit_dependencies"));
+    assert!(names.contains(&"audit_lockfile"));
+    assert!(names.contains(&"find_duplicate_versions"));
+    assert!(names.contains(&"analyze_manifest"));
 
     for tool in tools {
         let output_schema = tool
@@ -407,7 +421,7 @@ async fn final_protocol_discovery_is_enabled_without_a_session() {
     assert_eq!(response["result"]["resultType"], "complete");
     assert_eq!(response["result"]["ttlMs"], 3_600_000);
     assert_eq!(response["result"]["cacheScope"], "public");
-    assert_eq!(response["result"]["tools"].as_array().unwrap().len(), 29);
+    assert_eq!(response["result"]["tools"].as_array().unwrap().len(), 36);
 
     assert_eq!(handle.session_count().await, 0);
 }
@@ -1170,6 +1184,10 @@ fn full_test_router(state: Arc<AppState>) -> McpRouter {
         .tool(tools::crate_docs::build(state.clone()))
         .tool(tools::doc_item::build(state.clone()))
         .tool(tools::search_docs::build(state.clone()))
+        .tool(tools::search_signatures::build(state.clone()))
+        .tool(tools::trait_impls::build(state.clone()))
+        .tool(tools::api_diff::build(state.clone()))
+        .tool(tools::semver_check::build(state.clone()))
         .tool(tools::audit::build(state.clone()))
         .tool(tools::health_check::build(state.clone()))
 }
@@ -1279,6 +1297,509 @@ async fn tool_search_docs() {
     assert!(text.contains("No items matching"));
 }
 
+/// A crate with a few documented items, for search tests.
+fn documented_crate_json() -> Vec<u8> {
+    let function = |id: u32, name: &str, docs: &str| {
+        json!({
+            "id": id,
+            "crate_id": 0,
+            "name": name,
+            "span": null,
+            "visibility": "public",
+            "docs": docs,
+            "links": {},
+            "attrs": [],
+            "deprecation": null,
+            "inner": {
+                "function": {
+                    "sig": { "inputs": [], "output": null, "is_c_variadic": false },
+                    "generics": { "params": [], "where_predicates": [] },
+                    "header": {
+                        "is_const": false,
+                        "is_unsafe": false,
+                        "is_async": false,
+                        "abi": "Rust"
+                    },
+                    "has_body": true
+                }
+            }
+        })
+    };
+    let json = json!({
+        "root": 0,
+        "crate_version": "1.0.0",
+        "includes_private": false,
+        "index": {
+            "1": function(1, "timeout", "Set a timeout for the whole request."),
+            "2": function(2, "connect_timeout", "Set a timeout for only the connect phase."),
+            "3": function(3, "proxy", "Add a proxy; requests time out after the client timeout."),
+        },
+        "paths": {},
+        "external_crates": {},
+        "target": {
+            "triple": "x86_64-unknown-linux-gnu",
+            "target_features": []
+        },
+        "format_version": rustdoc_types::FORMAT_VERSION
+    });
+    serde_json::to_vec(&json).unwrap()
+}
+
+#[tokio::test]
+async fn tool_search_docs_modes() {
+    let crates_server = MockServer::start().await;
+    let docsrs_server = MockServer::start().await;
+    let osv_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/crate/reqwest/latest/json.gz"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_bytes(documented_crate_json())
+                .insert_header("content-type", "application/json"),
+        )
+        .expect(1)
+        .mount(&docsrs_server)
+        .await;
+
+    let mut client = full_initialized_client(&crates_server, &docsrs_server, &osv_server).await;
+    let mut search = async |query: &str, mode: &str| {
+        let result = client
+            .call_tool(
+                "search_docs",
+                json!({"name": "reqwest", "query": query, "mode": mode}),
+            )
+            .await;
+        assert!(!result.is_error, "{mode} search failed");
+        let names: Vec<String> = result.structured_content.unwrap()["matches"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|m| m["name"].as_str().unwrap().to_string())
+            .collect();
+        names
+    };
+
+    // Names alone don't answer a question
+    assert!(search("how do I set a timeout", "name").await.is_empty());
+    assert_eq!(
+        search("how do I set a timeout", "fulltext").await,
+        ["timeout", "connect_timeout", "proxy"]
+    );
+    // Name matches first, then the rest of the full-text results
+    assert_eq!(search("proxy", "both").await, ["proxy"]);
+    assert_eq!(search("connect", "both").await, ["connect_timeout"]);
+    assert_eq!(
+        search("timeout", "both").await,
+        ["timeout", "connect_timeout", "proxy"]
+    );
+}
+
+#[tokio::test]
+async fn tool_search_signatures() {
+    let crates_server = MockServer::start().await;
+    let docsrs_server = MockServer::start().await;
+    let osv_server = MockServer::start().await;
+
+    let str_ref = json!({ "borrowed_ref": { "lifetime": null, "is_mutable": false, "type": { "primitive": "str" } } });
+    let function = |id: u32, name: &str, output: serde_json::Value| {
+        json!({
+            "id": id,
+            "crate_id": 0,
+            "name": name,
+            "span": null,
+            "visibility": "public",
+            "docs": null,
+            "links": {},
+            "attrs": [],
+            "deprecation": null,
+            "inner": {
+                "function": {
+                    "sig": { "inputs": [["input", str_ref]], "output": output, "is_c_variadic": false },
+                    "generics": { "params": [], "where_predicates": [] },
+                    "header": {
+                        "is_const": false,
+                        "is_unsafe": false,
+                        "is_async": false,
+                        "abi": "Rust"
+                    },
+                    "has_body": true
+                }
+            }
+        })
+    };
+    let krate = json!({
+        "root": 0,
+        "crate_version": "2.5.0",
+        "includes_private": false,
+        "index": {
+            "1": function(1, "parse", json!({ "resolved_path": { "path": "Url", "id": 9, "args": null } })),
+            "2": function(2, "is_valid", json!({ "primitive": "bool" })),
+        },
+        "paths": {},
+        "external_crates": {},
+        "target": {
+            "triple": "x86_64-unknown-linux-gnu",
+            "target_features": []
+        },
+        "format_version": rustdoc_types::FORMAT_VERSION
+    });
+
+    Mock::given(method("GET"))
+        .and(path("/crate/url/latest/json.gz"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_bytes(serde_json::to_vec(&krate).unwrap())
+                .insert_header("content-type", "application/json"),
+        )
+        .mount(&docsrs_server)
+        .await;
+
+    let mut client = full_initialized_client(&crates_server, &docsrs_server, &osv_server).await;
+    let result = client
+        .call_tool(
+            "search_signatures",
+            json!({"name": "url", "query": "&str -> Url"}),
+        )
+        .await;
+    assert!(!result.is_error);
+    let structured = result.structured_content.unwrap();
+    assert_eq!(structured["total"], 1);
+    assert_eq!(structured["matches"][0]["path"], "parse");
+    assert_eq!(structured["matches"][0]["cost"], 0);
+    assert_eq!(
+        structured["matches"][0]["signature"],
+        "fn parse(input: &str) -> Url"
+    );
+
+    let result = client
+        .call_tool(
+            "search_signatures",
+            json!({"name": "url", "query": "&str -> Result<"}),
+        )
+        .await;
+    assert!(result.is_error);
+
+    // Overlong queries are refused before parsing
+    let result = client
+        .call_tool(
+            "search_signatures",
+            json!({"name": "url", "query": format!("{}str", "&".repeat(5000))}),
+        )
+        .await;
+    assert!(result.is_error);
+    assert!(result.all_text().contains("too long"));
+}
+
+#[tokio::test]
+async fn tool_get_trait_impls() {
+    let crates_server = MockServer::start().await;
+    let docsrs_server = MockServer::start().await;
+    let osv_server = MockServer::start().await;
+
+    let item = |id: u32, name: Option<&str>, inner: serde_json::Value| {
+        json!({
+            "id": id,
+            "crate_id": 0,
+            "name": name,
+            "span": null,
+            "visibility": "public",
+            "docs": null,
+            "links": {},
+            "attrs": [],
+            "deprecation": null,
+            "inner": inner,
+        })
+    };
+    let trait_impl = |id: u32, trait_: &str, trait_id: u32, synthetic: bool| {
+        item(
+            id,
+            None,
+            json!({
+                "impl": {
+                    "is_unsafe": false,
+                    "generics": { "params": [], "where_predicates": [] },
+                    "provided_trait_methods": [],
+                    "trait": { "path": trait_, "id": trait_id, "args": null },
+                    "for": { "resolved_path": { "path": "Router", "id": 1, "args": null } },
+                    "items": [],
+                    "is_negative": false,
+                    "is_synthetic": synthetic,
+                    "blanket_impl": null
+                }
+            }),
+        )
+    };
+    let krate = json!({
+        "root": 0,
+        "crate_version": "0.8.0",
+        "includes_private": false,
+        "index": {
+            "0": item(0, Some("axum"), json!({
+                "module": { "is_crate": true, "items": [1], "is_stripped": false }
+            })),
+            "1": item(1, Some("Router"), json!({
+                "struct": {
+                    "kind": "unit",
+                    "generics": { "params": [], "where_predicates": [] },
+                    "impls": [10, 11]
+                }
+            })),
+            "10": trait_impl(10, "Service", 90, false),
+            "11": trait_impl(11, "Send", 91, true),
+        },
+        "paths": {
+            "90": { "crate_id": 1, "path": ["tower_service", "Service"], "kind": "trait" }
+        },
+        "external_crates": {},
+        "target": {
+            "triple": "x86_64-unknown-linux-gnu",
+            "target_features": []
+        },
+        "format_version": rustdoc_types::FORMAT_VERSION
+    });
+
+    Mock::given(method("GET"))
+        .and(path("/crate/axum/latest/json.gz"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_bytes(serde_json::to_vec(&krate).unwrap())
+                .insert_header("content-type", "application/json"),
+        )
+        .mount(&docsrs_server)
+        .await;
+
+    let mut client = full_initialized_client(&crates_server, &docsrs_server, &osv_server).await;
+
+    // A trait from another crate: which types implement it
+    let result = client
+        .call_tool(
+            "get_trait_impls",
+            json!({"name": "axum", "item_path": "Service"}),
+        )
+        .await;
+    assert!(!result.is_error);
+    let structured = result.structured_content.unwrap();
+    assert_eq!(structured["kind"], "trait");
+    assert_eq!(structured["impls"][0]["for_type"], "Router");
+
+    // A type: which traits it implements, auto traits separately
+    let result = client
+        .call_tool(
+            "get_trait_impls",
+            json!({"name": "axum", "item_path": "Router"}),
+        )
+        .await;
+    assert!(!result.is_error);
+    let structured = result.structured_content.unwrap();
+    assert_eq!(structured["kind"], "struct");
+    assert_eq!(structured["impls"][0]["header"], "impl Service for Router");
+    assert_eq!(structured["synthetic_impls"][0]["trait_name"], "Send");
+
+    let result = client
+        .call_tool(
+            "get_trait_impls",
+            json!({"name": "axum", "item_path": "Layer"}),
+        )
+        .await;
+    assert!(result.is_error);
+}
+
+/// JSON for one public rustdoc item, as `docs::format::tests::item_json`
+/// builds it for the unit tests. Impls have no name.
+fn rustdoc_item(
+    id: u32,
+    name: Option<&str>,
+    docs: Option<&str>,
+    inner: serde_json::Value,
+) -> serde_json::Value {
+    json!({
+        "id": id,
+        "crate_id": 0,
+        "name": name,
+        "span": null,
+        "visibility": "public",
+        "docs": docs,
+        "links": {},
+        "attrs": [],
+        "deprecation": null,
+        "inner": inner,
+    })
+}
+
+/// JSON for a plain function inner with the given `[name, type]` inputs and
+/// output type.
+fn rustdoc_function(inputs: serde_json::Value, output: serde_json::Value) -> serde_json::Value {
+    json!({
+        "function": {
+            "sig": { "inputs": inputs, "output": output, "is_c_variadic": false },
+            "generics": { "params": [], "where_predicates": [] },
+            "header": {
+                "is_const": false,
+                "is_unsafe": false,
+                "is_async": false,
+                "abi": "Rust"
+            },
+            "has_body": true
+        }
+    })
+}
+
+/// Rustdoc JSON for crate `name`: a root module (id 0) listing `root_items`,
+/// plus `items`, as `docs::format::tests::synthetic_crate` builds it.
+fn rustdoc_crate(
+    name: &str,
+    version: &str,
+    root_items: &[u32],
+    items: Vec<serde_json::Value>,
+) -> serde_json::Value {
+    let mut index = serde_json::Map::new();
+    index.insert(
+        "0".to_string(),
+        rustdoc_item(
+            0,
+            Some(name),
+            None,
+            json!({ "module": { "is_crate": true, "items": root_items, "is_stripped": false } }),
+        ),
+    );
+    for item in items {
+        index.insert(item["id"].to_string(), item);
+    }
+    json!({
+        "root": 0,
+        "crate_version": version,
+        "includes_private": false,
+        "index": index,
+        "paths": {},
+        "external_crates": {},
+        "target": {
+            "triple": "x86_64-unknown-linux-gnu",
+            "target_features": []
+        },
+        "format_version": rustdoc_types::FORMAT_VERSION
+    })
+}
+
+/// Serve `krate` as the rustdoc JSON of `name` at `version`.
+async fn mount_rustdoc(server: &MockServer, name: &str, version: &str, krate: &serde_json::Value) {
+    Mock::given(method("GET"))
+        .and(path(format!("/crate/{name}/{version}/json.gz")))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_bytes(serde_json::to_vec(krate).unwrap())
+                .insert_header("content-type", "application/json"),
+        )
+        .mount(server)
+        .await;
+}
+
+/// A crate whose root module holds unit-returning functions with the given
+/// ids and names.
+fn functions_crate(version: &str, functions: &[(u32, &str)]) -> serde_json::Value {
+    let ids: Vec<u32> = functions.iter().map(|(id, _)| *id).collect();
+    let items = functions
+        .iter()
+        .map(|(id, name)| {
+            rustdoc_item(
+                *id,
+                Some(name),
+                None,
+                rustdoc_function(json!([]), json!(null)),
+            )
+        })
+        .collect();
+    rustdoc_crate("demo", version, &ids, items)
+}
+
+#[tokio::test]
+async fn tool_get_api_diff() {
+    let crates_server = MockServer::start().await;
+    let docsrs_server = MockServer::start().await;
+    let osv_server = MockServer::start().await;
+
+    for (version, functions) in [
+        ("1.0.0", &[(1, "connect"), (2, "legacy")][..]),
+        ("1.1.0", &[(1, "connect"), (3, "connect_with")][..]),
+    ] {
+        let krate = functions_crate(version, functions);
+        mount_rustdoc(&docsrs_server, "demo", version, &krate).await;
+    }
+
+    let mut client = full_initialized_client(&crates_server, &docsrs_server, &osv_server).await;
+    let result = client
+        .call_tool(
+            "get_api_diff",
+            json!({"name": "demo", "from_version": "1.0.0", "to_version": "1.1.0"}),
+        )
+        .await;
+    assert!(!result.is_error);
+    assert!(
+        result
+            .first_text()
+            .unwrap()
+            .contains("## Breaking Changes (1)")
+    );
+    let structured = result.structured_content.unwrap();
+    assert_eq!(structured["breaking"], 1);
+    assert_eq!(structured["non_breaking"], 1);
+    assert_eq!(structured["changes"][0]["path"], "demo::legacy");
+    assert_eq!(structured["changes"][0]["change"], "removed");
+    assert_eq!(structured["changes"][1]["path"], "demo::connect_with");
+    assert_eq!(structured["changes"][1]["breaking"], false);
+}
+
+#[tokio::test]
+async fn tool_check_semver_flags_minor_release_removals() {
+    let crates_server = MockServer::start().await;
+    let docsrs_server = MockServer::start().await;
+    let osv_server = MockServer::start().await;
+
+    let version = |num: &str, yanked: bool| json!({ "num": num, "yanked": yanked, "created_at": "2026-02-11T13:21:51.089324Z" });
+    Mock::given(method("GET"))
+        .and(path("/crates/demo"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "crate": {
+                "name": "demo",
+                "updated_at": "2026-02-11T13:21:51.089324Z",
+                "created_at": "2026-01-28T16:29:05.281129Z",
+                "downloads": 10,
+                "max_version": "1.1.0"
+            },
+            "versions": [
+                version("1.1.0", false),
+                version("1.0.1", true),
+                version("1.0.0", false),
+            ]
+        })))
+        .mount(&crates_server)
+        .await;
+    for (version, functions) in [
+        ("1.0.0", &[(1, "connect"), (2, "legacy")][..]),
+        ("1.1.0", &[(1, "connect"), (3, "connect_with")][..]),
+    ] {
+        let krate = functions_crate(version, functions);
+        mount_rustdoc(&docsrs_server, "demo", version, &krate).await;
+    }
+
+    let mut client = full_initialized_client(&crates_server, &docsrs_server, &osv_server).await;
+    let result = client
+        .call_tool("check_semver", json!({"name": "demo", "version": "1.1.0"}))
+        .await;
+    assert!(!result.is_error);
+    let structured = result.structured_content.unwrap();
+    // The yanked 1.0.1 is skipped
+    assert_eq!(structured["previous_version"], "1.0.0");
+    assert_eq!(structured["bump"], "minor");
+    assert_eq!(structured["compliant"], false);
+    assert_eq!(structured["violations"][0]["path"], "demo::legacy");
+
+    let result = client
+        .call_tool("check_semver", json!({"name": "demo", "version": "1.0.0"}))
+        .await;
+    assert!(result.is_error);
+}
+
 #[tokio::test]
 async fn tool_get_doc_item_not_found() {
     let crates_server = MockServer::start().await;
@@ -1329,10 +1850,10 @@ async fn tool_audit_dependencies_clean() {
         .mount(&crates_server)
         .await;
 
-    // Mount OSV query mock (no vulns for any package)
+    // Mount OSV query mock (no vulns for the crate or its normal dep)
     Mock::given(method("POST"))
-        .and(path("/query"))
-        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"vulns": []})))
+        .and(path("/querybatch"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"results": [{}, {}]})))
         .mount(&osv_server)
         .await;
 
@@ -1510,10 +2031,10 @@ async fn tool_get_crate_health() {
         .mount(&crates_server)
         .await;
 
-    // OSV: no vulnerabilities
+    // OSV: no vulnerabilities for the crate or its normal dep
     Mock::given(method("POST"))
-        .and(path("/query"))
-        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"vulns": []})))
+        .and(path("/querybatch"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"results": [{}, {}]})))
         .mount(&osv_server)
         .await;
 
@@ -1533,7 +2054,7 @@ async fn tool_get_crate_health() {
     assert!(text.contains("Compatibility"));
     assert!(text.contains("Dependency Weight"));
     assert!(text.contains("MIT OR Apache-2.0"));
-    assert!(text.contains("None")); // no vulnerabilities
+    assert!(text.contains("Known vulnerabilities**: None"));
 }
 
 // ── Find alternatives tool test ────────────────────────────────────────────